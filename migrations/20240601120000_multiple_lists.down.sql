@@ -0,0 +1,129 @@
+-- Add down migration script here
+
+DROP VIEW list_ranked_players;
+DROP VIEW ranked_players;
+DROP VIEW score_giving;
+
+DROP FUNCTION score_of_player(INTEGER, INTEGER);
+DROP TABLE player_scores;
+
+DELETE FROM records USING demons WHERE records.demon = demons.id AND demons.list <> 1;
+DELETE FROM creators USING demons WHERE creators.demon = demons.id AND demons.list <> 1;
+DELETE FROM demons WHERE list <> 1;
+
+ALTER TABLE demons DROP CONSTRAINT unique_position;
+ALTER TABLE demons ADD CONSTRAINT unique_position UNIQUE (position) DEFERRABLE;
+ALTER TABLE demons DROP COLUMN list;
+
+DROP TABLE lists;
+
+CREATE VIEW score_giving AS
+    SELECT records.progress, demons.position, demons.requirement, records.player
+    FROM records
+    INNER JOIN demons
+    ON demons.id = records.demon
+    WHERE records.status_ = 'APPROVED' AND (demons.position <= 75 OR records.progress = 100)
+
+    UNION
+
+    SELECT 100, demons.position, demons.requirement, demons.verifier
+    FROM demons;
+
+CREATE VIEW ranked_players AS
+    SELECT
+        ROW_NUMBER() OVER(ORDER BY players.score DESC, players.id) AS index,
+        RANK() OVER (ORDER BY players.score DESC) AS rank,
+        players.id, players.name, players.score, players.subdivision,
+        nationalities.iso_country_code,
+        nationalities.nation,
+        nationalities.continent
+    FROM players
+    LEFT OUTER JOIN nationalities
+                 ON players.nationality = nationalities.iso_country_code
+    WHERE NOT players.banned AND players.score > 0.0;
+
+CREATE OR REPLACE FUNCTION score_of_player(player_id INTEGER) RETURNS DOUBLE PRECISION AS $$
+    SELECT SUM(record_score(progress, position, 150, requirement))
+    FROM score_giving
+    WHERE player = player_id
+$$ LANGUAGE SQL;
+
+CREATE OR REPLACE FUNCTION score_of_nation(iso_country_code VARCHAR(2)) RETURNS DOUBLE PRECISION AS $$
+    SELECT SUM(record_score(q.progress, q.position, 150, q.requirement))
+    FROM (
+        SELECT DISTINCT ON (position) * from score_giving
+        INNER JOIN players
+                ON players.id=player
+        WHERE players.nationality = iso_country_code
+        ORDER BY position, progress DESC
+    ) q
+$$ LANGUAGE SQL;
+
+CREATE OR REPLACE FUNCTION score_of_subdivision(iso_country_code VARCHAR(2), iso_code VARCHAR(3)) RETURNS DOUBLE PRECISION AS $$
+    SELECT SUM(record_score(q.progress, q.position, 150, q.requirement))
+    FROM (
+        SELECT DISTINCT ON (position) * from score_giving
+        INNER JOIN players
+                ON players.id=player
+        WHERE players.nationality = iso_country_code
+          AND players.subdivision = iso_code
+        ORDER BY position, progress DESC
+    ) q
+$$ LANGUAGE SQL;
+
+CREATE OR REPLACE FUNCTION recompute_player_scores() RETURNS void AS $$
+    UPDATE players
+    SET score = coalesce(q.score, 0)
+    FROM players p
+        LEFT OUTER JOIN (
+            SELECT player, SUM(record_score(progress, position, 150, requirement)) as score
+            FROM score_giving
+            GROUP BY player
+        ) q
+        ON q.player = p.id
+    WHERE players.id = p.id;
+$$ LANGUAGE SQL;
+
+CREATE OR REPLACE FUNCTION recompute_nation_scores() RETURNS void AS $$
+    UPDATE nationalities
+    SET score = COALESCE(p.sum, 0)
+    FROM nationalities n
+        LEFT OUTER JOIN (
+            SELECT nationality, SUM(record_score(q.progress, q.position, 150, q.requirement))
+            FROM (
+                SELECT DISTINCT ON (position, nationality) * from score_giving
+                INNER JOIN players
+                        ON players.id=player
+                WHERE players.nationality IS NOT NULL
+                ORDER BY players.nationality, position, progress DESC
+            ) q
+            GROUP BY nationality
+        ) p
+        ON p.nationality = n.iso_country_code
+    WHERE n.iso_country_code = nationalities.iso_country_code
+$$ LANGUAGE SQL;
+
+CREATE OR REPLACE FUNCTION recompute_subdivision_scores() RETURNS void AS $$
+    UPDATE subdivisions
+    SET score = COALESCE(p.sum, 0)
+    FROM subdivisions s
+        LEFT OUTER JOIN (
+            SELECT nationality, subdivision, SUM(record_score(q.progress, q.position, 150, q.requirement))
+            FROM (
+                SELECT DISTINCT ON (position, nationality, subdivision) * from score_giving
+                INNER JOIN players
+                        ON players.id=player
+                WHERE players.nationality IS NOT NULL
+                AND players.subdivision IS NOT NULL
+                ORDER BY players.nationality, players.subdivision, position, progress DESC
+            ) q
+            GROUP BY nationality, subdivision
+        ) p
+        ON s.nation = p.nationality AND s.iso_code = p.subdivision
+    WHERE s.nation = subdivisions.nation
+      AND s.iso_code = subdivisions.iso_code
+$$ LANGUAGE SQL;
+
+SELECT recompute_player_scores();
+SELECT recompute_nation_scores();
+SELECT recompute_subdivision_scores();
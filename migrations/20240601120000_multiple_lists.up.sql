@@ -0,0 +1,183 @@
+-- Add up migration script here
+
+CREATE TABLE lists (
+    id SERIAL PRIMARY KEY,
+    slug VARCHAR(32) NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    list_size SMALLINT NOT NULL DEFAULT 75,
+    extended_list_size SMALLINT NOT NULL DEFAULT 150,
+
+    CONSTRAINT valid_list_sizes CHECK (0 < list_size AND list_size <= extended_list_size)
+);
+
+-- The list every pre-existing demon belongs to. Its ID is assumed to be 1 throughout the code base.
+INSERT INTO lists (id, slug, name) VALUES (1, 'main', 'Demonlist');
+SELECT setval('lists_id_seq', 1);
+
+ALTER TABLE demons ADD COLUMN list INTEGER NOT NULL DEFAULT 1 REFERENCES lists(id) ON DELETE RESTRICT;
+ALTER TABLE demons DROP CONSTRAINT unique_position;
+ALTER TABLE demons ADD CONSTRAINT unique_position UNIQUE (list, position) DEFERRABLE;
+
+-- Per-list scores of all players. The `score` column of the `players` table remains as a cache of the score on the main list.
+CREATE TABLE player_scores (
+    player INTEGER NOT NULL REFERENCES players(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    list INTEGER NOT NULL REFERENCES lists(id) ON DELETE CASCADE,
+    score DOUBLE PRECISION NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (player, list)
+);
+
+DROP VIEW ranked_players;
+DROP VIEW score_giving;
+
+CREATE VIEW score_giving AS
+    SELECT records.progress, demons.position, demons.requirement, records.player, demons.list
+    FROM records
+    INNER JOIN demons
+    ON demons.id = records.demon
+    INNER JOIN lists
+    ON lists.id = demons.list
+    WHERE records.status_ = 'APPROVED' AND (demons.position <= lists.list_size OR records.progress = 100)
+
+    UNION
+
+    SELECT 100, demons.position, demons.requirement, demons.verifier, demons.list
+    FROM demons;
+
+CREATE VIEW ranked_players AS
+    SELECT
+        ROW_NUMBER() OVER(ORDER BY players.score DESC, players.id) AS index,
+        RANK() OVER (ORDER BY players.score DESC) AS rank,
+        players.id, players.name, players.score, players.subdivision,
+        nationalities.iso_country_code,
+        nationalities.nation,
+        nationalities.continent
+    FROM players
+    LEFT OUTER JOIN nationalities
+                 ON players.nationality = nationalities.iso_country_code
+    WHERE NOT players.banned AND players.score > 0.0;
+
+CREATE VIEW list_ranked_players AS
+    SELECT
+        ROW_NUMBER() OVER(PARTITION BY player_scores.list ORDER BY player_scores.score DESC, players.id) AS index,
+        RANK() OVER (PARTITION BY player_scores.list ORDER BY player_scores.score DESC) AS rank,
+        player_scores.list,
+        players.id, players.name, player_scores.score, players.subdivision,
+        nationalities.iso_country_code,
+        nationalities.nation,
+        nationalities.continent
+    FROM player_scores
+    INNER JOIN players
+            ON players.id = player_scores.player
+    LEFT OUTER JOIN nationalities
+                 ON players.nationality = nationalities.iso_country_code
+    WHERE NOT players.banned AND player_scores.score > 0.0;
+
+CREATE FUNCTION score_of_player(player_id INTEGER, list_id INTEGER) RETURNS DOUBLE PRECISION AS $$
+    SELECT SUM(record_score(progress, position, lists.extended_list_size, requirement))
+    FROM score_giving
+    INNER JOIN lists
+            ON lists.id = score_giving.list
+    WHERE player = player_id AND list = list_id
+$$ LANGUAGE SQL;
+
+CREATE OR REPLACE FUNCTION score_of_player(player_id INTEGER) RETURNS DOUBLE PRECISION AS $$
+    SELECT score_of_player(player_id, 1)
+$$ LANGUAGE SQL;
+
+CREATE OR REPLACE FUNCTION recompute_player_scores() RETURNS void AS $$
+    DELETE FROM player_scores;
+
+    INSERT INTO player_scores (player, list, score)
+    SELECT player, list, SUM(record_score(progress, position, lists.extended_list_size, requirement))
+    FROM score_giving
+    INNER JOIN lists
+            ON lists.id = score_giving.list
+    GROUP BY player, list;
+
+    UPDATE players
+    SET score = coalesce(q.score, 0)
+    FROM players p
+        LEFT OUTER JOIN player_scores q
+        ON q.player = p.id AND q.list = 1
+    WHERE players.id = p.id;
+$$ LANGUAGE SQL;
+
+-- Nation and subdivision rankings are only computed for the main list
+
+CREATE OR REPLACE FUNCTION score_of_nation(iso_country_code VARCHAR(2)) RETURNS DOUBLE PRECISION AS $$
+    SELECT SUM(record_score(q.progress, q.position, lists.extended_list_size, q.requirement))
+    FROM (
+        SELECT DISTINCT ON (position) * from score_giving
+        INNER JOIN players
+                ON players.id=player
+        WHERE players.nationality = iso_country_code AND list = 1
+        ORDER BY position, progress DESC
+    ) q
+    INNER JOIN lists
+            ON lists.id = q.list
+$$ LANGUAGE SQL;
+
+CREATE OR REPLACE FUNCTION score_of_subdivision(iso_country_code VARCHAR(2), iso_code VARCHAR(3)) RETURNS DOUBLE PRECISION AS $$
+    SELECT SUM(record_score(q.progress, q.position, lists.extended_list_size, q.requirement))
+    FROM (
+        SELECT DISTINCT ON (position) * from score_giving
+        INNER JOIN players
+                ON players.id=player
+        WHERE players.nationality = iso_country_code
+          AND players.subdivision = iso_code
+          AND list = 1
+        ORDER BY position, progress DESC
+    ) q
+    INNER JOIN lists
+            ON lists.id = q.list
+$$ LANGUAGE SQL;
+
+CREATE OR REPLACE FUNCTION recompute_nation_scores() RETURNS void AS $$
+    UPDATE nationalities
+    SET score = COALESCE(p.sum, 0)
+    FROM nationalities n
+        LEFT OUTER JOIN (
+            SELECT nationality, SUM(record_score(q.progress, q.position, lists.extended_list_size, q.requirement))
+            FROM (
+                SELECT DISTINCT ON (position, nationality) * from score_giving
+                INNER JOIN players
+                        ON players.id=player
+                WHERE players.nationality IS NOT NULL AND list = 1
+                ORDER BY players.nationality, position, progress DESC
+            ) q
+            INNER JOIN lists
+                    ON lists.id = q.list
+            GROUP BY nationality
+        ) p
+        ON p.nationality = n.iso_country_code
+    WHERE n.iso_country_code = nationalities.iso_country_code
+$$ LANGUAGE SQL;
+
+CREATE OR REPLACE FUNCTION recompute_subdivision_scores() RETURNS void AS $$
+    UPDATE subdivisions
+    SET score = COALESCE(p.sum, 0)
+    FROM subdivisions s
+        LEFT OUTER JOIN (
+            SELECT nationality, subdivision, SUM(record_score(q.progress, q.position, lists.extended_list_size, q.requirement))
+            FROM (
+                SELECT DISTINCT ON (position, nationality, subdivision) * from score_giving
+                INNER JOIN players
+                        ON players.id=player
+                WHERE players.nationality IS NOT NULL
+                AND players.subdivision IS NOT NULL
+                AND list = 1
+                ORDER BY players.nationality, players.subdivision, position, progress DESC
+            ) q
+            INNER JOIN lists
+                    ON lists.id = q.list
+            GROUP BY nationality, subdivision
+        ) p
+        ON s.nation = p.nationality AND s.iso_code = p.subdivision
+    WHERE s.nation = subdivisions.nation
+      AND s.iso_code = subdivisions.iso_code
+$$ LANGUAGE SQL;
+
+SELECT recompute_player_scores();
+SELECT recompute_nation_scores();
+SELECT recompute_subdivision_scores();
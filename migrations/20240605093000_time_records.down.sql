@@ -0,0 +1,214 @@
+-- Add down migration script here
+
+CREATE OR REPLACE FUNCTION audit_record_modification() RETURNS trigger AS $record_modification_trigger$
+    DECLARE
+        progress_change SMALLINT;
+        video_change VARCHAR(200);
+        status_change RECORD_STATUS;
+        player_change INT;
+        demon_change INTEGER;
+    BEGIN
+        if (OLD.progress <> NEW.progress) THEN
+            progress_change = OLD.progress;
+        END IF;
+
+        IF (OLD.video <> NEW.video) THEN
+            video_change = OLD.video;
+        END IF;
+
+        IF (OLD.status_ <> NEW.status_) THEN
+            status_change = OLD.status_;
+        END IF;
+
+        IF (OLD.player <> NEW.player) THEN
+            player_change = OLD.player;
+        END IF;
+
+        IF (OLD.demon <> NEW.demon) THEN
+            demon_change = OLD.demon;
+        END IF;
+
+        INSERT INTO record_modifications (userid, id, progress, video, status_, player, demon)
+            (SELECT id, NEW.id, progress_change, video_change, status_change, player_change, demon_change
+            FROM active_user LIMIT 1);
+
+        RETURN NEW;
+    END;
+$record_modification_trigger$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION audit_demon_modification() RETURNS trigger AS $demon_modification_trigger$
+DECLARE
+    name_change CITEXT;
+    position_change SMALLINT;
+    requirement_change SMALLINT;
+    video_change VARCHAR(200);
+    thumbnail_change TEXT;
+    verifier_change INT;
+    publisher_change INT;
+BEGIN
+    IF (OLD.name <> NEW.name) THEN
+        name_change = OLD.name;
+    END IF;
+
+    IF (OLD.position <> NEW.position) THEN
+        position_change = OLD.position;
+    END IF;
+
+    IF (OLD.requirement <> NEW.requirement) THEN
+        requirement_change = OLD.requirement;
+    END IF;
+
+    IF (OLD.video <> NEW.video) THEN
+        video_change = OLD.video;
+    END IF;
+
+    IF (OLD.thumbnail <> NEW.thumbnail) THEN
+        thumbnail_change = OLD.thumbnail;
+    END IF;
+
+    IF (OLD.verifier <> NEW.verifier) THEN
+        verifier_change = OLD.verifier;
+    END IF;
+
+    IF (OLD.publisher <> NEW.publisher) THEN
+        publisher_change = OLD.publisher;
+    END IF;
+
+    INSERT INTO demon_modifications (userid, name, position, requirement, video, verifier, publisher, thumbnail, id)
+        (SELECT id, name_change, position_change, requirement_change, video_change, verifier_change, publisher_change, thumbnail_change, NEW.id
+         FROM active_user LIMIT 1);
+
+    RETURN NEW;
+END;
+$demon_modification_trigger$ LANGUAGE plpgsql;
+
+DROP FUNCTION record_worse_or_equal(SMALLINT, BIGINT, SMALLINT, BIGINT);
+
+DROP VIEW score_giving;
+
+CREATE VIEW score_giving AS
+    SELECT records.progress, demons.position, demons.requirement, records.player, demons.list
+    FROM records
+    INNER JOIN demons
+    ON demons.id = records.demon
+    INNER JOIN lists
+    ON lists.id = demons.list
+    WHERE records.status_ = 'APPROVED' AND (demons.position <= lists.list_size OR records.progress = 100)
+
+    UNION
+
+    SELECT 100, demons.position, demons.requirement, demons.verifier, demons.list
+    FROM demons;
+
+CREATE OR REPLACE FUNCTION score_of_player(player_id INTEGER, list_id INTEGER) RETURNS DOUBLE PRECISION AS $$
+    SELECT SUM(record_score(progress, position, lists.extended_list_size, requirement))
+    FROM score_giving
+    INNER JOIN lists
+            ON lists.id = score_giving.list
+    WHERE player = player_id AND list = list_id
+$$ LANGUAGE SQL;
+
+CREATE OR REPLACE FUNCTION recompute_player_scores() RETURNS void AS $$
+    DELETE FROM player_scores;
+
+    INSERT INTO player_scores (player, list, score)
+    SELECT player, list, SUM(record_score(progress, position, lists.extended_list_size, requirement))
+    FROM score_giving
+    INNER JOIN lists
+            ON lists.id = score_giving.list
+    GROUP BY player, list;
+
+    UPDATE players
+    SET score = coalesce(q.score, 0)
+    FROM players p
+        LEFT OUTER JOIN player_scores q
+        ON q.player = p.id AND q.list = 1
+    WHERE players.id = p.id;
+$$ LANGUAGE SQL;
+
+-- Nation and subdivision rankings are only computed for the main list
+
+CREATE OR REPLACE FUNCTION score_of_nation(iso_country_code VARCHAR(2)) RETURNS DOUBLE PRECISION AS $$
+    SELECT SUM(record_score(q.progress, q.position, lists.extended_list_size, q.requirement))
+    FROM (
+        SELECT DISTINCT ON (position) * from score_giving
+        INNER JOIN players
+                ON players.id=player
+        WHERE players.nationality = iso_country_code AND list = 1
+        ORDER BY position, progress DESC
+    ) q
+    INNER JOIN lists
+            ON lists.id = q.list
+$$ LANGUAGE SQL;
+
+CREATE OR REPLACE FUNCTION score_of_subdivision(iso_country_code VARCHAR(2), iso_code VARCHAR(3)) RETURNS DOUBLE PRECISION AS $$
+    SELECT SUM(record_score(q.progress, q.position, lists.extended_list_size, q.requirement))
+    FROM (
+        SELECT DISTINCT ON (position) * from score_giving
+        INNER JOIN players
+                ON players.id=player
+        WHERE players.nationality = iso_country_code
+          AND players.subdivision = iso_code
+          AND list = 1
+        ORDER BY position, progress DESC
+    ) q
+    INNER JOIN lists
+            ON lists.id = q.list
+$$ LANGUAGE SQL;
+
+CREATE OR REPLACE FUNCTION recompute_nation_scores() RETURNS void AS $$
+    UPDATE nationalities
+    SET score = COALESCE(p.sum, 0)
+    FROM nationalities n
+        LEFT OUTER JOIN (
+            SELECT nationality, SUM(record_score(q.progress, q.position, lists.extended_list_size, q.requirement))
+            FROM (
+                SELECT DISTINCT ON (position, nationality) * from score_giving
+                INNER JOIN players
+                        ON players.id=player
+                WHERE players.nationality IS NOT NULL AND list = 1
+                ORDER BY players.nationality, position, progress DESC
+            ) q
+            INNER JOIN lists
+                    ON lists.id = q.list
+            GROUP BY nationality
+        ) p
+        ON p.nationality = n.iso_country_code
+    WHERE n.iso_country_code = nationalities.iso_country_code
+$$ LANGUAGE SQL;
+
+CREATE OR REPLACE FUNCTION recompute_subdivision_scores() RETURNS void AS $$
+    UPDATE subdivisions
+    SET score = COALESCE(p.sum, 0)
+    FROM subdivisions s
+        LEFT OUTER JOIN (
+            SELECT nationality, subdivision, SUM(record_score(q.progress, q.position, lists.extended_list_size, q.requirement))
+            FROM (
+                SELECT DISTINCT ON (position, nationality, subdivision) * from score_giving
+                INNER JOIN players
+                        ON players.id=player
+                WHERE players.nationality IS NOT NULL
+                AND players.subdivision IS NOT NULL
+                AND list = 1
+                ORDER BY players.nationality, players.subdivision, position, progress DESC
+            ) q
+            INNER JOIN lists
+                    ON lists.id = q.list
+            GROUP BY nationality, subdivision
+        ) p
+        ON s.nation = p.nationality AND s.iso_code = p.subdivision
+    WHERE s.nation = subdivisions.nation
+      AND s.iso_code = subdivisions.iso_code
+$$ LANGUAGE SQL;
+
+SELECT recompute_player_scores();
+SELECT recompute_nation_scores();
+SELECT recompute_subdivision_scores();
+
+ALTER TABLE demon_modifications DROP COLUMN max_time;
+ALTER TABLE record_modifications DROP COLUMN completion_time;
+ALTER TABLE records DROP COLUMN completion_time;
+ALTER TABLE demons DROP COLUMN max_time;
+ALTER TABLE demons DROP COLUMN record_kind;
+
+DROP TYPE record_kind;
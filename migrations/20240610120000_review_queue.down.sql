@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP FUNCTION submitter_acceptance_rate(INTEGER);
+DROP TABLE review_locks;
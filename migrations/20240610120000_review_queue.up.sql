@@ -0,0 +1,22 @@
+-- Add up migration script here
+
+-- Records currently being reviewed by some list team member. Other reviewers will not be handed
+-- records locked by someone else when claiming the next record from the review queue.
+CREATE TABLE review_locks (
+    record INTEGER PRIMARY KEY REFERENCES records(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    reviewer INTEGER NOT NULL REFERENCES members(member_id) ON DELETE CASCADE,
+    locked_at TIMESTAMP WITHOUT TIME ZONE DEFAULT (NOW() AT TIME ZONE 'utc') NOT NULL,
+    -- NULL if the record was explicitly assigned to the reviewer by a list moderator. Such locks do
+    -- not expire and have to be released manually.
+    locked_until TIMESTAMP WITHOUT TIME ZONE NULL
+);
+
+CREATE INDEX review_locks_reviewer_idx ON review_locks(reviewer);
+
+-- The fraction of a submitter's decided submissions (i.e. those that were approved or rejected) that got
+-- approved. Submitters without any decided submissions have an acceptance rate of 0.
+CREATE FUNCTION submitter_acceptance_rate(submitter_id INTEGER) RETURNS DOUBLE PRECISION AS $$
+    SELECT COUNT(*) FILTER (WHERE status_ = 'APPROVED')::DOUBLE PRECISION / GREATEST(COUNT(*), 1)
+    FROM records
+    WHERE submitter = submitter_id AND status_ IN ('APPROVED', 'REJECTED')
+$$ LANGUAGE SQL STABLE;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP FUNCTION record_approvals(INTEGER);
+DROP TABLE record_approval_votes;
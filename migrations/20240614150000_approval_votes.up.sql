@@ -0,0 +1,24 @@
+-- Add up migration script here
+
+-- Votes list team members cast on whether a record should be approved. Like all other audit log
+-- tables, this table is append-only: If a reviewer changes their mind, they cast a new vote, and
+-- only their most recent one counts.
+CREATE TABLE record_approval_votes (
+    id INTEGER NOT NULL, -- the record voted on
+    approve BOOLEAN NOT NULL
+) INHERITS (audit_log2);
+
+CREATE INDEX record_approval_votes_id_idx ON record_approval_votes(id);
+
+-- The number of distinct list team members whose most recent vote on the given record is an
+-- approval. Only votes cast after the record was last unapproved count.
+CREATE FUNCTION record_approvals(record_id INTEGER) RETURNS BIGINT AS $$
+    SELECT COUNT(*)
+    FROM (
+        SELECT DISTINCT ON (userid) approve
+        FROM record_approval_votes
+        WHERE id = record_id AND time > COALESCE((SELECT MAX(time) FROM record_modifications WHERE id = record_id AND status_ = 'APPROVED'), '-infinity')
+        ORDER BY userid, time DESC, audit_id DESC
+    ) latest_votes
+    WHERE approve
+$$ LANGUAGE SQL STABLE;
@@ -0,0 +1,67 @@
+-- Add up migration script here
+
+-- Taxonomy of reasons for which records can get rejected. List moderators can add new reasons.
+CREATE TABLE rejection_reasons (
+    id SERIAL PRIMARY KEY,
+    name CITEXT NOT NULL UNIQUE CHECK (LENGTH(name) BETWEEN 1 AND 64),
+    description TEXT NULL DEFAULT NULL
+);
+
+INSERT INTO rejection_reasons (name, description) VALUES
+    ('No clicks', 'The video does not contain audible clicks'),
+    ('Spliced', 'The video consists of several attempts spliced together'),
+    ('Cheat indicator', 'The video shows signs of a hacked client or other modifications'),
+    ('Wrong level version', 'The record was achieved on a different version of the level');
+
+-- Only set if the record is rejected
+ALTER TABLE records ADD COLUMN rejection_reason INTEGER NULL DEFAULT NULL REFERENCES rejection_reasons(id) ON DELETE RESTRICT;
+
+ALTER TABLE record_modifications ADD COLUMN rejection_reason INTEGER NULL DEFAULT NULL;
+
+CREATE OR REPLACE FUNCTION audit_record_modification() RETURNS trigger AS $record_modification_trigger$
+    DECLARE
+        progress_change SMALLINT;
+        video_change VARCHAR(200);
+        status_change RECORD_STATUS;
+        player_change INT;
+        demon_change INTEGER;
+        completion_time_change BIGINT;
+        rejection_reason_change INTEGER;
+    BEGIN
+        if (OLD.progress <> NEW.progress) THEN
+            progress_change = OLD.progress;
+        END IF;
+
+        IF (OLD.video <> NEW.video) THEN
+            video_change = OLD.video;
+        END IF;
+
+        IF (OLD.status_ <> NEW.status_) THEN
+            status_change = OLD.status_;
+        END IF;
+
+        IF (OLD.player <> NEW.player) THEN
+            player_change = OLD.player;
+        END IF;
+
+        IF (OLD.demon <> NEW.demon) THEN
+            demon_change = OLD.demon;
+        END IF;
+
+        IF (OLD.completion_time IS DISTINCT FROM NEW.completion_time) THEN
+            completion_time_change = OLD.completion_time;
+        END IF;
+
+        IF (OLD.rejection_reason IS DISTINCT FROM NEW.rejection_reason) THEN
+            rejection_reason_change = OLD.rejection_reason;
+        END IF;
+
+        INSERT INTO record_modifications (userid, id, progress, video, status_, player, demon, completion_time, rejection_reason)
+            (SELECT id, NEW.id, progress_change, video_change, status_change, player_change, demon_change, completion_time_change,
+            rejection_reason_change
+            FROM active_user LIMIT 1);
+
+        RETURN NEW;
+    END;
+$record_modification_trigger$ LANGUAGE plpgsql;
+
@@ -0,0 +1,8 @@
+-- Add down migration script here
+
+INSERT INTO record_notes (record, content)
+    SELECT record, 'Raw footage: ' || url
+    FROM record_raw_footage
+    ORDER BY id;
+
+DROP TABLE record_raw_footage;
@@ -0,0 +1,22 @@
+-- Add up migration script here
+
+-- Raw footage for records. Only visible to list team members and the verified claimant of the record holder.
+CREATE TABLE record_raw_footage (
+    id SERIAL PRIMARY KEY,
+    record INTEGER NOT NULL REFERENCES records(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    url TEXT NOT NULL,
+    UNIQUE (record, url)
+);
+
+CREATE INDEX record_raw_footage_record_idx ON record_raw_footage(record);
+
+-- Up until now, raw footage was stored as a note of the form 'Raw footage: <url>'. Only notes
+-- consisting of nothing but such a link are converted, all other notes (for example ones that were
+-- edited to contain more text) are kept as they are.
+INSERT INTO record_raw_footage (record, url)
+    SELECT record, SUBSTRING(content FROM LENGTH('Raw footage: ') + 1)
+    FROM record_notes
+    WHERE content ~ '^Raw footage: \S+$'
+ON CONFLICT DO NOTHING;
+
+DELETE FROM record_notes WHERE content ~ '^Raw footage: \S+$';
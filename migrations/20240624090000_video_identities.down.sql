@@ -0,0 +1,9 @@
+-- Add down migration script here
+
+DROP INDEX records_video_identity_idx;
+
+ALTER TABLE records
+    DROP CONSTRAINT records_video_identity_check,
+    DROP COLUMN video_host,
+    DROP COLUMN video_id,
+    DROP COLUMN video_start;
@@ -0,0 +1,22 @@
+-- Add up migration script here
+
+-- The canonical identity of a record's video, see pointercrate_demonlist::video::VideoIdentity
+ALTER TABLE records
+    ADD COLUMN video_host TEXT NULL DEFAULT NULL,
+    ADD COLUMN video_id TEXT NULL DEFAULT NULL,
+    ADD COLUMN video_start INTEGER NULL DEFAULT NULL CHECK (video_start > 0),
+    ADD CONSTRAINT records_video_identity_check CHECK ((video_host IS NULL) = (video_id IS NULL));
+
+CREATE INDEX records_video_identity_idx ON records(video_host, video_id);
+
+-- Existing videos were normalized by the old validation code, so we only need to deal with its canonical formats
+UPDATE records SET video_host = 'youtube', video_id = SUBSTRING(video FROM '^https://www\.youtube\.com/watch\?v=([^&]+)$')
+    WHERE video ~ '^https://www\.youtube\.com/watch\?v=([^&]+)$';
+UPDATE records SET video_host = 'twitch', video_id = SUBSTRING(video FROM '^https://www\.twitch\.tv/videos/([^/?#]+)$')
+    WHERE video ~ '^https://www\.twitch\.tv/videos/([^/?#]+)$';
+UPDATE records SET video_host = 'everyplay', video_id = SUBSTRING(video FROM '^https://everyplay\.com/videos/([^/?#]+)$')
+    WHERE video ~ '^https://everyplay\.com/videos/([^/?#]+)$';
+UPDATE records SET video_host = 'bilibili', video_id = SUBSTRING(video FROM '^https://www\.bilibili\.com/video/([^/?#]+)$')
+    WHERE video ~ '^https://www\.bilibili\.com/video/([^/?#]+)$';
+UPDATE records SET video_host = 'vimeo', video_id = SUBSTRING(video FROM '^https://vimeo\.com/([^/?#]+)$')
+    WHERE video ~ '^https://vimeo\.com/([^/?#]+)$';
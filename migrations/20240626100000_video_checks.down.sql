@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE video_checks;
+DROP TYPE video_state;
@@ -0,0 +1,14 @@
+-- Add up migration script here
+
+CREATE TYPE video_state AS ENUM ('AVAILABLE', 'UNAVAILABLE', 'DEAD', 'UNKNOWN');
+
+-- History of availability checks on the videos of approved records and demons. Nothing is ever deleted based on these.
+CREATE TABLE video_checks (
+    id SERIAL PRIMARY KEY,
+    video VARCHAR(200) NOT NULL,
+    checked_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc'),
+    state video_state NOT NULL,
+    detail TEXT NULL
+);
+
+CREATE INDEX video_checks_video_idx ON video_checks(video, checked_at);
@@ -0,0 +1,13 @@
+-- Add up migration script here
+
+-- Thumbnails are now derived from the verification video by pointercrate itself, for all video hosts
+DROP TRIGGER demons_insert_set_thumbnail ON demons;
+DROP FUNCTION set_initial_thumbnail;
+
+-- Whether a list moderator manually set the thumbnail. Such thumbnails are never replaced automatically.
+ALTER TABLE demons ADD COLUMN thumbnail_overridden BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE demons
+SET thumbnail_overridden = TRUE
+WHERE thumbnail <> 'https://i.ytimg.com/vi/zebrafishes/mqdefault.jpg'
+  AND thumbnail IS DISTINCT FROM 'https://i.ytimg.com/vi/' || SUBSTRING(video FROM '%v=#"___________#"%' FOR '#') || '/mqdefault.jpg';
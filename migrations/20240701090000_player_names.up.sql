@@ -0,0 +1,24 @@
+-- Add up migration script here
+
+-- Names players were previously known under. Lookups of players by name also consider the ones that are not hidden.
+CREATE TABLE player_names (
+    id SERIAL PRIMARY KEY,
+    player INTEGER NOT NULL REFERENCES players(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    name CITEXT NOT NULL,
+    -- The time at which the player stopped using this name
+    used_until TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc'),
+    -- Hidden names are neither publicly associated with the player nor considered when looking them up by name
+    hidden BOOLEAN NOT NULL DEFAULT FALSE,
+    UNIQUE (player, name)
+);
+
+CREATE INDEX player_names_name_idx ON player_names(name);
+
+-- Recover previous names from the audit log, as far as possible
+INSERT INTO player_names (player, name, used_until)
+SELECT DISTINCT ON (player_modifications.id, player_modifications.name) player_modifications.id, player_modifications.name, player_modifications.time
+FROM player_modifications
+INNER JOIN players ON players.id = player_modifications.id
+WHERE player_modifications.name IS NOT NULL
+  AND player_modifications.name <> players.name
+ORDER BY player_modifications.id, player_modifications.name, player_modifications.time DESC;
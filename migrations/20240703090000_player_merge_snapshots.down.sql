@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE player_merge_records;
+DROP TABLE player_merges;
@@ -0,0 +1,39 @@
+-- Add up migration script here
+
+-- Snapshots of everything a player merge changed, allowing the merge to be undone
+CREATE TABLE player_merges (
+    id SERIAL PRIMARY KEY,
+    -- The player that remained after the merge
+    player INTEGER NOT NULL REFERENCES players(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    -- The row of the player that was merged into `player` (and deleted), as it was right before the merge
+    merged JSONB NOT NULL,
+    -- Demons whose creator entries were transferred from the merged player
+    created INTEGER[] NOT NULL,
+    -- Demons on which both players were creators, and whose creator entry for the merged player was deleted
+    co_created INTEGER[] NOT NULL,
+    verified INTEGER[] NOT NULL,
+    published INTEGER[] NOT NULL,
+    -- The rows of all claims on either player
+    claims JSONB NOT NULL,
+    -- The player_names entries transferred from the merged player
+    aliases INTEGER[] NOT NULL,
+    -- Earlier merges into the merged player, which were transferred over to `player` so they can still be undone
+    merges INTEGER[] NOT NULL,
+    merged_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc'),
+    undone_at TIMESTAMP WITHOUT TIME ZONE NULL
+);
+
+CREATE INDEX player_merges_player_idx ON player_merges(player);
+
+-- The records of either player at the time of a merge
+CREATE TABLE player_merge_records (
+    merge INTEGER NOT NULL REFERENCES player_merges(id) ON DELETE CASCADE,
+    record INTEGER NOT NULL,
+    -- Whether this record belonged to the merged player
+    from_merged BOOLEAN NOT NULL,
+    -- The row of the record, as it was right before the merge
+    snapshot JSONB NOT NULL,
+    notes INTEGER[] NOT NULL,
+    raw_footage TEXT[] NOT NULL,
+    PRIMARY KEY (merge, record)
+);
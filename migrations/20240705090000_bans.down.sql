@@ -0,0 +1,6 @@
+-- Add down migration script here
+
+ALTER TABLE player_merges DROP COLUMN bans;
+
+DROP TABLE submitter_bans;
+DROP TABLE player_bans;
@@ -0,0 +1,40 @@
+-- Add up migration script here
+
+-- History of all bans of players. The `banned` column of the players table reflects whether a ban is currently active.
+CREATE TABLE player_bans (
+    id SERIAL PRIMARY KEY,
+    player INTEGER NOT NULL REFERENCES players(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    reason TEXT NULL,
+    -- The member who issued the ban, taken from the active_user table
+    issued_by INTEGER NULL,
+    issued_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc'),
+    -- NULL for permanent bans
+    expires_at TIMESTAMP WITHOUT TIME ZONE NULL,
+    lifted_at TIMESTAMP WITHOUT TIME ZONE NULL,
+    -- NULL if the ban lifted automatically because it expired
+    lifted_by INTEGER NULL,
+    -- The records that were approved before the ban, and were rejected because of it
+    rejected_records INTEGER[] NOT NULL DEFAULT '{}'
+);
+
+CREATE UNIQUE INDEX player_bans_active ON player_bans(player) WHERE lifted_at IS NULL;
+
+-- The bans of the merged player, which get deleted together with it. Restored when the merge is undone.
+ALTER TABLE player_merges ADD COLUMN bans JSONB NOT NULL DEFAULT '[]';
+
+CREATE TABLE submitter_bans (
+    id SERIAL PRIMARY KEY,
+    submitter INTEGER NOT NULL REFERENCES submitters(submitter_id) ON DELETE CASCADE ON UPDATE CASCADE,
+    reason TEXT NULL,
+    issued_by INTEGER NULL,
+    issued_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc'),
+    expires_at TIMESTAMP WITHOUT TIME ZONE NULL,
+    lifted_at TIMESTAMP WITHOUT TIME ZONE NULL,
+    lifted_by INTEGER NULL
+);
+
+CREATE UNIQUE INDEX submitter_bans_active ON submitter_bans(submitter) WHERE lifted_at IS NULL;
+
+-- We know neither why nor since when existing bans are in place
+INSERT INTO player_bans (player) SELECT id FROM players WHERE banned;
+INSERT INTO submitter_bans (submitter) SELECT submitter_id FROM submitters WHERE banned;
@@ -0,0 +1,19 @@
+-- Add down migration script here
+
+CREATE FUNCTION submitter_acceptance_rate(submitter_id INTEGER) RETURNS DOUBLE PRECISION AS $$
+    SELECT COUNT(*) FILTER (WHERE status_ = 'APPROVED')::DOUBLE PRECISION / GREATEST(COUNT(*), 1)
+    FROM records
+    WHERE submitter = submitter_id AND status_ IN ('APPROVED', 'REJECTED')
+$$ LANGUAGE SQL STABLE;
+
+DROP TRIGGER record_submitter_statistics_trigger ON records;
+DROP FUNCTION record_submitter_statistics();
+
+ALTER TABLE submitters
+    DROP COLUMN reputation,
+    DROP COLUMN submitted,
+    DROP COLUMN approved,
+    DROP COLUMN rejected,
+    DROP COLUMN deleted_invalid,
+    DROP COLUMN flagged,
+    DROP COLUMN flag_cleared;
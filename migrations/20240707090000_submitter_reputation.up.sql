@@ -0,0 +1,53 @@
+-- Add up migration script here
+
+-- Statistics about how the submissions of each submitter fared in review. These are only ever incremented, so records
+-- being deleted later on (for example because a better record superseded them) do not affect them.
+ALTER TABLE submitters
+    ADD COLUMN submitted INTEGER NOT NULL DEFAULT 0,
+    -- Submissions that left the review queue by being approved
+    ADD COLUMN approved INTEGER NOT NULL DEFAULT 0,
+    -- Submissions that left the review queue by being rejected
+    ADD COLUMN rejected INTEGER NOT NULL DEFAULT 0,
+    -- Submissions that were deleted because their video turned out to be invalid
+    ADD COLUMN deleted_invalid INTEGER NOT NULL DEFAULT 0,
+    -- Set when the submitter's rejection rate crosses the configured threshold, until a moderator clears it
+    ADD COLUMN flagged BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Set once a moderator cleared the flag, after which the submitter is no longer flagged automatically
+    ADD COLUMN flag_cleared BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- The share of reviewed submissions that were approved, smoothed so that submitters without any reviewed submissions
+-- start out at 0.5
+ALTER TABLE submitters ADD COLUMN reputation DOUBLE PRECISION GENERATED ALWAYS AS
+    ((approved + 1)::DOUBLE PRECISION / (approved + rejected + deleted_invalid + 2)) STORED;
+
+-- We do not know which records went through the review queue, so we just count all of them
+UPDATE submitters SET
+    submitted = (SELECT COUNT(*) FROM records WHERE records.submitter = submitters.submitter_id),
+    approved = (SELECT COUNT(*) FROM records WHERE records.submitter = submitters.submitter_id AND status_ = 'APPROVED'),
+    rejected = (SELECT COUNT(*) FROM records WHERE records.submitter = submitters.submitter_id AND status_ = 'REJECTED');
+
+CREATE FUNCTION record_submitter_statistics() RETURNS trigger AS $record_submitter_statistics$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        UPDATE submitters SET submitted = submitted + 1 WHERE submitter_id = NEW.submitter;
+    -- Records added by list moderators are inserted as submitted and have their status changed in the same transaction.
+    -- They never went through the review queue, so they do not count towards the submitter's statistics.
+    ELSIF OLD.status_ = 'SUBMITTED' AND NEW.status_ IN ('APPROVED', 'REJECTED') AND EXISTS(
+        SELECT 1 FROM record_additions WHERE record_additions.id = NEW.id AND record_additions.xmin = pg_current_xact_id()::xid
+    ) THEN
+        UPDATE submitters SET submitted = submitted - 1 WHERE submitter_id = NEW.submitter;
+    ELSIF OLD.status_ IN ('SUBMITTED', 'UNDER_CONSIDERATION') AND NEW.status_ = 'APPROVED' THEN
+        UPDATE submitters SET approved = approved + 1 WHERE submitter_id = NEW.submitter;
+    ELSIF OLD.status_ IN ('SUBMITTED', 'UNDER_CONSIDERATION') AND NEW.status_ = 'REJECTED' THEN
+        UPDATE submitters SET rejected = rejected + 1 WHERE submitter_id = NEW.submitter;
+    END IF;
+
+    RETURN NULL;
+END;
+$record_submitter_statistics$ LANGUAGE plpgsql;
+
+CREATE TRIGGER record_submitter_statistics_trigger AFTER INSERT OR UPDATE OF status_ ON records
+    FOR EACH ROW EXECUTE PROCEDURE record_submitter_statistics();
+
+-- Superseded by submitters.reputation
+DROP FUNCTION submitter_acceptance_rate(INTEGER);
@@ -0,0 +1,7 @@
+-- Add down migration script here
+
+DROP TABLE submitter_range_bans;
+
+DROP INDEX submitters_ip_address_idx;
+
+-- The full addresses of merged submitters are lost, so they stay merged. The prefix is kept as the submitter's address.
@@ -0,0 +1,75 @@
+-- Add up migration script here
+
+-- IPv6 submitters are now identified by the /64 prefix of their address (the default value of SUBMITTER_IPV6_PREFIX_LENGTH),
+-- instead of by their full address. Submitters whose addresses share a prefix are merged into the oldest one among them.
+CREATE TEMPORARY TABLE submitter_prefixes AS
+    SELECT submitter_id, network(set_masklen(ip_address, 64))::inet AS prefix
+    FROM submitters
+    WHERE family(ip_address) = 6;
+
+CREATE TEMPORARY TABLE submitter_merges AS
+    SELECT submitter_id, prefix, MIN(submitter_id) OVER (PARTITION BY prefix) AS merged_into
+    FROM submitter_prefixes;
+
+-- Each submitter can only have one active ban, so of the active bans in a merged group only the most recent one is kept
+UPDATE submitter_bans SET lifted_at = (NOW() AT TIME ZONE 'utc')
+WHERE lifted_at IS NULL
+  AND submitter IN (SELECT submitter_id FROM submitter_merges)
+  AND id NOT IN (
+    SELECT DISTINCT ON (merged_into) submitter_bans.id
+    FROM submitter_bans
+    INNER JOIN submitter_merges ON submitter_merges.submitter_id = submitter_bans.submitter
+    WHERE lifted_at IS NULL
+    ORDER BY merged_into, issued_at DESC, submitter_bans.id DESC
+  );
+
+UPDATE submitter_bans SET submitter = merged_into
+FROM submitter_merges
+WHERE submitter_merges.submitter_id = submitter_bans.submitter AND merged_into <> submitter_merges.submitter_id;
+
+UPDATE records SET submitter = merged_into
+FROM submitter_merges
+WHERE submitter_merges.submitter_id = records.submitter AND merged_into <> submitter_merges.submitter_id;
+
+UPDATE submitters SET
+    ip_address = merged.prefix,
+    banned = merged.banned,
+    flagged = merged.flagged,
+    flag_cleared = merged.flag_cleared,
+    submitted = merged.submitted,
+    approved = merged.approved,
+    rejected = merged.rejected,
+    deleted_invalid = merged.deleted_invalid
+FROM (
+    SELECT merged_into, prefix, bool_or(banned) AS banned, bool_or(flagged) AS flagged, bool_or(flag_cleared) AS flag_cleared,
+           SUM(submitted) AS submitted,
+           SUM(approved) AS approved, SUM(rejected) AS rejected, SUM(deleted_invalid) AS deleted_invalid
+    FROM submitters
+    INNER JOIN submitter_merges ON submitter_merges.submitter_id = submitters.submitter_id
+    GROUP BY merged_into, prefix
+) merged
+WHERE submitters.submitter_id = merged.merged_into;
+
+DELETE FROM submitters WHERE submitter_id IN (SELECT submitter_id FROM submitter_merges WHERE merged_into <> submitter_id);
+
+DROP TABLE submitter_merges;
+DROP TABLE submitter_prefixes;
+
+-- Submitters are looked up by the network containing an address, so that changing SUBMITTER_IPV6_PREFIX_LENGTH later on
+-- does not split existing submitters
+CREATE INDEX submitters_ip_address_idx ON submitters USING gist (ip_address inet_ops);
+
+-- Bans of entire address ranges, preventing submissions from any address in the range (even from submitters that do not
+-- exist yet)
+CREATE TABLE submitter_range_bans (
+    id SERIAL PRIMARY KEY,
+    range CIDR NOT NULL,
+    reason TEXT NOT NULL,
+    issued_by INTEGER NULL,
+    issued_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc'),
+    expires_at TIMESTAMP WITHOUT TIME ZONE NULL,
+    lifted_at TIMESTAMP WITHOUT TIME ZONE NULL,
+    lifted_by INTEGER NULL
+);
+
+CREATE INDEX submitter_range_bans_active ON submitter_range_bans USING gist (range inet_ops) WHERE lifted_at IS NULL;
@@ -0,0 +1 @@
+ALTER TABLE player_claims DROP COLUMN verification_code;
@@ -0,0 +1,9 @@
+-- Code a claimant places on their Geometry Dash profile (or in one of their levels) to verify their claim without
+-- needing to contact a list moderator. The volatile default is evaluated per row, so existing claims get distinct codes.
+ALTER TABLE player_claims ADD COLUMN verification_code TEXT NOT NULL DEFAULT ('pointercrate-' || substr(md5(random()::text || clock_timestamp()::text), 1, 10));
+
+-- Merge snapshots restore claims from their JSON representation, which thus needs to contain a code as well
+UPDATE player_merges SET claims = (
+    SELECT COALESCE(jsonb_agg(claim || jsonb_build_object('verification_code', 'pointercrate-' || substr(md5(random()::text || clock_timestamp()::text), 1, 10))), '[]')
+    FROM jsonb_array_elements(claims) AS claim
+);
@@ -0,0 +1,18 @@
+-- Add down migration script here
+
+ALTER TABLE player_merges DROP COLUMN disputes;
+
+DROP TABLE claim_disputes;
+DROP TYPE CLAIM_DISPUTE_RESOLUTION;
+
+DROP TRIGGER player_claim_deletion_trigger ON player_claims;
+DROP FUNCTION audit_player_claim_deletion();
+DROP TABLE player_claim_deletions;
+
+DROP TRIGGER player_claim_modification_trigger ON player_claims;
+DROP FUNCTION audit_player_claim_modification();
+DROP TABLE player_claim_modifications;
+
+DROP TRIGGER player_claim_addition_trigger ON player_claims;
+DROP FUNCTION audit_player_claim_addition();
+DROP TABLE player_claim_additions;
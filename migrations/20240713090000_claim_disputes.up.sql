@@ -0,0 +1,115 @@
+-- Add up migration script here
+
+-- Audit log for player claims. Since claims are deleted (and re-created) whenever they change hands, every entry
+-- records the claimant and the claimed player, so that the full claim history of a player can be reconstructed.
+CREATE TABLE player_claim_additions (
+    id INTEGER NOT NULL, -- REFERENCES player_claims(id)
+    member_id INTEGER NOT NULL, -- REFERENCES members(member_id)
+    player_id INTEGER NOT NULL -- REFERENCES players(id)
+) INHERITS (audit_log2);
+
+CREATE FUNCTION audit_player_claim_addition() RETURNS trigger AS $player_claim_addition_trigger$
+    BEGIN
+        INSERT INTO player_claim_additions (userid, id, member_id, player_id)
+            (SELECT id, NEW.id, NEW.member_id, NEW.player_id FROM active_user LIMIT 1);
+
+        RETURN NEW;
+    END;
+$player_claim_addition_trigger$ LANGUAGE plpgsql;
+
+CREATE TRIGGER player_claim_addition_trigger AFTER INSERT ON player_claims FOR EACH ROW EXECUTE PROCEDURE audit_player_claim_addition();
+
+-- Like all other modification tables, the 'verified', 'lock_submissions' and 'player' columns hold the values from
+-- before the modification, and are NULL if unchanged. 'player_id' is the claimed player after the modification.
+CREATE TABLE player_claim_modifications (
+    id INTEGER NOT NULL, -- REFERENCES player_claims(id)
+    member_id INTEGER NOT NULL, -- REFERENCES members(member_id)
+    player_id INTEGER NOT NULL, -- REFERENCES players(id)
+
+    verified BOOLEAN NULL,
+    lock_submissions BOOLEAN NULL,
+    player INTEGER NULL -- REFERENCES players(id)
+) INHERITS (audit_log2);
+
+CREATE FUNCTION audit_player_claim_modification() RETURNS trigger AS $player_claim_modification_trigger$
+    DECLARE
+        verified_change BOOLEAN;
+        lock_submissions_change BOOLEAN;
+        player_change INTEGER;
+    BEGIN
+        IF (OLD.verified <> NEW.verified) THEN
+            verified_change = OLD.verified;
+        END IF;
+
+        IF (OLD.lock_submissions <> NEW.lock_submissions) THEN
+            lock_submissions_change = OLD.lock_submissions;
+        END IF;
+
+        IF (OLD.player_id <> NEW.player_id) THEN
+            player_change = OLD.player_id;
+        END IF;
+
+        -- Changes to the verification code are not interesting
+        IF (verified_change IS NULL AND lock_submissions_change IS NULL AND player_change IS NULL) THEN
+            RETURN NEW;
+        END IF;
+
+        INSERT INTO player_claim_modifications (userid, id, member_id, player_id, verified, lock_submissions, player)
+            (SELECT id, NEW.id, NEW.member_id, NEW.player_id, verified_change, lock_submissions_change, player_change
+            FROM active_user LIMIT 1);
+
+        RETURN NEW;
+    END;
+$player_claim_modification_trigger$ LANGUAGE plpgsql;
+
+CREATE TRIGGER player_claim_modification_trigger AFTER UPDATE ON player_claims FOR EACH ROW EXECUTE PROCEDURE audit_player_claim_modification();
+
+CREATE TABLE player_claim_deletions (
+    id INTEGER NOT NULL, -- REFERENCES player_claims(id)
+    member_id INTEGER NOT NULL, -- REFERENCES members(member_id)
+    player_id INTEGER NOT NULL, -- REFERENCES players(id)
+
+    -- Whether the claim was verified at the time of deletion
+    verified BOOLEAN NOT NULL
+) INHERITS (audit_log2);
+
+CREATE FUNCTION audit_player_claim_deletion() RETURNS trigger AS $player_claim_deletion_trigger$
+    BEGIN
+        INSERT INTO player_claim_deletions (userid, id, member_id, player_id, verified)
+            (SELECT id, OLD.id, OLD.member_id, OLD.player_id, OLD.verified FROM active_user LIMIT 1);
+
+        RETURN NULL;
+    END;
+$player_claim_deletion_trigger$ LANGUAGE plpgsql;
+
+CREATE TRIGGER player_claim_deletion_trigger AFTER DELETE ON player_claims FOR EACH ROW EXECUTE PROCEDURE audit_player_claim_deletion();
+
+CREATE INDEX player_claim_additions_player_id_idx ON player_claim_additions(player_id);
+CREATE INDEX player_claim_modifications_player_id_idx ON player_claim_modifications(player_id);
+CREATE INDEX player_claim_deletions_player_id_idx ON player_claim_deletions(player_id);
+
+-- 'TRANSFERRED' gives the disputing user a verified claim on the player, 'REVOKED' removes the existing claim without
+-- transferring it, and 'REJECTED' leaves the existing claim in place.
+CREATE TYPE CLAIM_DISPUTE_RESOLUTION AS ENUM ('TRANSFERRED', 'REJECTED', 'REVOKED');
+
+-- Disputes follow the player they were opened on, meaning merging a player away deletes its disputes (just like its bans)
+CREATE TABLE claim_disputes (
+    id SERIAL PRIMARY KEY,
+    player_id INTEGER NOT NULL REFERENCES players(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    -- The user contesting the claim
+    disputed_by INTEGER NOT NULL REFERENCES members(member_id) ON DELETE CASCADE,
+    -- The user holding the verified claim at the time the dispute was opened
+    holder INTEGER NULL,
+    evidence TEXT NOT NULL,
+    created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc'),
+    -- NULL while the dispute is open
+    resolution CLAIM_DISPUTE_RESOLUTION NULL,
+    resolution_reason TEXT NULL,
+    resolved_at TIMESTAMP WITHOUT TIME ZONE NULL,
+    resolved_by INTEGER NULL
+);
+
+CREATE UNIQUE INDEX claim_disputes_open ON claim_disputes(player_id, disputed_by) WHERE resolution IS NULL;
+
+-- The disputes opened on the merged player, which get deleted together with it. Restored when the merge is undone.
+ALTER TABLE player_merges ADD COLUMN disputes JSONB NOT NULL DEFAULT '[]';
@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+DROP TABLE claim_suggestions;
+DROP TYPE SUGGESTION_STATUS;
+DROP TYPE SUGGESTED_EDIT;
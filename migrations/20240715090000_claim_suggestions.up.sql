@@ -0,0 +1,28 @@
+-- Add up migration script here
+
+CREATE TYPE SUGGESTED_EDIT AS ENUM ('NAME', 'SUBDIVISION', 'RECORD_VIDEO', 'RECORD_REMOVAL');
+CREATE TYPE SUGGESTION_STATUS AS ENUM ('PENDING', 'APPROVED', 'REJECTED');
+
+-- Edits verified claimants propose for their claimed player. Which of the 'name', 'subdivision', 'record_id' and
+-- 'video' columns are set depends on the kind of edit.
+CREATE TABLE claim_suggestions (
+    id SERIAL PRIMARY KEY,
+    player_id INTEGER NOT NULL REFERENCES players(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    suggested_by INTEGER NOT NULL REFERENCES members(member_id) ON DELETE CASCADE,
+    kind SUGGESTED_EDIT NOT NULL,
+    name TEXT NULL,
+    -- NULL for suggestions to remove the subdivision
+    subdivision VARCHAR(3) NULL,
+    record_id INTEGER NULL REFERENCES records(id) ON DELETE CASCADE,
+    video TEXT NULL,
+    -- Optional explanation by the claimant
+    comment TEXT NULL,
+    created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT (NOW() AT TIME ZONE 'utc'),
+    status SUGGESTION_STATUS NOT NULL DEFAULT 'PENDING',
+    reviewed_at TIMESTAMP WITHOUT TIME ZONE NULL,
+    reviewed_by INTEGER NULL,
+    -- Optional explanation by the reviewing moderator
+    review_note TEXT NULL
+);
+
+CREATE INDEX claim_suggestions_pending ON claim_suggestions(created_at) WHERE status = 'PENDING';
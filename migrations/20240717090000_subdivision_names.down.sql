@@ -0,0 +1,2 @@
+ALTER TABLE subdivisions DROP CONSTRAINT subdivisions_nation_name_key;
+ALTER TABLE subdivisions ADD CONSTRAINT subdivisions_name_key UNIQUE (name);
@@ -0,0 +1,5 @@
+-- Subdivision names only need to be unique within their nation (e.g. both Brazil and Colombia have
+-- a subdivision called "Amazonas"), which matters once subdivisions of more nations are added via
+-- the nationality synchronization tool
+ALTER TABLE subdivisions DROP CONSTRAINT subdivisions_name_key;
+ALTER TABLE subdivisions ADD CONSTRAINT subdivisions_nation_name_key UNIQUE (nation, name);
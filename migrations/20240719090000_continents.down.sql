@@ -0,0 +1,63 @@
+DROP FUNCTION best_records_in_continent(INTEGER);
+DROP FUNCTION recompute_continent_scores();
+DROP FUNCTION score_of_continent(INTEGER);
+
+DROP VIEW ranked_continents;
+DROP VIEW ranked_nations;
+DROP VIEW ranked_players;
+DROP VIEW list_ranked_players;
+
+CREATE TYPE continent AS ENUM ('Asia', 'Europe', 'Australia and Oceania', 'Africa', 'North America', 'South America', 'Central America');
+
+ALTER TABLE nationalities ADD COLUMN continent_enum continent;
+
+UPDATE nationalities
+SET continent_enum = continents.name::TEXT::continent
+FROM continents
+WHERE continents.id = nationalities.continent;
+
+ALTER TABLE nationalities DROP COLUMN continent;
+ALTER TABLE nationalities RENAME COLUMN continent_enum TO continent;
+ALTER TABLE nationalities ALTER COLUMN continent SET NOT NULL;
+
+DROP TABLE continents;
+
+CREATE VIEW ranked_nations AS
+    SELECT
+        ROW_NUMBER() OVER(ORDER BY score DESC, iso_country_code) AS index,
+        RANK() OVER(ORDER BY score DESC) AS rank,
+        score,
+        iso_country_code,
+        nation,
+        continent
+    FROM nationalities
+    WHERE score > 0.0;
+
+CREATE VIEW ranked_players AS
+    SELECT
+        ROW_NUMBER() OVER(ORDER BY players.score DESC, players.id) AS index,
+        RANK() OVER (ORDER BY players.score DESC) AS rank,
+        players.id, players.name, players.score, players.subdivision,
+        nationalities.iso_country_code,
+        nationalities.nation,
+        nationalities.continent
+    FROM players
+    LEFT OUTER JOIN nationalities
+                 ON players.nationality = nationalities.iso_country_code
+    WHERE NOT players.banned AND players.score > 0.0;
+
+CREATE VIEW list_ranked_players AS
+    SELECT
+        ROW_NUMBER() OVER(PARTITION BY player_scores.list ORDER BY player_scores.score DESC, players.id) AS index,
+        RANK() OVER (PARTITION BY player_scores.list ORDER BY player_scores.score DESC) AS rank,
+        player_scores.list,
+        players.id, players.name, player_scores.score, players.subdivision,
+        nationalities.iso_country_code,
+        nationalities.nation,
+        nationalities.continent
+    FROM player_scores
+    INNER JOIN players
+            ON players.id = player_scores.player
+    LEFT OUTER JOIN nationalities
+                 ON players.nationality = nationalities.iso_country_code
+    WHERE NOT players.banned AND player_scores.score > 0.0;
@@ -0,0 +1,137 @@
+-- Continents used to be a postgres enum mirrored by a rust enum. They are now stored in a table of
+-- their own, so that they can be ranked (and added/renamed without touching any code)
+CREATE TABLE continents (
+    id SERIAL PRIMARY KEY,
+    name CITEXT NOT NULL UNIQUE,
+    score DOUBLE PRECISION NOT NULL DEFAULT 0.0
+);
+
+INSERT INTO continents (name)
+VALUES ('Asia'), ('Europe'), ('Australia and Oceania'), ('Africa'), ('North America'), ('South America'), ('Central America');
+
+DROP VIEW ranked_nations;
+DROP VIEW ranked_players;
+DROP VIEW list_ranked_players;
+
+ALTER TABLE nationalities ADD COLUMN continent_id INTEGER REFERENCES continents(id);
+
+UPDATE nationalities
+SET continent_id = continents.id
+FROM continents
+WHERE continents.name = nationalities.continent::TEXT;
+
+ALTER TABLE nationalities DROP COLUMN continent;
+ALTER TABLE nationalities RENAME COLUMN continent_id TO continent;
+ALTER TABLE nationalities ALTER COLUMN continent SET NOT NULL;
+
+DROP TYPE continent;
+
+CREATE VIEW ranked_nations AS
+    SELECT
+        ROW_NUMBER() OVER(ORDER BY score DESC, iso_country_code) AS index,
+        RANK() OVER(ORDER BY score DESC) AS rank,
+        score,
+        iso_country_code,
+        nation,
+        continent
+    FROM nationalities
+    WHERE score > 0.0;
+
+CREATE VIEW ranked_players AS
+    SELECT
+        ROW_NUMBER() OVER(ORDER BY players.score DESC, players.id) AS index,
+        RANK() OVER (ORDER BY players.score DESC) AS rank,
+        players.id, players.name, players.score, players.subdivision,
+        nationalities.iso_country_code,
+        nationalities.nation,
+        nationalities.continent
+    FROM players
+    LEFT OUTER JOIN nationalities
+                 ON players.nationality = nationalities.iso_country_code
+    WHERE NOT players.banned AND players.score > 0.0;
+
+CREATE VIEW list_ranked_players AS
+    SELECT
+        ROW_NUMBER() OVER(PARTITION BY player_scores.list ORDER BY player_scores.score DESC, players.id) AS index,
+        RANK() OVER (PARTITION BY player_scores.list ORDER BY player_scores.score DESC) AS rank,
+        player_scores.list,
+        players.id, players.name, player_scores.score, players.subdivision,
+        nationalities.iso_country_code,
+        nationalities.nation,
+        nationalities.continent
+    FROM player_scores
+    INNER JOIN players
+            ON players.id = player_scores.player
+    LEFT OUTER JOIN nationalities
+                 ON players.nationality = nationalities.iso_country_code
+    WHERE NOT players.banned AND player_scores.score > 0.0;
+
+-- Unlike the nation ranking, every continent is ranked, even without any score
+CREATE VIEW ranked_continents AS
+    SELECT
+        RANK() OVER(ORDER BY score DESC) AS rank,
+        id,
+        name,
+        score
+    FROM continents;
+
+-- Same as score_of_nation, counting one best record per demon for the entire continent
+CREATE FUNCTION score_of_continent(continent_id INTEGER) RETURNS DOUBLE PRECISION AS $$
+    SELECT SUM(record_score(q.progress, q.position, lists.extended_list_size, q.requirement) * q.time_factor)
+    FROM (
+        SELECT DISTINCT ON (position) * from score_giving
+        INNER JOIN players
+                ON players.id=player
+        INNER JOIN nationalities
+                ON nationalities.iso_country_code = players.nationality
+        WHERE nationalities.continent = continent_id AND list = 1
+        ORDER BY position, progress DESC, time_factor DESC
+    ) q
+    INNER JOIN lists
+            ON lists.id = q.list
+$$ LANGUAGE SQL;
+
+CREATE FUNCTION recompute_continent_scores() RETURNS void AS $$
+    UPDATE continents
+    SET score = COALESCE(p.sum, 0)
+    FROM continents c
+        LEFT OUTER JOIN (
+            SELECT continent, SUM(record_score(q.progress, q.position, lists.extended_list_size, q.requirement) * q.time_factor)
+            FROM (
+                SELECT DISTINCT ON (position, nationalities.continent) * from score_giving
+                INNER JOIN players
+                        ON players.id=player
+                INNER JOIN nationalities
+                        ON nationalities.iso_country_code = players.nationality
+                WHERE list = 1
+                ORDER BY nationalities.continent, position, progress DESC, time_factor DESC
+            ) q
+            INNER JOIN lists
+                    ON lists.id = q.list
+            GROUP BY continent
+        ) p
+        ON p.continent = c.id
+    WHERE c.id = continents.id
+$$ LANGUAGE SQL;
+
+CREATE FUNCTION best_records_in_continent(continent_id INTEGER)
+    RETURNS TABLE (id INTEGER, progress SMALLINT, player INTEGER, demon INTEGER)
+    AS
+$body$
+    WITH grp AS (
+        SELECT records.id, records.progress, records.player, records.demon,
+               RANK() OVER (PARTITION BY demon ORDER BY demon, progress DESC) AS rk
+        FROM records
+        INNER JOIN players
+        ON players.id = player
+        INNER JOIN nationalities
+        ON nationalities.iso_country_code = players.nationality
+        WHERE status_='APPROVED' AND nationalities.continent = continent_id
+    )
+    SELECT id, progress, player, demon
+    FROM grp
+    WHERE rk = 1;
+$body$
+LANGUAGE SQL;
+
+SELECT recompute_continent_scores();
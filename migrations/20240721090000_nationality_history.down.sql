@@ -0,0 +1,12 @@
+DROP FUNCTION best_records_in(VARCHAR(2), BOOLEAN);
+DROP VIEW ranked_nations_at_approval;
+DROP VIEW score_giving_at_approval;
+DROP TRIGGER record_status_since_trigger ON records;
+DROP FUNCTION track_record_status_since();
+ALTER TABLE records DROP COLUMN status_since;
+DROP INDEX record_additions_id_idx;
+DROP INDEX record_modifications_id_idx;
+DROP FUNCTION nationality_at(INTEGER, TIMESTAMP WITHOUT TIME ZONE);
+DROP TRIGGER player_nationality_history_trigger ON players;
+DROP FUNCTION track_player_nationality();
+DROP TABLE player_nationality_history;
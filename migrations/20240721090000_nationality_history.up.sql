@@ -0,0 +1,162 @@
+-- Every nationality (and subdivision) a player held, together with the time they started holding it.
+-- A row with NULL nationality means the player's nationality was unset at that time, a row with NULL
+-- since means the player held the nationality since before history was tracked.
+CREATE TABLE player_nationality_history (
+    id SERIAL PRIMARY KEY,
+    player INTEGER NOT NULL REFERENCES players(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    nationality VARCHAR(2) REFERENCES nationalities(iso_country_code),
+    subdivision VARCHAR(3),
+    since TIMESTAMP WITHOUT TIME ZONE DEFAULT (NOW() AT TIME ZONE 'utc')
+);
+
+CREATE INDEX player_nationality_history_player_since_idx ON player_nationality_history (player, since);
+
+-- We do not know since when players have held their current nationality, so assume they always did
+INSERT INTO player_nationality_history (player, nationality, subdivision, since)
+SELECT id, nationality, subdivision, NULL
+FROM players
+WHERE nationality IS NOT NULL;
+
+CREATE FUNCTION track_player_nationality() RETURNS trigger AS $$
+BEGIN
+    IF (TG_OP = 'INSERT' AND NEW.nationality IS NULL) THEN
+        RETURN NEW;
+    END IF;
+
+    IF (TG_OP = 'UPDATE' AND OLD.nationality IS NOT DISTINCT FROM NEW.nationality AND OLD.subdivision IS NOT DISTINCT FROM NEW.subdivision) THEN
+        RETURN NEW;
+    END IF;
+
+    INSERT INTO player_nationality_history (player, nationality, subdivision)
+    VALUES (NEW.id, NEW.nationality, NEW.subdivision);
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER player_nationality_history_trigger AFTER INSERT OR UPDATE OF nationality, subdivision ON players FOR EACH ROW EXECUTE PROCEDURE track_player_nationality();
+
+-- The nationality (and subdivision) the given player held at the given time. A NULL time stands for
+-- some time before history was tracked (such as the approval of records predating the audit log), for
+-- which the nationality held when tracking started is returned. Returns no rows if the player had no
+-- nationality at that time.
+CREATE FUNCTION nationality_at(player_id INTEGER, at TIMESTAMP WITHOUT TIME ZONE)
+    RETURNS TABLE (nationality VARCHAR(2), subdivision VARCHAR(3))
+    AS
+$body$
+    SELECT held.nationality, held.subdivision
+    FROM (
+        SELECT history.nationality, history.subdivision
+        FROM player_nationality_history history
+        WHERE history.player = player_id AND (history.since IS NULL OR history.since <= at)
+        ORDER BY history.since DESC NULLS LAST, history.id DESC
+        LIMIT 1
+    ) held
+    WHERE held.nationality IS NOT NULL
+$body$
+LANGUAGE SQL STABLE;
+
+CREATE INDEX record_modifications_id_idx ON record_modifications (id);
+CREATE INDEX record_additions_id_idx ON record_additions (id);
+
+-- The time each record was last changed into its current status (for approved records, the time of
+-- their approval). NULL for records predating the audit log.
+ALTER TABLE records ADD COLUMN status_since TIMESTAMP WITHOUT TIME ZONE;
+
+UPDATE records
+SET status_since = COALESCE(
+    (SELECT MAX(time) FROM record_modifications WHERE record_modifications.id = records.id AND status_ IS NOT NULL),
+    (SELECT time FROM record_additions WHERE record_additions.id = records.id)
+);
+
+CREATE FUNCTION track_record_status_since() RETURNS trigger AS $$
+BEGIN
+    -- Records restored from a player merge snapshot keep their time
+    IF (TG_OP = 'INSERT') THEN
+        IF (NEW.status_since IS NULL) THEN
+            NEW.status_since := NOW() AT TIME ZONE 'utc';
+        END IF;
+    ELSIF (OLD.status_ IS DISTINCT FROM NEW.status_) THEN
+        NEW.status_since := NOW() AT TIME ZONE 'utc';
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER record_status_since_trigger BEFORE INSERT OR UPDATE OF status_ ON records FOR EACH ROW EXECUTE PROCEDURE track_record_status_since();
+
+-- Same as score_giving, but additionally with the nationality each record's player held at the time
+-- the record was approved. Verifications count as approved when the demon was added. If several
+-- records (or a record and the verification) give the same score, the earliest approved one counts.
+CREATE VIEW score_giving_at_approval AS
+    SELECT score_giving.*, held.nationality, held.subdivision
+    FROM score_giving
+    LEFT OUTER JOIN LATERAL (
+        -- Completion times order candidates like score_giving's time factors do
+        SELECT approved_at
+        FROM (
+            SELECT records.completion_time, records.status_since AS approved_at
+            FROM records
+            WHERE records.player = score_giving.player AND records.demon = score_giving.demon
+              AND records.progress = score_giving.progress AND records.status_ = 'APPROVED'
+
+            UNION ALL
+
+            SELECT NULL, (SELECT time FROM demon_additions WHERE demon_additions.id = demons.id)
+            FROM demons
+            WHERE demons.id = score_giving.demon AND demons.verifier = score_giving.player AND score_giving.progress = 100
+        ) candidates
+        ORDER BY completion_time NULLS FIRST, approved_at
+        LIMIT 1
+    ) approval ON TRUE
+    LEFT OUTER JOIN LATERAL nationality_at(score_giving.player, approval.approved_at) held ON TRUE;
+
+-- Same as ranked_nations, but attributing records to the nationality held when they were approved.
+-- Computed on the fly, as opposed to using the cached scores in the nationalities table.
+CREATE VIEW ranked_nations_at_approval AS
+    SELECT
+        ROW_NUMBER() OVER(ORDER BY scores.score DESC, nationalities.iso_country_code) AS index,
+        RANK() OVER(ORDER BY scores.score DESC) AS rank,
+        scores.score,
+        nationalities.iso_country_code,
+        nationalities.nation,
+        nationalities.continent
+    FROM (
+        SELECT q.nationality, SUM(record_score(q.progress, q.position, lists.extended_list_size, q.requirement) * q.time_factor) AS score
+        FROM (
+            SELECT DISTINCT ON (position, nationality) *
+            FROM score_giving_at_approval
+            WHERE nationality IS NOT NULL AND list = 1
+            ORDER BY nationality, position, progress DESC, time_factor DESC
+        ) q
+        INNER JOIN lists
+                ON lists.id = q.list
+        GROUP BY q.nationality
+    ) scores
+    INNER JOIN nationalities
+            ON nationalities.iso_country_code = scores.nationality
+    WHERE scores.score > 0.0;
+
+-- Same as best_records_in(country), but optionally attributing records to the nationality held when
+-- they were approved
+CREATE FUNCTION best_records_in(country VARCHAR(2), at_approval BOOLEAN)
+    RETURNS TABLE (id INTEGER, progress SMALLINT, player INTEGER, demon INTEGER)
+    AS
+$body$
+    WITH grp AS (
+        SELECT records.id, records.progress, records.player, records.demon,
+               RANK() OVER (PARTITION BY records.demon ORDER BY records.demon, records.progress DESC) AS rk
+        FROM records
+        INNER JOIN players
+        ON players.id = records.player
+        WHERE records.status_ = 'APPROVED' AND CASE
+            WHEN at_approval THEN (SELECT nationality FROM nationality_at(records.player, records.status_since)) = country
+            ELSE players.nationality = country
+        END
+    )
+    SELECT id, progress, player, demon
+    FROM grp
+    WHERE rk = 1;
+$body$
+LANGUAGE SQL;
@@ -109,7 +109,7 @@ pub async fn pagination_response<Q: PaginationQuery, P: Paginatable<Q>>(
 
     let mut links = LinksBuilder::new(endpoint);
 
-    if let Some((min_id, max_id)) = P::first_and_last(connection).await? {
+    if let Some((min_id, max_id)) = P::first_and_last(&query, connection).await? {
         links = links.with_first(min_id - 1).with_last(max_id + 1);
     }
 
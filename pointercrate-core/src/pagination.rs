@@ -136,7 +136,12 @@ pub trait Paginatable<Q: PaginationQuery>: Serialize + Sized {
     /// The number of items in the returned `Vec` must not exceed [`PaginationParameters::limit`].
     async fn page(query: &Q, connection: &mut PgConnection) -> Result<(Vec<Self>, PageContext), sqlx::Error>;
 
-    async fn first_and_last(connection: &mut PgConnection) -> Result<Option<(i32, i32)>, sqlx::Error>;
+    /// Returns the smallest and largest [`pagination_id`] of all objects matching the given query,
+    /// ignoring its `before` and `after` fields, or `None` if there are no such objects.
+    ///
+    /// The bounds may be less tight than that (e.g. span all objects in the table), as long as they
+    /// include all matching objects.
+    async fn first_and_last(query: &Q, connection: &mut PgConnection) -> Result<Option<(i32, i32)>, sqlx::Error>;
 
     fn pagination_id(&self) -> i32;
 }
@@ -198,8 +203,8 @@ pub fn __pagination_compat<T>(params: &PaginationParameters, mut objects: Vec<T>
 
 #[macro_export]
 macro_rules! first_and_last {
-    ($table_name: expr, $id_column: expr) => {
-        async fn first_and_last(connection: &mut PgConnection) -> std::result::Result<Option<(i32, i32)>, sqlx::Error> {
+    ($query: ty, $table_name: expr, $id_column: expr) => {
+        async fn first_and_last(_: &$query, connection: &mut PgConnection) -> std::result::Result<Option<(i32, i32)>, sqlx::Error> {
             let row = sqlx::query!(
                 "SELECT CAST(MIN(" + $id_column + ") AS INTEGER), CAST(MAX(" + $id_column + ") AS INTEGER) FROM " + $table_name
             )
@@ -209,8 +214,8 @@ macro_rules! first_and_last {
             Ok(row.min.zip(row.max))
         }
     };
-    ($table_name: expr) => {
-        first_and_last!($table_name, "id");
+    ($query: ty, $table_name: expr) => {
+        first_and_last!($query, $table_name, "id");
     };
 }
 
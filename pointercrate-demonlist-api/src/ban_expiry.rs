@@ -0,0 +1,59 @@
+//! Background task periodically lifting bans whose expiry has passed
+//!
+//! The task is always started once the server launches. Its configuration can be changed by
+//! registering a [`BanExpiry`] with the rocket instance passed to [`setup`](crate::setup).
+
+use log::{error, info};
+use pointercrate_core::pool::PointercratePool;
+use pointercrate_demonlist::ban::lift_expired_bans;
+use rocket::tokio;
+use std::time::Duration;
+
+/// Configuration of the background task lifting expired bans
+#[derive(Clone)]
+pub struct BanExpiry {
+    interval: Duration,
+}
+
+impl Default for BanExpiry {
+    /// Checks for expired bans every 5 minutes
+    fn default() -> Self {
+        BanExpiry {
+            interval: Duration::from_secs(300),
+        }
+    }
+}
+
+impl BanExpiry {
+    /// Sets the time between two checks for expired bans
+    pub fn every(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub(crate) fn spawn(self, pool: PointercratePool) {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(self.interval).await;
+
+                if let Err(err) = Self::lift_expired(&pool).await {
+                    error!("INTERNAL SERVER ERROR: Failure to lift expired bans: {:?}", err);
+                }
+            }
+        });
+    }
+
+    async fn lift_expired(pool: &PointercratePool) -> pointercrate_demonlist::error::Result<()> {
+        let mut connection = pool.transaction().await?;
+
+        let lifted = lift_expired_bans(&mut *connection).await?;
+
+        connection.commit().await?;
+
+        if lifted > 0 {
+            info!("Lifted {} expired bans", lifted);
+        }
+
+        Ok(())
+    }
+}
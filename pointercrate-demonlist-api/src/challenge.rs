@@ -0,0 +1,123 @@
+//! [`ChallengeVerifier`]s for third party captcha services
+//!
+//! Both hCaptcha and Cloudflare Turnstile verify responses via a `siteverify` endpoint accepting
+//! the same parameters, so a single [`HostedChallenge`] implementation covers both.
+
+use crate::config;
+use log::warn;
+use pointercrate_demonlist::record::challenge::{ChallengeVerifier, ChallengeWidget, SubmissionChallenge};
+use reqwest::Client;
+use rocket::{
+    request::{FromRequest, Outcome},
+    Request,
+};
+use serde::Deserialize;
+use std::{convert::Infallible, net::IpAddr, time::Duration};
+
+/// The response to the submission challenge, taken from the `X-Challenge-Response` header
+pub struct ChallengeResponse(pub Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ChallengeResponse {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(ChallengeResponse(
+            request.headers().get_one("X-Challenge-Response").map(ToString::to_string),
+        ))
+    }
+}
+
+/// Constructs the [`SubmissionChallenge`] configured via the `CHALLENGE_PROVIDER` (either `hcaptcha`
+/// or `turnstile`), `CHALLENGE_SITE_KEY` and `CHALLENGE_SECRET` environment variables. If these are
+/// not set, no challenge is required.
+pub fn configured_challenge() -> SubmissionChallenge {
+    match (
+        config::challenge_provider(),
+        config::challenge_site_key(),
+        config::challenge_secret(),
+    ) {
+        (Some(provider), Some(site_key), Some(secret)) => match provider.as_str() {
+            "hcaptcha" => SubmissionChallenge::new(HostedChallenge::hcaptcha(site_key, secret)),
+            "turnstile" => SubmissionChallenge::new(HostedChallenge::turnstile(site_key, secret)),
+            _ => panic!(
+                "Unknown challenge provider '{}', expected either 'hcaptcha' or 'turnstile'",
+                provider
+            ),
+        },
+        _ => SubmissionChallenge::disabled(),
+    }
+}
+
+/// Verifies responses to a captcha widget hosted by a third party
+pub struct HostedChallenge {
+    client: Client,
+    widget: ChallengeWidget,
+    verification_endpoint: &'static str,
+    secret: String,
+}
+
+#[derive(Deserialize)]
+struct SiteVerifyResponse {
+    success: bool,
+}
+
+impl HostedChallenge {
+    fn new(widget: ChallengeWidget, verification_endpoint: &'static str, secret: String) -> Self {
+        HostedChallenge {
+            client: Client::builder().timeout(Duration::from_secs(10)).build().unwrap(),
+            widget,
+            verification_endpoint,
+            secret,
+        }
+    }
+
+    pub fn hcaptcha(site_key: String, secret: String) -> Self {
+        let widget = ChallengeWidget::Hosted {
+            script: "https://js.hcaptcha.com/1/api.js".to_string(),
+            class: "h-captcha".to_string(),
+            site_key,
+            response_field: "h-captcha-response".to_string(),
+        };
+
+        HostedChallenge::new(widget, "https://api.hcaptcha.com/siteverify", secret)
+    }
+
+    pub fn turnstile(site_key: String, secret: String) -> Self {
+        let widget = ChallengeWidget::Hosted {
+            script: "https://challenges.cloudflare.com/turnstile/v0/api.js".to_string(),
+            class: "cf-turnstile".to_string(),
+            site_key,
+            response_field: "cf-turnstile-response".to_string(),
+        };
+
+        HostedChallenge::new(widget, "https://challenges.cloudflare.com/turnstile/v0/siteverify", secret)
+    }
+}
+
+#[rocket::async_trait]
+impl ChallengeVerifier for HostedChallenge {
+    fn widget(&self) -> ChallengeWidget {
+        self.widget.clone()
+    }
+
+    async fn verify(&self, response: &str, ip: IpAddr) -> bool {
+        let ip = ip.to_string();
+        let request = self.client.post(self.verification_endpoint).form(&[
+            ("secret", self.secret.as_str()),
+            ("response", response),
+            ("remoteip", ip.as_str()),
+        ]);
+
+        let result = async { request.send().await?.error_for_status()?.json::<SiteVerifyResponse>().await }.await;
+
+        match result {
+            Ok(verification) => verification.success,
+            Err(err) => {
+                warn!("Failed to verify challenge response: {}", err);
+
+                false
+            },
+        }
+    }
+}
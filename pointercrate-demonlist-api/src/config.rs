@@ -5,3 +5,35 @@ pub fn submission_webhook() -> Option<String> {
 pub fn abstract_api_key() -> Option<String> {
     std::env::var("ABSTRACT_API_KEY").ok()
 }
+
+pub fn geolocation_database() -> Option<String> {
+    std::env::var("GEOLOCATION_DATABASE").ok()
+}
+
+pub fn anonymous_ip_database() -> Option<String> {
+    std::env::var("ANONYMOUS_IP_DATABASE").ok()
+}
+
+pub fn youtube_api_key() -> Option<String> {
+    std::env::var("YOUTUBE_API_KEY").ok()
+}
+
+pub fn twitch_client_id() -> Option<String> {
+    std::env::var("TWITCH_CLIENT_ID").ok()
+}
+
+pub fn twitch_access_token() -> Option<String> {
+    std::env::var("TWITCH_ACCESS_TOKEN").ok()
+}
+
+pub fn challenge_provider() -> Option<String> {
+    std::env::var("CHALLENGE_PROVIDER").ok()
+}
+
+pub fn challenge_site_key() -> Option<String> {
+    std::env::var("CHALLENGE_SITE_KEY").ok()
+}
+
+pub fn challenge_secret() -> Option<String> {
+    std::env::var("CHALLENGE_SECRET").ok()
+}
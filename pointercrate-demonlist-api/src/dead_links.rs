@@ -0,0 +1,125 @@
+//! Background task periodically checking whether the videos of approved records and demons are
+//! still available
+//!
+//! The checker is only started if a [`DeadLinkChecker`] is registered with the rocket instance
+//! passed to [`setup`](crate::setup).
+
+use log::{error, info};
+use pointercrate_core::pool::PointercratePool;
+use pointercrate_demonlist::video::{
+    availability::{check_due_videos, Probe, VideoProber, VideoState},
+    VideoIdentity,
+};
+use reqwest::{Client, StatusCode};
+use rocket::tokio;
+use std::{sync::Arc, time::Duration};
+
+/// Probes videos via HTTP, using the host's oEmbed endpoint if it has one, and a `HEAD` request on
+/// the video itself otherwise
+pub struct HttpVideoProber {
+    client: Client,
+}
+
+impl Default for HttpVideoProber {
+    fn default() -> Self {
+        HttpVideoProber {
+            client: Client::builder().timeout(Duration::from_secs(20)).build().unwrap(),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl VideoProber for HttpVideoProber {
+    async fn probe(&self, video: &str) -> Probe {
+        let request = match VideoIdentity::of(video).ok().and_then(|identity| identity.oembed_endpoint()) {
+            Some(endpoint) => self.client.get(endpoint).query(&[("url", video), ("format", "json")]),
+            None => self.client.head(video),
+        };
+
+        match request.send().await {
+            Ok(response) => {
+                let status = response.status();
+
+                let state = match status {
+                    _ if status.is_success() || status.is_redirection() => VideoState::Available,
+                    StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => VideoState::Unavailable,
+                    StatusCode::NOT_FOUND | StatusCode::GONE => VideoState::Dead,
+                    _ => VideoState::Unknown,
+                };
+
+                Probe {
+                    state,
+                    detail: Some(format!("HTTP {}", status)),
+                }
+            },
+            Err(err) => Probe {
+                state: VideoState::Unknown,
+                detail: Some(err.to_string()),
+            },
+        }
+    }
+}
+
+/// Configuration of the background task checking for dead video links
+#[derive(Clone)]
+pub struct DeadLinkChecker {
+    prober: Arc<dyn VideoProber>,
+    interval: Duration,
+    recheck_after_hours: i32,
+    batch_size: i64,
+}
+
+impl DeadLinkChecker {
+    /// Constructs a checker using the given prober, which every 10 minutes checks 50 videos that
+    /// have not been checked in the past week
+    pub fn new(prober: impl VideoProber + 'static) -> Self {
+        DeadLinkChecker {
+            prober: Arc::new(prober),
+            interval: Duration::from_secs(600),
+            recheck_after_hours: 24 * 7,
+            batch_size: 50,
+        }
+    }
+
+    /// Sets the time between two batches of checks
+    pub fn every(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets how many hours need to pass before a video is checked again
+    pub fn recheck_after_hours(mut self, hours: i32) -> Self {
+        self.recheck_after_hours = hours;
+        self
+    }
+
+    /// Sets the maximal number of videos checked per batch
+    pub fn batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    pub(crate) fn spawn(self, pool: PointercratePool) {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(self.interval).await;
+
+                if let Err(err) = self.check_batch(&pool).await {
+                    error!("INTERNAL SERVER ERROR: Failure to check for dead video links: {:?}", err);
+                }
+            }
+        });
+    }
+
+    async fn check_batch(&self, pool: &PointercratePool) -> pointercrate_demonlist::error::Result<()> {
+        let mut connection = pool.connection().await?;
+
+        let checked = check_due_videos(&*self.prober, self.recheck_after_hours, self.batch_size, &mut *connection).await?;
+
+        if checked > 0 {
+            info!("Checked availability of {} videos", checked);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+use pointercrate_core::pool::PointercratePool;
+use pointercrate_core_api::{error::Result, etag::Tagged};
+use pointercrate_demonlist::nationality::{Continent, ContinentRecord, RankedContinent};
+use rocket::{serde::json::Json, State};
+
+#[rocket::get("/")]
+pub async fn ranking(pool: &State<PointercratePool>) -> Result<Json<Vec<RankedContinent>>> {
+    Ok(Json(Continent::ranking(&mut *pool.connection().await?).await?))
+}
+
+#[rocket::get("/<continent_id>")]
+pub async fn continent(pool: &State<PointercratePool>, continent_id: i32) -> Result<Tagged<ContinentRecord>> {
+    let mut connection = pool.connection().await?;
+
+    let continent = Continent::by_id(continent_id, &mut *connection).await?;
+
+    Ok(Tagged(continent.upgrade(&mut *connection).await?))
+}
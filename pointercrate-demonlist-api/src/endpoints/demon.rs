@@ -15,6 +15,7 @@ use pointercrate_demonlist::{
     },
     error::DemonlistError,
     player::DatabasePlayer,
+    video::thumbnail::ThumbnailResolver,
     LIST_ADMINISTRATOR, LIST_MODERATOR,
 };
 use pointercrate_user_api::auth::TokenAuth;
@@ -63,13 +64,13 @@ pub async fn movement_log(demon_id: i32, pool: &State<PointercratePool>) -> Resu
 
 #[rocket::post("/", data = "<data>")]
 pub async fn post(
-    mut auth: TokenAuth, data: Json<PostDemon>, ratelimits: &State<DemonlistRatelimits>,
+    mut auth: TokenAuth, data: Json<PostDemon>, ratelimits: &State<DemonlistRatelimits>, thumbnails: &State<ThumbnailResolver>,
 ) -> Result<Response2<Tagged<FullDemon>>> {
     auth.require_permission(LIST_MODERATOR)?;
 
     ratelimits.add_demon()?;
 
-    let demon = FullDemon::create_from(data.0, &mut auth.connection).await?;
+    let demon = FullDemon::create_from(data.0, thumbnails, &mut auth.connection).await?;
 
     auth.commit().await?;
 
@@ -81,13 +82,15 @@ pub async fn post(
 }
 
 #[rocket::patch("/<demon_id>", data = "<patch>")]
-pub async fn patch(demon_id: i32, mut auth: TokenAuth, precondition: Precondition, patch: Json<PatchDemon>) -> Result<Tagged<FullDemon>> {
+pub async fn patch(
+    demon_id: i32, mut auth: TokenAuth, precondition: Precondition, patch: Json<PatchDemon>, thumbnails: &State<ThumbnailResolver>,
+) -> Result<Tagged<FullDemon>> {
     auth.require_permission(LIST_MODERATOR)?;
 
     let demon = FullDemon::by_id(demon_id, &mut auth.connection)
         .await?
         .require_match(precondition)?
-        .apply_patch(patch.0, &mut auth.connection)
+        .apply_patch(patch.0, thumbnails, &mut auth.connection)
         .await?;
 
     auth.commit().await?;
@@ -0,0 +1,79 @@
+use pointercrate_core::pool::PointercratePool;
+use pointercrate_core_api::{
+    error::Result,
+    etag::{Precondition, TaggableExt, Tagged},
+    pagination::pagination_response,
+    query::Query,
+    response::Response2,
+};
+use pointercrate_demonlist::{
+    demon::{Demon, DemonPositionPagination},
+    list::{List, PatchList, PostList},
+    player::{RankedPlayer, RankingPagination},
+    LIST_ADMINISTRATOR,
+};
+use pointercrate_user_api::auth::TokenAuth;
+use rocket::{http::Status, serde::json::Json, State};
+
+#[rocket::get("/")]
+pub async fn get_all(pool: &State<PointercratePool>) -> Result<Json<Vec<List>>> {
+    Ok(Json(List::all(&mut *pool.connection().await?).await?))
+}
+
+#[rocket::get("/<slug>")]
+pub async fn get(slug: &str, pool: &State<PointercratePool>) -> Result<Tagged<List>> {
+    Ok(Tagged(List::by_slug(slug, &mut *pool.connection().await?).await?))
+}
+
+#[rocket::post("/", data = "<data>")]
+pub async fn post(mut auth: TokenAuth, data: Json<PostList>) -> Result<Response2<Tagged<List>>> {
+    auth.require_permission(LIST_ADMINISTRATOR)?;
+
+    let list = List::create_from(data.0, &mut auth.connection).await?;
+
+    auth.commit().await?;
+
+    let slug = list.slug.clone();
+
+    Ok(Response2::tagged(list)
+        .status(Status::Created)
+        .with_header("Location", format!("/api/v2/lists/{}/", slug)))
+}
+
+#[rocket::patch("/<slug>", data = "<patch>")]
+pub async fn patch(slug: &str, mut auth: TokenAuth, precondition: Precondition, patch: Json<PatchList>) -> Result<Tagged<List>> {
+    auth.require_permission(LIST_ADMINISTRATOR)?;
+
+    let list = List::by_slug(slug, &mut auth.connection)
+        .await?
+        .require_match(precondition)?
+        .apply_patch(patch.0, &mut auth.connection)
+        .await?;
+
+    auth.commit().await?;
+
+    Ok(Tagged(list))
+}
+
+#[rocket::get("/<slug>/demons/listed")]
+pub async fn paginate_listed(
+    slug: &str, pool: &State<PointercratePool>, mut pagination: Query<DemonPositionPagination>,
+) -> Result<Response2<Json<Vec<Demon>>>> {
+    let mut connection = pool.connection().await?;
+
+    pagination.0.list = Some(List::by_slug(slug, &mut *connection).await?.id);
+
+    // The generated links point to the unscoped endpoint, with the list given as a query parameter
+    Ok(pagination_response("/api/v2/demons/listed/", pagination.0, &mut *connection).await?)
+}
+
+#[rocket::get("/<slug>/players/ranking")]
+pub async fn ranking(
+    slug: &str, pool: &State<PointercratePool>, mut query: Query<RankingPagination>,
+) -> Result<Response2<Json<Vec<RankedPlayer>>>> {
+    let mut connection = pool.connection().await?;
+
+    query.0.list = Some(List::by_slug(slug, &mut *connection).await?.id);
+
+    Ok(pagination_response("/api/v1/players/ranking/", query.0, &mut *connection).await?)
+}
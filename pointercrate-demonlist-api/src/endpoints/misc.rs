@@ -1,15 +1,19 @@
-use pointercrate_demonlist::config;
-use rocket::serde::json::Json;
+use pointercrate_core::pool::PointercratePool;
+use pointercrate_core_api::error::Result;
+use pointercrate_demonlist::list::{List, DEFAULT_LIST};
+use rocket::{serde::json::Json, State};
 use serde_json::{json, Value};
 
 #[rocket::get("/")]
-pub fn list_information() -> Json<Value> {
+pub async fn list_information(pool: &State<PointercratePool>) -> Result<Json<Value>> {
+    let list = List::by_id(DEFAULT_LIST, &mut *pool.connection().await?).await?;
+
     let data = json! {
         {
-            "list_size": config::list_size(),
-            "extended_list_size": config::extended_list_size()
+            "list_size": list.list_size,
+            "extended_list_size": list.extended_list_size
         }
     };
 
-    Json(data)
+    Ok(Json(data))
 }
@@ -1,6 +1,11 @@
+pub(crate) mod continent;
 pub(crate) mod demon;
+pub(crate) mod list;
 pub(crate) mod misc;
 pub(crate) mod nationality;
 pub(crate) mod player;
+pub(crate) mod range_ban;
 pub(crate) mod record;
+pub(crate) mod rejection_reason;
 pub(crate) mod submitter;
+pub(crate) mod video;
@@ -18,12 +18,16 @@ pub async fn ranking(pool: &State<PointercratePool>, pagination: Query<Nationali
     Ok(Json(pagination.0.page(&mut *pool.connection().await?).await?))
 }
 
-#[rocket::get("/<iso_code>")]
-pub async fn nation(pool: &State<PointercratePool>, iso_code: String) -> Result<Tagged<NationalityRecord>> {
+/// Gets a breakdown of the given nation's achievements
+///
+/// If `at_approval` is set, records are attributed to the nation their player was from when they
+/// were approved, instead of to their player's current nation
+#[rocket::get("/<iso_code>?<at_approval>")]
+pub async fn nation(pool: &State<PointercratePool>, iso_code: String, at_approval: Option<bool>) -> Result<Tagged<NationalityRecord>> {
     let mut connection = pool.connection().await?;
 
     // good code
     let nationality = Nationality::by_country_code_or_name(iso_code.to_uppercase().as_ref(), &mut *connection).await?;
 
-    Ok(Tagged(nationality.upgrade(&mut *connection).await?))
+    Ok(Tagged(nationality.upgrade(at_approval.unwrap_or(false), &mut *connection).await?))
 }
@@ -1,6 +1,5 @@
-use crate::{config, ratelimits::DemonlistRatelimits};
-use log::warn;
-use pointercrate_core::{error::CoreError, pool::PointercratePool};
+use crate::ratelimits::DemonlistRatelimits;
+use pointercrate_core::pool::PointercratePool;
 use pointercrate_core_api::{
     error::Result,
     etag::{Precondition, TaggableExt, Tagged},
@@ -9,17 +8,26 @@ use pointercrate_core_api::{
     response::Response2,
 };
 use pointercrate_demonlist::{
+    ban::{Ban, PostBan},
     error::DemonlistError,
-    nationality::Nationality,
+    nationality::{geolocation::Geolocator, Nationality},
     player::{
-        claim::{ListedClaim, PatchPlayerClaim, PlayerClaim, PlayerClaimPagination},
+        alias::{PatchPlayerAlias, PlayerAlias},
+        claim::{
+            audit::ClaimAuditLogEntry, ClaimDispute, ClaimSuggestion, ListedClaim, PatchPlayerClaim, PlayerClaim, PlayerClaimPagination,
+            PostClaimDispute, PostClaimSuggestion, ResolveClaimDispute, ReviewClaimSuggestion,
+        },
+        merge::{MergePreview, PlayerMerge},
+        nationality_history::NationalityChange,
         DatabasePlayer, FullPlayer, PatchPlayer, Player, PlayerPagination, RankedPlayer, RankingPagination,
     },
+    record::approval::ApprovalPolicy,
+    submitter::normalize_ip,
     LIST_HELPER, LIST_MODERATOR,
 };
+use pointercrate_integrate::claim::{ClaimVerifier, CodeLocation};
 use pointercrate_user_api::auth::TokenAuth;
 use rocket::{http::Status, serde::json::Json, State};
-use serde::Deserialize;
 use std::net::IpAddr;
 
 #[rocket::get("/")]
@@ -70,6 +78,143 @@ pub async fn patch(
     Ok(Tagged(player))
 }
 
+/// Reports what merging the player with id `other_id` into the player with id `player_id` would do
+#[rocket::get("/<player_id>/merge/<other_id>")]
+pub async fn preview_merge(player_id: i32, other_id: i32, mut auth: TokenAuth) -> Result<Tagged<MergePreview>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    let player = Player::by_id(player_id, &mut auth.connection)
+        .await?
+        .upgrade(&mut auth.connection)
+        .await?;
+    let other = DatabasePlayer::by_id(other_id, &mut auth.connection).await?;
+
+    Ok(Tagged(player.preview_merge(&other, &mut auth.connection).await?))
+}
+
+/// Merges the player with id `other_id` into the player with id `player_id`, deleting the former
+///
+/// The merge has to be confirmed by providing the ETag of the corresponding merge preview (see
+/// [`preview_merge`]) in the `If-Match` header. If what the merge would do has changed since the
+/// preview was generated, `412 PRECONDITION FAILED` is returned.
+#[rocket::post("/<player_id>/merge/<other_id>")]
+pub async fn merge(player_id: i32, other_id: i32, mut auth: TokenAuth, precondition: Precondition) -> Result<Tagged<FullPlayer>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    let mut player = Player::by_id(player_id, &mut auth.connection)
+        .await?
+        .upgrade(&mut auth.connection)
+        .await?;
+    let other = DatabasePlayer::by_id(other_id, &mut auth.connection).await?;
+
+    player
+        .preview_merge(&other, &mut auth.connection)
+        .await?
+        .require_match(precondition)?;
+    player.merge(other, &mut auth.connection).await?;
+
+    auth.commit().await?;
+
+    Ok(Tagged(player))
+}
+
+/// Lists all merges of other players into the given player, including ones that were undone
+#[rocket::get("/<player_id>/merges")]
+pub async fn merges(player_id: i32, mut auth: TokenAuth) -> Result<Json<Vec<PlayerMerge>>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    let player = DatabasePlayer::by_id(player_id, &mut auth.connection).await?;
+
+    Ok(Json(player.merges(&mut auth.connection).await?))
+}
+
+/// Undoes the merge with id `merge_id`, splitting the merged player back out of the player with id
+/// `player_id`. Responds with the restored player.
+#[rocket::post("/<player_id>/merges/<merge_id>/undo")]
+pub async fn undo_merge(player_id: i32, merge_id: i32, mut auth: TokenAuth) -> Result<Response2<Tagged<FullPlayer>>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    let mut player = Player::by_id(player_id, &mut auth.connection)
+        .await?
+        .upgrade(&mut auth.connection)
+        .await?;
+
+    let restored = player.undo_merge(merge_id, &mut auth.connection).await?;
+    let restored = Player::by_id(restored.id, &mut auth.connection)
+        .await?
+        .upgrade(&mut auth.connection)
+        .await?;
+
+    auth.commit().await?;
+
+    let restored_id = restored.player.base.id;
+
+    Ok(Response2::tagged(restored)
+        .status(Status::Created)
+        .with_header("Location", format!("/api/v1/players/{}/", restored_id)))
+}
+
+/// Lists all bans ever issued against the given player, most recent first
+#[rocket::get("/<player_id>/bans")]
+pub async fn bans(player_id: i32, mut auth: TokenAuth) -> Result<Json<Vec<Ban>>> {
+    auth.require_permission(LIST_HELPER)?;
+
+    let player = DatabasePlayer::by_id(player_id, &mut auth.connection).await?;
+
+    Ok(Json(player.bans(&mut auth.connection).await?))
+}
+
+/// Bans the given player, replacing their current ban if they are already banned
+#[rocket::post("/<player_id>/bans", data = "<ban>")]
+pub async fn ban(player_id: i32, mut auth: TokenAuth, ban: Json<PostBan>) -> Result<Response2<Json<Ban>>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    let mut player = DatabasePlayer::by_id(player_id, &mut auth.connection).await?;
+    let ban = player.issue_ban(ban.0, &mut auth.connection).await?;
+
+    player.update_score(&mut auth.connection).await?;
+
+    auth.commit().await?;
+
+    Ok(Response2::json(ban)
+        .status(Status::Created)
+        .with_header("Location", format!("/api/v1/players/{}/bans/", player.id)))
+}
+
+/// Lists all names the given player was previously known under, including hidden ones
+#[rocket::get("/<player_id>/aliases")]
+pub async fn aliases(player_id: i32, mut auth: TokenAuth) -> Result<Json<Vec<PlayerAlias>>> {
+    auth.require_permission(LIST_HELPER)?;
+
+    let player = DatabasePlayer::by_id(player_id, &mut auth.connection).await?;
+
+    Ok(Json(player.aliases(true, &mut auth.connection).await?))
+}
+
+/// Lists the nationalities the given player held, most recent first
+#[rocket::get("/<player_id>/nationalities")]
+pub async fn nationality_history(player_id: i32, pool: &State<PointercratePool>) -> Result<Json<Vec<NationalityChange>>> {
+    let mut connection = pool.connection().await?;
+
+    let player = DatabasePlayer::by_id(player_id, &mut *connection).await?;
+
+    Ok(Json(player.nationality_history(&mut *connection).await?))
+}
+
+#[rocket::patch("/<player_id>/aliases/<alias_id>", data = "<patch>")]
+pub async fn patch_alias(player_id: i32, alias_id: i32, mut auth: TokenAuth, patch: Json<PatchPlayerAlias>) -> Result<Json<PlayerAlias>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    let alias = PlayerAlias::by_id(player_id, alias_id, &mut auth.connection)
+        .await?
+        .apply_patch(patch.0, &mut auth.connection)
+        .await?;
+
+    auth.commit().await?;
+
+    Ok(Json(alias))
+}
+
 #[rocket::put("/<player_id>/claims")]
 pub async fn put_claim(player_id: i32, mut auth: TokenAuth) -> Result<Response2<Json<PlayerClaim>>> {
     let user_id = auth.user.inner().id;
@@ -127,6 +272,49 @@ pub async fn patch_claim(player_id: i32, user_id: i32, mut auth: TokenAuth, data
     Ok(Json(claim))
 }
 
+/// Verifies a claim by checking that the claimant placed the claim's verification code at the
+/// given location on the claimed player's Geometry Dash account. Can only be done by the person
+/// holding the claim.
+#[rocket::post("/<player_id>/claims/<user_id>/verify", data = "<location>")]
+pub async fn verify_claim(
+    player_id: i32, user_id: i32, mut auth: TokenAuth, location: Json<CodeLocation>, verifier: &State<ClaimVerifier>,
+    ratelimits: &State<DemonlistRatelimits>,
+) -> Result<Json<PlayerClaim>> {
+    let mut claim = match PlayerClaim::get(user_id, player_id, &mut auth.connection).await {
+        Ok(claim) if claim.user_id == auth.user.inner().id => claim,
+        _ => {
+            return Err(DemonlistError::ClaimNotFound {
+                member_id: user_id,
+                player_id,
+            }
+            .into())
+        },
+    };
+
+    if claim.verified {
+        return Ok(Json(claim));
+    }
+
+    // Someone else might have gotten their claim verified in the meantime
+    if PlayerClaim::verified_claim_on(player_id, &mut auth.connection).await?.is_some() {
+        return Err(DemonlistError::AlreadyClaimed.into());
+    }
+
+    ratelimits.claim_verification(user_id)?;
+
+    let player = DatabasePlayer::by_id(player_id, &mut auth.connection).await?;
+    let code = claim.verification_code(&mut auth.connection).await?;
+
+    if !verifier.check(&player.name, &code, &location).await {
+        return Err(DemonlistError::ClaimVerificationFailed.into());
+    }
+
+    claim.set_verified(true, &mut auth.connection).await?;
+    auth.commit().await?;
+
+    Ok(Json(claim))
+}
+
 #[rocket::delete("/<player_id>/claims/<user_id>")]
 pub async fn delete_claim(player_id: i32, user_id: i32, mut auth: TokenAuth) -> Result<Status> {
     auth.require_permission(LIST_MODERATOR)?;
@@ -139,28 +327,143 @@ pub async fn delete_claim(player_id: i32, user_id: i32, mut auth: TokenAuth) ->
     Ok(Status::NoContent)
 }
 
-#[rocket::get("/claims")]
-pub async fn paginate_claims(mut auth: TokenAuth, pagination: Query<PlayerClaimPagination>) -> Result<Response2<Json<Vec<ListedClaim>>>> {
+/// Gets the audit log of all claims (past and present) on the given player
+#[rocket::get("/<player_id>/claims/audit")]
+pub async fn claim_audit(player_id: i32, mut auth: TokenAuth) -> Result<Json<Vec<ClaimAuditLogEntry>>> {
     auth.require_permission(LIST_MODERATOR)?;
 
-    Ok(pagination_response("/api/v1/players/claims/", pagination.0, &mut auth.connection).await?)
+    let log = pointercrate_demonlist::player::claim::audit::audit_log_for_claims_on(player_id, &mut auth.connection).await?;
+
+    Ok(Json(log))
 }
 
-#[derive(Deserialize, Debug)]
-struct Security {
-    is_vpn: bool,
+/// Disputes the verified claim on the given player
+#[rocket::post("/<player_id>/claims/disputes", data = "<dispute>")]
+pub async fn dispute_claim(player_id: i32, mut auth: TokenAuth, dispute: Json<PostClaimDispute>) -> Result<Response2<Json<ClaimDispute>>> {
+    let player = DatabasePlayer::by_id(player_id, &mut auth.connection).await?;
+    let dispute = player.dispute_claim(auth.user.inner().id, dispute.0, &mut auth.connection).await?;
+
+    auth.commit().await?;
+
+    let dispute_id = dispute.id;
+
+    Ok(Response2::json(dispute)
+        .status(Status::Created)
+        .with_header("Location", format!("/api/v1/players/claims/disputes/{}/", dispute_id)))
 }
 
-#[derive(Deserialize, Debug)]
-struct GeolocationResponse {
-    security: Security,
-    country_code: String,
-    region_iso_code: Option<String>,
+#[rocket::get("/<player_id>/claims/disputes")]
+pub async fn player_disputes(player_id: i32, mut auth: TokenAuth) -> Result<Json<Vec<ClaimDispute>>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    Ok(Json(ClaimDispute::on_player(player_id, &mut auth.connection).await?))
 }
 
+#[rocket::get("/claims/disputes")]
+pub async fn open_disputes(mut auth: TokenAuth) -> Result<Json<Vec<ClaimDispute>>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    Ok(Json(ClaimDispute::open(&mut auth.connection).await?))
+}
+
+/// Disputes can be viewed by list moderators and by the user who opened them
+#[rocket::get("/claims/disputes/<dispute_id>")]
+pub async fn get_dispute(dispute_id: i32, mut auth: TokenAuth) -> Result<Json<ClaimDispute>> {
+    let dispute = ClaimDispute::by_id(dispute_id, &mut auth.connection).await?;
+
+    if dispute.disputed_by != auth.user.inner().id {
+        auth.require_permission(LIST_MODERATOR)?;
+    }
+
+    Ok(Json(dispute))
+}
+
+#[rocket::post("/claims/disputes/<dispute_id>/resolution", data = "<resolution>")]
+pub async fn resolve_dispute(dispute_id: i32, mut auth: TokenAuth, resolution: Json<ResolveClaimDispute>) -> Result<Json<ClaimDispute>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    let mut dispute = ClaimDispute::by_id(dispute_id, &mut auth.connection).await?;
+
+    dispute.resolve(resolution.0, &mut auth.connection).await?;
+    auth.commit().await?;
+
+    Ok(Json(dispute))
+}
+
+/// Suggests an edit for the given player. Only possible for the holder of the verified claim on
+/// the player.
+#[rocket::post("/<player_id>/suggestions", data = "<suggestion>")]
+pub async fn suggest_edit(
+    player_id: i32, mut auth: TokenAuth, suggestion: Json<PostClaimSuggestion>,
+) -> Result<Response2<Json<ClaimSuggestion>>> {
+    let claim = PlayerClaim::get(auth.user.inner().id, player_id, &mut auth.connection).await?;
+    let suggestion = claim.suggest_edit(suggestion.0, &mut auth.connection).await?;
+
+    auth.commit().await?;
+
+    let suggestion_id = suggestion.id;
+
+    Ok(Response2::json(suggestion)
+        .status(Status::Created)
+        .with_header("Location", format!("/api/v1/players/claims/suggestions/{}/", suggestion_id)))
+}
+
+/// Gets all edits ever suggested for the given player. Available to list moderators and the
+/// verified claimant of the player.
+#[rocket::get("/<player_id>/suggestions")]
+pub async fn player_suggestions(player_id: i32, mut auth: TokenAuth) -> Result<Json<Vec<ClaimSuggestion>>> {
+    match PlayerClaim::get(auth.user.inner().id, player_id, &mut auth.connection).await {
+        Ok(claim) if claim.verified => (),
+        _ => auth.require_permission(LIST_MODERATOR)?,
+    }
+
+    Ok(Json(ClaimSuggestion::for_player(player_id, &mut auth.connection).await?))
+}
+
+#[rocket::get("/claims/suggestions")]
+pub async fn pending_suggestions(mut auth: TokenAuth) -> Result<Json<Vec<ClaimSuggestion>>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    Ok(Json(ClaimSuggestion::pending(&mut auth.connection).await?))
+}
+
+#[rocket::get("/claims/suggestions/<suggestion_id>")]
+pub async fn get_suggestion(suggestion_id: i32, mut auth: TokenAuth) -> Result<Json<ClaimSuggestion>> {
+    let suggestion = ClaimSuggestion::by_id(suggestion_id, &mut auth.connection).await?;
+
+    if suggestion.suggested_by != auth.user.inner().id {
+        auth.require_permission(LIST_MODERATOR)?;
+    }
+
+    Ok(Json(suggestion))
+}
+
+#[rocket::post("/claims/suggestions/<suggestion_id>/review", data = "<review>")]
+pub async fn review_suggestion(
+    suggestion_id: i32, mut auth: TokenAuth, review: Json<ReviewClaimSuggestion>, approval_policy: &State<ApprovalPolicy>,
+) -> Result<Json<ClaimSuggestion>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    let mut suggestion = ClaimSuggestion::by_id(suggestion_id, &mut auth.connection).await?;
+
+    suggestion.review(review.0, approval_policy, &mut auth.connection).await?;
+    auth.commit().await?;
+
+    Ok(Json(suggestion))
+}
+
+#[rocket::get("/claims")]
+pub async fn paginate_claims(mut auth: TokenAuth, pagination: Query<PlayerClaimPagination>) -> Result<Response2<Json<Vec<ListedClaim>>>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    Ok(pagination_response("/api/v1/players/claims/", pagination.0, &mut auth.connection).await?)
+}
+
+/// Sets the nationality of a player to the one determined by geolocating the IP address of their
+/// verified claimant, using the configured [`Geolocator`]
 #[rocket::post("/<player_id>/geolocate")]
 pub async fn geolocate_nationality(
-    player_id: i32, ip: IpAddr, mut auth: TokenAuth, ratelimits: &State<DemonlistRatelimits>,
+    player_id: i32, ip: IpAddr, mut auth: TokenAuth, ratelimits: &State<DemonlistRatelimits>, geolocator: &State<Geolocator>,
 ) -> Result<Json<Nationality>> {
     let mut player = Player::by_id(player_id, &mut auth.connection).await?;
     let claim = PlayerClaim::get(auth.user.inner().id, player_id, &mut auth.connection).await?;
@@ -169,40 +472,9 @@ pub async fn geolocate_nationality(
         return Err(DemonlistError::ClaimUnverified.into());
     }
 
-    ratelimits.geolocate(ip)?;
+    ratelimits.geolocate(normalize_ip(ip))?;
 
-    let response = reqwest::get(format!(
-        "https://ipgeolocation.abstractapi.com/v1/?api_key={}&ip_address={}&fields=security,country_code,region_iso_code",
-        config::abstract_api_key().ok_or_else(|| CoreError::internal_server_error("No API key for abstract configured"))?,
-        ip
-    ))
-    .await
-    .map_err(|err| CoreError::internal_server_error(format!("Ip Geolocation failed: {}", err)))?;
-
-    let data = response.json::<GeolocationResponse>().await.map_err(|err| {
-        CoreError::internal_server_error(format!(
-            "Ip Geolocation succeeded, but we could not deserialize the response: {}",
-            err
-        ))
-    })?;
-
-    if data.security.is_vpn {
-        return Err(DemonlistError::VpsDetected.into());
-    }
-
-    let mut nationality = Nationality::by_country_code_or_name(&data.country_code, &mut auth.connection).await?;
-    if let Some(region) = data.region_iso_code {
-        nationality.subdivision = nationality
-            .subdivision_by_code(&region, &mut auth.connection)
-            .await
-            .inspect_err(|err| {
-                warn!(
-                    "No subdivision {} for nation {}, or nation does not support subdivisions: {:?}",
-                    region, nationality.iso_country_code, err
-                )
-            })
-            .ok();
-    }
+    let nationality = geolocator.nationality_of(ip, &mut auth.connection).await?;
 
     player.set_nationality(Some(nationality), &mut auth.connection).await?;
 
@@ -0,0 +1,48 @@
+use pointercrate_core_api::{error::Result, response::Response2};
+use pointercrate_demonlist::{
+    submitter::{PostRangeBan, RangeBan},
+    LIST_MODERATOR,
+};
+use pointercrate_user_api::auth::TokenAuth;
+use rocket::{http::Status, serde::json::Json};
+
+/// Lists all address ranges ever banned, most recent first
+#[rocket::get("/")]
+pub async fn get_all(mut auth: TokenAuth) -> Result<Json<Vec<RangeBan>>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    Ok(Json(RangeBan::all(&mut auth.connection).await?))
+}
+
+#[rocket::get("/<ban_id>")]
+pub async fn get(ban_id: i32, mut auth: TokenAuth) -> Result<Json<RangeBan>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    Ok(Json(RangeBan::by_id(ban_id, &mut auth.connection).await?))
+}
+
+#[rocket::post("/", data = "<ban>")]
+pub async fn post(mut auth: TokenAuth, ban: Json<PostRangeBan>) -> Result<Response2<Json<RangeBan>>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    let ban = RangeBan::create(ban.0, &mut auth.connection).await?;
+
+    auth.commit().await?;
+
+    let location = format!("/api/v1/range_bans/{}/", ban.id);
+
+    Ok(Response2::json(ban).status(Status::Created).with_header("Location", location))
+}
+
+/// Lifts the given range ban. The ban itself is kept for future reference.
+#[rocket::delete("/<ban_id>")]
+pub async fn lift(ban_id: i32, mut auth: TokenAuth) -> Result<Status> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    let mut ban = RangeBan::by_id(ban_id, &mut auth.connection).await?;
+
+    ban.lift(&mut auth.connection).await?;
+    auth.commit().await?;
+
+    Ok(Status::NoContent)
+}
@@ -1,6 +1,6 @@
-use crate::ratelimits::DemonlistRatelimits;
+use crate::{challenge::ChallengeResponse, ratelimits::DemonlistRatelimits};
 use log::{debug, error, warn};
-use pointercrate_core::{audit::AuditLogEntry, error::CoreError, pool::PointercratePool};
+use pointercrate_core::{audit::AuditLogEntry, error::CoreError, etag::Taggable, pool::PointercratePool};
 use pointercrate_core_api::{
     error::Result,
     etag::{Precondition, TaggableExt, Tagged},
@@ -10,29 +10,32 @@ use pointercrate_core_api::{
 };
 use pointercrate_demonlist::{
     error::DemonlistError,
-    player::claim::PlayerClaim,
     record::{
-        audit::RecordModificationData,
-        note::{notes_on, NewNote, Note, PatchNote},
+        approval::{votes_on, ApprovalPolicy, ApprovalVote, CastVote, PendingApproval},
+        audit::{RecordModificationData, StatusChange},
+        challenge::SubmissionChallenge,
+        note::{NewNote, Note, PatchNote},
+        policy::SubmissionPolicy,
+        queue::{AssignReviewer, ClaimQuery, QueueStatistics, ReviewLock},
+        visibility::RecordAccess,
         FullRecord, MinimalRecordPD, PatchRecord, RecordPagination, RecordStatus, Submission,
     },
-    submitter::Submitter,
+    submitter::{normalize_ip, RangeBan, ReputationPolicy, Submitter},
     LIST_ADMINISTRATOR, LIST_HELPER, LIST_MODERATOR,
 };
+use pointercrate_user::User;
 use pointercrate_user_api::auth::TokenAuth;
-use rocket::{http::Status, serde::json::Json, tokio, State};
-use sqlx::{pool::PoolConnection, Postgres};
+use rocket::{http::Status, serde::json::Json, tokio, Either, State};
+use sqlx::{pool::PoolConnection, PgConnection, Postgres};
 use std::net::IpAddr;
 
 /// Pagination endpoint for records in case authentication is provided
 ///
 /// Subject to the following constraints
 /// + Only users with `LIST_MODERATOR` permissions can filter by submitter.
-/// + Only users with `LIST_HELPER` permissions can filter by record status. For all other users,
-/// the `status` property defaults to `APPROVED` (although explicitly setting the status to
-/// `APPROVED` is allowed, UNLESS we also filter by player and the player we filter by match a
-/// verified claim of the user making the request, in which case access to all records is allowed
-/// (the `status` property does not get defaulted, and filtering on it is allowed)
+/// + Which records are visible is determined by the [`RecordAccess`] of the user to the records of
+/// the player filtered by (if any). Without access to non-approved records, the `status` property
+/// defaults to `APPROVED` (although explicitly setting the status to `APPROVED` is allowed)
 #[rocket::get("/")]
 pub async fn paginate(mut auth: TokenAuth, query: Query<RecordPagination>) -> Result<Response2<Json<Vec<MinimalRecordPD>>>> {
     let mut pagination = query.0;
@@ -41,17 +44,15 @@ pub async fn paginate(mut auth: TokenAuth, query: Query<RecordPagination>) -> Re
         auth.require_permission(LIST_MODERATOR)?;
     }
 
-    let claim = PlayerClaim::by_user(auth.user.inner().id, &mut auth.connection)
-        .await?
-        .filter(|c| c.verified);
-
-    if (claim.is_none() || claim.map(|c| c.player.id) != pagination.player) && !auth.has_permission(LIST_HELPER) {
-        if pagination.status.is_some() && pagination.status != Some(RecordStatus::Approved) {
-            return Err(CoreError::MissingPermissions { required: LIST_HELPER }.into());
-        }
+    let access = RecordAccess::of(
+        Some(auth.user.inner().id),
+        auth.has_permission(LIST_HELPER),
+        pagination.player,
+        &mut auth.connection,
+    )
+    .await?;
 
-        pagination.status = Some(RecordStatus::Approved);
-    }
+    access.restrict(&mut pagination)?;
 
     Ok(pagination_response("/api/v1/records/", pagination, &mut auth.connection).await?)
 }
@@ -67,11 +68,10 @@ pub async fn unauthed_pagination(
         return Err(CoreError::Unauthorized.into());
     }
 
-    if pagination.status.is_some() && pagination.status != Some(RecordStatus::Approved) {
-        return Err(CoreError::Unauthorized.into());
-    }
-
-    pagination.status = Some(RecordStatus::Approved);
+    // Signing in might grant access to the filtered records, so this is a 401 instead of a 403
+    RecordAccess::Public
+        .restrict(&mut pagination)
+        .map_err(|_| CoreError::Unauthorized)?;
 
     Ok(pagination_response("/api/v1/records/", pagination, &mut *connection).await?)
 }
@@ -79,9 +79,11 @@ pub async fn unauthed_pagination(
 #[rocket::post("/", data = "<submission>")]
 pub async fn submit(
     ip: IpAddr, auth: Option<TokenAuth>, submission: Json<Submission>, pool: &State<PointercratePool>,
-    ratelimits: &State<DemonlistRatelimits>,
-) -> Result<Tagged<FullRecord>> {
+    ratelimits: &State<DemonlistRatelimits>, policy: &State<SubmissionPolicy>, approval_policy: &State<ApprovalPolicy>,
+    reputation_policy: &State<ReputationPolicy>, challenge: &State<SubmissionChallenge>, challenge_response: ChallengeResponse,
+) -> Result<ApprovalResponse> {
     let submission = submission.0;
+    let approval_requested = submission.status() == RecordStatus::Approved;
     let (is_team_member, user_id) = match auth {
         Some(ref auth) => (auth.has_permission(LIST_HELPER), Some(auth.user.inner().id)),
         None => (false, None),
@@ -94,11 +96,23 @@ pub async fn submit(
         }
     }
 
+    // Logged in users are accountable for their submissions, so only anonymous ones need to prove
+    // that they are not a bot
+    if auth.is_none() {
+        challenge.verify(challenge_response.0.as_deref(), ip).await?;
+    }
+
     let mut connection = match auth {
         Some(auth) => auth.connection,
         None => pool.transaction().await?,
     };
 
+    // Range bans also apply to addresses that have not submitted anything yet, so check them before
+    // creating a new submitter
+    if RangeBan::covering(ip, &mut *connection).await?.is_some() {
+        return Err(DemonlistError::BannedFromSubmissions.into());
+    }
+
     let submitter = match Submitter::by_ip(ip, &mut *connection).await? {
         Some(submitter) => submitter,
         None => {
@@ -125,18 +139,23 @@ pub async fn submit(
         }
     }
 
-    let validated = normalized.validate(&mut *connection).await?;
+    let validated = normalized.validate(policy, &mut *connection).await?;
 
     if !is_team_member {
         // Check ratelimits before any change is made to the database so that the transaction rollback is
         // easier.
 
         // Also check the local ratelimit first since that one expires earlier
-        ratelimits.record_submission(ip)?;
+        ratelimits.record_submission(normalize_ip(ip))?;
         ratelimits.record_submission_global()?;
     }
 
-    let mut record = validated.create(submitter, &mut *connection).await?;
+    let mut record = validated.create(submitter, approval_policy, &mut *connection).await?;
+
+    let pending = match approval_requested {
+        true => record.pending_approval(approval_policy, &mut *connection).await?,
+        false => None,
+    };
 
     connection.commit().await.map_err(DemonlistError::from)?;
 
@@ -145,8 +164,10 @@ pub async fn submit(
         if let Some(ref video) = record.video {
             tokio::spawn(validate(
                 record.id,
+                submitter.id,
                 video.to_string(),
                 webhook_embed(&record),
+                reputation_policy.inner().clone(),
                 pool.connection().await?,
             ));
         }
@@ -154,16 +175,21 @@ pub async fn submit(
 
     if !is_team_member {
         record.submitter = None;
+        record.raw_footage.clear();
     }
 
-    Ok(Tagged(record))
+    Ok(approval_response(record, pending))
 }
 
+/// Retrieves a record
+///
+/// What parts of the record are visible depends on the [`RecordAccess`] of the requesting user to
+/// the records of the record's player. Records that are not visible at all 404.
 #[rocket::get("/<record_id>")]
 pub async fn get(record_id: i32, auth: Option<TokenAuth>, pool: &State<PointercratePool>) -> Result<Tagged<FullRecord>> {
-    let is_helper = match auth {
-        Some(ref auth) => auth.has_permission(LIST_HELPER),
-        _ => false,
+    let (is_helper, user_id) = match auth {
+        Some(ref auth) => (auth.has_permission(LIST_HELPER), Some(auth.user.inner().id)),
+        _ => (false, None),
     };
 
     let mut connection = match auth {
@@ -173,13 +199,9 @@ pub async fn get(record_id: i32, auth: Option<TokenAuth>, pool: &State<Pointercr
 
     let mut record = FullRecord::by_id(record_id, &mut *connection).await?;
 
-    // TODO: allow access if auth is provided and a verified claim on the record's player is given
-    if !is_helper {
-        if record.status != RecordStatus::Approved {
-            return Err(DemonlistError::RecordNotFound { record_id }.into());
-        }
-        record.submitter = None;
-    }
+    RecordAccess::of(user_id, is_helper, Some(record.player.id), &mut *connection)
+        .await?
+        .redact(&mut record)?;
 
     Ok(Tagged(record))
 }
@@ -197,28 +219,67 @@ pub async fn audit(record_id: i32, mut auth: TokenAuth) -> Result<Json<Vec<Audit
     Ok(Json(log))
 }
 
+/// Retrieves the history of status changes of a record
+///
+/// Available to list helpers and the verified claimant of the record's player. As opposed to the
+/// full audit log, this does not reveal who made the changes.
+#[rocket::get("/<record_id>/audit/status")]
+pub async fn status_history(record_id: i32, mut auth: TokenAuth) -> Result<Json<Vec<StatusChange>>> {
+    let record = FullRecord::by_id(record_id, &mut auth.connection).await?;
+    let access = RecordAccess::of(
+        Some(auth.user.inner().id),
+        auth.has_permission(LIST_HELPER),
+        Some(record.player.id),
+        &mut auth.connection,
+    )
+    .await?;
+
+    Ok(Json(access.status_history(&record, &mut auth.connection).await?))
+}
+
 #[rocket::patch("/<record_id>", data = "<patch>")]
 pub async fn patch(
-    record_id: i32, mut auth: TokenAuth, precondition: Precondition, patch: Json<PatchRecord>,
-) -> Result<Tagged<FullRecord>> {
+    record_id: i32, mut auth: TokenAuth, precondition: Precondition, patch: Json<PatchRecord>, approval_policy: &State<ApprovalPolicy>,
+    reputation_policy: &State<ReputationPolicy>,
+) -> Result<ApprovalResponse> {
     let record = FullRecord::by_id(record_id, &mut auth.connection).await?;
+    let previous_status = record.status;
+    let approval_requested = patch.0.requests_approval();
+    let list = record.demon.list(&mut auth.connection).await?;
 
-    if record.demon.position > pointercrate_demonlist::config::extended_list_size() {
+    if record.demon.position > list.extended_list_size {
         auth.require_permission(LIST_MODERATOR)?;
     } else {
         auth.require_permission(LIST_HELPER)?;
     }
 
+    // Records locked for review by someone else can only be modified by moderators
+    if !auth.has_permission(LIST_MODERATOR) {
+        ReviewLock::ensure_unlocked_for(record_id, auth.user.inner().id, &mut auth.connection).await?;
+    }
+
     let record = record
         .require_match(precondition)?
-        .apply_patch(patch.0, &mut auth.connection)
+        .apply_patch(patch.0, approval_policy, &mut auth.connection)
         .await?;
 
+    // Rejecting a submission might push its submitter over the thresholds of the reputation policy
+    if record.status == RecordStatus::Rejected && previous_status != RecordStatus::Rejected {
+        if let Some(submitter) = record.submitter {
+            reputation_policy.enforce(submitter.id, &mut auth.connection).await?;
+        }
+    }
+
+    let pending = match approval_requested {
+        true => record.pending_approval(approval_policy, &mut auth.connection).await?,
+        false => None,
+    };
+
     auth.commit().await?;
 
     tokio::spawn(execute_webhook(webhook_status_embed(&record)));
 
-    Ok(Tagged(record))
+    Ok(approval_response(record, pending))
 }
 
 #[rocket::delete("/<record_id>")]
@@ -239,6 +300,149 @@ pub async fn delete(record_id: i32, mut auth: TokenAuth, precondition: Precondit
     Ok(Status::NoContent)
 }
 
+#[rocket::get("/<record_id>/votes")]
+pub async fn get_votes(record_id: i32, mut auth: TokenAuth) -> Result<Json<Vec<ApprovalVote>>> {
+    auth.require_permission(LIST_HELPER)?;
+
+    // Make sure we 404 on non-existing records
+    FullRecord::by_id(record_id, &mut auth.connection).await?;
+
+    Ok(Json(votes_on(record_id, &mut auth.connection).await?))
+}
+
+/// Casts a vote on whether the given record should be approved
+///
+/// Approving votes are equivalent to setting the record's status to 'approved', meaning the record
+/// gets approved once it has gathered the number of approvals the [`ApprovalPolicy`] requires.
+/// Dissenting votes are only recorded.
+#[rocket::post("/<record_id>/votes", data = "<vote>")]
+pub async fn vote(
+    record_id: i32, mut auth: TokenAuth, vote: Json<CastVote>, approval_policy: &State<ApprovalPolicy>,
+) -> Result<ApprovalResponse> {
+    let mut record = FullRecord::by_id(record_id, &mut auth.connection).await?;
+    let list = record.demon.list(&mut auth.connection).await?;
+
+    if record.demon.position > list.extended_list_size {
+        auth.require_permission(LIST_MODERATOR)?;
+    } else {
+        auth.require_permission(LIST_HELPER)?;
+    }
+
+    if !auth.has_permission(LIST_MODERATOR) {
+        ReviewLock::ensure_unlocked_for(record_id, auth.user.inner().id, &mut auth.connection).await?;
+    }
+
+    let pending = match vote.0.approve {
+        true => record.approve(approval_policy, &mut auth.connection).await?,
+        false => {
+            record.cast_vote(false, &mut auth.connection).await?;
+
+            None
+        },
+    };
+
+    if vote.0.approve && pending.is_none() {
+        record.player.update_score(&mut auth.connection).await?;
+    }
+
+    auth.commit().await?;
+
+    Ok(approval_response(record, pending))
+}
+
+/// Response to a request attempting to approve a record, which under the [`ApprovalPolicy`] might
+/// only have cast an approving vote
+type ApprovalResponse = Either<Tagged<FullRecord>, Response2<Json<serde_json::Value>>>;
+
+/// Responds with the record if it was approved (or approval was not requested), and with a `202
+/// Accepted` that contains the record together with its approval progress if it still awaits
+/// further approvals
+fn approval_response(record: FullRecord, pending: Option<PendingApproval>) -> ApprovalResponse {
+    match pending {
+        None => Either::Left(Tagged(record)),
+        Some(pending) => {
+            let etag = record.etag_string();
+
+            Either::Right(
+                Response2::json(serde_json::json! {{"data": record, "pending_approval": pending}})
+                    .status(Status::Accepted)
+                    .with_header("etag", etag),
+            )
+        },
+    }
+}
+
+/// Claims the next record from the review queue, locking it to the requesting user
+#[rocket::post("/queue/claim")]
+pub async fn claim_next(mut auth: TokenAuth, query: Query<ClaimQuery>) -> Result<Json<ReviewLock>> {
+    auth.require_permission(LIST_HELPER)?;
+
+    let lock = ReviewLock::claim_next(auth.user.inner().id, query.0.order, &mut auth.connection).await?;
+
+    auth.commit().await?;
+
+    Ok(Json(lock))
+}
+
+#[rocket::get("/queue/statistics")]
+pub async fn queue_statistics(mut auth: TokenAuth) -> Result<Json<QueueStatistics>> {
+    auth.require_permission(LIST_HELPER)?;
+
+    Ok(Json(QueueStatistics::compute(&mut auth.connection).await?))
+}
+
+#[rocket::get("/<record_id>/lock")]
+pub async fn get_lock(record_id: i32, mut auth: TokenAuth) -> Result<Json<ReviewLock>> {
+    auth.require_permission(LIST_HELPER)?;
+
+    match ReviewLock::active_on(record_id, &mut auth.connection).await? {
+        Some(lock) => Ok(Json(lock)),
+        None => Err(DemonlistError::ReviewLockNotFound { record_id }.into()),
+    }
+}
+
+/// Assigns a record from the review queue to a specific list team member
+///
+/// Only list moderators can assign records, and they can only be assigned to users with at least
+/// `LIST_HELPER` permissions.
+#[rocket::put("/<record_id>/lock", data = "<data>")]
+pub async fn assign_reviewer(record_id: i32, mut auth: TokenAuth, data: Json<AssignReviewer>) -> Result<Json<ReviewLock>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    let reviewer = User::by_id(data.0.reviewer, &mut auth.connection).await?;
+
+    if auth.permissions.require_permission(reviewer.permissions, LIST_HELPER).is_err() {
+        return Err(DemonlistError::NotAReviewer.into());
+    }
+
+    let lock = ReviewLock::assign(record_id, reviewer.id, &mut auth.connection).await?;
+
+    auth.commit().await?;
+
+    Ok(Json(lock))
+}
+
+/// Releases the review lock on a record, putting it back into the review queue
+///
+/// Locks can be released by their holder, or by list moderators.
+#[rocket::delete("/<record_id>/lock")]
+pub async fn release_lock(record_id: i32, mut auth: TokenAuth) -> Result<Status> {
+    auth.require_permission(LIST_HELPER)?;
+
+    let lock = ReviewLock::active_on(record_id, &mut auth.connection)
+        .await?
+        .ok_or(DemonlistError::ReviewLockNotFound { record_id })?;
+
+    if lock.reviewer != auth.user.inner().id {
+        auth.require_permission(LIST_MODERATOR)?;
+    }
+
+    lock.release(&mut auth.connection).await?;
+    auth.commit().await?;
+
+    Ok(Status::NoContent)
+}
+
 #[rocket::get("/<record_id>/notes")]
 pub async fn get_notes(record_id: i32, mut auth: TokenAuth) -> Result<Response2<Json<Vec<Note>>>> {
     let record_holder_id = sqlx::query!("SELECT player FROM records WHERE id = $1", record_id)
@@ -253,15 +457,15 @@ pub async fn get_notes(record_id: i32, mut auth: TokenAuth) -> Result<Response2<
         })?
         .player;
 
-    let notes = if auth.has_permission(LIST_HELPER) {
-        notes_on(record_id, false, &mut auth.connection).await?
-    } else {
-        match PlayerClaim::get(auth.user.inner().id, record_holder_id, &mut auth.connection).await {
-            Ok(claim) if claim.verified => notes_on(record_id, true, &mut auth.connection).await?,
-            Ok(_) | Err(DemonlistError::ClaimNotFound { .. }) => return Err(DemonlistError::RecordNotFound { record_id }.into()),
-            Err(err) => return Err(err.into()),
-        }
-    };
+    let notes = RecordAccess::of(
+        Some(auth.user.inner().id),
+        auth.has_permission(LIST_HELPER),
+        Some(record_holder_id),
+        &mut auth.connection,
+    )
+    .await?
+    .notes_on(record_id, &mut auth.connection)
+    .await?;
 
     Ok(Response2::json(notes))
 }
@@ -319,7 +523,10 @@ pub async fn delete_note(record_id: i32, note_id: i32, mut auth: TokenAuth) -> R
     Ok(Status::NoContent)
 }
 
-async fn validate(record_id: i32, video: String, body: serde_json::Value, mut connection: PoolConnection<Postgres>) {
+async fn validate(
+    record_id: i32, submitter_id: i32, video: String, body: serde_json::Value, reputation_policy: ReputationPolicy,
+    mut connection: PoolConnection<Postgres>,
+) {
     debug!("Verifying that submission {} with video {} actually is valid", record_id, video);
 
     match reqwest::get(&video).await {
@@ -333,7 +540,7 @@ async fn validate(record_id: i32, video: String, body: serde_json::Value, mut co
             } else {
                 warn!("Server response to 'GET {}' was {:?}, deleting submission!", video, response);
 
-                match FullRecord::delete_by_id(record_id, &mut *connection).await {
+                match delete_invalid(record_id, submitter_id, &reputation_policy, &mut connection).await {
                     Ok(_) => (),
                     Err(error) => error!("INTERNAL SERVER ERROR: Failure to delete record - {:?}!", error),
                 }
@@ -345,7 +552,7 @@ async fn validate(record_id: i32, video: String, body: serde_json::Value, mut co
                 error
             );
 
-            match FullRecord::delete_by_id(record_id, &mut *connection).await {
+            match delete_invalid(record_id, submitter_id, &reputation_policy, &mut connection).await {
                 Ok(_) => (),
                 Err(error) => error!("INTERNAL SERVER ERROR: Failure to delete record - {:?}!", error),
             }
@@ -353,6 +560,15 @@ async fn validate(record_id: i32, video: String, body: serde_json::Value, mut co
     }
 }
 
+async fn delete_invalid(
+    record_id: i32, submitter_id: i32, reputation_policy: &ReputationPolicy, connection: &mut PgConnection,
+) -> std::result::Result<(), DemonlistError> {
+    FullRecord::delete_invalid(record_id, &mut *connection).await?;
+    reputation_policy.enforce(submitter_id, connection).await?;
+
+    Ok(())
+}
+
 async fn execute_webhook(body: serde_json::Value) {
     if let Some(ref webhook_url) = crate::config::submission_webhook() {
         match reqwest::Client::new()
@@ -0,0 +1,32 @@
+use pointercrate_core::pool::PointercratePool;
+use pointercrate_core_api::{error::Result, response::Response2};
+use pointercrate_demonlist::{
+    record::rejection::{PostRejectionReason, RejectionReason, RejectionReasonStatistics},
+    LIST_HELPER, LIST_MODERATOR,
+};
+use pointercrate_user_api::auth::TokenAuth;
+use rocket::{http::Status, serde::json::Json, State};
+
+#[rocket::get("/")]
+pub async fn get_all(pool: &State<PointercratePool>) -> Result<Json<Vec<RejectionReason>>> {
+    Ok(Json(RejectionReason::all(&mut *pool.connection().await?).await?))
+}
+
+#[rocket::post("/", data = "<data>")]
+pub async fn post(mut auth: TokenAuth, data: Json<PostRejectionReason>) -> Result<Response2<Json<RejectionReason>>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    let reason = RejectionReason::create_from(data.0, &mut auth.connection).await?;
+
+    auth.commit().await?;
+
+    Ok(Response2::json(reason).status(Status::Created))
+}
+
+/// Reports how many records are currently rejected for each reason
+#[rocket::get("/statistics")]
+pub async fn statistics(mut auth: TokenAuth) -> Result<Json<Vec<RejectionReasonStatistics>>> {
+    auth.require_permission(LIST_HELPER)?;
+
+    Ok(Json(RejectionReasonStatistics::compute(&mut auth.connection).await?))
+}
@@ -6,11 +6,12 @@ use pointercrate_core_api::{
     response::Response2,
 };
 use pointercrate_demonlist::{
+    ban::{Ban, PostBan},
     submitter::{PatchSubmitter, Submitter, SubmitterPagination},
     LIST_MODERATOR,
 };
 use pointercrate_user_api::auth::TokenAuth;
-use rocket::serde::json::Json;
+use rocket::{http::Status, serde::json::Json};
 
 #[rocket::get("/")]
 pub async fn paginate(mut auth: TokenAuth, pagination: Query<SubmitterPagination>) -> Result<Response2<Json<Vec<Submitter>>>> {
@@ -42,3 +43,28 @@ pub async fn patch(
 
     Ok(Tagged(submitter))
 }
+
+/// Lists all bans ever issued against the given submitter, most recent first
+#[rocket::get("/<submitter_id>/bans")]
+pub async fn bans(submitter_id: i32, mut auth: TokenAuth) -> Result<Json<Vec<Ban>>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    let submitter = Submitter::by_id(submitter_id, &mut auth.connection).await?;
+
+    Ok(Json(submitter.bans(&mut auth.connection).await?))
+}
+
+/// Bans the given submitter, replacing their current ban if they are already banned
+#[rocket::post("/<submitter_id>/bans", data = "<ban>")]
+pub async fn ban(submitter_id: i32, mut auth: TokenAuth, ban: Json<PostBan>) -> Result<Response2<Json<Ban>>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    let mut submitter = Submitter::by_id(submitter_id, &mut auth.connection).await?;
+    let ban = submitter.issue_ban(ban.0, &mut auth.connection).await?;
+
+    auth.commit().await?;
+
+    Ok(Response2::json(ban)
+        .status(Status::Created)
+        .with_header("Location", format!("/api/v1/submitters/{}/bans/", submitter.id)))
+}
@@ -0,0 +1,23 @@
+use pointercrate_core_api::error::Result;
+use pointercrate_demonlist::{
+    video::availability::{history, unavailable_videos, UnavailableVideo, VideoCheck},
+    LIST_MODERATOR,
+};
+use pointercrate_user_api::auth::TokenAuth;
+use rocket::serde::json::Json;
+
+/// Lists all videos of approved records and demons that were found to be dead or unavailable
+#[rocket::get("/unavailable")]
+pub async fn unavailable(mut auth: TokenAuth) -> Result<Json<Vec<UnavailableVideo>>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    Ok(Json(unavailable_videos(&mut auth.connection).await?))
+}
+
+/// Retrieves the availability history of a single video
+#[rocket::get("/history?<video>")]
+pub async fn availability_history(video: String, mut auth: TokenAuth) -> Result<Json<Vec<VideoCheck>>> {
+    auth.require_permission(LIST_MODERATOR)?;
+
+    Ok(Json(history(&video, &mut auth.connection).await?))
+}
@@ -0,0 +1,23 @@
+use crate::config;
+use pointercrate_demonlist::nationality::geolocation::Geolocator;
+use pointercrate_integrate::geolocation::{AbstractApi, MaxMindDatabase};
+
+/// Constructs the [`Geolocator`] configured via environment variables
+///
+/// If `GEOLOCATION_DATABASE` is set, IP addresses are looked up offline in the MaxMind database at
+/// the given path (with VPN detection via the anonymous IP database at `ANONYMOUS_IP_DATABASE`, if
+/// set). Otherwise, if `ABSTRACT_API_KEY` is set, abstract's IP geolocation API is used. If neither
+/// is set, geolocation is disabled.
+pub fn configured_geolocator() -> Geolocator {
+    if let Some(database) = config::geolocation_database() {
+        let provider = MaxMindDatabase::open(&database, config::anonymous_ip_database())
+            .unwrap_or_else(|err| panic!("Failed to open geolocation database at '{}': {}", database, err));
+
+        return Geolocator::new(provider);
+    }
+
+    match config::abstract_api_key() {
+        Some(api_key) => Geolocator::new(AbstractApi::new(api_key)),
+        None => Geolocator::disabled(),
+    }
+}
@@ -1,17 +1,90 @@
-use crate::{endpoints::misc, ratelimits::DemonlistRatelimits};
+use crate::{ban_expiry::BanExpiry, dead_links::DeadLinkChecker, endpoints::misc, ratelimits::DemonlistRatelimits};
 use pointercrate_core::pool::PointercratePool;
-use pointercrate_integrate::gd::GeometryDashConnector;
-use rocket::{Build, Rocket};
+use pointercrate_demonlist::{
+    nationality::geolocation::Geolocator,
+    record::{approval::ApprovalPolicy, challenge::SubmissionChallenge, policy::SubmissionPolicy},
+    submitter::ReputationPolicy,
+    video::thumbnail::ThumbnailResolver,
+};
+use pointercrate_integrate::{claim::ClaimVerifier, gd::GeometryDashConnector};
+use rocket::{fairing::AdHoc, Build, Rocket};
 
+pub mod ban_expiry;
+pub mod challenge;
 pub(crate) mod config;
+pub mod dead_links;
 mod endpoints;
+pub mod geolocation;
 pub(crate) mod pages;
 pub(crate) mod ratelimits;
+pub mod thumbnails;
+pub mod upload_dates;
 
+/// Registers all demonlist related endpoints
+///
+/// If no [`SubmissionPolicy`], [`ApprovalPolicy`] or [`ReputationPolicy`] was registered with the
+/// given rocket instance, pointercrate's default policies are used. If no [`ThumbnailResolver`] was
+/// registered, the one constructed by [`http_thumbnail_resolver`](thumbnails::http_thumbnail_resolver)
+/// is used. If no [`SubmissionChallenge`] was registered, the one configured via environment variables (see
+/// [`configured_challenge`](challenge::configured_challenge)) is used. If no [`ClaimVerifier`] was registered, claims are
+/// verified against the official Geometry Dash servers. If no [`Geolocator`] was registered, the one configured
+/// via environment variables (see [`configured_geolocator`](geolocation::configured_geolocator)) is used. If a
+/// [`DeadLinkChecker`] was registered, it is started once the server launches. Expired bans are lifted periodically,
+/// as configured by the registered [`BanExpiry`] (or its default).
 pub fn setup(rocket: Rocket<Build>) -> Rocket<Build> {
     let ratelimits = DemonlistRatelimits::new();
     let dash_rs = GeometryDashConnector::new(rocket.state::<PointercratePool>().unwrap().clone_inner());
 
+    let rocket = match rocket.state::<SubmissionPolicy>() {
+        Some(_) => rocket,
+        None => rocket.manage(SubmissionPolicy::default()),
+    };
+
+    let rocket = match rocket.state::<ApprovalPolicy>() {
+        Some(_) => rocket,
+        None => rocket.manage(ApprovalPolicy::default()),
+    };
+
+    let rocket = match rocket.state::<ReputationPolicy>() {
+        Some(_) => rocket,
+        None => rocket.manage(ReputationPolicy::default()),
+    };
+
+    let rocket = match rocket.state::<ThumbnailResolver>() {
+        Some(_) => rocket,
+        None => rocket.manage(thumbnails::http_thumbnail_resolver()),
+    };
+
+    let rocket = match rocket.state::<SubmissionChallenge>() {
+        Some(_) => rocket,
+        None => rocket.manage(challenge::configured_challenge()),
+    };
+
+    let rocket = match rocket.state::<ClaimVerifier>() {
+        Some(_) => rocket,
+        None => rocket.manage(ClaimVerifier::new(dash_rs.clone())),
+    };
+
+    let rocket = match rocket.state::<Geolocator>() {
+        Some(_) => rocket,
+        None => rocket.manage(geolocation::configured_geolocator()),
+    };
+
+    let rocket = match rocket.state::<DeadLinkChecker>().cloned() {
+        Some(checker) => {
+            let pool = PointercratePool::from(rocket.state::<PointercratePool>().unwrap().clone_inner());
+
+            rocket.attach(AdHoc::on_liftoff("Dead link checker", |_| {
+                Box::pin(async move { checker.spawn(pool) })
+            }))
+        },
+        None => rocket,
+    };
+
+    let ban_expiry = rocket.state::<BanExpiry>().cloned().unwrap_or_default();
+    let pool = PointercratePool::from(rocket.state::<PointercratePool>().unwrap().clone_inner());
+    let rocket = rocket.attach(AdHoc::on_liftoff("Ban expiry", |_| Box::pin(async move { ban_expiry.spawn(pool) })));
+
     rocket
         .manage(ratelimits)
         .manage(dash_rs)
@@ -21,7 +94,18 @@ pub fn setup(rocket: Rocket<Build>) -> Rocket<Build> {
             rocket::routes![
                 endpoints::submitter::paginate,
                 endpoints::submitter::get,
-                endpoints::submitter::patch
+                endpoints::submitter::patch,
+                endpoints::submitter::bans,
+                endpoints::submitter::ban
+            ],
+        )
+        .mount(
+            "/api/v1/range_bans/",
+            rocket::routes![
+                endpoints::range_ban::get_all,
+                endpoints::range_ban::get,
+                endpoints::range_ban::post,
+                endpoints::range_ban::lift
             ],
         )
         .mount(
@@ -30,6 +114,7 @@ pub fn setup(rocket: Rocket<Build>) -> Rocket<Build> {
                 endpoints::record::get_notes,
                 endpoints::record::add_note,
                 endpoints::record::audit,
+                endpoints::record::status_history,
                 endpoints::record::delete,
                 endpoints::record::delete_note,
                 endpoints::record::get,
@@ -37,20 +122,60 @@ pub fn setup(rocket: Rocket<Build>) -> Rocket<Build> {
                 endpoints::record::unauthed_pagination,
                 endpoints::record::patch,
                 endpoints::record::patch_note,
-                endpoints::record::submit
+                endpoints::record::submit,
+                endpoints::record::claim_next,
+                endpoints::record::queue_statistics,
+                endpoints::record::get_lock,
+                endpoints::record::assign_reviewer,
+                endpoints::record::release_lock,
+                endpoints::record::get_votes,
+                endpoints::record::vote
             ],
         )
+        .mount(
+            "/api/v1/rejection_reasons/",
+            rocket::routes![
+                endpoints::rejection_reason::get_all,
+                endpoints::rejection_reason::post,
+                endpoints::rejection_reason::statistics
+            ],
+        )
+        .mount(
+            "/api/v1/videos/",
+            rocket::routes![endpoints::video::unavailable, endpoints::video::availability_history],
+        )
         .mount(
             "/api/v1/players/",
             rocket::routes![
                 endpoints::player::get,
                 endpoints::player::paginate,
                 endpoints::player::patch,
+                endpoints::player::aliases,
+                endpoints::player::nationality_history,
+                endpoints::player::bans,
+                endpoints::player::ban,
+                endpoints::player::patch_alias,
+                endpoints::player::preview_merge,
+                endpoints::player::merge,
+                endpoints::player::merges,
+                endpoints::player::undo_merge,
                 endpoints::player::ranking,
                 endpoints::player::put_claim,
                 endpoints::player::patch_claim,
+                endpoints::player::verify_claim,
                 endpoints::player::paginate_claims,
                 endpoints::player::delete_claim,
+                endpoints::player::claim_audit,
+                endpoints::player::dispute_claim,
+                endpoints::player::player_disputes,
+                endpoints::player::open_disputes,
+                endpoints::player::get_dispute,
+                endpoints::player::resolve_dispute,
+                endpoints::player::suggest_edit,
+                endpoints::player::player_suggestions,
+                endpoints::player::pending_suggestions,
+                endpoints::player::get_suggestion,
+                endpoints::player::review_suggestion,
                 endpoints::player::geolocate_nationality
             ],
         )
@@ -62,6 +187,10 @@ pub fn setup(rocket: Rocket<Build>) -> Rocket<Build> {
                 endpoints::nationality::nation
             ],
         )
+        .mount(
+            "/api/v1/continents/",
+            rocket::routes![endpoints::continent::ranking, endpoints::continent::continent],
+        )
         .mount(
             "/api/v2/demons/",
             rocket::routes![
@@ -76,6 +205,17 @@ pub fn setup(rocket: Rocket<Build>) -> Rocket<Build> {
                 endpoints::demon::delete_creator
             ],
         )
+        .mount(
+            "/api/v2/lists/",
+            rocket::routes![
+                endpoints::list::get_all,
+                endpoints::list::get,
+                endpoints::list::post,
+                endpoints::list::patch,
+                endpoints::list::paginate_listed,
+                endpoints::list::ranking
+            ],
+        )
         .mount(
             "/demonlist/",
             rocket::routes![
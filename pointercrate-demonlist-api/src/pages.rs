@@ -12,7 +12,9 @@ use pointercrate_core_pages::head::HeadLike;
 use pointercrate_demonlist::{
     demon::{audit::audit_log_for_demon, current_list, list_at, FullDemon, MinimalDemon},
     error::DemonlistError,
-    nationality::Nationality,
+    list::{List, DEFAULT_LIST},
+    nationality::{Continent, Nationality},
+    record::challenge::SubmissionChallenge,
     LIST_ADMINISTRATOR, LIST_HELPER, LIST_MODERATOR,
 };
 use pointercrate_demonlist_pages::{
@@ -35,13 +37,14 @@ pub fn stats_viewer_redirect() -> Redirect {
 #[rocket::get("/?<timemachine>&<submitter>")]
 pub async fn overview(
     pool: &State<PointercratePool>, timemachine: Option<bool>, submitter: Option<bool>, cookies: &CookieJar<'_>, auth: Option<TokenAuth>,
+    challenge: &State<SubmissionChallenge>,
 ) -> Result<Page> {
     // A few months before pointercrate first went live - definitely the oldest data we have
     let beginning_of_time = NaiveDate::from_ymd_opt(2019, 4, 19).unwrap().and_hms_opt(0, 0, 0).unwrap();
 
     let mut connection = pool.connection().await?;
 
-    let demonlist = current_list(&mut *connection).await?;
+    let demonlist = current_list(DEFAULT_LIST, &mut *connection).await?;
 
     let mut specified_when = cookies
         .get("when")
@@ -72,7 +75,7 @@ pub async fn overview(
     let mut tardis = Tardis::new(timemachine.unwrap_or(false));
 
     if let Some(destination) = specified_when {
-        let demons_then = list_at(&mut *connection, destination.naive_utc()).await?;
+        let demons_then = list_at(DEFAULT_LIST, &mut *connection, destination.naive_utc()).await?;
         tardis.activate(destination, demons_then, !is_april_1st)
     }
 
@@ -82,9 +85,11 @@ pub async fn overview(
             moderators: User::by_permission(LIST_MODERATOR, &mut *connection).await?,
             helpers: User::by_permission(LIST_HELPER, &mut *connection).await?,
         },
+        list: List::by_id(DEFAULT_LIST, &mut *connection).await?,
         demonlist,
         time_machine: tardis,
         submitter_initially_visible: submitter.unwrap_or(false),
+        challenge: auth.is_none().then(|| challenge.widget()).flatten(),
     });
 
     if let Some(token_auth) = auth {
@@ -97,6 +102,7 @@ pub async fn overview(
 #[rocket::get("/permalink/<demon_id>")]
 pub async fn demon_permalink(
     demon_id: i32, pool: &State<PointercratePool>, gd: &State<GeometryDashConnector>, auth: Option<TokenAuth>,
+    challenge: &State<SubmissionChallenge>,
 ) -> Result<Page> {
     let mut connection = pool.connection().await?;
 
@@ -144,10 +150,12 @@ pub async fn demon_permalink(
             moderators: User::by_permission(LIST_MODERATOR, &mut *connection).await?,
             helpers: User::by_permission(LIST_HELPER, &mut *connection).await?,
         },
-        demonlist: current_list(&mut *connection).await?,
+        list: List::by_id(full_demon.demon.list, &mut *connection).await?,
+        demonlist: current_list(full_demon.demon.list, &mut *connection).await?,
         movements: modifications,
         integration: gd.load_level_for_demon(&full_demon.demon).await,
         data: full_demon,
+        challenge: auth.is_none().then(|| challenge.widget()).flatten(),
     });
 
     if let Some(token_auth) = auth {
@@ -161,7 +169,7 @@ pub async fn demon_permalink(
 pub async fn demon_page(position: i16, pool: &State<PointercratePool>) -> Result<Redirect> {
     let mut connection = pool.connection().await?;
 
-    let id = MinimalDemon::by_position(position, &mut connection).await?.id;
+    let id = MinimalDemon::by_position(DEFAULT_LIST, position, &mut connection).await?.id;
 
     Ok(Redirect::to(rocket::uri!("/demonlist", demon_permalink(id))))
 }
@@ -172,12 +180,17 @@ pub async fn stats_viewer(pool: &State<PointercratePool>) -> Result<Page> {
 
     Ok(Page::new(IndividualStatsViewer {
         nationalities_in_use: Nationality::used(&mut *connection).await?,
+        continents: Continent::all(&mut *connection).await?,
     }))
 }
 
 #[rocket::get("/statsviewer/nations")]
-pub async fn nation_stats_viewer() -> Page {
-    Page::new(pointercrate_demonlist_pages::statsviewer::national::nation_based_stats_viewer())
+pub async fn nation_stats_viewer(pool: &State<PointercratePool>) -> Result<Page> {
+    let continents = Continent::all(&mut *pool.connection().await?).await?;
+
+    Ok(Page::new(
+        pointercrate_demonlist_pages::statsviewer::national::nation_based_stats_viewer(&continents),
+    ))
 }
 
 #[rocket::get("/statsviewer/heatmap.css")]
@@ -12,6 +12,8 @@ ratelimits! {
 
         geolocate[1u32 per 2_678_400 per IpAddr] => "You can only geolocate once per month!",
 
+        claim_verification[3u32 per 600 per i32] => "You can only attempt to verify your claim three times every 10 minutes!",
+
         add_demon[1u32 per 60] => "Please don't spam the button, rSteel",
     }
 }
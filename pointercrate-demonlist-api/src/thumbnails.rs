@@ -0,0 +1,141 @@
+//! [`ThumbnailStrategy`]s for video hosts whose thumbnails can only be determined by querying the
+//! host's API
+
+use crate::config;
+use log::warn;
+use pointercrate_demonlist::video::{
+    thumbnail::{ThumbnailResolver, ThumbnailStrategy},
+    VideoIdentity,
+};
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Constructs a [`ThumbnailResolver`] supporting YouTube, Vimeo and Bilibili videos, as well as
+/// Twitch videos if Twitch API credentials are configured
+pub fn http_thumbnail_resolver() -> ThumbnailResolver {
+    let client = Client::builder().timeout(Duration::from_secs(10)).build().unwrap();
+
+    let resolver = ThumbnailResolver::default()
+        .with("vimeo", VimeoThumbnails { client: client.clone() })
+        .with("bilibili", BilibiliThumbnails { client: client.clone() });
+
+    match (config::twitch_client_id(), config::twitch_access_token()) {
+        (Some(client_id), Some(access_token)) => resolver.with(
+            "twitch",
+            TwitchThumbnails {
+                client,
+                client_id,
+                access_token,
+            },
+        ),
+        _ => resolver,
+    }
+}
+
+pub(crate) async fn fetch_json<T: for<'de> Deserialize<'de>>(request: reqwest::RequestBuilder) -> Option<T> {
+    let result = async { request.send().await?.error_for_status()?.json::<T>().await }.await;
+
+    match result {
+        Ok(json) => Some(json),
+        Err(err) => {
+            warn!("Failed to query video host API: {}", err);
+
+            None
+        },
+    }
+}
+
+/// Retrieves thumbnails of Vimeo videos via Vimeo's oEmbed endpoint
+struct VimeoThumbnails {
+    client: Client,
+}
+
+#[derive(Deserialize)]
+struct OEmbedResponse {
+    thumbnail_url: Option<String>,
+}
+
+#[rocket::async_trait]
+impl ThumbnailStrategy for VimeoThumbnails {
+    async fn thumbnail(&self, video: &VideoIdentity) -> Option<String> {
+        let endpoint = video.oembed_endpoint()?;
+        let request = self.client.get(endpoint).query(&[("url", video.url())]);
+
+        fetch_json::<OEmbedResponse>(request).await?.thumbnail_url
+    }
+}
+
+/// Retrieves the cover images of Bilibili videos via Bilibili's web API
+struct BilibiliThumbnails {
+    client: Client,
+}
+
+#[derive(Deserialize)]
+struct BilibiliResponse {
+    data: Option<BilibiliVideo>,
+}
+
+#[derive(Deserialize)]
+struct BilibiliVideo {
+    pic: String,
+}
+
+#[rocket::async_trait]
+impl ThumbnailStrategy for BilibiliThumbnails {
+    async fn thumbnail(&self, video: &VideoIdentity) -> Option<String> {
+        // Bilibili videos are either identified by a 'BV' ID, or by a legacy numeric 'av' ID
+        let query = match video.video_id.strip_prefix("av") {
+            Some(aid) => ("aid", aid),
+            None => ("bvid", video.video_id.as_str()),
+        };
+
+        let request = self.client.get("https://api.bilibili.com/x/web-interface/view").query(&[query]);
+
+        // Bilibili links its cover images via plain http
+        fetch_json::<BilibiliResponse>(request)
+            .await?
+            .data
+            .map(|video| video.pic.replacen("http://", "https://", 1))
+    }
+}
+
+/// Retrieves thumbnails of Twitch VODs via the Twitch Helix API
+///
+/// Requires a Twitch application's client ID and an app access token
+struct TwitchThumbnails {
+    client: Client,
+    client_id: String,
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct TwitchResponse {
+    data: Vec<TwitchVideo>,
+}
+
+#[derive(Deserialize)]
+struct TwitchVideo {
+    thumbnail_url: String,
+}
+
+#[rocket::async_trait]
+impl ThumbnailStrategy for TwitchThumbnails {
+    async fn thumbnail(&self, video: &VideoIdentity) -> Option<String> {
+        let request = self
+            .client
+            .get("https://api.twitch.tv/helix/videos")
+            .query(&[("id", &video.video_id)])
+            .header("Client-Id", &self.client_id)
+            .bearer_auth(&self.access_token);
+
+        let video = fetch_json::<TwitchResponse>(request).await?.data.into_iter().next()?;
+
+        // Twitch thumbnail links are templates with placeholders for the desired dimensions.
+        // Thumbnails of VODs that are still being processed are empty.
+        match video.thumbnail_url.is_empty() {
+            true => None,
+            false => Some(video.thumbnail_url.replace("%{width}", "320").replace("%{height}", "180")),
+        }
+    }
+}
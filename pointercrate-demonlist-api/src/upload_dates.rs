@@ -0,0 +1,172 @@
+//! [`UploadDateStrategy`]s querying the APIs of video hosts
+
+use crate::{config, thumbnails::fetch_json};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use pointercrate_demonlist::video::{
+    upload_date::{UploadDateResolver, UploadDateStrategy},
+    VideoIdentity,
+};
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Constructs an [`UploadDateResolver`] supporting Vimeo and Bilibili videos, as well as YouTube
+/// and Twitch videos if the respective API credentials are configured
+pub fn http_upload_date_resolver() -> UploadDateResolver {
+    let client = Client::builder().timeout(Duration::from_secs(10)).build().unwrap();
+
+    let mut resolver = UploadDateResolver::default()
+        .with("vimeo", VimeoUploadDates { client: client.clone() })
+        .with("bilibili", BilibiliUploadDates { client: client.clone() });
+
+    if let Some(api_key) = config::youtube_api_key() {
+        resolver = resolver.with(
+            "youtube",
+            YouTubeUploadDates {
+                client: client.clone(),
+                api_key,
+            },
+        );
+    }
+
+    match (config::twitch_client_id(), config::twitch_access_token()) {
+        (Some(client_id), Some(access_token)) => resolver.with(
+            "twitch",
+            TwitchUploadDates {
+                client,
+                client_id,
+                access_token,
+            },
+        ),
+        _ => resolver,
+    }
+}
+
+/// Retrieves upload dates of YouTube videos via the YouTube Data API
+///
+/// Requires an API key
+struct YouTubeUploadDates {
+    client: Client,
+    api_key: String,
+}
+
+#[derive(Deserialize)]
+struct YouTubeResponse {
+    items: Vec<YouTubeVideo>,
+}
+
+#[derive(Deserialize)]
+struct YouTubeVideo {
+    snippet: YouTubeSnippet,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YouTubeSnippet {
+    published_at: DateTime<Utc>,
+}
+
+#[rocket::async_trait]
+impl UploadDateStrategy for YouTubeUploadDates {
+    async fn upload_date(&self, video: &VideoIdentity) -> Option<NaiveDateTime> {
+        let request = self.client.get("https://www.googleapis.com/youtube/v3/videos").query(&[
+            ("part", "snippet"),
+            ("id", video.video_id.as_str()),
+            ("key", self.api_key.as_str()),
+        ]);
+
+        let video = fetch_json::<YouTubeResponse>(request).await?.items.into_iter().next()?;
+
+        Some(video.snippet.published_at.naive_utc())
+    }
+}
+
+/// Retrieves upload dates of Vimeo videos via Vimeo's oEmbed endpoint
+struct VimeoUploadDates {
+    client: Client,
+}
+
+#[derive(Deserialize)]
+struct OEmbedResponse {
+    upload_date: Option<String>,
+}
+
+#[rocket::async_trait]
+impl UploadDateStrategy for VimeoUploadDates {
+    async fn upload_date(&self, video: &VideoIdentity) -> Option<NaiveDateTime> {
+        let endpoint = video.oembed_endpoint()?;
+        let request = self.client.get(endpoint).query(&[("url", video.url())]);
+
+        // Vimeo gives upload dates in the form '2013-05-27 10:16:39', without a time zone
+        let upload_date = fetch_json::<OEmbedResponse>(request).await?.upload_date?;
+
+        NaiveDateTime::parse_from_str(&upload_date, "%Y-%m-%d %H:%M:%S").ok()
+    }
+}
+
+/// Retrieves upload dates of Bilibili videos via Bilibili's web API
+struct BilibiliUploadDates {
+    client: Client,
+}
+
+#[derive(Deserialize)]
+struct BilibiliResponse {
+    data: Option<BilibiliVideo>,
+}
+
+#[derive(Deserialize)]
+struct BilibiliVideo {
+    /// Unix timestamp of the video's publication
+    pubdate: i64,
+}
+
+#[rocket::async_trait]
+impl UploadDateStrategy for BilibiliUploadDates {
+    async fn upload_date(&self, video: &VideoIdentity) -> Option<NaiveDateTime> {
+        // Bilibili videos are either identified by a 'BV' ID, or by a legacy numeric 'av' ID
+        let query = match video.video_id.strip_prefix("av") {
+            Some(aid) => ("aid", aid),
+            None => ("bvid", video.video_id.as_str()),
+        };
+
+        let request = self.client.get("https://api.bilibili.com/x/web-interface/view").query(&[query]);
+        let video = fetch_json::<BilibiliResponse>(request).await?.data?;
+
+        DateTime::from_timestamp(video.pubdate, 0).map(|date| date.naive_utc())
+    }
+}
+
+/// Retrieves upload dates of Twitch VODs via the Twitch Helix API
+///
+/// Requires a Twitch application's client ID and an app access token
+struct TwitchUploadDates {
+    client: Client,
+    client_id: String,
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct TwitchResponse {
+    data: Vec<TwitchVideo>,
+}
+
+#[derive(Deserialize)]
+struct TwitchVideo {
+    created_at: DateTime<Utc>,
+}
+
+#[rocket::async_trait]
+impl UploadDateStrategy for TwitchUploadDates {
+    async fn upload_date(&self, video: &VideoIdentity) -> Option<NaiveDateTime> {
+        let request = self
+            .client
+            .get("https://api.twitch.tv/helix/videos")
+            .query(&[("id", &video.video_id)])
+            .header("Client-Id", &self.client_id)
+            .bearer_auth(&self.access_token);
+
+        let video = fetch_json::<TwitchResponse>(request).await?.data.into_iter().next()?;
+
+        Some(video.created_at.naive_utc())
+    }
+}
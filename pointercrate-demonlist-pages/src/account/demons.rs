@@ -277,11 +277,7 @@ fn change_thumbnail_dialog() -> Markup {
                     "Change thumbnail link:"
                 }
                 p style = "max-width: 400px"{
-                    "Change the thumbnail link for this record. To link it to the thumbnail of a youtube video, set it to "
-                    i {
-                        "https://i.ytimg.com/vi/" b{"VIDEO_ID"} "/mqdefault.jpg"
-                    }
-                    "."
+                    "Change the thumbnail link for this demon. By default, the thumbnail is derived from the verification video and updated whenever the video changes. Manually setting a thumbnail stops these automatic updates."
                 }
                 form.flex.col novalidate = "" {
                     p.info-red.output {}
@@ -5,7 +5,10 @@ use pointercrate_core_pages::{
     error::ErrorFragment,
     util::{filtered_paginator, paginator},
 };
-use pointercrate_demonlist::{player::claim::PlayerClaim, LIST_MODERATOR};
+use pointercrate_demonlist::{
+    player::claim::{ClaimDispute, ClaimSuggestion, PlayerClaim, SuggestedEdit},
+    LIST_MODERATOR,
+};
 use pointercrate_user::{sqlx::PgConnection, AuthenticatedUser};
 use pointercrate_user_pages::account::AccountPageTab;
 
@@ -60,6 +63,28 @@ impl AccountPageTab for ListIntegrationTab {
             },
         };
         let is_moderator = permissions.require_permission(user.inner().permissions, LIST_MODERATOR).is_ok();
+        let open_disputes = match is_moderator {
+            true => match ClaimDispute::open(connection).await {
+                Ok(disputes) => disputes,
+                Err(err) => {
+                    error!("Error retrieving open claim disputes: {:?}", err);
+
+                    Vec::new()
+                },
+            },
+            false => Vec::new(),
+        };
+        let pending_suggestions = match is_moderator {
+            true => match ClaimSuggestion::pending(connection).await {
+                Ok(suggestions) => suggestions,
+                Err(err) => {
+                    error!("Error retrieving pending claim suggestions: {:?}", err);
+
+                    Vec::new()
+                },
+            },
+            false => Vec::new(),
+        };
 
         html! {
             div.left {
@@ -91,7 +116,12 @@ impl AccountPageTab for ListIntegrationTab {
                                     }
                                     span.arrow.hover {}
                                 },
-                                Some(_) => i{"Unverified"},
+                                Some(_) => {
+                                    i style="margin-right: 15px;" {
+                                        "Unverified"
+                                    }
+                                    span.arrow.hover {}
+                                },
                                 _ => {}
                             }
                         }
@@ -110,9 +140,9 @@ impl AccountPageTab for ListIntegrationTab {
                                     }
                                 }
                                 p {
-                                    "Clicking the above button let's you set your claimed player's statsviewer flag via IP Geolocation. To offer this functionality, pointercrate uses "
+                                    "Clicking the above button let's you set your claimed player's statsviewer flag via IP Geolocation. To offer this functionality, pointercrate looks up your IP in a geolocation database. Depending on this site's configuration, this lookup might be performed by a third party (such as "
                                     a.link href = "https://www.abstractapi.com/ip-geolocation-api" { "abstract's IP geolocation API"}
-                                    ". Clicking the above button also counts as your consent for pointercrate to send your IP to abstract."
+                                    "). Clicking the above button also counts as your consent for pointercrate to send your IP to this third party."
                                 }
                                 div.cb-container.flex.no-stretch style="justify-content: space-between; align-items: center" {
                                     @if claim.lock_submissions {
@@ -130,6 +160,37 @@ impl AccountPageTab for ListIntegrationTab {
                                 }
                             }
                         }
+                        @else {
+                            div.overlined.pad.js-collapse-content #claims-verify-panel style="display:none" {
+                                p.info-red.output style = "margin: 10px 0" {}
+                                p.info-green.output style = "margin: 10px 0" {}
+                                p {
+                                    "To verify your claim, place the following code either in a comment on your Geometry Dash profile, or in the description of one of your levels:"
+                                }
+                                p style = "text-align: center; font-size: 1.2em" {
+                                    code #claims-verification-code {
+                                        (claim.verification_code)
+                                    }
+                                }
+                                div.flex.no-stretch style="justify-content: space-between; align-items: center" {
+                                    b {
+                                        "Check profile comments:"
+                                    }
+                                    a.button.purple.hover #claims-verify-profile {
+                                        "Go"
+                                    }
+                                }
+                                div.flex.no-stretch style="justify-content: space-between; align-items: center; margin-top: 10px" {
+                                    input #claims-verify-level-id type = "number" min = "1" placeholder = "Level ID" style = "margin-right: 8px";
+                                    a.button.purple.hover #claims-verify-level {
+                                        "Check level"
+                                    }
+                                }
+                                p {
+                                    "Once your claim is verified, you can remove the code again."
+                                }
+                            }
+                        }
                     }
                 }
                 @if let Some(claim) = player_claim {
@@ -147,6 +208,42 @@ impl AccountPageTab for ListIntegrationTab {
                             }
                             (paginator("claims-record-pagination", "/api/v1/records/"))
                         }
+                        div.panel.fade #claims-suggest-panel {
+                            h2.pad.underlined {
+                                "Suggest edits"
+                            }
+                            p {
+                                "Suggest changes to your claimed player below. Suggestions are reviewed by a list moderator before being applied. Note that you can only change the capitalization of your player's name. For actual renames, please contact the list team directly."
+                            }
+                            p.info-red.output {}
+                            p.info-green.output {}
+                            input #claims-suggest-comment type = "text" placeholder = "Comment for the reviewing moderator (optional)" style = "width: 100%; margin-bottom: 10px";
+                            div.flex.no-stretch style="justify-content: space-between; align-items: center; margin-bottom: 10px" {
+                                input #claims-suggest-name type = "text" placeholder = "Name" value = (claim.player.name) style = "margin-right: 8px";
+                                a.button.purple.hover #claims-suggest-name-submit {
+                                    "Suggest name"
+                                }
+                            }
+                            div.flex.no-stretch style="justify-content: space-between; align-items: center; margin-bottom: 10px" {
+                                input #claims-suggest-subdivision type = "text" maxlength = "3" placeholder = "Subdivision code (leave empty to remove)" style = "margin-right: 8px";
+                                a.button.purple.hover #claims-suggest-subdivision-submit {
+                                    "Suggest subdivision"
+                                }
+                            }
+                            div.flex.no-stretch style="justify-content: space-between; align-items: center; margin-bottom: 10px" {
+                                input #claims-suggest-record type = "number" min = "1" placeholder = "Record ID" style = "margin-right: 8px";
+                                input #claims-suggest-video type = "url" placeholder = "New video" style = "margin-right: 8px";
+                                a.button.purple.hover #claims-suggest-video-submit {
+                                    "Suggest video"
+                                }
+                            }
+                            div.flex.no-stretch style="justify-content: space-between; align-items: center" {
+                                input #claims-suggest-removal type = "number" min = "1" placeholder = "Record ID" style = "margin-right: 8px";
+                                a.button.purple.hover #claims-suggest-removal-submit {
+                                    "Suggest removal"
+                                }
+                            }
+                        }
                     }
                 }
                 @if is_moderator {
@@ -165,6 +262,110 @@ impl AccountPageTab for ListIntegrationTab {
                         }
                         (filtered_paginator("claim-pagination", "/api/v1/players/claims/"))
                     }
+                    div.panel.fade #claim-disputes {
+                        h2.pad.underlined {
+                            "Claim Disputes"
+                        }
+                        p {
+                            "Disputes users opened on verified claims they believe to be illegitimate. Enter a reason below, then either transfer the claim to the disputing user, revoke the claim altogether, or reject the dispute (leaving the claim as is)."
+                        }
+                        p.info-red.output {}
+                        p.info-green.output {}
+                        input #claim-dispute-reason type = "text" placeholder = "Reason for your decision" style = "width: 100%; margin-bottom: 10px";
+                        @if open_disputes.is_empty() {
+                            p {
+                                i {
+                                    "No open disputes"
+                                }
+                            }
+                        }
+                        ul #claim-dispute-list {
+                            @for dispute in &open_disputes {
+                                li.dark-gray.flex.no-stretch data-id = (dispute.id) style = "justify-content: space-between; align-items: center; padding: 0.5em 0.75em; margin-bottom: 5px" {
+                                    div {
+                                        b { "Player: " } (dispute.player.name) " (" (dispute.player.id) ")"
+                                        br;
+                                        b { "Disputed by user: " } (dispute.disputed_by)
+                                        @if let Some(holder) = dispute.holder {
+                                            ", " b { "claim held by user: " } (holder)
+                                        }
+                                        br;
+                                        b { "Evidence: " } (dispute.evidence)
+                                    }
+                                    div.flex {
+                                        a.button.purple.hover.js-resolve-dispute data-resolution = "transferred" title = "Transfer claim" style = "margin-right: 5px" {
+                                            i.fas.fa-exchange-alt {}
+                                        }
+                                        a.button.purple.hover.js-resolve-dispute data-resolution = "revoked" title = "Revoke claim" style = "margin-right: 5px" {
+                                            i.fas.fa-trash-alt {}
+                                        }
+                                        a.button.purple.hover.js-resolve-dispute data-resolution = "rejected" title = "Reject dispute" {
+                                            i.fas.fa-times {}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    div.panel.fade #claim-suggestions {
+                        h2.pad.underlined {
+                            "Suggested Edits"
+                        }
+                        p {
+                            "Edits verified claimants suggested for their claimed players. Approved edits are applied immediately, and show up in the audit logs as made by the claimant. The note is optional, and shown to the claimant."
+                        }
+                        p.info-red.output {}
+                        p.info-green.output {}
+                        input #claim-suggestion-note type = "text" placeholder = "Note for the claimant (optional)" style = "width: 100%; margin-bottom: 10px";
+                        @if pending_suggestions.is_empty() {
+                            p {
+                                i {
+                                    "No pending suggestions"
+                                }
+                            }
+                        }
+                        ul #claim-suggestion-list {
+                            @for suggestion in &pending_suggestions {
+                                li.dark-gray.flex.no-stretch data-id = (suggestion.id) style = "justify-content: space-between; align-items: center; padding: 0.5em 0.75em; margin-bottom: 5px" {
+                                    div {
+                                        b { "Player: " } (suggestion.player.name) " (" (suggestion.player.id) ")"
+                                        br;
+                                        b { "Suggested by user: " } (suggestion.suggested_by)
+                                        br;
+                                        @match suggestion.edit {
+                                            SuggestedEdit::Name { ref name } => {
+                                                b { "Rename to: " } (name)
+                                            },
+                                            SuggestedEdit::Subdivision { subdivision: Some(ref subdivision) } => {
+                                                b { "Set subdivision to: " } (subdivision)
+                                            },
+                                            SuggestedEdit::Subdivision { subdivision: None } => {
+                                                b { "Remove subdivision" }
+                                            },
+                                            SuggestedEdit::RecordVideo { record_id, ref video } => {
+                                                b { "Change video of record " (record_id) " to: " } a.link href = (video) target = "_blank" { (video) }
+                                            },
+                                            SuggestedEdit::RecordRemoval { record_id } => {
+                                                b { "Remove record " (record_id) }
+                                            },
+                                        }
+                                        @if let Some(ref comment) = suggestion.comment {
+                                            br;
+                                            b { "Comment: " } (comment)
+                                        }
+                                    }
+                                    div.flex {
+                                        a.button.purple.hover.js-review-suggestion data-approve = "true" title = "Approve" style = "margin-right: 5px" {
+                                            i.fas.fa-check {}
+                                        }
+                                        a.button.purple.hover.js-review-suggestion data-approve = "false" title = "Reject" {
+                                            i.fas.fa-times {}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
                 }
             }
             div.right {
@@ -177,6 +378,20 @@ impl AccountPageTab for ListIntegrationTab {
                     }
                     (filtered_paginator("claims-initiate-claim-pagination", "/api/v1/players/"))
                 }
+                div.panel.fade #claims-dispute-panel style = "display: none;" {
+                    h2.underlined.pad {
+                        "Dispute Claim"
+                    }
+                    p {
+                        "The player " i #claims-dispute-player {} " has already been claimed by a different user. If you believe this claim to be illegitimate, you can dispute it. Please describe why the player is yours (e.g. links to videos or profiles only you have access to), so a list moderator can review your dispute."
+                    }
+                    p.info-red.output {}
+                    p.info-green.output {}
+                    textarea #claims-dispute-evidence placeholder = "Evidence" style = "width: 100%; min-height: 100px; margin-bottom: 10px" {}
+                    a.button.purple.hover #claims-dispute-submit {
+                        "Open dispute"
+                    }
+                }
                 div.panel.fade {
                     h2.underlined.pad {
                         "Claiming 101"
@@ -184,9 +399,9 @@ impl AccountPageTab for ListIntegrationTab {
                     p {
                         "Player claiming is the process of associated a demonlist player with a pointercrate user account. A verified claim allows you to to modify some of the player's properties, such as nationality. "
                         br;
-                        "To initiate a claim, click the pen left of the 'Claimed Player' heading. Once initiated, you have an unverified claim on a player. These claims will then be manually verified by members of the pointercrate team. You can request verification in " a.link href=(self.0) {"this discord server"} "."
+                        "To initiate a claim, click the pen left of the 'Claimed Player' heading. Once initiated, you have an unverified claim on a player. You can verify it yourself by placing the code shown in the 'Claimed Player' panel on the player's Geometry Dash account. Alternatively, you can request manual verification by members of the pointercrate team in " a.link href=(self.0) {"this discord server"} "."
                         br;
-                        "You cannot initiate a claim on a player that already has a verified claim by a different user on it. "
+                        "You cannot initiate a claim on a player that already has a verified claim by a different user on it. If you believe such a claim to be illegitimate, you can dispute it instead by selecting the player as if you were claiming it."
                     }
                 }
                 @if is_moderator {
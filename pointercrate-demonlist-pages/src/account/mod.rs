@@ -1,7 +1,42 @@
 //! Module containing the tabs of the management web interfaces shown on the account page
 
+use maud::{html, Markup};
+
 pub mod demons;
 pub mod list_integration;
 pub mod players;
 pub mod records;
 pub mod submitters;
+
+/// The dialog for issuing reasoned and temporary bans, used by both the player and submitter
+/// manager
+fn ban_dialog(dialog_id: &str) -> Markup {
+    html! {
+        div.overlay.closable {
+            div.dialog id = (dialog_id) {
+                span.plus.cross.hover {}
+                h2.underlined.pad {
+                    "Issue ban:"
+                }
+                p style = "max-width: 400px" {
+                    "Bans without an expiry date are permanent and have to be lifted manually by setting 'Banned' to 'no'. Temporary bans lift automatically once they expire. If the target is already banned, their current ban is replaced."
+                }
+                form.flex.col novalidate = "" {
+                    p.info-red.output {}
+                    p.info-green.output {}
+                    span.form-input id = {(dialog_id) "-reason"} {
+                        label for = "reason" {"Reason:"}
+                        input name = "reason" type = "text" required = "";
+                        p.error {}
+                    }
+                    span.form-input id = {(dialog_id) "-expiry"} {
+                        label for = "expires_at" {"Expires at (UTC, leave empty for a permanent ban):"}
+                        input name = "expires_at" type = "datetime-local";
+                        p.error {}
+                    }
+                    input.button.red.hover type = "submit" style = "margin: 15px auto 0px;" value = "Ban";
+                }
+            }
+        }
+    }
+}
@@ -25,6 +25,7 @@ impl AccountPageTab for PlayersPage {
             versioned_import_string!("/static/core/js/modules/form.js"),
             versioned_import_string!("/static/demonlist/js/modules/demonlist.js"),
             versioned_import_string!("/static/demonlist/js/account/records.js"),
+            versioned_import_string!("/static/demonlist/js/account/bans.js"),
         ]
     }
 
@@ -75,7 +76,7 @@ impl AccountPageTab for PlayersPage {
                                     i.fa.fa-pencil-alt.clickable #player-name-pen aria-hidden = "true" {} (PreEscaped("&nbsp;")) i #player-player-name {}
                                 }
                                 p {
-                                    "Welcome to the player manager. Here you can ban or unban players. Banning a player will delete all records of theirs which are in the submitted or under consideration state. All approved records will instead be set to rejected."
+                                    "Welcome to the player manager. Here you can ban or unban players. Banning a player will delete all records of theirs which are in the submitted or under consideration state. All approved records will instead be set to rejected. If the ban is temporary, these records are approved again once it lifts."
                                 }
                                 p.info-red.output style = "margin: 10px" {}
                                 p.info-green.output style = "margin: 10px" {}
@@ -144,6 +145,16 @@ impl AccountPageTab for PlayersPage {
                                         }
                                     }
                                 }
+                                div.stats-container.flex.space {
+                                    span {
+                                        b {
+                                            "Ban history:"
+                                        }
+                                        br;
+                                        ul #player-ban-history {}
+                                    }
+                                }
+                                span.button.red.hover #player-ban style = "margin: 15px auto 0px" {"Issue ban"};
                                 span.button.purple.hover #player-list-records style = "margin: 15px auto 0px" {"Show records in record manager"};
                             }
                         }
@@ -155,6 +166,7 @@ impl AccountPageTab for PlayersPage {
                 (player_selector())
             }
             (change_name_dialog())
+            (super::ban_dialog("player-ban-dialog"))
         }
     }
 }
@@ -190,7 +202,7 @@ fn change_name_dialog() -> Markup {
                     "Change player name:"
                 }
                 p style = "max-width: 400px"{
-                    "Change the name of this player. This will update their name on every one of their records. If a player with the new name already exists, the player objects will be merged, with the new object receiving the ID of the player you are currently editing. In this case, the record lists of the players are merged and their creator/verifier/publisher information is updated. Internally, each record is moved to to the new player, an on conflicts the same rules apply as when editing a record's holder."
+                    "Change the name of this player. This will update their name on every one of their records. The old name is kept as an alias, under which the player can still be found. If a player with the new name already exists, the rename fails. If both are the same person, the players have to be merged explicitly, after reviewing a preview of the merge."
                 }
                 form.flex.col novalidate = "" {
                     p.info-red.output {}
@@ -10,6 +10,8 @@ use pointercrate_core_pages::{
 };
 use pointercrate_demonlist::{
     demon::{current_list, Demon},
+    list::{List, DEFAULT_LIST},
+    record::rejection::RejectionReason,
     LIST_HELPER,
 };
 use pointercrate_user::{sqlx::PgConnection, AuthenticatedUser};
@@ -52,7 +54,19 @@ impl AccountPageTab for RecordsPage {
     }
 
     async fn content(&self, _user: &AuthenticatedUser, _permissions: &PermissionsManager, connection: &mut PgConnection) -> Markup {
-        let demons = match current_list(connection).await {
+        let list = match List::by_id(DEFAULT_LIST, connection).await {
+            Ok(list) => list,
+            Err(err) => {
+                return ErrorFragment {
+                    status: err.status_code(),
+                    reason: "Internal Server Error".to_string(),
+                    message: err.to_string(),
+                }
+                .body()
+            },
+        };
+
+        let demons = match current_list(DEFAULT_LIST, connection).await {
             Ok(demons) => demons,
             Err(err) => {
                 return ErrorFragment {
@@ -64,10 +78,22 @@ impl AccountPageTab for RecordsPage {
             },
         };
 
+        let reasons = match RejectionReason::all(connection).await {
+            Ok(reasons) => reasons,
+            Err(err) => {
+                return ErrorFragment {
+                    status: err.status_code(),
+                    reason: "Internal Server Error".to_string(),
+                    message: err.to_string(),
+                }
+                .body()
+            },
+        };
+
         html! {
             div.left {
-                (RecordSubmitter::new(false, &demons[..]))
-                (record_manager(&demons[..]))
+                (RecordSubmitter::new(false, &list, &demons[..]))
+                (record_manager(&demons[..], &reasons[..]))
                 (note_adder())
                 div.panel.fade #record-notes-container style = "display:none" {
                     div.medium-gray.hover.clickable #add-record-note-open {
@@ -78,6 +104,7 @@ impl AccountPageTab for RecordsPage {
                 (manager_help())
             }
             div.right {
+                (review_queue())
                 (status_selector())
                 (record_selector())
                 (player_selector())
@@ -91,7 +118,7 @@ impl AccountPageTab for RecordsPage {
     }
 }
 
-fn record_manager(demons: &[Demon]) -> Markup {
+fn record_manager(demons: &[Demon], reasons: &[RejectionReason]) -> Markup {
     html! {
         div.panel.fade #record-manager {
             h2.underlined.pad {
@@ -140,6 +167,15 @@ fn record_manager(demons: &[Demon]) -> Markup {
                                 a.link #record-video-link target = "_blank" {}
                             }
                         }
+                        div.stats-container.flex.space  {
+                            span {
+                                b {
+                                    "Raw footage:"
+                                }
+                                br;
+                                span #record-raw-footage {}
+                            }
+                        }
                         div.stats-container.flex.space {
                             span {
                                 b {
@@ -172,6 +208,26 @@ fn record_manager(demons: &[Demon]) -> Markup {
                                 span #record-submitter {}
                             }
                         }
+                        div.stats-container.flex.space {
+                            span {
+                                b {
+                                    "Rejection reason:"
+                                }
+                                br;
+                                div.dropdown-menu.js-search #edit-record-rejection-reason style = "max-width: 220px" {
+                                    div{
+                                        input type="text" placeholder = "None";
+                                    }
+                                    div.menu {
+                                        ul {
+                                            @for reason in reasons {
+                                                li.colorless.hover data-value = (reason.id) data-display = (reason.name) title = [reason.description.as_deref()] {(reason.name)}
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
                         span.button.red.hover #record-delete style = "margin: 15px auto 0px" {"Delete Record"};
                     }
                 }
@@ -197,6 +253,8 @@ fn manager_help() -> Markup {
                         b{"Rejected: "} "If the record is 'rejected', it means that Bob has no other record in other states on Cataclysm and no submissions for Bob on Cataclysm are possible. Conversely, this means if Bob has a record on Catalysm that's not rejected, we immediately know that no rejected record for Bob on Cataclysm exists. "
                         br;
                         "Rejecting any record of Bob's on Cataclysm will delete all other record's of Bob on Cataclysm to ensure the above uniqueness"
+                        br;
+                        "Rejected records can be given a rejection reason, which Bob gets to see if he has verified his claim on his player."
                     }
                     li {
                         b{"Approved: "} "If the record is 'approved', it means that no submissions with less progress than the 'approved' record exist or are permitted."
@@ -223,6 +281,32 @@ fn manager_help() -> Markup {
     }
 }
 
+fn review_queue() -> Markup {
+    html! {
+        div.panel.fade #review-queue style = "overflow: visible" {
+            h2.underlined.pad {
+                "Review Queue"
+            }
+            p {
+                "Claim the next record awaiting review. Claimed records are locked to you for a while, so that nobody else reviews them at the same time. Records a moderator assigned to you are always handed out first."
+            }
+            p.info-red.output {}
+            p.info-green.output {}
+            (dropdown("oldest", html! {
+                li.colorless.hover.underlined data-value = "oldest" data-display = "Oldest first" {"Oldest first"}
+            }, vec![
+                html! { li.colorless.hover data-value = "position" data-display = "Highest demon first" {"Highest demon first"} },
+                html! { li.colorless.hover data-value = "reputation" data-display = "Most reliable submitter first" {"Most reliable submitter first"} },
+            ].into_iter()))
+            div.flex style = "margin-top: 15px" {
+                div.button.purple.hover.small #review-queue-claim style = "margin: 0px 5px" {"Claim next"}
+                div.button.red.hover.small #review-queue-release style = "margin: 0px 5px" {"Release"}
+            }
+            p #review-queue-stats style = "margin-top: 15px" {} // populated by javascript
+        }
+    }
+}
+
 fn status_selector() -> Markup {
     // FIXME: no vec
     let dropdown_items = vec![
@@ -24,6 +24,7 @@ impl AccountPageTab for SubmittersPage {
         vec![
             versioned_import_string!("/static/core/js/modules/form.js"),
             versioned_import_string!("/static/demonlist/js/account/records.js"),
+            versioned_import_string!("/static/demonlist/js/account/bans.js"),
         ]
     }
 
@@ -65,6 +66,9 @@ impl AccountPageTab for SubmittersPage {
                                 p {
                                     "Banning a submitter will delete all records they have submitted and which are still in the 'submitted' state. All submissions of their which are approved, rejected or under consideration are untouched. "
                                 }
+                                p {
+                                    "A submitter's reputation is the share of their reviewed submissions that were approved. Submitters who get too many of their submissions rejected (or deleted because of an invalid video) are automatically flagged. Once you looked into a flagged submitter, set 'Flagged' back to 'no'."
+                                }
                                 p.info-red.output style = "margin: 10px" {}
                                 p.info-green.output style = "margin: 10px" {}
                                 div.stats-container.flex.space {
@@ -85,7 +89,57 @@ impl AccountPageTab for SubmittersPage {
                                             }
                                         }
                                     }
+                                    span {
+                                        b {
+                                            "Flagged:"
+                                        }
+                                        br;
+                                        div.dropdown-menu.js-search #edit-submitter-flagged style = "max-width: 50px" {
+                                            div{
+                                                input type="text" style = "font-weight: bold;";
+                                            }
+                                            div.menu {
+                                                ul {
+                                                    li.colorless.hover data-value="true" {"yes"}
+                                                    li.colorless.hover data-value="false" {"no"}
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                                div.stats-container.flex.space {
+                                    span {
+                                        b {
+                                            "Submissions:"
+                                        }
+                                        br;
+                                        span #submitter-submitted {}
+                                    }
+                                    span {
+                                        b {
+                                            "Approved / Rejected / Invalid:"
+                                        }
+                                        br;
+                                        span #submitter-outcomes {}
+                                    }
+                                    span {
+                                        b {
+                                            "Reputation:"
+                                        }
+                                        br;
+                                        span #submitter-reputation {}
+                                    }
                                 }
+                                div.stats-container.flex.space {
+                                    span {
+                                        b {
+                                            "Ban history:"
+                                        }
+                                        br;
+                                        ul #submitter-ban-history {}
+                                    }
+                                }
+                                span.button.red.hover #submitter-ban style = "margin: 15px auto 0px" {"Issue ban"};
                                 span.button.purple.hover #submitter-list-records style = "margin: 15px auto 0px" {"Show records in record manager"};
                             }
                         }
@@ -95,7 +149,9 @@ impl AccountPageTab for SubmittersPage {
             }
             div.right {
                 (submitter_selector())
+                (range_bans())
             }
+            (super::ban_dialog("submitter-ban-dialog"))
         }
     }
 }
@@ -121,3 +177,40 @@ fn submitter_selector() -> Markup {
         }
     }
 }
+
+fn range_bans() -> Markup {
+    html! {
+        div.panel.fade {
+            h2.underlined.pad {
+                "Ban address range"
+            }
+            p {
+                "Banning an address range (given in CIDR notation, e.g. '192.0.2.0/24' or '2001:db8::/48') prevents any submissions from addresses in that range, including from submitters that do not exist yet. Existing submissions are untouched."
+            }
+            form.flex.col #range-ban-form novalidate = "" {
+                p.info-red.output {}
+                p.info-green.output {}
+                span.form-input #range-ban-range {
+                    label for = "range" {"Address range:"}
+                    input required = "" type = "text" name = "range" style="width:93%";
+                    p.error {}
+                }
+                span.form-input #range-ban-reason {
+                    label for = "reason" {"Reason:"}
+                    input required = "" type = "text" name = "reason" style="width:93%";
+                    p.error {}
+                }
+                span.form-input #range-ban-expiry {
+                    label for = "expires_at" {"Expires at (UTC, leave empty for a permanent ban):"}
+                    input type = "datetime-local" name = "expires_at";
+                    p.error {}
+                }
+                input.button.red.hover type = "submit" style = "margin: 15px auto 0px;" value="Ban range";
+            }
+            h3 style = "font-size:1.1em; margin: 10px 0" {
+                "Active range bans (click to lift):"
+            }
+            ul #range-ban-list {}
+        }
+    }
+}
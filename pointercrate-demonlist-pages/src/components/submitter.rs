@@ -1,17 +1,57 @@
 use crate::components::{demon_dropdown, player_selection_dialog};
 use maud::{html, Markup, Render};
-use pointercrate_demonlist::{config, demon::Demon};
+use pointercrate_demonlist::{demon::Demon, list::List, record::challenge::ChallengeWidget};
 
 pub struct RecordSubmitter<'a> {
     initially_visible: bool,
+    list: &'a List,
     demons: &'a [Demon],
+    challenge: Option<&'a ChallengeWidget>,
 }
 
-impl RecordSubmitter<'_> {
-    pub fn new(visible: bool, demons: &[Demon]) -> RecordSubmitter {
+impl<'a> RecordSubmitter<'a> {
+    pub fn new(visible: bool, list: &'a List, demons: &'a [Demon]) -> RecordSubmitter<'a> {
         RecordSubmitter {
             initially_visible: visible,
+            list,
             demons,
+            challenge: None,
+        }
+    }
+
+    /// Renders the given challenge, which has to be solved before submitting
+    pub fn with_challenge(mut self, challenge: Option<&'a ChallengeWidget>) -> Self {
+        self.challenge = challenge;
+        self
+    }
+}
+
+/// The name of the input the response to the given challenge ends up in
+fn response_field(widget: &ChallengeWidget) -> &str {
+    match widget {
+        ChallengeWidget::Hosted { response_field, .. } => response_field,
+        ChallengeWidget::Input => "challenge",
+    }
+}
+
+fn challenge(widget: &ChallengeWidget) -> Markup {
+    html! {
+        h3 {
+            "Challenge:"
+        }
+        p {
+            "Since you are not logged in, please prove that you are not a bot."
+        }
+        div #submit-challenge data-response-field = (response_field(widget)) {
+            @match widget {
+                ChallengeWidget::Hosted { script, class, site_key, .. } => {
+                    script src = (script) async defer {}
+                    div class = (class) data-sitekey = (site_key) {}
+                },
+                ChallengeWidget::Input => {
+                    input type = "text" name = "challenge" placeholder = "Challenge response";
+                },
+            }
         }
     }
 }
@@ -31,10 +71,10 @@ impl Render for RecordSubmitter<'_> {
                         "Demon:"
                     }
                     p {
-                        "The demon the record was made on. Only demons in the top " (config::extended_list_size()) " are accepted. This excludes legacy demons!"
+                        "The demon the record was made on. Only demons in the top " (self.list.extended_list_size) " are accepted. This excludes legacy demons!"
                     }
                     span.form-input data-type = "dropdown" {
-                        (demon_dropdown("id_demon", self.demons.iter().filter(|demon| demon.base.position <= config::extended_list_size())))
+                        (demon_dropdown("id_demon", self.demons.iter().filter(|demon| demon.base.position <= self.list.extended_list_size)))
                         p.error {}
                     }
                     h3 {
@@ -103,6 +143,9 @@ impl Render for RecordSubmitter<'_> {
                         textarea name = "note" placeholder = "Your dreams and hopes for this record... or something like that" {}
                         p.error {}
                     }
+                    @if let Some(widget) = self.challenge {
+                        (challenge(widget))
+                    }
                     input.button.purple.hover type = "submit" style = "margin: 15px auto 0px;" value="Submit record";
                 }
             }
@@ -9,11 +9,12 @@ use chrono::NaiveDateTime;
 use maud::{html, Markup, PreEscaped};
 use pointercrate_core_pages::{head::HeadLike, PageFragment};
 use pointercrate_demonlist::{
-    config::{self as list_config, extended_list_size},
-    demon::{Demon, FullDemon},
+    demon::{Demon, FullDemon, RecordKind},
+    list::List,
+    record::challenge::ChallengeWidget,
+    video::VideoIdentity,
 };
 use pointercrate_integrate::gd::{IntegrationLevel, Thunk};
-use url::Url;
 
 #[derive(Debug)]
 pub struct DemonMovement {
@@ -23,10 +24,17 @@ pub struct DemonMovement {
 
 pub struct DemonPage {
     pub team: Team,
+
+    /// The list the demon is placed on
+    pub list: List,
+
     pub demonlist: Vec<Demon>,
     pub data: FullDemon,
     pub movements: Vec<DemonMovement>,
     pub integration: Option<IntegrationLevel>,
+
+    /// The challenge to render on the record submission form, if any
+    pub challenge: Option<ChallengeWidget>,
 }
 
 impl From<DemonPage> for PageFragment {
@@ -53,7 +61,7 @@ impl DemonPage {
             self.data.demon.base.name // FIXME: flatten the structs, holy shit
         );
 
-        if self.data.demon.base.position <= extended_list_size() {
+        if self.data.demon.base.position <= self.list.extended_list_size {
             title = format!("#{} - {}", self.data.demon.base.position, title);
         }
 
@@ -113,13 +121,13 @@ impl DemonPage {
                     window.list_length = {0};
                     window.extended_list_length = {1};
                     window.demon_id = {2};
-                </script>", list_config::list_size(), list_config::extended_list_size(), self.data.demon.base.id
+                </script>", self.list.list_size, self.list.extended_list_size, self.data.demon.base.id
             )))
         }
     }
 
     fn body(&self) -> Markup {
-        let dropdowns = super::dropdowns(&self.demonlist.iter().collect::<Vec<_>>()[..], Some(&self.data.demon));
+        let dropdowns = super::dropdowns(&self.list, &self.demonlist.iter().collect::<Vec<_>>()[..], Some(&self.data.demon));
 
         let mut labels = Vec::new();
 
@@ -151,7 +159,7 @@ impl DemonPage {
 
             div.flex.m-center.container {
                 main.left {
-                    (RecordSubmitter::new(false, &self.demonlist))
+                    (RecordSubmitter::new(false, &self.list, &self.demonlist).with_challenge(self.challenge.as_ref()))
                     (self.demon_panel())
                     (self.level_info_panel())
                     div.panel.fade.js-scroll-anim.js-collapse data-anim = "fade" {
@@ -198,8 +206,8 @@ impl DemonPage {
         let position = self.data.demon.base.position;
         let name = &self.data.demon.base.name;
 
-        let score100 = self.data.demon.score(100);
-        let score_requirement = self.data.demon.score(self.data.demon.requirement);
+        let score100 = self.data.demon.score(100, &self.list);
+        let score_requirement = self.data.demon.score(self.data.demon.requirement, &self.list);
 
         html! {
             section.panel.fade.js-scroll-anim data-anim = "fade" {
@@ -248,7 +256,7 @@ impl DemonPage {
                     }
                 }
                 div.underlined.pad.flex.wrap #level-info {
-                    @if position <= list_config::extended_list_size() {
+                    @if position <= self.list.extended_list_size {
                         span {
                             b {
                                 "Demonlist score (100%): "
@@ -257,7 +265,7 @@ impl DemonPage {
                             (format!("{:.2}", score100))
                         }
                     }
-                    @if position <= list_config::list_size(){
+                    @if position <= self.list.list_size{
                         span {
                             b {
                                 "Demonlist score (" (self.data.demon.requirement) "%): "
@@ -344,18 +352,28 @@ impl DemonPage {
         let _name = &self.data.demon.base.name;
 
         html! {
-            @if !self.data.records.is_empty() || position <= list_config::extended_list_size() {
+            @if !self.data.records.is_empty() || position <= self.list.extended_list_size {
                 section.records.panel.fade.js-scroll-anim data-anim = "fade" {
                     div.underlined.pad {
                         h2 {
                             "Records"
                         }
-                        @if position <= list_config::list_size() {
+                        @if self.data.demon.record_kind == RecordKind::Time {
+                            h3 {
+                                @if let Some(max_time) = self.data.demon.max_time {
+                                    "Completion in " (format_completion_time(max_time)) " or faster required to qualify"
+                                }
+                                @else {
+                                    "Records are ranked by completion time"
+                                }
+                            }
+                        }
+                        @else if position <= self.list.list_size {
                             h3 {
                                 (self.data.demon.requirement) "% or better required to qualify"
                             }
                         }
-                        @else if position <= list_config::extended_list_size() {
+                        @else if position <= self.list.extended_list_size {
                             h3 {
                                 "100% required to qualify"
                             }
@@ -373,7 +391,7 @@ impl DemonPage {
                     }
                     @if self.data.records.is_empty() {
                         h3 {
-                            @if position > list_config::extended_list_size() {
+                            @if position > self.list.extended_list_size {
                                 "No records!"
                             }
                             @else {
@@ -390,7 +408,12 @@ impl DemonPage {
                                         "Record Holder"
                                     }
                                     th.medium-gray {
-                                        "Progress"
+                                        @if self.data.demon.record_kind == RecordKind::Time {
+                                            "Time"
+                                        }
+                                        @else {
+                                            "Progress"
+                                        }
                                     }
                                     th.video-link.medium-gray {
                                         "Video Proof"
@@ -414,7 +437,12 @@ impl DemonPage {
                                             }
                                         }
                                         td {
-                                            (record.progress) "%"
+                                            @if let Some(completion_time) = record.completion_time {
+                                                (format_completion_time(completion_time))
+                                            }
+                                            @else {
+                                                (record.progress) "%"
+                                            }
                                         }
                                         td.video-link {
                                             @if let Some(ref video) = record.video {
@@ -434,37 +462,25 @@ impl DemonPage {
     }
 }
 
-fn host(video: &str) -> &str {
-    match Url::parse(video).unwrap().domain().unwrap() {
-        "www.youtube.com" => "YouTube",
-        "www.twitch.tv" => "Twitch",
-        "everyplay.com" => "Everyplay",
-        "www.bilibili.com" => "Bilibili",
-        "vimeo.com" => "Vimeo",
-        host => panic!("{}", host),
+/// Formats a completion time given in milliseconds as `[h:]mm:ss.mmm`
+fn format_completion_time(millis: i64) -> String {
+    let (hours, minutes, seconds, millis) = (millis / 3_600_000, millis / 60_000 % 60, millis / 1000 % 60, millis % 1000);
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}.{:03}", hours, minutes, seconds, millis)
+    } else {
+        format!("{:02}:{:02}.{:03}", minutes, seconds, millis)
     }
 }
 
-fn embed(video: &str) -> Option<String> {
-    // Video URLs need to be wellformed once we get here!
-    let url = Url::parse(video).unwrap();
-
-    match url.domain()? {
-        "www.youtube.com" => {
-            let video_id = url
-                .query_pairs()
-                .find_map(|(key, value)| if key == "v" { Some(value) } else { None })?;
-
-            Some(format!("https://www.youtube.com/embed/{}", video_id))
-        },
-        "www.twitch.tv" => {
-            // per validation always of the form 'https://www.twitch.tv/videos/[video id]/'
-            let mut url_segment = url.path_segments()?;
-            url_segment.next()?;
-            let video_id = url_segment.next()?;
-
-            Some(format!("https://player.twitch.tv/?video={}&autoplay=false", video_id))
-        },
-        _ => None,
+fn host(video: &str) -> &'static str {
+    // Very old links might predate any validation
+    match VideoIdentity::of(video) {
+        Ok(identity) => identity.host_name(),
+        Err(_) => "Video",
     }
 }
+
+fn embed(video: &str) -> Option<String> {
+    VideoIdentity::of(video).ok()?.embed_url()
+}
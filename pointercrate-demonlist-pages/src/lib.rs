@@ -1,6 +1,6 @@
 use maud::{html, Markup};
 
-use pointercrate_demonlist::{config, demon::Demon};
+use pointercrate_demonlist::{demon::Demon, list::List};
 
 pub mod account;
 pub mod components;
@@ -41,20 +41,20 @@ static LEGACY_SECTION: ListSection = ListSection {
     numbered: false,
 };
 
-fn dropdowns(all_demons: &[&Demon], current: Option<&Demon>) -> Markup {
-    let (main, extended, legacy) = if all_demons.len() < config::list_size() as usize {
+fn dropdowns(list: &List, all_demons: &[&Demon], current: Option<&Demon>) -> Markup {
+    let (main, extended, legacy) = if all_demons.len() < list.list_size as usize {
         (all_demons, Default::default(), Default::default())
     } else {
-        let (extended, legacy) = if all_demons.len() < config::extended_list_size() as usize {
-            (&all_demons[config::list_size() as usize..], Default::default())
+        let (extended, legacy) = if all_demons.len() < list.extended_list_size as usize {
+            (&all_demons[list.list_size as usize..], Default::default())
         } else {
             (
-                &all_demons[config::list_size() as usize..config::extended_list_size() as usize],
-                &all_demons[config::extended_list_size() as usize..],
+                &all_demons[list.list_size as usize..list.extended_list_size as usize],
+                &all_demons[list.extended_list_size as usize..],
             )
         };
 
-        (&all_demons[..config::list_size() as usize], extended, legacy)
+        (&all_demons[..list.list_size as usize], extended, legacy)
     };
 
     html! {
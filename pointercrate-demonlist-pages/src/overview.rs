@@ -9,18 +9,26 @@ use crate::{
 use maud::{html, Markup, PreEscaped};
 use pointercrate_core_pages::{head::HeadLike, PageFragment};
 use pointercrate_demonlist::{
-    config as list_config,
     demon::{Demon, TimeShiftedDemon},
+    list::List,
+    record::challenge::ChallengeWidget,
 };
 
 pub struct OverviewPage {
     pub team: Team,
+
+    /// The list whose demons are displayed
+    pub list: List,
+
     pub demonlist: Vec<Demon>,
     pub time_machine: Tardis,
     pub submitter_initially_visible: bool,
+
+    /// The challenge to render on the record submission form, if any
+    pub challenge: Option<ChallengeWidget>,
 }
 
-fn demon_panel(demon: &Demon, current_position: Option<i16>) -> Markup {
+fn demon_panel(list: &List, demon: &Demon, current_position: Option<i16>) -> Markup {
     html! {
          section.panel.fade style="overflow:hidden" {
              div.flex style = "align-items: center" {
@@ -44,7 +52,7 @@ fn demon_panel(demon: &Demon, current_position: Option<i16>) -> Markup {
                          }
                          @if let Some(current_position) = current_position {
                              br;
-                             @if current_position > list_config::extended_list_size() {
+                             @if current_position > list.extended_list_size {
                                  "Currently Legacy"
                              }
                              @else {
@@ -114,7 +122,7 @@ impl OverviewPage {
                 <script>
                     window.list_length = {0};
                     window.extended_list_length = {1}
-                </script>", list_config::list_size(), list_config::extended_list_size())
+                </script>", self.list.list_size, self.list.extended_list_size)
             ))
             // FIXME: abstract away
             link ref = "canonical" href = "https://pointercrate.xyze.dev/demonlist/";
@@ -127,7 +135,7 @@ impl OverviewPage {
             _ => self.demonlist.iter().collect(),
         };
 
-        let dropdowns = super::dropdowns(&demons_for_dropdown[..], None);
+        let dropdowns = super::dropdowns(&self.list, &demons_for_dropdown[..], None);
 
         html! {
             (dropdowns)
@@ -135,20 +143,20 @@ impl OverviewPage {
             div.flex.m-center.container {
                 main.left {
                     (self.time_machine)
-                    (RecordSubmitter::new(self.submitter_initially_visible, &self.demonlist))
+                    (RecordSubmitter::new(self.submitter_initially_visible, &self.list, &self.demonlist).with_challenge(self.challenge.as_ref()))
 
                     @match &self.time_machine {
                         Tardis::Activated { demons, ..} => {
                             @for TimeShiftedDemon {current_demon, position_now} in demons {
-                                @if current_demon.base.position <= list_config::extended_list_size() {
-                                    (demon_panel(current_demon, Some(*position_now)))
+                                @if current_demon.base.position <= self.list.extended_list_size {
+                                    (demon_panel(&self.list, current_demon, Some(*position_now)))
                                 }
                             }
                         },
                         _ => {
                             @for demon in &self.demonlist {
-                                @if demon.base.position <= list_config::extended_list_size() {
-                                    (demon_panel(demon, None))
+                                @if demon.base.position <= self.list.extended_list_size {
+                                    (demon_panel(&self.list, demon, None))
                                 }
                             }
                         }
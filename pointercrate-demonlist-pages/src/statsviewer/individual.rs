@@ -1,11 +1,12 @@
 use crate::statsviewer::stats_viewer_html;
 use maud::{html, Markup};
 use pointercrate_core_pages::{head::HeadLike, PageFragment};
-use pointercrate_demonlist::nationality::Nationality;
+use pointercrate_demonlist::nationality::{Continent, Nationality};
 
 #[derive(Debug)]
 pub struct IndividualStatsViewer {
     pub nationalities_in_use: Vec<Nationality>,
+    pub continents: Vec<Continent>,
 }
 
 impl From<IndividualStatsViewer> for PageFragment {
@@ -44,7 +45,7 @@ impl IndividualStatsViewer {
                     (stats_viewer_html(Some(&self.nationalities_in_use), super::standard_stats_viewer_rows()))
                 }
                 aside.right {
-                    (super::continent_panel())
+                    (super::continent_panel(&self.continents))
                     section.panel.fade style = "overflow: initial;" {
                         h3.underlined {
                             "Political Subdivision:"
@@ -1,6 +1,6 @@
 use maud::{html, Markup, PreEscaped};
-use pointercrate_core_pages::util::{dropdown, filtered_paginator, simple_dropdown};
-use pointercrate_demonlist::nationality::Nationality;
+use pointercrate_core_pages::util::{dropdown, filtered_paginator};
+use pointercrate_demonlist::nationality::{Continent, Nationality};
 
 pub mod individual;
 pub mod national;
@@ -23,7 +23,7 @@ pub(crate) fn stats_viewer_panel() -> Markup {
     }
 }
 
-fn continent_panel() -> Markup {
+fn continent_panel(continents: &[Continent]) -> Markup {
     html! {
         section.panel.fade style="overflow:initial"{
             h3.underlined {
@@ -32,7 +32,23 @@ fn continent_panel() -> Markup {
             p {
                 "Select a continent below to focus the stats viewer to that continent. Select 'All' to reset selection."
             }
-            (simple_dropdown("continent-dropdown", Some("All"), vec!["Asia", "Europe", "Australia", "Africa", "North America", "South America", "Central America"].into_iter()))
+            div.dropdown-menu.js-search.no-stretch #continent-dropdown {
+                div {
+                    input type="text" autocomplete="off" data-default="All" style = "font-weight: bold;";
+                }
+                div.menu {
+                    ul {
+                        li.colorless.underlined.hover data-value="All" data-display="All" {
+                            b {"All"}
+                        }
+                        @for continent in continents {
+                            li.colorless.hover data-value=(continent.id) data-display=(continent.name) {
+                                b {(continent.name)}
+                            }
+                        }
+                    }
+                }
+            }
         }
     }
 }
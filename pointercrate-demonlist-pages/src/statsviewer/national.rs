@@ -1,8 +1,9 @@
 use crate::statsviewer::{stats_viewer_html, StatsViewerRow};
 use maud::{html, Markup};
 use pointercrate_core_pages::{head::HeadLike, PageFragment};
+use pointercrate_demonlist::nationality::Continent;
 
-pub fn nation_based_stats_viewer() -> PageFragment {
+pub fn nation_based_stats_viewer(continents: &[Continent]) -> PageFragment {
     use pointercrate_core_pages::{versioned_import, with_version_string};
 
     PageFragment::new(
@@ -17,10 +18,10 @@ pub fn nation_based_stats_viewer() -> PageFragment {
     .import(versioned_import!("/static/demonlist/js/modules/statsviewer.js"))
     .stylesheet(with_version_string!("/static/demonlist/css/statsviewer.css"))
     .stylesheet(with_version_string!("/static/core/css/sidebar.css"))
-    .body(nation_based_stats_viewer_html())
+    .body(nation_based_stats_viewer_html(continents))
 }
 
-fn nation_based_stats_viewer_html() -> Markup {
+fn nation_based_stats_viewer_html(continents: &[Continent]) -> Markup {
     let mut rows = super::standard_stats_viewer_rows();
 
     rows[0].0.insert(1, ("Players", "players"));
@@ -40,7 +41,7 @@ fn nation_based_stats_viewer_html() -> Markup {
                 (stats_viewer_html(None, rows))
             }
             aside.right {
-                (super::continent_panel())
+                (super::continent_panel(continents))
             }
         }
     }
@@ -0,0 +1,101 @@
+import {
+  displayError,
+  FormDialog,
+  get,
+  post,
+  valueMissing,
+} from "/static/core/js/modules/form.js";
+
+/**
+ * Displays the ban history of the object currently selected in a paginator (either a player or a
+ * submitter), and allows issuing new bans against it.
+ */
+export class BanEditor {
+  /**
+   * @param paginator The paginator whose selected object's bans should be managed
+   * @param historyId The id of the list into which the ban history should be rendered
+   * @param dialogId The id of the dialog for issuing new bans
+   * @param buttonId The id of the button opening the dialog
+   */
+  constructor(paginator, historyId, dialogId, buttonId) {
+    this._paginator = paginator;
+    this._history = document.getElementById(historyId);
+
+    let dialog = new FormDialog(dialogId);
+
+    dialog.form.addValidators({
+      [dialogId + "-reason"]: {
+        "Please provide a reason for the ban": valueMissing,
+      },
+    });
+
+    dialog.submissionPredicateFactory = (data) => {
+      // datetime-local inputs do not include seconds, which the server requires
+      if (data.expires_at) {
+        data.expires_at += ":00";
+      }
+
+      return post(this.url(), {}, data)
+        .then(() => {
+          this._paginator.output.setSuccess("Ban issued!");
+          this._paginator.refresh();
+          return this._paginator.selectArbitrary(this._paginator.currentObject.id);
+        })
+        .catch((response) => {
+          displayError(dialog.form)(response);
+          throw response;
+        });
+    };
+
+    document
+      .getElementById(buttonId)
+      .addEventListener("click", () => dialog.open().catch(() => {}));
+  }
+
+  url() {
+    return this._paginator.retrievalEndpoint + this._paginator.currentObject.id + "/bans/";
+  }
+
+  /**
+   * Retrieves and displays the ban history of the currently selected object
+   */
+  refresh() {
+    return get(this.url())
+      .then((response) => {
+        this._history.innerHTML = "";
+
+        if (response.data.length === 0) {
+          this._history.appendChild(generateBanItem("Never banned"));
+        }
+
+        for (let ban of response.data) {
+          let text = ban.issued_at.replace("T", " ") + ": " + (ban.reason || "No reason given");
+
+          if (ban.expires_at) {
+            text += " (until " + ban.expires_at.replace("T", " ") + ")";
+          } else {
+            text += " (permanent)";
+          }
+
+          if (ban.lifted_at) {
+            text += ", lifted " + ban.lifted_at.replace("T", " ");
+          }
+
+          this._history.appendChild(generateBanItem(text, ban.lifted_at === null));
+        }
+      })
+      .catch(displayError(this._paginator.output));
+  }
+}
+
+function generateBanItem(text, active = false) {
+  let li = document.createElement("li");
+
+  li.innerText = text;
+
+  if (active) {
+    li.style.fontWeight = "bold";
+  }
+
+  return li;
+}
@@ -169,16 +169,88 @@ export function initialize() {
 
     let claimedPlayer = document.getElementById("claimed-player");
 
+    let disputePanel = document.getElementById("claims-dispute-panel");
+    let disputeOutput = new Output(disputePanel);
+    let disputedPlayer = null;
+
     let playerPaginator = new ClaimPlayerPaginator();
     playerPaginator.initialize();
     playerPaginator.addSelectionListener(selected => {
         put("/api/v1/players/" + selected.id + "/claims/")
             .then(() => {
                 window.location.reload();
-            }).catch(displayError(playerPaginator));
+            }).catch(displayError(playerPaginator, {
+                // Player already has a verified claim by someone else, offer to dispute it instead
+                42231: () => {
+                    disputedPlayer = selected;
+                    document.getElementById("claims-dispute-player").innerText = selected.name;
+                    disputePanel.style.display = "block";
+                }
+            }));
     })
 
-    document.getElementById("player-claim-pen").addEventListener("click", () => {
+    document.getElementById("claims-dispute-submit").addEventListener("click", () => {
+        let evidence = document.getElementById("claims-dispute-evidence").value;
+
+        if (!evidence.trim()) {
+            disputeOutput.setError("Please provide evidence for your dispute");
+            return;
+        }
+
+        post("/api/v1/players/" + disputedPlayer.id + "/claims/disputes", {}, {evidence: evidence})
+            .then(() => disputeOutput.setSuccess("Dispute opened! A list moderator will review it soon."))
+            .catch(displayError(disputeOutput));
+    });
+
+    let disputeList = document.getElementById("claim-dispute-list");
+
+    if (disputeList) {
+        let disputes = new Output(document.getElementById("claim-disputes"));
+        let reason = document.getElementById("claim-dispute-reason");
+
+        for (let button of disputeList.getElementsByClassName("js-resolve-dispute")) {
+            button.addEventListener("click", () => {
+                if (!reason.value.trim()) {
+                    disputes.setError("Please provide a reason for your decision");
+                    return;
+                }
+
+                let li = button.closest("li");
+
+                post("/api/v1/players/claims/disputes/" + li.dataset.id + "/resolution", {}, {resolution: button.dataset.resolution, reason: reason.value})
+                    .then(() => {
+                        li.remove();
+                        disputes.setSuccess("Dispute resolved!");
+                        if (claimManager) claimManager.refresh();
+                    }).catch(displayError(disputes));
+            });
+        }
+    }
+
+    let suggestionList = document.getElementById("claim-suggestion-list");
+
+    if (suggestionList) {
+        let suggestions = new Output(document.getElementById("claim-suggestions"));
+        let note = document.getElementById("claim-suggestion-note");
+
+        for (let button of suggestionList.getElementsByClassName("js-review-suggestion")) {
+            button.addEventListener("click", () => {
+                let li = button.closest("li");
+                let review = {approve: button.dataset.approve === "true"};
+
+                if (note.value.trim())
+                    review.note = note.value;
+
+                post("/api/v1/players/claims/suggestions/" + li.dataset.id + "/review", {}, review)
+                    .then(() => {
+                        li.remove();
+                        suggestions.setSuccess(review.approve ? "Suggestion approved and applied!" : "Suggestion rejected!");
+                    }).catch(displayError(suggestions));
+            });
+        }
+    }
+
+        document.getElementById("player-claim-pen").addEventListener("click", () => {
         playerPaginator.html.parentElement.style.display = "block";
     });
 
@@ -211,5 +283,72 @@ export function initialize() {
 
         let recordPaginator = new ClaimedPlayerRecordPaginator(playerId);
         recordPaginator.initialize();
+
+        let suggestOutput = new Output(document.getElementById("claims-suggest-panel"));
+        let comment = document.getElementById("claims-suggest-comment");
+
+        let suggest = edit => {
+            if (comment.value.trim())
+                edit.comment = comment.value;
+
+            post("/api/v1/players/" + playerId + "/suggestions", {}, edit)
+                .then(() => suggestOutput.setSuccess("Suggestion submitted! A list moderator will review it soon."))
+                .catch(displayError(suggestOutput));
+        };
+
+        document.getElementById("claims-suggest-name-submit").addEventListener("click", () => {
+            suggest({kind: "name", name: document.getElementById("claims-suggest-name").value});
+        });
+
+        document.getElementById("claims-suggest-subdivision-submit").addEventListener("click", () => {
+            let subdivision = document.getElementById("claims-suggest-subdivision").value.trim();
+
+            suggest({kind: "subdivision", subdivision: subdivision ? subdivision : null});
+        });
+
+        document.getElementById("claims-suggest-video-submit").addEventListener("click", () => {
+            let recordId = document.getElementById("claims-suggest-record").value;
+
+            if (!recordId) {
+                suggestOutput.setError("Please enter the ID of the record whose video should be changed");
+            } else {
+                suggest({kind: "record_video", record_id: parseInt(recordId), video: document.getElementById("claims-suggest-video").value});
+            }
+        });
+
+        document.getElementById("claims-suggest-removal-submit").addEventListener("click", () => {
+            let recordId = document.getElementById("claims-suggest-removal").value;
+
+            if (!recordId) {
+                suggestOutput.setError("Please enter the ID of the record that should be removed");
+            } else {
+                suggest({kind: "record_removal", record_id: parseInt(recordId)});
+            }
+        });
+    }
+
+    let verifyPanel = document.getElementById("claims-verify-panel");
+
+    if (verifyPanel) {
+        let output = new Output(verifyPanel);
+        let playerId = claimedPlayer.dataset.id;
+
+        let verify = location => {
+            post("/api/v1/players/" + playerId + "/claims/" + window.userId + "/verify", {}, location)
+                .then(() => window.location.reload())
+                .catch(displayError(output));
+        };
+
+        document.getElementById("claims-verify-profile").addEventListener("click", () => verify({location: "profile_comment"}));
+
+        let levelId = document.getElementById("claims-verify-level-id");
+
+        document.getElementById("claims-verify-level").addEventListener("click", () => {
+            if (!levelId.value) {
+                output.setError("Please enter the ID of the level whose description contains the code");
+            } else {
+                verify({location: "level_description", level_id: parseInt(levelId.value)});
+            }
+        });
     }
 }
\ No newline at end of file
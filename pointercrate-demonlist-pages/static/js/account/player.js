@@ -10,6 +10,7 @@ import {
   Viewer, get,
 } from "/static/core/js/modules/form.js";
 import { recordManager, initialize as initRecords } from "./records.js";
+import { BanEditor } from "./bans.js";
 
 export let playerManager;
 
@@ -49,6 +50,8 @@ class PlayerManager extends FilteredPaginator {
         { None: null }
     );
 
+    this._bans = new BanEditor(this, "player-ban-history", "player-ban-dialog", "player-ban");
+
     this.initNameDialog();
   }
 
@@ -63,6 +66,7 @@ class PlayerManager extends FilteredPaginator {
     this._name.innerText = this.currentObject.name;
 
     this._banned.selectSilently(this.currentObject.banned.toString());
+    this._bans.refresh();
 
     if (this.currentObject.nationality) {
       this._nationality.selectSilently(
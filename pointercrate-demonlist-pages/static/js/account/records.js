@@ -39,6 +39,7 @@ class RecordManager extends Paginator {
 
     this._video = document.getElementById("record-video");
     this._video_link = document.getElementById("record-video-link");
+    this._raw_footage = document.getElementById("record-raw-footage");
     this._id = document.getElementById("record-id");
     this._demon = document.getElementById("record-demon");
     this._holder = document.getElementById("record-holder");
@@ -70,6 +71,20 @@ class RecordManager extends Paginator {
       this.output
     );
 
+    let reasonIds = {};
+
+    for (let li of document.getElementById("edit-record-rejection-reason").getElementsByTagName("li")) {
+      reasonIds[li.dataset.value] = parseInt(li.dataset.value);
+    }
+
+    this._rejectionReason = setupDropdownEditor(
+      new PaginatorEditorBackend(this, true),
+      "edit-record-rejection-reason",
+      "rejection_reason",
+      this.output,
+      reasonIds
+    );
+
     this.initProgressDialog();
     this.initVideoDialog();
 
@@ -157,6 +172,29 @@ class RecordManager extends Paginator {
       this._video_link.style.display = "none";
     }
 
+    while (this._raw_footage.firstChild) {
+      this._raw_footage.removeChild(this._raw_footage.firstChild);
+    }
+
+    if (this.currentObject.raw_footage.length === 0) {
+      this._raw_footage.innerText = "None";
+    }
+
+    for (let url of this.currentObject.raw_footage) {
+      // Raw footage can be any URL, so only turn the ones that are safe to open into links
+      let link = document.createElement(/^https?:\/\//i.test(url) ? "a" : "span");
+
+      if (link.tagName === "A") {
+        link.classList.add("link");
+        link.target = "_blank";
+        link.href = url;
+      }
+      link.innerText = url;
+
+      this._raw_footage.appendChild(link);
+      this._raw_footage.appendChild(document.createElement("br"));
+    }
+
     this._id.innerHTML = this.currentObject.id;
     this._demon.innerHTML =
       this.currentObject.demon.name + " (" + this.currentObject.demon.id + ")";
@@ -166,8 +204,19 @@ class RecordManager extends Paginator {
       this.currentObject.player.id +
       ")";
     this._status.selectSilently(this.currentObject.status);
+    if (this.currentObject.rejection_reason) {
+      this._rejectionReason.selectSilently(this.currentObject.rejection_reason.id.toString());
+    } else {
+      this._rejectionReason.reset();
+    }
     this._progress.innerHTML = this.currentObject.progress + "%";
-    this._submitter.innerHTML = this.currentObject.submitter.id;
+    this._submitter.innerText =
+      this.currentObject.submitter.id +
+      " (Reputation: " +
+      Math.round(this.currentObject.submitter.statistics.reputation * 100) +
+      "%" +
+      (this.currentObject.submitter.flagged ? ", flagged" : "") +
+      ")";
 
     // this is introducing race conditions. Oh well.
     return get("/api/v1/records/" + this.currentObject.id + "/notes").then(response => {
@@ -354,12 +403,61 @@ function setupEditRecordForm() {
   });
 }
 
+function setupReviewQueue() {
+  let panel = document.getElementById("review-queue");
+  let output = new Output(panel);
+  let order = new Dropdown(panel.getElementsByClassName("dropdown-menu")[0]);
+  let stats = document.getElementById("review-queue-stats");
+
+  let refreshStats = () => {
+    get("/api/v1/records/queue/statistics/").then((response) => {
+      let data = response.data;
+
+      stats.innerText =
+        data.submitted +
+        " submitted, " +
+        data.under_consideration +
+        " under consideration, " +
+        data.locked +
+        " currently being reviewed";
+    });
+  };
+
+  document.getElementById("review-queue-claim").addEventListener("click", () => {
+    post("/api/v1/records/queue/claim/?order=" + order.selected)
+      .then((response) => {
+        output.setSuccess("Claimed record #" + response.data.record + "!");
+        refreshStats();
+
+        return recordManager.selectArbitrary(response.data.record);
+      })
+      .catch(displayError(output));
+  });
+
+  document.getElementById("review-queue-release").addEventListener("click", () => {
+    if (!recordManager.currentObject) {
+      output.setError("Select a record on the left first!");
+      return;
+    }
+
+    del("/api/v1/records/" + recordManager.currentObject.id + "/lock/")
+      .then(() => {
+        output.setSuccess("Released record #" + recordManager.currentObject.id + "!");
+        refreshStats();
+      })
+      .catch(displayError(output));
+  });
+
+  refreshStats();
+}
+
 export function initialize() {
   setupRecordFilterPlayerIdForm();
   setupRecordFilterPlayerNameForm();
   setupAddNote();
   setupEditRecordForm();
   setupRecordSearchRecordIdForm();
+  setupReviewQueue();
 
   initializeRecordSubmitter(true);
 
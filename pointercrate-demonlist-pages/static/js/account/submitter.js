@@ -6,8 +6,12 @@ import {
   Paginator,
   setupDropdownEditor,
   PaginatorEditorBackend,
+  get,
+  post,
+  del,
 } from "/static/core/js/modules/form.js";
 import { recordManager, initialize as initRecords } from "./records.js";
+import { BanEditor } from "./bans.js";
 
 export let submitterManager;
 
@@ -21,6 +25,8 @@ function generateSubmitter(submitter) {
 
   if (submitter.banned) {
     li.style.borderLeftColor = "rgba(221, 54, 78, .8)";
+  } else if (submitter.flagged) {
+    li.style.borderLeftColor = "rgba(255, 190, 60, .8)";
   } else {
     li.style.borderLeftColor = "rgba(164, 253, 106, .8)";
   }
@@ -52,6 +58,20 @@ class SubmitterManager extends Paginator {
       this.output,
       { true: true, false: false }
     );
+
+    this._flagged = setupDropdownEditor(
+      new PaginatorEditorBackend(this, true),
+      "edit-submitter-flagged",
+      "flagged",
+      this.output,
+      { true: true, false: false }
+    );
+
+    this._submitted = document.getElementById("submitter-submitted");
+    this._outcomes = document.getElementById("submitter-outcomes");
+    this._reputation = document.getElementById("submitter-reputation");
+
+    this._bans = new BanEditor(this, "submitter-ban-history", "submitter-ban-dialog", "submitter-ban");
   }
 
   onReceive(response) {
@@ -63,6 +83,15 @@ class SubmitterManager extends Paginator {
 
     this._id.innerText = this.currentObject.id;
     this._banned.selectSilently(this.currentObject.banned.toString());
+    this._flagged.selectSilently(this.currentObject.flagged.toString());
+
+    let statistics = this.currentObject.statistics;
+
+    this._submitted.innerText = statistics.submitted;
+    this._outcomes.innerText =
+      statistics.approved + " / " + statistics.rejected + " / " + statistics.deleted_invalid;
+    this._reputation.innerText = Math.round(statistics.reputation * 100) + "%";
+    this._bans.refresh();
   }
 }
 
@@ -80,8 +109,83 @@ function setupSubmitterSearchSubmitterIdForm() {
   });
 }
 
+function refreshRangeBans(output) {
+  let list = document.getElementById("range-ban-list");
+
+  return get("/api/v1/range_bans/")
+    .then((response) => {
+      list.innerHTML = "";
+
+      for (let ban of response.data) {
+        if (ban.lifted_at !== null) {
+          continue;
+        }
+
+        let li = document.createElement("li");
+        let text = ban.range + ": " + ban.reason;
+
+        if (ban.expires_at) {
+          text += " (until " + ban.expires_at.replace("T", " ") + ")";
+        }
+
+        li.innerText = text;
+        li.className = "hover";
+        li.addEventListener("click", () => {
+          if (!confirm("Lift the ban of " + ban.range + "?")) {
+            return;
+          }
+
+          del("/api/v1/range_bans/" + ban.id + "/")
+            .then(() => {
+              output.setSuccess("Ban of " + ban.range + " lifted!");
+              return refreshRangeBans(output);
+            })
+            .catch(displayError(output));
+        });
+
+        list.appendChild(li);
+      }
+    })
+    .catch(displayError(output));
+}
+
+function setupRangeBanForm() {
+  var rangeBanForm = new Form(document.getElementById("range-ban-form"));
+
+  rangeBanForm.addValidators({
+    "range-ban-range": {
+      "Address range required": valueMissing,
+    },
+    "range-ban-reason": {
+      "Please provide a reason for the ban": valueMissing,
+    },
+  });
+
+  rangeBanForm.addErrorOverride(42249, "range-ban-range");
+
+  rangeBanForm.onSubmit(function () {
+    let data = rangeBanForm.serialize();
+
+    // datetime-local inputs do not include seconds, which the server requires
+    if (data.expires_at) {
+      data.expires_at += ":00";
+    }
+
+    post("/api/v1/range_bans/", {}, data)
+      .then((response) => {
+        rangeBanForm.setSuccess("Banned " + response.data.range + "!");
+        rangeBanForm.clear();
+        return refreshRangeBans(rangeBanForm);
+      })
+      .catch(displayError(rangeBanForm));
+  });
+
+  refreshRangeBans(rangeBanForm);
+}
+
 export function initialize(tabber) {
   setupSubmitterSearchSubmitterIdForm();
+  setupRangeBanForm();
 
   submitterManager = new SubmitterManager();
   submitterManager.initialize();
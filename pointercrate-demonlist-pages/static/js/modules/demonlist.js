@@ -17,19 +17,31 @@ import {
 
 export function embedVideo(video) {
   if (!video) return;
-  // welcome to incredibly fragile string parsing with stadust
-  // see pointercrate::video::embed for a proper implementation of this
+  // Videos are always in the canonical format produced by the backend here
+  // see pointercrate_demonlist::video::host for a proper implementation of this
 
-  if (video.startsWith("https://www.youtube")) {
-    return "https://www.youtube.com/embed/" + video.substring(32);
+  let url = new URL(video);
+  let start = url.searchParams.get("t");
+
+  if (url.hostname === "www.youtube.com") {
+    return (
+      "https://www.youtube.com/embed/" +
+      url.searchParams.get("v") +
+      (start ? "?start=" + parseInt(start) : "")
+    );
   }
 
-  if (video.startsWith("https://www.twitch")) {
+  if (url.hostname === "www.twitch.tv") {
     return (
       "https://player.twitch.tv/?autoplay=false&parent=pointercrate.xyze.dev&video=" +
-      video.substring(29)
+      url.pathname.substring(8) +
+      (start ? "&time=" + start : "")
     );
   }
+
+  if (url.hostname === "streamable.com") {
+    return "https://streamable.com/e" + url.pathname;
+  }
 }
 
 export function initializeTimeMachine() {
@@ -111,7 +123,24 @@ export function initializeRecordSubmitter(submitApproved = false) {
     if (submitApproved) {
       data.status = "approved";
     }
+
+    // Only rendered for users that are not logged in
+    let challenge = document.getElementById("submit-challenge");
+
+    if (challenge) {
+      let response = challenge.querySelector("[name='" + challenge.dataset.responseField + "']");
+
+      if (response && response.value) {
+        headers["X-Challenge-Response"] = response.value;
+      }
+    }
+
     post("/api/v1/records/", headers, data)
+      .finally(() => {
+        // Responses to hosted challenges can only be used once
+        if (window.turnstile) window.turnstile.reset();
+        if (window.hcaptcha) window.hcaptcha.reset();
+      })
       .then(() => {
         submissionForm.setSuccess("Record successfully submitted");
         submissionForm.clear();
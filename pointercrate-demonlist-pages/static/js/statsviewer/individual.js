@@ -18,6 +18,12 @@ class IndividualStatsViewer extends StatsViewer {
 
         this.setName(playerData.name, playerData.nationality);
 
+        if (playerData.aliases.length > 0) {
+            this._name.title = "Previously known as " + playerData.aliases.map(alias => alias.name).join(", ");
+        } else {
+            this._name.removeAttribute("title");
+        }
+
         this.formatDemonsInto(this._created, playerData.created);
         this.formatDemonsInto(this._published, playerData.published);
         this.formatDemonsInto(this._verified, playerData.verified);
@@ -1,6 +1,6 @@
 SELECT demons.id AS demon_id, demons.name::text AS demon_name, demons.position, demons.requirement, demons.level_id, CASE WHEN verifiers.link_banned THEN NULL ElSE demons.video::text END, demons.thumbnail,
        verifiers.id AS verifier_id, verifiers.name::text AS verifier_name, verifiers.banned AS verifier_banned,
-       publishers.id AS publisher_id, publishers.name::text AS publisher_name, publishers.banned AS publisher_banned
+       publishers.id AS publisher_id, publishers.name::text AS publisher_name, publishers.banned AS publisher_banned, demons.list, demons.record_kind::text AS record_kind, demons.max_time
 FROM demons
 INNER JOIN players AS verifiers ON verifiers.id=demons.verifier
 INNER JOIN players AS publishers ON publishers.id=demons.publisher
@@ -15,5 +15,6 @@ WHERE (demons.id < $1 OR $1 IS NULL)
   AND (publishers.id = $9 OR $9 IS NULL)
   AND (publishers.name::CITEXT = $10 OR $10 IS NULL)
   AND (STRPOS(demons.name, $11::CITEXT) > 0 OR $11 is NULL)
+  AND (demons.list = $13 OR $13 IS NULL)
 ORDER BY demons.id {}
 LIMIT $12
\ No newline at end of file
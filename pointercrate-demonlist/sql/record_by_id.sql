@@ -1,9 +1,12 @@
-SELECT progress, CASE WHEN players.link_banned THEN NULL ELSE records.video::text END, status_::text AS "status!: String" ,
+SELECT progress, records.completion_time, CASE WHEN players.link_banned THEN NULL ELSE records.video::text END, status_::text AS "status!: String" ,
        players.id AS player_id, players.name AS "player_name: String", players.banned AS player_banned,
        demons.id AS demon_id, demons.name AS "demon_name: String", demons.position,
-       submitters.submitter_id AS submitter_id, submitters.banned AS submitter_banned
+       submitters.submitter_id AS submitter_id, submitters.banned AS submitter_banned, submitters.flagged AS submitter_flagged,
+       submitters.submitted, submitters.approved, submitters.rejected, submitters.deleted_invalid, submitters.reputation AS "reputation!",
+       rejection_reasons.id AS "rejection_reason_id?", rejection_reasons.name::text AS "rejection_reason_name?", rejection_reasons.description AS rejection_reason_description
 FROM records
 INNER JOIN players ON records.player = players.id
 INNER JOIN demons ON records.demon = demons.id
 INNER JOIN submitters ON records.submitter = submitters.submitter_id
+LEFT OUTER JOIN rejection_reasons ON records.rejection_reason = rejection_reasons.id
 WHERE records.id = $1
\ No newline at end of file
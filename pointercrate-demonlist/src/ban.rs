@@ -0,0 +1,103 @@
+//! Module for bans of players and submitters
+//!
+//! Bans are issued by list moderators, and can be given a reason and an expiry date. Once a ban
+//! expires, it is lifted automatically by [`lift_expired_bans`]. Past bans are kept, so that a
+//! player's or submitter's ban history can be inspected.
+//!
+//! Whether a player or submitter is currently banned is still tracked by their `banned` flag, which
+//! is kept in sync with the ban history.
+//!
+//! Bans of entire address ranges work differently, see [`RangeBan`](crate::submitter::RangeBan).
+
+use crate::{
+    error::{DemonlistError, Result},
+    player::DatabasePlayer,
+    submitter::Submitter,
+};
+use chrono::{NaiveDateTime, Utc};
+use log::info;
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
+pub struct Ban {
+    pub id: i32,
+
+    /// The reason given by the issuing moderator. `None` for bans issued without a reason (e.g. by
+    /// simply setting a player's `banned` flag)
+    pub reason: Option<String>,
+
+    /// The ID of the member who issued this ban
+    pub issued_by: Option<i32>,
+    pub issued_at: NaiveDateTime,
+
+    /// The time at which this ban lifts automatically. `None` for permanent bans
+    pub expires_at: Option<NaiveDateTime>,
+    pub lifted_at: Option<NaiveDateTime>,
+
+    /// The ID of the member who lifted this ban. `None` if the ban expired
+    pub lifted_by: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PostBan {
+    pub reason: String,
+
+    #[serde(default)]
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+impl PostBan {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.reason.trim().is_empty() {
+            return Err(DemonlistError::InvalidBan);
+        }
+
+        match self.expires_at {
+            Some(expires_at) if expires_at <= Utc::now().naive_utc() => Err(DemonlistError::InvalidBan),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Lifts all bans whose expiry has passed, returning the number of lifted bans
+pub async fn lift_expired_bans(connection: &mut PgConnection) -> Result<usize> {
+    let players = sqlx::query!("SELECT player FROM player_bans WHERE lifted_at IS NULL AND expires_at <= (NOW() AT TIME ZONE 'utc')")
+        .fetch_all(&mut *connection)
+        .await?;
+
+    for row in &players {
+        let mut player = DatabasePlayer::by_id(row.player, &mut *connection).await?;
+
+        info!("Ban of player {} expired", player);
+
+        player.lift_ban(true, &mut *connection).await?;
+        player.update_score(&mut *connection).await?;
+    }
+
+    let submitters =
+        sqlx::query!("SELECT submitter FROM submitter_bans WHERE lifted_at IS NULL AND expires_at <= (NOW() AT TIME ZONE 'utc')")
+            .fetch_all(&mut *connection)
+            .await?;
+
+    for row in &submitters {
+        let mut submitter = Submitter::by_id(row.submitter, &mut *connection).await?;
+
+        info!("Ban of submitter {} expired", submitter);
+
+        submitter.lift_ban(true, &mut *connection).await?;
+    }
+
+    let ranges = sqlx::query!(
+        "UPDATE submitter_range_bans SET lifted_at = expires_at WHERE lifted_at IS NULL AND expires_at <= (NOW() AT TIME ZONE 'utc') \
+         RETURNING range::text AS \"range!\""
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for row in &ranges {
+        info!("Ban of address range {} expired", row.range);
+    }
+
+    Ok(players.len() + submitters.len() + ranges.len())
+}
@@ -1,9 +1,18 @@
 use pointercrate_core::util::from_env_or_default;
 
-pub fn list_size() -> i16 {
-    from_env_or_default("LIST_SIZE", 75)
+/// The number of minutes a record claimed from the review queue stays locked to its reviewer
+pub fn review_lock_duration() -> i32 {
+    from_env_or_default("REVIEW_LOCK_MINUTES", 15)
 }
 
-pub fn extended_list_size() -> i16 {
-    from_env_or_default("EXTENDED_LIST_SIZE", 150)
+/// Whether rejecting a record requires specifying one of the
+/// [`RejectionReason`](crate::record::rejection::RejectionReason)s
+pub fn rejection_reason_required() -> bool {
+    from_env_or_default("REQUIRE_REJECTION_REASON", false)
+}
+
+/// The length of the prefix by which IPv6 addresses are identified for the purpose of submitter
+/// identity and ratelimits. IPv4 addresses are always considered in full.
+pub fn ipv6_prefix_length() -> u8 {
+    from_env_or_default("SUBMITTER_IPV6_PREFIX_LENGTH", 64)
 }
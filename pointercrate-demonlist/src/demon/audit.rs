@@ -16,6 +16,7 @@ pub struct DemonModificationData {
     pub video: Option<String>,
     pub verifier: Option<NamedId>,
     pub publisher: Option<NamedId>,
+    pub max_time: Option<i64>,
 }
 
 #[derive(Serialize, Debug)]
@@ -104,7 +105,9 @@ pub async fn movement_log_for_demon(demon_id: i32, connection: &mut PgConnection
                     }
 
                     // update the previous entry's "new_position" field
-                    movement_log.last_mut().map(|entry| entry.new_position = Some(old_position));
+                    if let Some(entry) = movement_log.last_mut() {
+                        entry.new_position = Some(old_position);
+                    }
 
                     // if the time part of the datetime object is just zeros, the log entry was generated from deltas,
                     // meaning we can't figure out reasons accurately
@@ -168,11 +171,11 @@ pub async fn movement_log_for_demon(demon_id: i32, connection: &mut PgConnection
     }
 
     // update the last entry with the current position
-    MinimalDemon::by_id(demon_id, &mut *connection).await.map(|minimal_demon| {
-        movement_log
-            .last_mut()
-            .map(|entry| entry.new_position = Some(minimal_demon.position));
-    })?;
+    let minimal_demon = MinimalDemon::by_id(demon_id, &mut *connection).await?;
+
+    if let Some(entry) = movement_log.last_mut() {
+        entry.new_position = Some(minimal_demon.position);
+    }
 
     Ok(movement_log)
 }
@@ -215,7 +218,8 @@ pub async fn audit_log_for_demon(demon_id: i32, connection: &mut PgConnection) -
                 verifier,
                 verifiers.name::text as verifier_name,
                 publisher,
-                publishers.name::text as publisher_name
+                publishers.name::text as publisher_name,
+                max_time
            FROM demon_modifications
            LEFT OUTER JOIN members ON members.member_id = userid
            LEFT OUTER JOIN players AS verifiers ON verifier=verifiers.id
@@ -253,6 +257,7 @@ pub async fn audit_log_for_demon(demon_id: i32, connection: &mut PgConnection) -
                     }),
                     None => None,
                 },
+                max_time: row.max_time,
             }),
             user: NamedId {
                 name: row.username,
@@ -1,6 +1,6 @@
 use crate::{
     creator::creators_of,
-    demon::{Demon, FullDemon, MinimalDemon, TimeShiftedDemon},
+    demon::{Demon, FullDemon, MinimalDemon, RecordKind, TimeShiftedDemon},
     error::{DemonlistError, Result},
     player::DatabasePlayer,
     record::approved_records_on,
@@ -20,9 +20,10 @@ impl MinimalDemon {
             })
     }
 
-    pub async fn by_position(position: i16, connection: &mut PgConnection) -> Result<MinimalDemon> {
+    pub async fn by_position(list: i32, position: i16, connection: &mut PgConnection) -> Result<MinimalDemon> {
         let row = sqlx::query!(
-            r#"SELECT id, name as "name: String", position FROM demons WHERE position = $1"#,
+            r#"SELECT id, name as "name: String", position FROM demons WHERE list = $1 AND position = $2"#,
+            list,
             position
         )
         .fetch_one(connection)
@@ -77,8 +78,8 @@ impl FullDemon {
         Demon::by_id(id, connection).await?.upgrade(connection).await
     }
 
-    pub async fn by_position(position: i16, connection: &mut PgConnection) -> Result<FullDemon> {
-        Demon::by_position(position, connection).await?.upgrade(connection).await
+    pub async fn by_position(list: i32, position: i16, connection: &mut PgConnection) -> Result<FullDemon> {
+        Demon::by_position(list, position, connection).await?.upgrade(connection).await
     }
 }
 
@@ -106,8 +107,8 @@ impl Demon {
             })
     }
 
-    pub async fn by_position(position: i16, connection: &mut PgConnection) -> Result<Demon> {
-        sqlx::query_file_as!(FetchedDemon, "sql/demon_by_position.sql", position)
+    pub async fn by_position(list: i32, position: i16, connection: &mut PgConnection) -> Result<Demon> {
+        sqlx::query_file_as!(FetchedDemon, "sql/demon_by_position.sql", list, position)
             .fetch_one(connection)
             .await
             .map(Into::into)
@@ -161,6 +162,9 @@ struct FetchedDemon {
     verifier_name: String,
     verifier_banned: bool,
     level_id: Option<i64>,
+    list: i32,
+    record_kind: String,
+    max_time: Option<i64>,
 }
 
 impl From<FetchedDemon> for Demon {
@@ -185,12 +189,15 @@ impl From<FetchedDemon> for Demon {
                 banned: fetched.verifier_banned,
             },
             level_id: fetched.level_id.map(|id| id as u64),
+            list: fetched.list,
+            record_kind: RecordKind::from_sql(&fetched.record_kind),
+            max_time: fetched.max_time,
         }
     }
 }
 
-pub async fn current_list(connection: &mut PgConnection) -> Result<Vec<Demon>> {
-    Ok(sqlx::query_file_as!(FetchedDemon, "sql/all_demons.sql")
+pub async fn current_list(list: i32, connection: &mut PgConnection) -> Result<Vec<Demon>> {
+    Ok(sqlx::query_file_as!(FetchedDemon, "sql/all_demons.sql", list)
         .fetch_all(connection)
         .await?
         .into_iter()
@@ -198,8 +205,8 @@ pub async fn current_list(connection: &mut PgConnection) -> Result<Vec<Demon>> {
         .collect())
 }
 
-pub async fn list_at(connection: &mut PgConnection, at: NaiveDateTime) -> Result<Vec<TimeShiftedDemon>> {
-    let mut stream = sqlx::query_file!("sql/all_demons_at.sql", at).fetch(connection);
+pub async fn list_at(list: i32, connection: &mut PgConnection, at: NaiveDateTime) -> Result<Vec<TimeShiftedDemon>> {
+    let mut stream = sqlx::query_file!("sql/all_demons_at.sql", at, list).fetch(connection);
     let mut demons = Vec::new();
 
     while let Some(row) = stream.next().await {
@@ -226,6 +233,9 @@ pub async fn list_at(connection: &mut PgConnection, at: NaiveDateTime) -> Result
                     banned: row.verifier_banned,
                 },
                 level_id: row.level_id.map(|i| i as u64),
+                list: row.list,
+                record_kind: RecordKind::from_sql(&row.record_kind),
+                max_time: row.max_time,
             },
             position_now: row.current_position,
         })
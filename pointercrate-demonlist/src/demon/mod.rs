@@ -6,6 +6,7 @@ pub use self::{
 };
 use crate::{
     error::{DemonlistError, Result},
+    list::List,
     player::DatabasePlayer,
     record::MinimalRecordP,
 };
@@ -57,6 +58,46 @@ pub struct Demon {
     /// This is automatically queried based on the level name, but can be manually overridden by a
     /// list mod.
     pub level_id: Option<u64>,
+
+    /// The ID of the [`List`] this [`Demon`] is placed on
+    pub list: i32,
+
+    /// Whether records on this [`Demon`] are ranked by progress or by completion time
+    pub record_kind: RecordKind,
+
+    /// The maximal completion time (in milliseconds) a record on this [`Demon`] may have to be
+    /// accepted. Only meaningful if [`Demon::record_kind`] is [`RecordKind::Time`].
+    pub max_time: Option<i64>,
+}
+
+/// How records on a demon are ranked.
+///
+/// Classic levels are ranked by the progress (in percent) a player achieved, while platformer levels
+/// are ranked by the time a player needed to complete them.
+#[derive(Debug, Deserialize, Serialize, Hash, Eq, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordKind {
+    #[default]
+    Progress,
+    Time,
+}
+
+impl RecordKind {
+    pub fn to_sql(self) -> String {
+        match self {
+            RecordKind::Progress => "PROGRESS",
+            RecordKind::Time => "TIME",
+        }
+        .to_owned()
+    }
+
+    pub(crate) fn from_sql(sql: &str) -> Self {
+        match sql {
+            "PROGRESS" => RecordKind::Progress,
+            "TIME" => RecordKind::Time,
+            _ => panic!("invalid record kind: {}", sql),
+        }
+    }
 }
 
 /// Absolutely minimal representation of a demon to be sent when a demon is part of another object
@@ -66,9 +107,9 @@ pub struct MinimalDemon {
     /// The [`Demon`]'s unique internal pointercrate ID
     pub id: i32,
 
-    /// The [`Demon`]'s position on the demonlist
+    /// The [`Demon`]'s position on its list
     ///
-    /// Positions for consecutive demons are always consecutive positive integers
+    /// Positions for consecutive demons on the same list are always consecutive positive integers
     pub position: i16,
 
     /// The [`Demon`]'s Geometry Dash level name
@@ -107,6 +148,46 @@ impl MinimalDemon {
             .await?
             .requirement)
     }
+
+    /// Checks whether a record with the given progress and completion time would be valid on this
+    /// demon
+    ///
+    /// For progress based demons, the progress must meet the demon's requirement and no completion
+    /// time may be given. For time based demons, records are always 100% completions and need a
+    /// positive completion time no slower than the demon's maximal time (if it has one).
+    pub async fn validate_record(&self, progress: i16, completion_time: Option<i64>, connection: &mut PgConnection) -> Result<()> {
+        let row = sqlx::query!(
+            r#"SELECT requirement, record_kind::text AS "record_kind!", max_time FROM demons WHERE id = $1"#,
+            self.id
+        )
+        .fetch_one(connection)
+        .await?;
+
+        match (RecordKind::from_sql(&row.record_kind), completion_time) {
+            (RecordKind::Progress, Some(_)) => Err(DemonlistError::UnexpectedCompletionTime),
+            (RecordKind::Progress, None) if progress > 100 || progress < row.requirement => Err(DemonlistError::InvalidProgress {
+                requirement: row.requirement,
+            }),
+            (RecordKind::Progress, None) => Ok(()),
+            (RecordKind::Time, None) => Err(DemonlistError::CompletionTimeRequired),
+            (RecordKind::Time, Some(_)) if progress != 100 => Err(DemonlistError::InvalidProgress { requirement: 100 }),
+            (RecordKind::Time, Some(time)) if time <= 0 => Err(DemonlistError::InvalidCompletionTime),
+            (RecordKind::Time, Some(time)) => match row.max_time {
+                Some(max_time) if time > max_time => Err(DemonlistError::CompletionTimeTooSlow { max_time }),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// Queries the [`List`] this demon is placed on from the database
+    pub async fn list(&self, connection: &mut PgConnection) -> Result<List> {
+        let list_id = sqlx::query!("SELECT list FROM demons WHERE id = $1", self.id)
+            .fetch_one(&mut *connection)
+            .await?
+            .list;
+
+        List::by_id(list_id, connection).await
+    }
 }
 
 impl FullDemon {
@@ -175,10 +256,18 @@ impl Demon {
         Ok(())
     }
 
-    pub async fn validate_position(position: i16, connection: &mut PgConnection) -> Result<()> {
+    pub fn validate_max_time(record_kind: RecordKind, max_time: Option<i64>) -> Result<()> {
+        match (record_kind, max_time) {
+            (_, None) => Ok(()),
+            (RecordKind::Time, Some(max_time)) if max_time > 0 => Ok(()),
+            _ => Err(DemonlistError::InvalidMaxTime),
+        }
+    }
+
+    pub async fn validate_position(list: i32, position: i16, connection: &mut PgConnection) -> Result<()> {
         // To prevent holes from being created in the list, the new position must lie between 1 and (current
         // last position + 1), inclusive
-        let maximal_position = Demon::max_position(connection).await? + 1;
+        let maximal_position = Demon::max_position(list, connection).await? + 1;
 
         if position > maximal_position || position < 1 {
             return Err(DemonlistError::InvalidPosition { maximal: maximal_position });
@@ -187,31 +276,40 @@ impl Demon {
         Ok(())
     }
 
-    /// Increments the position of all demons with positions equal to or greater than the given one,
-    /// by one.
-    async fn shift_down(starting_at: i16, connection: &mut PgConnection) -> Result<()> {
-        info!("Shifting down all demons, starting at {}", starting_at);
+    /// Increments the position of all demons on the given list with positions equal to or greater
+    /// than the given one, by one.
+    async fn shift_down(list: i32, starting_at: i16, connection: &mut PgConnection) -> Result<()> {
+        info!("Shifting down all demons on list {}, starting at {}", list, starting_at);
 
-        sqlx::query!("UPDATE demons SET position = position + 1 WHERE position >= $1", starting_at)
-            .execute(connection)
-            .await?;
+        sqlx::query!(
+            "UPDATE demons SET position = position + 1 WHERE list = $1 AND position >= $2",
+            list,
+            starting_at
+        )
+        .execute(connection)
+        .await?;
 
         Ok(())
     }
 
-    /// Gets the current max position a demon has, or `0` if there are no demons
-    /// in the database
-    pub async fn max_position(connection: &mut PgConnection) -> Result<i16> {
-        Ok(sqlx::query!("SELECT MAX(position) as max_position FROM demons")
-            .fetch_one(connection)
-            .await?
-            .max_position
-            .unwrap_or(0))
+    /// Gets the current max position a demon on the given list has, or `0` if there are no demons
+    /// on that list
+    pub async fn max_position(list: i32, connection: &mut PgConnection) -> Result<i16> {
+        Ok(
+            sqlx::query!("SELECT MAX(position) as max_position FROM demons WHERE list = $1", list)
+                .fetch_one(connection)
+                .await?
+                .max_position
+                .unwrap_or(0),
+        )
     }
 
-    pub fn score(&self, progress: i16) -> f64 {
-        let position = self.base.position;
-        let mut score = 150f64 * f64::exp((1f64 - f64::from(position)) * (1f64 / 30f64).ln() / (-149f64));
+    /// The score a record with the given progress on this [`Demon`] gives, where `list` is the
+    /// [`List`] this demon is placed on
+    pub fn score(&self, progress: i16, list: &List) -> f64 {
+        let position = f64::from(self.base.position);
+        let list_size = f64::from(list.extended_list_size);
+        let mut score = list_size * f64::exp((1f64 - position) * (1f64 / 30f64).ln() / (1f64 - list_size));
 
         if progress != 100 {
             score *= 0.25f64 + (f64::from(progress) - f64::from(self.requirement)) / (100f64 - f64::from(self.requirement)) * 0.25f64
@@ -1,5 +1,6 @@
 use crate::{
-    demon::{Demon, MinimalDemon},
+    demon::{Demon, MinimalDemon, RecordKind},
+    list::DEFAULT_LIST,
     player::DatabasePlayer,
 };
 use futures::stream::StreamExt;
@@ -42,6 +43,9 @@ pub struct DemonIdPagination {
     #[serde(default, deserialize_with = "non_nullable")]
     #[serde(rename = "requirement__lt")]
     requirement_lt: Option<i16>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub list: Option<i32>,
 }
 
 impl PaginationQuery for DemonIdPagination {
@@ -58,7 +62,7 @@ impl PaginationQuery for DemonIdPagination {
 }
 
 impl Paginatable<DemonIdPagination> for Demon {
-    first_and_last!("demons");
+    first_and_last!(DemonIdPagination, "demons");
 
     async fn page(query: &DemonIdPagination, connection: &mut PgConnection) -> Result<(Vec<Demon>, PageContext), sqlx::Error> {
         let order = query.params.order();
@@ -79,6 +83,7 @@ impl Paginatable<DemonIdPagination> for Demon {
             .bind(query.publisher_name.as_deref())
             .bind(query.name_contains.as_deref())
             .bind(query.params.limit + 1)
+            .bind(query.list)
             .fetch(connection);
 
         let mut demons = Vec::new();
@@ -108,6 +113,9 @@ impl Paginatable<DemonIdPagination> for Demon {
                     banned: row.get("verifier_banned"),
                 },
                 level_id: row.get::<Option<i64>, _>("level_id").map(|id| id as u64),
+                list: row.get("list"),
+                record_kind: RecordKind::from_sql(row.get("record_kind")),
+                max_time: row.get("max_time"),
             })
         }
 
@@ -150,6 +158,11 @@ pub struct DemonPositionPagination {
     #[serde(default, deserialize_with = "non_nullable")]
     #[serde(rename = "requirement__lt")]
     pub requirement_lt: Option<i16>,
+
+    /// The list whose demons to paginate. Defaults to [`DEFAULT_LIST`], as positions are only
+    /// unique within one list.
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub list: Option<i32>,
 }
 
 impl PaginationQuery for DemonPositionPagination {
@@ -166,7 +179,7 @@ impl PaginationQuery for DemonPositionPagination {
 }
 
 impl Paginatable<DemonPositionPagination> for Demon {
-    first_and_last!("demons", "position");
+    first_and_last!(DemonPositionPagination, "demons", "position");
 
     async fn page(query: &DemonPositionPagination, connection: &mut PgConnection) -> Result<(Vec<Demon>, PageContext), sqlx::Error> {
         let order = query.params.order();
@@ -187,6 +200,7 @@ impl Paginatable<DemonPositionPagination> for Demon {
             .bind(query.publisher_name.as_deref())
             .bind(query.name_contains.as_deref())
             .bind(query.params.limit + 1)
+            .bind(query.list.unwrap_or(DEFAULT_LIST))
             .fetch(connection);
 
         let mut demons = Vec::new();
@@ -216,6 +230,9 @@ impl Paginatable<DemonPositionPagination> for Demon {
                     banned: row.get("verifier_banned"),
                 },
                 level_id: row.get::<Option<i64>, _>("level_id").map(|id| id as u64),
+                list: row.get("list"),
+                record_kind: RecordKind::from_sql(row.get("record_kind")),
+                max_time: row.get("max_time"),
             })
         }
 
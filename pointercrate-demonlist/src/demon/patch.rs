@@ -2,6 +2,7 @@ use crate::{
     demon::{Demon, FullDemon, MinimalDemon},
     error::{DemonlistError, Result},
     player::{recompute_scores, DatabasePlayer},
+    video::thumbnail::{ThumbnailResolver, DEFAULT_THUMBNAIL},
 };
 use log::{debug, info, warn};
 use pointercrate_core::util::{non_nullable, nullable};
@@ -19,8 +20,9 @@ pub struct PatchDemon {
     #[serde(default, deserialize_with = "nullable")]
     pub video: Option<Option<String>>,
 
-    #[serde(default, deserialize_with = "non_nullable")]
-    pub thumbnail: Option<String>,
+    /// Setting the thumbnail to `null` reverts it to being derived from the verification video
+    #[serde(default, deserialize_with = "nullable")]
+    pub thumbnail: Option<Option<String>>,
 
     #[serde(default, deserialize_with = "non_nullable")]
     pub requirement: Option<i16>,
@@ -30,18 +32,29 @@ pub struct PatchDemon {
 
     #[serde(default, deserialize_with = "non_nullable")]
     pub publisher: Option<String>,
+
+    /// Note that a demon's [`RecordKind`](crate::demon::RecordKind) cannot be changed after
+    /// creation, as its existing records would become meaningless.
+    #[serde(default, deserialize_with = "nullable")]
+    pub max_time: Option<Option<i64>>,
 }
 
 impl FullDemon {
-    pub async fn apply_patch(mut self, patch: PatchDemon, connection: &mut PgConnection) -> Result<Self> {
+    pub async fn apply_patch(mut self, patch: PatchDemon, thumbnails: &ThumbnailResolver, connection: &mut PgConnection) -> Result<Self> {
         let changes_requirement = patch.requirement.is_some();
+        let changes_max_time = patch.max_time.is_some();
 
-        let updated_demon = self.demon.apply_patch(patch, connection).await?;
+        let updated_demon = self.demon.apply_patch(patch, thumbnails, connection).await?;
 
         if changes_requirement {
             self.records.retain(|record| record.progress >= updated_demon.requirement);
         }
 
+        if let (true, Some(max_time)) = (changes_max_time, updated_demon.max_time) {
+            self.records
+                .retain(|record| record.completion_time.is_none_or(|completion_time| completion_time <= max_time));
+        }
+
         Ok(FullDemon {
             demon: updated_demon,
             ..self
@@ -51,9 +64,12 @@ impl FullDemon {
 
 impl Demon {
     /// Must run inside a transaction!
-    pub async fn apply_patch(mut self, patch: PatchDemon, connection: &mut PgConnection) -> Result<Self> {
+    pub async fn apply_patch(mut self, patch: PatchDemon, thumbnails: &ThumbnailResolver, connection: &mut PgConnection) -> Result<Self> {
         // duplicate names are OK nowadays
 
+        // The thumbnail depends on both the video and the verifier's link ban
+        let mut refresh_thumbnail = patch.video.is_some() || patch.verifier.is_some();
+
         if let Some(position) = patch.position {
             self.base.mv(position, connection).await?;
         }
@@ -70,7 +86,14 @@ impl Demon {
         }
 
         if let Some(thumbnail) = patch.thumbnail {
-            self.set_thumbnail(thumbnail, connection).await?;
+            match thumbnail {
+                None => {
+                    self.reset_thumbnail(connection).await?;
+
+                    refresh_thumbnail = true;
+                },
+                Some(thumbnail) => self.set_thumbnail(thumbnail, connection).await?,
+            }
         }
 
         if let Some(verifier) = patch.verifier {
@@ -89,6 +112,14 @@ impl Demon {
             self.set_requirement(requirement, connection).await?;
         }
 
+        if let Some(max_time) = patch.max_time {
+            self.set_max_time(max_time, connection).await?;
+        }
+
+        if refresh_thumbnail {
+            self.refresh_thumbnail(thumbnails, connection).await?;
+        }
+
         Ok(self)
     }
 
@@ -138,6 +169,32 @@ impl Demon {
         Ok(())
     }
 
+    pub async fn set_max_time(&mut self, max_time: Option<i64>, connection: &mut PgConnection) -> Result<()> {
+        Demon::validate_max_time(self.record_kind, max_time)?;
+
+        if let Some(max_time) = max_time {
+            sqlx::query!(
+                "DELETE FROM records WHERE demon = $1 AND completion_time > $2",
+                self.base.id,
+                max_time
+            )
+            .execute(&mut *connection)
+            .await?;
+        }
+
+        sqlx::query!("UPDATE demons SET max_time = $1 WHERE id = $2", max_time, self.base.id)
+            .execute(&mut *connection)
+            .await?;
+
+        self.max_time = max_time;
+
+        // The players whose records were removed need to lose the points they got from them
+        recompute_scores(connection).await?;
+
+        Ok(())
+    }
+
+    /// Note that this does not update the thumbnail, see [`Demon::refresh_thumbnail`]
     pub async fn set_video(&mut self, video: String, connection: &mut PgConnection) -> Result<()> {
         let video = crate::video::validate(&video)?;
 
@@ -150,6 +207,7 @@ impl Demon {
         Ok(())
     }
 
+    /// Note that this does not update the thumbnail, see [`Demon::refresh_thumbnail`]
     pub async fn remove_video(&mut self, connection: &mut PgConnection) -> Result<()> {
         sqlx::query!("UPDATE demons SET video = NULL WHERE id = $1", self.base.id)
             .execute(connection)
@@ -160,12 +218,64 @@ impl Demon {
         Ok(())
     }
 
+    /// Manually overrides this demon's thumbnail, meaning it will no longer be updated when the
+    /// verification video changes
     pub async fn set_thumbnail(&mut self, thumbnail: String, connection: &mut PgConnection) -> Result<()> {
-        sqlx::query!("UPDATE demons SET thumbnail = $1::text WHERE id = $2", thumbnail, self.base.id)
+        sqlx::query!(
+            "UPDATE demons SET thumbnail = $1::text, thumbnail_overridden = TRUE WHERE id = $2",
+            thumbnail,
+            self.base.id
+        )
+        .execute(connection)
+        .await?;
+
+        self.thumbnail = thumbnail;
+
+        Ok(())
+    }
+
+    /// Undoes a manual override of this demon's thumbnail. The thumbnail itself is only updated
+    /// by the next call to [`Demon::refresh_thumbnail`]
+    pub async fn reset_thumbnail(&mut self, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!("UPDATE demons SET thumbnail_overridden = FALSE WHERE id = $1", self.base.id)
             .execute(connection)
             .await?;
 
-        self.thumbnail = thumbnail;
+        Ok(())
+    }
+
+    /// Derives this demon's thumbnail from its verification video, unless it was manually
+    /// overridden via [`Demon::set_thumbnail`]
+    ///
+    /// If the verifier is link banned, or no thumbnail can be derived from the video, the
+    /// [`DEFAULT_THUMBNAIL`] is used.
+    pub async fn refresh_thumbnail(&mut self, thumbnails: &ThumbnailResolver, connection: &mut PgConnection) -> Result<()> {
+        let row = sqlx::query!(
+            "SELECT thumbnail_overridden, link_banned FROM demons INNER JOIN players ON players.id = demons.verifier WHERE demons.id = $1",
+            self.base.id
+        )
+        .fetch_one(&mut *connection)
+        .await?;
+
+        if row.thumbnail_overridden {
+            return Ok(());
+        }
+
+        let thumbnail = match self.video {
+            Some(ref video) if !row.link_banned.unwrap_or(false) => thumbnails.resolve(video).await,
+            _ => None,
+        }
+        .unwrap_or_else(|| DEFAULT_THUMBNAIL.to_string());
+
+        if thumbnail != self.thumbnail {
+            debug!("Updating thumbnail of demon {} to {}", self, thumbnail);
+
+            sqlx::query!("UPDATE demons SET thumbnail = $1::text WHERE id = $2", thumbnail, self.base.id)
+                .execute(connection)
+                .await?;
+
+            self.thumbnail = thumbnail;
+        }
 
         Ok(())
     }
@@ -184,13 +294,15 @@ impl MinimalDemon {
         Ok(())
     }
 
-    /// Moves this demon to the specified position
+    /// Moves this demon to the specified position on its list
     ///
     /// Validates that `to` is `> 0` and less than or equal to the currently highest position on the
     /// list (to preven "holes")
     pub async fn mv(&mut self, to: i16, connection: &mut PgConnection) -> Result<()> {
+        let list = self.list(connection).await?;
+
         // This returns 0 if the list is empty, but if the list is empty then there is no demon for us to do a move with, so we will never get here anyway.
-        let maximal_position = Demon::max_position(connection).await?;
+        let maximal_position = Demon::max_position(list.id, connection).await?;
 
         if to > maximal_position || to < 1 {
             return Err(DemonlistError::InvalidPosition { maximal: maximal_position });
@@ -216,7 +328,8 @@ impl MinimalDemon {
             );
 
             sqlx::query!(
-                "UPDATE demons SET position = position - 1 WHERE position > $1 AND position <= $2",
+                "UPDATE demons SET position = position - 1 WHERE list = $1 AND position > $2 AND position <= $3",
+                list.id,
                 self.position,
                 to
             )
@@ -229,7 +342,8 @@ impl MinimalDemon {
             );
 
             sqlx::query!(
-                "UPDATE demons SET position = position + 1 WHERE position >= $1 AND position < $2",
+                "UPDATE demons SET position = position + 1 WHERE list = $1 AND position >= $2 AND position < $3",
+                list.id,
                 to,
                 self.position
             )
@@ -1,8 +1,10 @@
 use crate::{
     creator::Creator,
-    demon::{Demon, FullDemon, MinimalDemon},
+    demon::{Demon, FullDemon, MinimalDemon, RecordKind},
     error::Result,
+    list::{List, DEFAULT_LIST},
     player::{recompute_scores, DatabasePlayer},
+    video::thumbnail::ThumbnailResolver,
 };
 use log::info;
 use serde::Deserialize;
@@ -17,41 +19,63 @@ pub struct PostDemon {
     publisher: String,
     creators: Vec<String>,
     video: Option<String>,
+
+    /// The ID of the list to add the demon to. Defaults to [`DEFAULT_LIST`]
+    #[serde(default)]
+    list: Option<i32>,
+
+    /// Whether records on the demon are ranked by progress or by completion time. Defaults to
+    /// [`RecordKind::Progress`]
+    #[serde(default)]
+    record_kind: RecordKind,
+
+    /// The maximal completion time (in milliseconds) of records on the demon. Only allowed for
+    /// time based demons
+    #[serde(default)]
+    max_time: Option<i64>,
 }
 
 impl FullDemon {
     /// Must be run within a transaction!
-    pub async fn create_from(data: PostDemon, connection: &mut PgConnection) -> Result<FullDemon> {
+    ///
+    /// The demon's thumbnail is derived from its verification video using the given resolver.
+    pub async fn create_from(data: PostDemon, thumbnails: &ThumbnailResolver, connection: &mut PgConnection) -> Result<FullDemon> {
         info!("Creating new demon from {:?}", data);
 
         Demon::validate_requirement(data.requirement)?;
+        Demon::validate_max_time(data.record_kind, data.max_time)?;
 
         let video = match data.video {
             Some(ref video) => Some(crate::video::validate(video)?),
             None => None,
         };
 
-        Demon::validate_position(data.position, connection).await?;
+        let list = List::by_id(data.list.unwrap_or(DEFAULT_LIST), connection).await?;
+
+        Demon::validate_position(list.id, data.position, connection).await?;
 
         let publisher = DatabasePlayer::by_name_or_create(data.publisher.as_ref(), connection).await?;
         let verifier = DatabasePlayer::by_name_or_create(data.verifier.as_ref(), connection).await?;
 
-        Demon::shift_down(data.position, connection).await?;
+        Demon::shift_down(list.id, data.position, connection).await?;
 
         let created = sqlx::query!(
-            "INSERT INTO demons (name, position, requirement, video, verifier, publisher) VALUES ($1::text,$2,$3,$4::text,$5,$6) \
-             RETURNING id, thumbnail",
+            "INSERT INTO demons (name, position, requirement, video, verifier, publisher, list, record_kind, max_time) VALUES \
+             ($1::text,$2,$3,$4::text,$5,$6,$7,$8::text::record_kind,$9) RETURNING id, thumbnail",
             data.name.to_string(),
             data.position,
             data.requirement,
             video.as_ref(),
             verifier.id,
-            publisher.id
+            publisher.id,
+            list.id,
+            data.record_kind.to_sql(),
+            data.max_time
         )
         .fetch_one(&mut *connection)
         .await?;
 
-        let demon = Demon {
+        let mut demon = Demon {
             base: MinimalDemon {
                 id: created.id,
                 position: data.position,
@@ -63,8 +87,13 @@ impl FullDemon {
             publisher,
             verifier,
             level_id: None,
+            list: list.id,
+            record_kind: data.record_kind,
+            max_time: data.max_time,
         };
 
+        demon.refresh_thumbnail(thumbnails, &mut *connection).await?;
+
         let mut creators = Vec::new();
 
         for creator in data.creators {
@@ -1,4 +1,5 @@
 use crate::{demon::MinimalDemon, record::RecordStatus};
+use chrono::NaiveDateTime;
 use derive_more::Display;
 
 use pointercrate_core::error::{CoreError, PointercrateError};
@@ -38,6 +39,14 @@ pub enum DemonlistError {
     #[display(fmt = "This player has requested that only they themselves can submit their records")]
     NoThirdPartySubmissions,
 
+    /// `403 FORBIDDEN` variant returned if an anonymous record submission did not include a valid
+    /// response to the deployment's
+    /// [`SubmissionChallenge`](crate::record::challenge::SubmissionChallenge)
+    ///
+    /// Error Code `40309`
+    #[display(fmt = "Please complete the challenge to prove that you are not a bot")]
+    ChallengeFailed,
+
     #[display(fmt = "No submitter with id {} found", id)]
     SubmitterNotFound { id: i32 },
 
@@ -53,6 +62,9 @@ pub enum DemonlistError {
     #[display(fmt = "No subdivision with code {} found in nation {}", subdivision_code, nation_code)]
     SubdivisionNotFound { subdivision_code: String, nation_code: String },
 
+    #[display(fmt = "No continent with id {} found", continent_id)]
+    ContinentNotFound { continent_id: i32 },
+
     #[display(fmt = "No player with id {} found", player_id)]
     PlayerNotFound { player_id: i32 },
 
@@ -74,6 +86,36 @@ pub enum DemonlistError {
     #[display(fmt = "No claim by user {} on player {} found", member_id, player_id)]
     ClaimNotFound { member_id: i32, player_id: i32 },
 
+    #[display(fmt = "No list with id {} found", list_id)]
+    ListNotFound { list_id: i32 },
+
+    #[display(fmt = "No list with slug {} found", slug)]
+    ListNotFoundSlug { slug: String },
+
+    #[display(fmt = "There are no unclaimed records in the review queue")]
+    ReviewQueueEmpty,
+
+    #[display(fmt = "Record {} is not locked for review", record_id)]
+    ReviewLockNotFound { record_id: i32 },
+
+    #[display(fmt = "No rejection reason with id {} found", reason_id)]
+    RejectionReasonNotFound { reason_id: i32 },
+
+    #[display(fmt = "No alias with id {} of player {} found", alias_id, player_id)]
+    PlayerAliasNotFound { player_id: i32, alias_id: i32 },
+
+    #[display(fmt = "No merge with id {} into player {} that can be undone found", merge_id, player_id)]
+    PlayerMergeNotFound { player_id: i32, merge_id: i32 },
+
+    #[display(fmt = "No range ban with id {} found", id)]
+    RangeBanNotFound { id: i32 },
+
+    #[display(fmt = "No claim dispute with id {} found", id)]
+    ClaimDisputeNotFound { id: i32 },
+
+    #[display(fmt = "No edit suggestion with id {} found", id)]
+    SuggestionNotFound { id: i32 },
+
     #[display(fmt = "This player is already registered as a creator on this demon")]
     CreatorExists,
 
@@ -96,6 +138,74 @@ pub enum DemonlistError {
     )]
     ConflictingClaims { player1: String, player2: String },
 
+    /// `409 CONFLICT` variant
+    ///
+    /// Error Code `40909`
+    #[display(fmt = "A list with this slug already exists")]
+    ListSlugTaken,
+
+    /// `409 CONFLICT` variant returned if attempted to modify a record that another list team
+    /// member currently holds a review lock on
+    ///
+    /// Error Code `40910`
+    #[display(fmt = "This record is currently being reviewed by {}", reviewer)]
+    RecordLocked {
+        /// The name of the reviewer holding the lock
+        reviewer: String,
+
+        /// The time the lock expires at, or `None` if the record was assigned to the reviewer
+        locked_until: Option<NaiveDateTime>,
+    },
+
+    /// `409 CONFLICT` variant
+    ///
+    /// Error Code `40911`
+    #[display(fmt = "A rejection reason with this name already exists")]
+    RejectionReasonExists,
+
+    /// `409 CONFLICT` variant returned if attempted to rename a player to the name of a different
+    /// player. Such players need to be merged explicitly.
+    ///
+    /// Error Code `40912`
+    #[display(
+        fmt = "Player {} already goes by this name. If both are the same person, merge the players instead",
+        player_id
+    )]
+    PlayerNameTaken { player_id: i32 },
+
+    /// `409 CONFLICT` variant returned if a user attempts to dispute a claim they already have an
+    /// open dispute on
+    ///
+    /// Error Code `40913`
+    #[display(fmt = "You already have an open dispute on this player's claim")]
+    DisputeAlreadyOpen,
+
+    /// `409 CONFLICT` variant
+    ///
+    /// Error Code `40914`
+    #[display(fmt = "This dispute has already been resolved")]
+    DisputeAlreadyResolved,
+
+    /// `409 CONFLICT` variant
+    ///
+    /// Error Code `40915`
+    #[display(fmt = "This suggestion has already been reviewed")]
+    SuggestionAlreadyReviewed,
+
+    /// `409 CONFLICT` variant returned if attempted to transfer or revoke a disputed claim after
+    /// the verified claim on the player changed hands since the dispute was opened
+    ///
+    /// Error Code `40916`
+    #[display(fmt = "The verified claim on this player has changed since the dispute was opened")]
+    DisputedClaimChanged,
+
+    /// `409 CONFLICT` variant returned if attempted to approve a suggested edit whose suggester no
+    /// longer holds a verified claim on the player
+    ///
+    /// Error Code `40917`
+    #[display(fmt = "The user who suggested this edit no longer holds a verified claim on this player")]
+    SuggesterNotClaimant,
+
     /// `422 UNPROCESSABLE ENTITY` variant returned if attempted to create a demon with a record
     /// requirements outside of [0, 100]
     ///
@@ -157,7 +267,9 @@ pub enum DemonlistError {
     /// `422 UNPROCESSABLE ENTITY` variant
     ///
     /// Error Code `42224`
-    #[display(fmt = "The given video host is not supported. Supported are 'youtube', 'vimeo', 'everyplay', 'twitch' and 'bilibili'")]
+    #[display(
+        fmt = "The given video host is not supported. Supported are 'youtube', 'twitch', 'bilibili', 'vimeo', 'medal' and 'streamable'"
+    )]
     UnsupportedVideoHost,
 
     /// `422 UNPROCESSABLE ENTITY` variant
@@ -189,6 +301,172 @@ pub enum DemonlistError {
     /// Error Code `42233`
     #[display(fmt = "Raw footage needs to be a valid URL")]
     MalformedRawUrl,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if attempted to create a list whose main
+    /// section is empty or larger than its extended section
+    ///
+    /// Error Code `42234`
+    #[display(fmt = "The list size needs to be at least 1 and at most the extended list size")]
+    InvalidListSizes,
+
+    /// `422 UNPROCESSABLE ENTITY` variant
+    ///
+    /// Error Code `42235`
+    #[display(fmt = "List slugs must be between 1 and 32 characters long and consist only of lowercase letters, digits and underscores")]
+    MalformedListSlug,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if a record on a time based demon is submitted
+    /// without a completion time
+    ///
+    /// Error Code `42236`
+    #[display(fmt = "Records on this demon need to specify a completion time")]
+    CompletionTimeRequired,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if a record on a progress based demon is
+    /// submitted with a completion time
+    ///
+    /// Error Code `42237`
+    #[display(fmt = "Only records on time based demons can have a completion time")]
+    UnexpectedCompletionTime,
+
+    /// `422 UNPROCESSABLE ENTITY` variant
+    ///
+    /// Error Code `42238`
+    #[display(fmt = "Record completion time must be a positive amount of milliseconds")]
+    InvalidCompletionTime,
+
+    /// `422 UNPROCESSABLE ENTITY` variant
+    ///
+    /// Error Code `42239`
+    #[display(fmt = "Record completion time must be at most {} milliseconds!", max_time)]
+    CompletionTimeTooSlow {
+        /// The [`Demon`]'s maximal completion time
+        max_time: i64,
+    },
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if attempted to set a maximal completion time
+    /// that isn't positive, or to set one on a progress based demon
+    ///
+    /// Error Code `42240`
+    #[display(fmt = "Maximal completion time must be a positive amount of milliseconds and can only be set on time based demons")]
+    InvalidMaxTime,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if attempted to assign a record that is
+    /// neither submitted nor under consideration to a reviewer
+    ///
+    /// Error Code `42241`
+    #[display(fmt = "Only records that are submitted or under consideration can be assigned for review")]
+    RecordNotInQueue,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if attempted to assign a record to a user that
+    /// is not a member of the list team
+    ///
+    /// Error Code `42242`
+    #[display(fmt = "Records can only be assigned to list helpers")]
+    NotAReviewer,
+
+    /// `422 UNPROCESSABLE ENTITY` variant
+    ///
+    /// Error Code `42243`
+    #[display(fmt = "Rejection reason names must be between 1 and 64 characters long")]
+    MalformedRejectionReason,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if attempted to reject a record without giving
+    /// a reason, while [`rejection_reason_required`](crate::config::rejection_reason_required) is
+    /// set
+    ///
+    /// Error Code `42244`
+    #[display(fmt = "Records can only be rejected if a rejection reason is given")]
+    RejectionReasonRequired,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if attempted to set a rejection reason on a
+    /// record that is not rejected
+    ///
+    /// Error Code `42245`
+    #[display(fmt = "Only rejected records can have a rejection reason")]
+    RejectionReasonWithoutRejection,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if attempted to merge a player with itself
+    ///
+    /// Error Code `42247`
+    #[display(fmt = "A player cannot be merged with itself")]
+    SelfMerge,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if attempted to issue a ban whose expiry lies
+    /// in the past, or without giving a reason
+    ///
+    /// Error Code `42248`
+    #[display(fmt = "Bans need to be given a reason, and can only expire in the future")]
+    InvalidBan,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if attempted to ban an address range that is not
+    /// given in valid CIDR notation
+    ///
+    /// Error Code `42249`
+    #[display(fmt = "Address ranges need to be given in CIDR notation, e.g. 192.0.2.0/24")]
+    InvalidIpRange,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if the verification code of a claim could not be
+    /// found at the location specified by the claimant
+    ///
+    /// Error Code `42250`
+    #[display(fmt = "Could not find your verification code on the Geometry Dash account of the claimed player")]
+    ClaimVerificationFailed,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if attempted to dispute a claim that is either
+    /// unverified (in which case the player can simply be claimed), or held by the disputing user
+    ///
+    /// Error Code `42251`
+    #[display(fmt = "Only verified claims held by other users can be disputed")]
+    UndisputableClaim,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if a dispute is opened without evidence, or
+    /// resolved without a reason
+    ///
+    /// Error Code `42252`
+    #[display(fmt = "Disputes need to be opened with evidence and resolved with a reason")]
+    InvalidDispute,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if a claimant suggests an edit that cannot be
+    /// applied to their player, such as a rename that changes more than capitalization, or changes
+    /// to a record of a different player
+    ///
+    /// Error Code `42253`
+    #[display(fmt = "This edit cannot be suggested for your claimed player")]
+    InvalidSuggestion,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if the configured geolocation provider does not
+    /// know where an IP address is located
+    ///
+    /// Error Code `42254`
+    #[display(fmt = "Could not determine the location of your IP address")]
+    UnknownLocation,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if the video of a submission was uploaded before
+    /// the demon was added to the list
+    ///
+    /// Error Code `42255`
+    #[display(fmt = "The given video was uploaded before the demon was added to the list")]
+    VideoPredatesPlacement {
+        /// The time the video was uploaded at
+        uploaded_at: NaiveDateTime,
+
+        /// The time the [`Demon`] was added to the list at
+        placed_at: NaiveDateTime,
+    },
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if a nationality dataset assigns a nation to a
+    /// continent that does not exist
+    ///
+    /// Error Code `42256`
+    #[display(fmt = "Nation '{}' belongs to unknown continent '{}'", nation, continent)]
+    UnknownContinent {
+        /// The ISO 3166-1 code of the nation
+        nation: String,
+
+        /// The continent name given for the nation
+        continent: String,
+    },
 }
 
 impl std::error::Error for DemonlistError {}
@@ -211,8 +489,10 @@ impl PointercrateError for DemonlistError {
             ClaimUnverified => 40306,
             VpsDetected => 40307,
             NoThirdPartySubmissions => 40308,
+            ChallengeFailed => 40309,
             NationalityNotFound { .. } => 40401,
             SubdivisionNotFound { .. } => 40401,
+            ContinentNotFound { .. } => 40401,
             PlayerNotFound { .. } => 40401,
             PlayerNotFoundName { .. } => 40401,
             DemonNotFound { .. } => 40401,
@@ -220,9 +500,28 @@ impl PointercrateError for DemonlistError {
             DemonNotFoundPosition { .. } => 40401,
             RecordNotFound { .. } => 40401,
             ClaimNotFound { .. } => 40401,
+            ListNotFound { .. } => 40401,
+            ListNotFoundSlug { .. } => 40401,
+            ReviewQueueEmpty => 40401,
+            ReviewLockNotFound { .. } => 40401,
+            RejectionReasonNotFound { .. } => 40401,
+            PlayerAliasNotFound { .. } => 40401,
+            PlayerMergeNotFound { .. } => 40401,
+            RangeBanNotFound { .. } => 40401,
+            ClaimDisputeNotFound { .. } => 40401,
+            SuggestionNotFound { .. } => 40401,
             DuplicateVideo { .. } => 40906,
             NoNationSet => 40907,
             ConflictingClaims { .. } => 40908,
+            ListSlugTaken => 40909,
+            RecordLocked { .. } => 40910,
+            RejectionReasonExists => 40911,
+            PlayerNameTaken { .. } => 40912,
+            DisputeAlreadyOpen => 40913,
+            DisputeAlreadyResolved => 40914,
+            SuggestionAlreadyReviewed => 40915,
+            DisputedClaimChanged => 40916,
+            SuggesterNotClaimant => 40917,
             InvalidProgress { .. } => 42215,
             SubmissionExists { .. } => 42217,
             PlayerBanned => 42218,
@@ -233,6 +532,28 @@ impl PointercrateError for DemonlistError {
             AlreadyClaimed => 42231,
             RawRequired => 42232,
             MalformedRawUrl => 42233,
+            InvalidListSizes => 42234,
+            MalformedListSlug => 42235,
+            CompletionTimeRequired => 42236,
+            UnexpectedCompletionTime => 42237,
+            InvalidCompletionTime => 42238,
+            CompletionTimeTooSlow { .. } => 42239,
+            InvalidMaxTime => 42240,
+            RecordNotInQueue => 42241,
+            NotAReviewer => 42242,
+            MalformedRejectionReason => 42243,
+            RejectionReasonRequired => 42244,
+            RejectionReasonWithoutRejection => 42245,
+            SelfMerge => 42247,
+            InvalidBan => 42248,
+            InvalidIpRange => 42249,
+            ClaimVerificationFailed => 42250,
+            UndisputableClaim => 42251,
+            InvalidDispute => 42252,
+            InvalidSuggestion => 42253,
+            UnknownLocation => 42254,
+            VideoPredatesPlacement { .. } => 42255,
+            UnknownContinent { .. } => 42256,
         }
     }
 }
@@ -3,14 +3,16 @@ use pointercrate_user::ADMINISTRATOR;
 
 #[macro_use]
 pub mod demon;
+pub mod ban;
 pub mod config;
 pub mod creator;
 pub mod error;
+pub mod list;
 pub mod nationality;
 pub mod player;
 pub mod record;
 pub mod submitter;
-mod video;
+pub mod video;
 
 pub const LIST_HELPER: Permission = Permission::new("List Helper", 0x2);
 pub const LIST_MODERATOR: Permission = Permission::new("List Moderator", 0x4);
@@ -0,0 +1,43 @@
+use crate::{
+    error::{DemonlistError, Result},
+    list::List,
+};
+use sqlx::{Error, PgConnection};
+
+impl List {
+    pub async fn by_id(id: i32, connection: &mut PgConnection) -> Result<List> {
+        sqlx::query_as!(
+            List,
+            "SELECT id, slug, name, list_size, extended_list_size FROM lists WHERE id = $1",
+            id
+        )
+        .fetch_one(connection)
+        .await
+        .map_err(|err| match err {
+            Error::RowNotFound => DemonlistError::ListNotFound { list_id: id },
+            _ => err.into(),
+        })
+    }
+
+    pub async fn by_slug(slug: &str, connection: &mut PgConnection) -> Result<List> {
+        sqlx::query_as!(
+            List,
+            "SELECT id, slug, name, list_size, extended_list_size FROM lists WHERE slug = $1",
+            slug
+        )
+        .fetch_one(connection)
+        .await
+        .map_err(|err| match err {
+            Error::RowNotFound => DemonlistError::ListNotFoundSlug { slug: slug.to_string() },
+            _ => err.into(),
+        })
+    }
+
+    pub async fn all(connection: &mut PgConnection) -> Result<Vec<List>> {
+        Ok(
+            sqlx::query_as!(List, "SELECT id, slug, name, list_size, extended_list_size FROM lists ORDER BY id")
+                .fetch_all(connection)
+                .await?,
+        )
+    }
+}
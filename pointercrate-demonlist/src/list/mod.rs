@@ -0,0 +1,43 @@
+use derive_more::Display;
+use pointercrate_core::etag::Taggable;
+use serde::{Deserialize, Serialize};
+
+pub use self::{patch::PatchList, post::PostList};
+
+mod get;
+mod patch;
+mod post;
+
+/// The ID of the list all demons that predate the introduction of multiple lists belong to.
+///
+/// All endpoints not explicitly parameterized by a list operate on this list.
+pub const DEFAULT_LIST: i32 = 1;
+
+/// Struct modelling one of the independent lists hosted by this pointercrate instance
+///
+/// Each list has its own ordering of demons, its own records, scores and player rankings.
+/// Players are shared between all lists.
+#[derive(Debug, Deserialize, Serialize, Hash, Display, Clone, PartialEq, Eq)]
+#[display(fmt = "{} (ID: {})", name, id)]
+pub struct List {
+    pub id: i32,
+
+    /// Short, URL-safe identifier of this list, e.g. `main` or `platformer`
+    pub slug: String,
+
+    pub name: String,
+
+    /// The number of demons on the main section of this list
+    ///
+    /// Records on demons past this position are only accepted (and only give score) if they are
+    /// 100% completions.
+    pub list_size: i16,
+
+    /// The number of demons on the main and extended section of this list combined
+    ///
+    /// Demons past this position are legacy demons and do not accept new submissions. This value
+    /// is also the `list_size` parameter of this list's scoring formula.
+    pub extended_list_size: i16,
+}
+
+impl Taggable for List {}
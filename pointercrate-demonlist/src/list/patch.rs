@@ -0,0 +1,56 @@
+use crate::{error::Result, list::List, player::recompute_scores};
+use log::info;
+use pointercrate_core::util::non_nullable;
+use serde::Deserialize;
+use sqlx::PgConnection;
+
+#[derive(Deserialize, Debug, Default)]
+pub struct PatchList {
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub name: Option<String>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub list_size: Option<i16>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub extended_list_size: Option<i16>,
+}
+
+impl List {
+    /// Must run inside a transaction!
+    pub async fn apply_patch(mut self, patch: PatchList, connection: &mut PgConnection) -> Result<Self> {
+        info!("Patching list {} with {:?}", self, patch);
+
+        if let Some(name) = patch.name {
+            sqlx::query!("UPDATE lists SET name = $1 WHERE id = $2", name, self.id)
+                .execute(&mut *connection)
+                .await?;
+
+            self.name = name;
+        }
+
+        if patch.list_size.is_some() || patch.extended_list_size.is_some() {
+            let list_size = patch.list_size.unwrap_or(self.list_size);
+            let extended_list_size = patch.extended_list_size.unwrap_or(self.extended_list_size);
+
+            List::validate_sizes(list_size, extended_list_size)?;
+
+            sqlx::query!(
+                "UPDATE lists SET list_size = $1, extended_list_size = $2 WHERE id = $3",
+                list_size,
+                extended_list_size,
+                self.id
+            )
+            .execute(&mut *connection)
+            .await?;
+
+            self.list_size = list_size;
+            self.extended_list_size = extended_list_size;
+
+            // Both sizes are parameters of the scoring formula
+            recompute_scores(connection).await?;
+        }
+
+        Ok(self)
+    }
+}
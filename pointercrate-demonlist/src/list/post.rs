@@ -0,0 +1,68 @@
+use crate::{
+    error::{DemonlistError, Result},
+    list::List,
+};
+use log::info;
+use serde::Deserialize;
+use sqlx::PgConnection;
+
+#[derive(Deserialize, Debug)]
+pub struct PostList {
+    slug: String,
+    name: String,
+    list_size: i16,
+    extended_list_size: i16,
+}
+
+impl List {
+    pub(crate) fn validate_sizes(list_size: i16, extended_list_size: i16) -> Result<()> {
+        if list_size < 1 || extended_list_size < list_size {
+            return Err(DemonlistError::InvalidListSizes);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn validate_slug(slug: &str) -> Result<()> {
+        if slug.is_empty() || slug.len() > 32 || !slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+            return Err(DemonlistError::MalformedListSlug);
+        }
+
+        Ok(())
+    }
+
+    /// Must be run within a transaction!
+    pub async fn create_from(data: PostList, connection: &mut PgConnection) -> Result<List> {
+        info!("Creating new list from {:?}", data);
+
+        List::validate_slug(&data.slug)?;
+        List::validate_sizes(data.list_size, data.extended_list_size)?;
+
+        if sqlx::query!("SELECT id FROM lists WHERE slug = $1", data.slug)
+            .fetch_optional(&mut *connection)
+            .await?
+            .is_some()
+        {
+            return Err(DemonlistError::ListSlugTaken);
+        }
+
+        let id = sqlx::query!(
+            "INSERT INTO lists (slug, name, list_size, extended_list_size) VALUES ($1, $2, $3, $4) RETURNING id",
+            data.slug,
+            data.name,
+            data.list_size,
+            data.extended_list_size
+        )
+        .fetch_one(connection)
+        .await?
+        .id;
+
+        Ok(List {
+            id,
+            slug: data.slug,
+            name: data.name,
+            list_size: data.list_size,
+            extended_list_size: data.extended_list_size,
+        })
+    }
+}
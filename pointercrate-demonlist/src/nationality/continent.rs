@@ -0,0 +1,230 @@
+use crate::{
+    demon::MinimalDemon,
+    error::{DemonlistError, Result},
+    list::DEFAULT_LIST,
+    nationality::{BestRecord, MiniDemon, MiniDemonWithPlayers},
+};
+use futures::StreamExt;
+use pointercrate_core::etag::Taggable;
+use serde::{Deserialize, Serialize};
+use sqlx::{Error, PgConnection};
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Hash, Clone)]
+pub struct Continent {
+    pub id: i32,
+    pub name: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RankedContinent {
+    pub rank: i64,
+    pub score: f64,
+    #[serde(flatten)]
+    pub continent: Continent,
+}
+
+#[derive(Debug, Hash, Serialize)]
+pub struct ContinentRecord {
+    pub continent: Continent,
+
+    #[serde(rename = "records")]
+    pub best_records: Vec<BestRecord>,
+    pub created: Vec<MiniDemonWithPlayers>,
+    pub verified: Vec<MiniDemon>,
+    pub published: Vec<MiniDemon>,
+    pub unbeaten: Vec<MinimalDemon>,
+}
+
+impl Taggable for ContinentRecord {}
+
+impl Continent {
+    pub async fn by_id(id: i32, connection: &mut PgConnection) -> Result<Continent> {
+        let result = sqlx::query!(r#"SELECT name::text AS "name!" FROM continents WHERE id = $1"#, id)
+            .fetch_one(connection)
+            .await;
+
+        match result {
+            Ok(row) => Ok(Continent { id, name: row.name }),
+            Err(Error::RowNotFound) => Err(DemonlistError::ContinentNotFound { continent_id: id }),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub async fn all(connection: &mut PgConnection) -> Result<Vec<Continent>> {
+        let mut stream = sqlx::query!(r#"SELECT id, name::text AS "name!" FROM continents ORDER BY name"#).fetch(connection);
+        let mut continents = Vec::new();
+
+        while let Some(row) = stream.next().await {
+            let row = row?;
+
+            continents.push(Continent {
+                id: row.id,
+                name: row.name,
+            })
+        }
+
+        Ok(continents)
+    }
+
+    /// All continents, ordered by their score
+    pub async fn ranking(connection: &mut PgConnection) -> Result<Vec<RankedContinent>> {
+        let mut stream = sqlx::query!(
+            r#"SELECT rank AS "rank!", score AS "score!", id AS "id!", name::text AS "name!" FROM ranked_continents ORDER BY rank, id"#
+        )
+        .fetch(connection);
+        let mut continents = Vec::new();
+
+        while let Some(row) = stream.next().await {
+            let row = row?;
+
+            continents.push(RankedContinent {
+                rank: row.rank,
+                score: row.score,
+                continent: Continent {
+                    id: row.id,
+                    name: row.name,
+                },
+            })
+        }
+
+        Ok(continents)
+    }
+
+    pub async fn upgrade(self, connection: &mut PgConnection) -> Result<ContinentRecord> {
+        Ok(ContinentRecord {
+            best_records: best_records_in(&self, connection).await?,
+            created: created_in(&self, connection).await?,
+            verified: verified_in(&self, connection).await?,
+            published: published_in(&self, connection).await?,
+            unbeaten: unbeaten_in(&self, connection).await?,
+            continent: self,
+        })
+    }
+}
+
+pub async fn unbeaten_in(continent: &Continent, connection: &mut PgConnection) -> Result<Vec<MinimalDemon>> {
+    let mut stream = sqlx::query!(
+        r#"select name::text as "name!", id as "id!", position as "position!" from demons where list = $2 and position <= (select extended_list_size from lists where id = $2) except (select demons.name, demons.id, position from records inner join players on
+         players.id=records.player inner join nationalities on nationalities.iso_country_code=players.nationality inner join demons on demons.id=records.demon where status_='APPROVED' and nationalities.continent=$1 and progress=100 union select demons.name, demons.id, demons.position from demons inner join players on players.id=verifier inner join nationalities on nationalities.iso_country_code=players.nationality where nationalities.continent=$1)"#,
+        continent.id,
+        DEFAULT_LIST
+    )
+    .fetch(connection);
+
+    let mut unbeaten = Vec::new();
+
+    while let Some(row) = stream.next().await {
+        let row = row?;
+
+        unbeaten.push(MinimalDemon {
+            id: row.id,
+            position: row.position,
+            name: row.name,
+        });
+    }
+
+    Ok(unbeaten)
+}
+
+pub async fn created_in(continent: &Continent, connection: &mut PgConnection) -> Result<Vec<MiniDemonWithPlayers>> {
+    let mut stream = sqlx::query!(
+        r#"select demon, demons.name::text as "demon_name!", demons.position, players.name::text as "player_name!" from creators inner join demons on demons.id=demon inner join players on players.id=creator inner join nationalities on nationalities.iso_country_code=players.nationality where nationalities.continent=$1 and demons.list=$2 order by demon"#,
+        continent.id,
+        DEFAULT_LIST
+    )
+    .fetch(connection);
+
+    let mut creations = Vec::<MiniDemonWithPlayers>::new();
+
+    while let Some(row) = stream.next().await {
+        let row = row?;
+
+        match creations.last_mut() {
+            Some(mini_demon) if mini_demon.demon == row.demon_name => mini_demon.players.push(row.player_name),
+            _ => creations.push(MiniDemonWithPlayers {
+                id: row.demon,
+                demon: row.demon_name,
+                position: row.position,
+                players: vec![row.player_name],
+            }),
+        }
+    }
+
+    Ok(creations)
+}
+
+pub async fn verified_in(continent: &Continent, connection: &mut PgConnection) -> Result<Vec<MiniDemon>> {
+    let mut stream = sqlx::query!(
+        r#"select demons.id as demon, demons.name::text as "demon_name!", demons.position, players.name::text as "player_name!" from demons inner join players on players.id=verifier inner join nationalities on nationalities.iso_country_code=players.nationality where nationalities.continent=$1 and demons.list=$2"#,
+        continent.id,
+        DEFAULT_LIST
+    )
+    .fetch(connection);
+
+    let mut demons = Vec::new();
+
+    while let Some(row) = stream.next().await {
+        let row = row?;
+
+        demons.push(MiniDemon {
+            id: row.demon,
+            demon: row.demon_name,
+            position: row.position,
+            player: row.player_name,
+        });
+    }
+
+    Ok(demons)
+}
+
+pub async fn published_in(continent: &Continent, connection: &mut PgConnection) -> Result<Vec<MiniDemon>> {
+    let mut stream = sqlx::query!(
+        r#"select demons.id as demon, demons.name::text as "demon_name!", demons.position, players.name::text as "player_name!" from demons inner join players on players.id=publisher inner join nationalities on nationalities.iso_country_code=players.nationality where nationalities.continent=$1 and demons.list=$2"#,
+        continent.id,
+        DEFAULT_LIST
+    )
+    .fetch(connection);
+
+    let mut demons = Vec::new();
+
+    while let Some(row) = stream.next().await {
+        let row = row?;
+
+        demons.push(MiniDemon {
+            id: row.demon,
+            demon: row.demon_name,
+            position: row.position,
+            player: row.player_name,
+        });
+    }
+
+    Ok(demons)
+}
+
+pub async fn best_records_in(continent: &Continent, connection: &mut PgConnection) -> Result<Vec<BestRecord>> {
+    let mut stream = sqlx::query!(
+        r#"SELECT progress as "progress!", demons.id AS "demon_id!", demons.name as "demon_name!: String", demons.position as "position!", players.name as "player_name!: String" FROM best_records_in_continent($1) as records INNER JOIN demons ON records.demon = demons.id INNER JOIN players ON players.id = records.player WHERE demons.list = $2 ORDER BY demons.position"#,
+        continent.id,
+        DEFAULT_LIST
+    )
+    .fetch(connection);
+
+    let mut records = Vec::<BestRecord>::new();
+
+    while let Some(row) = stream.next().await {
+        let row = row?;
+
+        match records.last_mut() {
+            Some(record) if record.demon == row.demon_name => record.players.push(row.player_name),
+            _ => records.push(BestRecord {
+                id: row.demon_id,
+                demon: row.demon_name,
+                position: row.position,
+                progress: row.progress,
+                players: vec![row.player_name],
+            }),
+        }
+    }
+
+    Ok(records)
+}
@@ -0,0 +1,111 @@
+//! Module for determining a player's nationality from the IP address of their claimant
+//!
+//! How an IP address is resolved to a location is encapsulated in a [`GeolocationProvider`], for
+//! example a third party API or a local geolocation database. The [`Geolocator`] configured for a
+//! deployment maps the provider's results to [`Nationality`]s and
+//! [`Subdivision`](crate::nationality::Subdivision)s known to pointercrate.
+
+use crate::{
+    error::{DemonlistError, Result},
+    nationality::Nationality,
+};
+use async_trait::async_trait;
+use log::{info, warn};
+use pointercrate_core::error::CoreError;
+use sqlx::PgConnection;
+use std::{collections::HashMap, net::IpAddr, sync::Arc};
+
+/// The location of an IP address, as reported by a [`GeolocationProvider`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// The ISO 3166-1 alpha-2 code of the country the address is located in
+    pub country_code: String,
+
+    /// The ISO 3166-2 code of the subdivision the address is located in, without the country
+    /// prefix (e.g. `BY` for Bavaria instead of `DE-BY`)
+    pub subdivision_code: Option<String>,
+
+    /// Whether the address belongs to a VPN, proxy or hosting provider, making its location
+    /// meaningless
+    pub is_vpn: bool,
+}
+
+/// Something that can resolve IP addresses to locations
+#[async_trait]
+pub trait GeolocationProvider: Send + Sync {
+    /// Looks up the location of the given IP address, returning `None` if it is unknown
+    async fn locate(&self, ip: IpAddr) -> Result<Option<Location>>;
+}
+
+/// A [`GeolocationProvider`] resolving a fixed set of addresses
+///
+/// Meant for tests and local development.
+#[derive(Default)]
+pub struct StaticGeolocation {
+    locations: HashMap<IpAddr, Location>,
+}
+
+impl StaticGeolocation {
+    pub fn with_location(mut self, ip: IpAddr, location: Location) -> Self {
+        self.locations.insert(ip, location);
+        self
+    }
+}
+
+#[async_trait]
+impl GeolocationProvider for StaticGeolocation {
+    async fn locate(&self, ip: IpAddr) -> Result<Option<Location>> {
+        Ok(self.locations.get(&ip).cloned())
+    }
+}
+
+/// The geolocation configured for a deployment
+#[derive(Clone)]
+pub struct Geolocator {
+    provider: Option<Arc<dyn GeolocationProvider>>,
+}
+
+impl Geolocator {
+    pub fn new(provider: impl GeolocationProvider + 'static) -> Self {
+        Geolocator {
+            provider: Some(Arc::new(provider)),
+        }
+    }
+
+    /// Constructs a geolocator for deployments that do not offer geolocation
+    pub fn disabled() -> Self {
+        Geolocator { provider: None }
+    }
+
+    /// Determines the nationality (including subdivision, if known) of the given IP address
+    pub async fn nationality_of(&self, ip: IpAddr, connection: &mut PgConnection) -> Result<Nationality> {
+        let Some(ref provider) = self.provider else {
+            return Err(CoreError::internal_server_error("No geolocation provider configured").into());
+        };
+
+        let location = provider.locate(ip).await?.ok_or(DemonlistError::UnknownLocation)?;
+
+        if location.is_vpn {
+            info!("Geolocation of {} detected VPN usage", ip);
+
+            return Err(DemonlistError::VpsDetected);
+        }
+
+        let mut nationality = Nationality::by_country_code_or_name(&location.country_code, &mut *connection).await?;
+
+        if let Some(region) = location.subdivision_code {
+            nationality.subdivision = nationality
+                .subdivision_by_code(&region, connection)
+                .await
+                .inspect_err(|err| {
+                    warn!(
+                        "No subdivision {} for nation {}, or nation does not support subdivisions: {:?}",
+                        region, nationality.iso_country_code, err
+                    )
+                })
+                .ok();
+        }
+
+        Ok(nationality)
+    }
+}
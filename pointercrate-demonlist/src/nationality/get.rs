@@ -1,6 +1,7 @@
 use crate::{
     demon::MinimalDemon,
     error::{DemonlistError, Result},
+    list::DEFAULT_LIST,
     nationality::{BestRecord, MiniDemon, MiniDemonWithPlayers, Nationality, NationalityRecord, Subdivision},
 };
 use futures::stream::StreamExt;
@@ -113,24 +114,31 @@ impl Nationality {
         Ok(nationalities)
     }
 
-    pub async fn upgrade(self, connection: &mut PgConnection) -> Result<NationalityRecord> {
+    /// Computes the breakdown of this nation's achievements
+    ///
+    /// If `at_approval` is set, records and verifications count for the nation the player was from at
+    /// the time of the record's approval (respectively the demon's addition), instead of for the
+    /// player's current nation. Creators and publishers are always attributed to their current
+    /// nation.
+    pub async fn upgrade(self, at_approval: bool, connection: &mut PgConnection) -> Result<NationalityRecord> {
         Ok(NationalityRecord {
-            best_records: best_records_in(&self, connection).await?,
+            best_records: best_records_in(&self, at_approval, connection).await?,
             created: created_in(&self, connection).await?,
-            verified: verified_in(&self, connection).await?,
+            verified: verified_in(&self, at_approval, connection).await?,
             published: published_in(&self, connection).await?,
-            unbeaten: unbeaten_in(&self, connection).await?,
+            unbeaten: unbeaten_in(&self, at_approval, connection).await?,
             nation: self,
         })
     }
 }
 
-pub async fn unbeaten_in(nation: &Nationality, connection: &mut PgConnection) -> Result<Vec<MinimalDemon>> {
+pub async fn unbeaten_in(nation: &Nationality, at_approval: bool, connection: &mut PgConnection) -> Result<Vec<MinimalDemon>> {
     let mut stream = sqlx::query!(
-        r#"select name::text as "name!", id as "id!", position as "position!" from demons where position <= $1 except (select demons.name, demons.id, position from records inner join players on 
-         players.id=records.player inner join demons on demons.id=records.demon where status_='APPROVED' and nationality=$2 and progress=100 union select demons.name, demons.id, demons.position from demons inner join players on players.id=verifier where players.nationality=$2)"#,
-        crate::config::extended_list_size(),
-        nation.iso_country_code
+        r#"select name::text as "name!", id as "id!", position as "position!" from demons where list = $2 and position <= (select extended_list_size from lists where id = $2) except (select demons.name, demons.id, position from records inner join players on 
+         players.id=records.player inner join demons on demons.id=records.demon where status_='APPROVED' and case when $3 then (select nationality from nationality_at(records.player, records.status_since)) = $1 else nationality=$1 end and progress=100 union select demons.name, demons.id, demons.position from demons inner join players on players.id=verifier where case when $3 then (select nationality from nationality_at(verifier, (select time from demon_additions where demon_additions.id = demons.id))) = $1 else players.nationality=$1 end)"#,
+        nation.iso_country_code,
+        DEFAULT_LIST,
+        at_approval
     )
     .fetch(connection);
 
@@ -150,7 +158,7 @@ pub async fn unbeaten_in(nation: &Nationality, connection: &mut PgConnection) ->
 }
 
 pub async fn created_in(nation: &Nationality, connection: &mut PgConnection) -> Result<Vec<MiniDemonWithPlayers>> {
-    let mut stream = sqlx::query!( r#"select distinct on (demon) demon, demons.name::text as "demon_name!", demons.position, players.name::text as "player_name!" from creators inner join demons on demons.id=demon inner join players on players.id=creator where nationality=$1"#, nation.iso_country_code).fetch(connection);
+    let mut stream = sqlx::query!( r#"select distinct on (demon) demon, demons.name::text as "demon_name!", demons.position, players.name::text as "player_name!" from creators inner join demons on demons.id=demon inner join players on players.id=creator where nationality=$1 and demons.list=$2"#, nation.iso_country_code, DEFAULT_LIST).fetch(connection);
 
     let mut creations = Vec::<MiniDemonWithPlayers>::new();
 
@@ -171,9 +179,9 @@ pub async fn created_in(nation: &Nationality, connection: &mut PgConnection) ->
     Ok(creations)
 }
 
-pub async fn verified_in(nation: &Nationality, connection: &mut PgConnection) -> Result<Vec<MiniDemon>> {
+pub async fn verified_in(nation: &Nationality, at_approval: bool, connection: &mut PgConnection) -> Result<Vec<MiniDemon>> {
     let mut stream = sqlx::query!(
-        r#"select demons.id as demon, demons.name::text as "demon_name!", demons.position, players.name::text as "player_name!" from demons inner join players on players.id=verifier where nationality=$1"#, nation.iso_country_code).fetch(connection);
+        r#"select demons.id as demon, demons.name::text as "demon_name!", demons.position, players.name::text as "player_name!" from demons inner join players on players.id=verifier where case when $3 then (select nationality from nationality_at(verifier, (select time from demon_additions where demon_additions.id = demons.id))) = $1 else nationality=$1 end and demons.list=$2"#, nation.iso_country_code, DEFAULT_LIST, at_approval).fetch(connection);
 
     let mut demons = Vec::new();
 
@@ -193,7 +201,7 @@ pub async fn verified_in(nation: &Nationality, connection: &mut PgConnection) ->
 
 pub async fn published_in(nation: &Nationality, connection: &mut PgConnection) -> Result<Vec<MiniDemon>> {
     let mut stream = sqlx::query!(
-        r#"select demons.id as demon, demons.name::text as "demon_name!", demons.position, players.name::text as "player_name!" from demons inner join players on players.id=publisher where nationality=$1"#, nation.iso_country_code).fetch(connection);
+        r#"select demons.id as demon, demons.name::text as "demon_name!", demons.position, players.name::text as "player_name!" from demons inner join players on players.id=publisher where nationality=$1 and demons.list=$2"#, nation.iso_country_code, DEFAULT_LIST).fetch(connection);
 
     let mut demons = Vec::new();
 
@@ -211,10 +219,12 @@ pub async fn published_in(nation: &Nationality, connection: &mut PgConnection) -
     Ok(demons)
 }
 
-pub async fn best_records_in(nation: &Nationality, connection: &mut PgConnection) -> Result<Vec<BestRecord>> {
+pub async fn best_records_in(nation: &Nationality, at_approval: bool, connection: &mut PgConnection) -> Result<Vec<BestRecord>> {
     let mut stream = sqlx::query!(
-        r#"SELECT progress as "progress!", demons.id AS "demon_id!", demons.name as "demon_name!: String", demons.position as "position!", players.name as "player_name!: String" FROM best_records_in($1) as records INNER JOIN demons ON records.demon = demons.id INNER JOIN players ON players.id = records.player"#,
-        nation.iso_country_code
+        r#"SELECT progress as "progress!", demons.id AS "demon_id!", demons.name as "demon_name!: String", demons.position as "position!", players.name as "player_name!: String" FROM best_records_in($1, $3) as records INNER JOIN demons ON records.demon = demons.id INNER JOIN players ON players.id = records.player WHERE demons.list = $2"#,
+        nation.iso_country_code,
+        DEFAULT_LIST,
+        at_approval
     )
         .fetch(connection);
 
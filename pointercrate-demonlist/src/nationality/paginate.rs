@@ -1,7 +1,4 @@
-use crate::{
-    error::Result,
-    nationality::{Continent, Nationality},
-};
+use crate::{error::Result, nationality::Nationality};
 use futures::StreamExt;
 use pointercrate_core::util::non_nullable;
 use serde::{Deserialize, Serialize};
@@ -9,11 +6,17 @@ use sqlx::PgConnection;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct NationalityRankingPagination {
+    /// The id of the continent whose nations to rank
     #[serde(default, deserialize_with = "non_nullable")]
-    continent: Option<Continent>,
+    continent: Option<i32>,
 
     #[serde(default, deserialize_with = "non_nullable")]
     name_contains: Option<String>,
+
+    /// Whether records should count towards the nation their player was from at the time of the
+    /// record's approval, instead of towards the player's current nation
+    #[serde(default)]
+    at_approval: bool,
 }
 
 #[derive(Serialize, Debug)]
@@ -27,10 +30,13 @@ pub struct RankedNation {
 impl NationalityRankingPagination {
     pub async fn page(&self, connection: &mut PgConnection) -> Result<Vec<RankedNation>> {
         let mut stream = sqlx::query!(
-            r#"SELECT rank as "rank!", score as "score!", nation as "nation!", iso_country_code as "iso_country_code!" FROM ranked_nations WHERE (STRPOS(nation, $1) > 
-             0 OR $1 is NULL) AND (continent::text = $2 OR $2 IS NULL)"#,
+            r#"SELECT rank as "rank!", score as "score!", nation as "nation!", iso_country_code as "iso_country_code!" FROM (SELECT rank, score,
+             nation, iso_country_code, continent FROM ranked_nations WHERE NOT $3 UNION ALL SELECT rank, score, nation, iso_country_code,
+             continent FROM ranked_nations_at_approval WHERE $3) ranking WHERE (STRPOS(nation, $1) > 0 OR $1 is NULL) AND (continent = $2 OR
+             $2 IS NULL) ORDER BY rank, iso_country_code"#,
             self.name_contains,
-            self.continent.map(|c| c.to_sql())
+            self.continent,
+            self.at_approval
         )
         .fetch(connection);
 
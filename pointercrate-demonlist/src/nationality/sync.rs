@@ -0,0 +1,484 @@
+//! Module for synchronizing the known nations and subdivisions with an ISO 3166 dataset
+//!
+//! Instead of changing the `nationalities` and `subdivisions` tables via hand-written migrations,
+//! changes to ISO 3166-1 and ISO 3166-2 are made to a dataset file, which is then applied to the
+//! database via [`synchronize`].
+//!
+//! The dataset bundled with this crate (at `data/iso3166.json`) was generated from the ISO 3166-1
+//! and ISO 3166-2 data of the `iso-codes` package (version 4.15.0). It contains every nation together
+//! with its top-level subdivisions. Nations and subdivisions pointercrate already knew keep their
+//! previous (mostly English) names, and Norway keeps its previous counties, as the ISO data predates
+//! the 2024 county reform. Kosovo is included under its user-assigned code `XK`. Continents are not
+//! part of ISO 3166 and were assigned by hand.
+//!
+//! Nations and subdivisions are identified by their codes, meaning a changed name is a rename.
+//! Players (and users) in a nation or subdivision that no longer exists are moved to the entry
+//! listing the old code in its `formerly` field, or lose their nationality (respectively
+//! subdivision) if there is none.
+
+use crate::error::{DemonlistError, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Display, Formatter},
+};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Iso3166Dataset {
+    pub nations: Vec<DatasetNation>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DatasetNation {
+    /// The ISO 3166-1 alpha-2 code of this nation
+    pub code: String,
+    pub name: String,
+
+    /// The name of the continent this nation belongs to, as stored in the `continents` table
+    pub continent: String,
+
+    /// Codes this nation was previously known under
+    #[serde(default)]
+    pub formerly: Vec<String>,
+
+    #[serde(default)]
+    pub subdivisions: Vec<DatasetSubdivision>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DatasetSubdivision {
+    /// The ISO 3166-2 code of this subdivision, without the country prefix
+    pub code: String,
+    pub name: String,
+
+    /// Codes (again without country prefix) this subdivision was previously known under
+    #[serde(default)]
+    pub formerly: Vec<String>,
+}
+
+impl Iso3166Dataset {
+    pub fn from_json(json: &str) -> std::result::Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// The dataset bundled with this crate
+    pub fn bundled() -> Self {
+        Iso3166Dataset::from_json(include_str!("../../data/iso3166.json")).expect("bundled ISO 3166 dataset is malformed")
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct Rename {
+    pub code: String,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct Removal {
+    pub code: String,
+    pub name: String,
+
+    /// The code of the entry players were moved to, if any
+    pub replaced_by: Option<String>,
+
+    /// The number of players that were moved (or lost their nationality/subdivision)
+    pub players_affected: u64,
+}
+
+/// The changes made by [`synchronize`]
+///
+/// Subdivisions are identified by their full ISO 3166-2 code (e.g. `DE-BY`).
+#[derive(Debug, Serialize, Default, PartialEq, Eq)]
+pub struct SynchronizationReport {
+    pub added_nations: Vec<String>,
+    pub renamed_nations: Vec<Rename>,
+    pub removed_nations: Vec<Removal>,
+    pub added_subdivisions: Vec<String>,
+    pub renamed_subdivisions: Vec<Rename>,
+    pub removed_subdivisions: Vec<Removal>,
+}
+
+impl SynchronizationReport {
+    pub fn is_empty(&self) -> bool {
+        *self == SynchronizationReport::default()
+    }
+}
+
+impl Display for SynchronizationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "Nations and subdivisions are up to date");
+        }
+
+        for code in self.added_nations.iter().chain(&self.added_subdivisions) {
+            writeln!(f, "Added {}", code)?;
+        }
+
+        for rename in self.renamed_nations.iter().chain(&self.renamed_subdivisions) {
+            writeln!(f, "Renamed {} from '{}' to '{}'", rename.code, rename.from, rename.to)?;
+        }
+
+        for removal in self.removed_nations.iter().chain(&self.removed_subdivisions) {
+            match removal.replaced_by {
+                Some(ref replacement) => writeln!(
+                    f,
+                    "Removed {} ('{}'), moved {} players to {}",
+                    removal.code, removal.name, removal.players_affected, replacement
+                )?,
+                None => writeln!(
+                    f,
+                    "Removed {} ('{}'), {} players were unassigned",
+                    removal.code, removal.name, removal.players_affected
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Makes the `nationalities` and `subdivisions` tables match the given dataset
+///
+/// Scores of nations and subdivisions that players were moved to, as well as all continent scores,
+/// are recomputed. Should be run inside a transaction, so that nothing is changed if the dataset
+/// turns out to violate some constraint halfway through.
+///
+/// Moving players to a replacement nation or subdivision is not a change of their nationality, so it
+/// does not show up in their nationality history. Instead, their history is rewritten as if they
+/// always held the replacement.
+pub async fn synchronize(dataset: &Iso3166Dataset, connection: &mut PgConnection) -> Result<SynchronizationReport> {
+    let mut report = SynchronizationReport::default();
+
+    let continents: HashSet<String> = sqlx::query!(r#"SELECT name::text AS "name!" FROM continents"#)
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|row| row.name)
+        .collect();
+
+    if let Some(nation) = dataset.nations.iter().find(|nation| !continents.contains(&nation.continent)) {
+        return Err(DemonlistError::UnknownContinent {
+            nation: nation.code.clone(),
+            continent: nation.continent.clone(),
+        });
+    }
+
+    let known_nations: HashMap<String, String> =
+        sqlx::query!(r#"SELECT iso_country_code AS "code!", nation::text AS "name!" FROM nationalities"#)
+            .fetch_all(&mut *connection)
+            .await?
+            .into_iter()
+            .map(|row| (row.code, row.name))
+            .collect();
+
+    let known_subdivisions: HashMap<(String, String), String> =
+        sqlx::query!(r#"SELECT nation AS "nation!", iso_code AS "code!", name::text AS "name!" FROM subdivisions"#)
+            .fetch_all(&mut *connection)
+            .await?
+            .into_iter()
+            .map(|row| ((row.nation, row.code), row.name))
+            .collect();
+
+    let dataset_nations: HashMap<&str, &str> = dataset
+        .nations
+        .iter()
+        .map(|nation| (nation.code.as_str(), nation.name.as_str()))
+        .collect();
+    let dataset_subdivisions: HashMap<(&str, &str), &str> = dataset
+        .nations
+        .iter()
+        .flat_map(|nation| {
+            nation
+                .subdivisions
+                .iter()
+                .map(|subdivision| ((nation.code.as_str(), subdivision.code.as_str()), subdivision.name.as_str()))
+        })
+        .collect();
+
+    // Names are unique, so temporarily move the names of renamed and removed entries out of the way.
+    // Otherwise, an entry taking over the name of another one would violate that constraint.
+    for (code, name) in &known_nations {
+        if dataset_nations.get(code.as_str()) != Some(&name.as_str()) {
+            sqlx::query!(
+                "UPDATE nationalities SET nation = '~' || iso_country_code WHERE iso_country_code = $1",
+                code
+            )
+            .execute(&mut *connection)
+            .await?;
+        }
+    }
+
+    for ((nation, code), name) in &known_subdivisions {
+        if dataset_subdivisions.get(&(nation.as_str(), code.as_str())) != Some(&name.as_str()) {
+            sqlx::query!(
+                "UPDATE subdivisions SET name = '~' || iso_code WHERE nation = $1 AND iso_code = $2",
+                nation,
+                code
+            )
+            .execute(&mut *connection)
+            .await?;
+        }
+    }
+
+    for nation in &dataset.nations {
+        match known_nations.get(&nation.code) {
+            None => {
+                sqlx::query!(
+                    "INSERT INTO nationalities (iso_country_code, nation, continent) VALUES ($1, $2::text, (SELECT id FROM continents WHERE name = $3::text))",
+                    nation.code,
+                    nation.name,
+                    nation.continent
+                )
+                .execute(&mut *connection)
+                .await?;
+
+                report.added_nations.push(nation.code.clone());
+            },
+            Some(name) => {
+                sqlx::query!(
+                    "UPDATE nationalities SET nation = $2::text, continent = (SELECT id FROM continents WHERE name = $3::text) WHERE iso_country_code = $1",
+                    nation.code,
+                    nation.name,
+                    nation.continent
+                )
+                .execute(&mut *connection)
+                .await?;
+
+                if *name != nation.name {
+                    report.renamed_nations.push(Rename {
+                        code: nation.code.clone(),
+                        from: name.clone(),
+                        to: nation.name.clone(),
+                    });
+                }
+            },
+        }
+
+        for subdivision in &nation.subdivisions {
+            let full_code = format!("{}-{}", nation.code, subdivision.code);
+
+            match known_subdivisions.get(&(nation.code.clone(), subdivision.code.clone())) {
+                None => {
+                    sqlx::query!(
+                        "INSERT INTO subdivisions (iso_code, name, nation) VALUES ($1, $2::text, $3)",
+                        subdivision.code,
+                        subdivision.name,
+                        nation.code
+                    )
+                    .execute(&mut *connection)
+                    .await?;
+
+                    report.added_subdivisions.push(full_code);
+                },
+                Some(name) if *name != subdivision.name => {
+                    sqlx::query!(
+                        "UPDATE subdivisions SET name = $3::text WHERE nation = $1 AND iso_code = $2",
+                        nation.code,
+                        subdivision.code,
+                        subdivision.name
+                    )
+                    .execute(&mut *connection)
+                    .await?;
+
+                    report.renamed_subdivisions.push(Rename {
+                        code: full_code,
+                        from: name.clone(),
+                        to: subdivision.name.clone(),
+                    });
+                },
+                Some(_) => (),
+            }
+        }
+    }
+
+    let mut affected_nations = HashSet::new();
+    let mut affected_subdivisions = HashSet::new();
+
+    // Moving players below makes the history trigger on the players table record a nationality
+    // change for each of them. These entries are removed again afterwards
+    let history_watermark = sqlx::query!(r#"SELECT COALESCE(MAX(id), 0) AS "id!" FROM player_nationality_history"#)
+        .fetch_one(&mut *connection)
+        .await?
+        .id;
+    let mut moved_players = Vec::new();
+
+    // Move players out of removed nations first. Their subdivisions would not exist in the new
+    // nation, so they are unset
+    for (code, name) in &known_nations {
+        if dataset_nations.contains_key(code.as_str()) {
+            continue;
+        }
+
+        let replacement = dataset
+            .nations
+            .iter()
+            .find(|nation| nation.formerly.contains(code))
+            .map(|nation| nation.code.clone());
+
+        let moved: Vec<i32> = sqlx::query!(
+            "UPDATE players SET nationality = $2, subdivision = NULL WHERE nationality = $1 RETURNING id",
+            code,
+            replacement
+        )
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|row| row.id)
+        .collect();
+
+        sqlx::query!("UPDATE members SET nationality = $2 WHERE nationality = $1", code, replacement)
+            .execute(&mut *connection)
+            .await?;
+
+        // Rewrite history as well, so that past records are attributed to the replacement nation
+        sqlx::query!(
+            "UPDATE player_nationality_history SET nationality = $2, subdivision = NULL WHERE nationality = $1",
+            code,
+            replacement
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        if let Some(ref replacement) = replacement {
+            affected_nations.insert(replacement.clone());
+        }
+
+        report.removed_nations.push(Removal {
+            code: code.clone(),
+            name: name.clone(),
+            replaced_by: replacement,
+            players_affected: moved.len() as u64,
+        });
+
+        moved_players.extend(moved);
+    }
+
+    for ((nation, code), name) in &known_subdivisions {
+        if dataset_subdivisions.contains_key(&(nation.as_str(), code.as_str())) {
+            continue;
+        }
+
+        let replacement = dataset
+            .nations
+            .iter()
+            .filter(|dataset_nation| dataset_nation.code == *nation)
+            .flat_map(|dataset_nation| &dataset_nation.subdivisions)
+            .find(|subdivision| subdivision.formerly.contains(code))
+            .map(|subdivision| subdivision.code.clone());
+
+        let moved: Vec<i32> = sqlx::query!(
+            "UPDATE players SET subdivision = $3 WHERE nationality = $1 AND subdivision = $2 RETURNING id",
+            nation,
+            code,
+            replacement
+        )
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|row| row.id)
+        .collect();
+
+        sqlx::query!(
+            "UPDATE player_nationality_history SET subdivision = $3 WHERE nationality = $1 AND subdivision = $2",
+            nation,
+            code,
+            replacement
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!("DELETE FROM subdivisions WHERE nation = $1 AND iso_code = $2", nation, code)
+            .execute(&mut *connection)
+            .await?;
+
+        if let Some(ref replacement) = replacement {
+            affected_subdivisions.insert((nation.clone(), replacement.clone()));
+        }
+
+        report.removed_subdivisions.push(Removal {
+            code: format!("{}-{}", nation, code),
+            name: name.clone(),
+            replaced_by: replacement.map(|replacement| format!("{}-{}", nation, replacement)),
+            players_affected: moved.len() as u64,
+        });
+
+        moved_players.extend(moved);
+    }
+
+    sqlx::query!(
+        "DELETE FROM player_nationality_history WHERE id > $1 AND player = ANY($2)",
+        history_watermark,
+        &moved_players
+    )
+    .execute(&mut *connection)
+    .await?;
+
+    for removal in &report.removed_nations {
+        sqlx::query!("DELETE FROM nationalities WHERE iso_country_code = $1", removal.code)
+            .execute(&mut *connection)
+            .await?;
+    }
+
+    for nation in affected_nations {
+        sqlx::query!(
+            "UPDATE nationalities SET score = COALESCE(score_of_nation($1), 0) WHERE iso_country_code = $1",
+            nation
+        )
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    for (nation, subdivision) in affected_subdivisions {
+        sqlx::query!(
+            "UPDATE subdivisions SET score = COALESCE(score_of_subdivision($1, $2), 0) WHERE nation = $1 AND iso_code = $2",
+            nation,
+            subdivision
+        )
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    // Nations might have moved between continents, or lost/gained players
+    sqlx::query!("SELECT recompute_continent_scores()")
+        .execute(&mut *connection)
+        .await?;
+
+    // HashMap iteration order is random, so sort to get a stable report
+    report.removed_nations.sort_by(|a, b| a.code.cmp(&b.code));
+    report.removed_subdivisions.sort_by(|a, b| a.code.cmp(&b.code));
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nationality::sync::Iso3166Dataset;
+
+    fn script(letter: char) -> Option<&'static str> {
+        match letter {
+            'A'..='Z' | 'a'..='z' | '\u{00C0}'..='\u{02AF}' | '\u{1E00}'..='\u{1EFF}' => Some("Latin"),
+            '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => Some("Greek"),
+            '\u{0400}'..='\u{052F}' => Some("Cyrillic"),
+            // Modifier letters (such as the ʻ in romanized Arabic names) go with any script
+            '\u{02B0}'..='\u{02FF}' => None,
+            _ => Some("other"),
+        }
+    }
+
+    #[test]
+    fn test_bundled_names_are_single_script() {
+        let dataset = Iso3166Dataset::bundled();
+
+        for nation in &dataset.nations {
+            for name in std::iter::once(&nation.name).chain(nation.subdivisions.iter().map(|subdivision| &subdivision.name)) {
+                let mut scripts = name.chars().filter(|c| c.is_alphabetic()).filter_map(script);
+
+                if let Some(first) = scripts.next() {
+                    assert!(scripts.all(|other| other == first), "'{}' ({}) mixes scripts", name, nation.code);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Module containing code for dealing with the names players were previously known under
+//!
+//! Whenever a player is renamed (or merged into another player), their old name is kept as an
+//! alias, so that they can still be found under it. List moderators can hide aliases, after which
+//! they are no longer publicly associated with the player, and no longer considered when looking up
+//! players by name. Hidden aliases are only kept so that moderators can see them and unhide them
+//! again.
+
+use crate::{
+    error::{DemonlistError, Result},
+    player::DatabasePlayer,
+};
+use chrono::NaiveDateTime;
+use log::info;
+use pointercrate_core::util::non_nullable;
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
+pub struct PlayerAlias {
+    pub id: i32,
+    pub name: String,
+
+    /// The time at which the player stopped using this name
+    pub used_until: NaiveDateTime,
+    pub hidden: bool,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct PatchPlayerAlias {
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub hidden: Option<bool>,
+}
+
+impl PlayerAlias {
+    pub async fn by_id(player_id: i32, alias_id: i32, connection: &mut PgConnection) -> Result<PlayerAlias> {
+        sqlx::query_as!(
+            PlayerAlias,
+            r#"SELECT id, name::text AS "name!", used_until, hidden FROM player_names WHERE player = $1 AND id = $2"#,
+            player_id,
+            alias_id
+        )
+        .fetch_optional(connection)
+        .await?
+        .ok_or(DemonlistError::PlayerAliasNotFound { player_id, alias_id })
+    }
+
+    pub async fn apply_patch(mut self, patch: PatchPlayerAlias, connection: &mut PgConnection) -> Result<Self> {
+        if let Some(hidden) = patch.hidden {
+            self.set_hidden(hidden, connection).await?;
+        }
+
+        Ok(self)
+    }
+
+    pub async fn set_hidden(&mut self, hidden: bool, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!("UPDATE player_names SET hidden = $1 WHERE id = $2", hidden, self.id)
+            .execute(connection)
+            .await?;
+
+        self.hidden = hidden;
+
+        Ok(())
+    }
+}
+
+impl DatabasePlayer {
+    /// Gets the names this player was previously known under, most recently used first
+    pub async fn aliases(&self, include_hidden: bool, connection: &mut PgConnection) -> Result<Vec<PlayerAlias>> {
+        Ok(sqlx::query_as!(
+            PlayerAlias,
+            r#"SELECT id, name::text AS "name!", used_until, hidden FROM player_names WHERE player = $1 AND (NOT hidden OR $2) ORDER BY
+             used_until DESC, id DESC"#,
+            self.id,
+            include_hidden
+        )
+        .fetch_all(connection)
+        .await?)
+    }
+
+    /// Records that this player was known under the given name until now
+    pub(crate) async fn add_alias(&self, name: &str, connection: &mut PgConnection) -> Result<()> {
+        info!("Recording {} as previous name of player {}", name, self);
+
+        sqlx::query!(
+            "INSERT INTO player_names (player, name) VALUES ($1, $2::text) ON CONFLICT (player, name) DO UPDATE SET used_until = \
+             EXCLUDED.used_until",
+            self.id,
+            name
+        )
+        .execute(connection)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes the alias with the given name from this player, if it exists. Used when the player
+    /// goes back to using this name
+    pub(crate) async fn remove_alias(&self, name: &str, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM player_names WHERE player = $1 AND name = $2::text::citext",
+            self.id,
+            name
+        )
+        .execute(connection)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Transfers all aliases of the given player to this one. If both players share an alias, the
+    /// one of this player is kept.
+    pub(crate) async fn take_aliases_of(&self, other: &DatabasePlayer, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM player_names WHERE player = $2 AND name IN (SELECT name FROM player_names WHERE player = $1)",
+            self.id,
+            other.id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!("UPDATE player_names SET player = $1 WHERE player = $2", self.id, other.id)
+            .execute(connection)
+            .await?;
+
+        Ok(())
+    }
+}
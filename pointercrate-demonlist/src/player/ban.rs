@@ -0,0 +1,147 @@
+use crate::{
+    ban::{Ban, PostBan},
+    error::Result,
+    player::DatabasePlayer,
+};
+use chrono::NaiveDateTime;
+use log::info;
+use sqlx::PgConnection;
+
+impl DatabasePlayer {
+    /// Gets all bans ever issued against this player, most recent first
+    pub async fn bans(&self, connection: &mut PgConnection) -> Result<Vec<Ban>> {
+        Ok(sqlx::query_as!(
+            Ban,
+            "SELECT id, reason, issued_by, issued_at, expires_at, lifted_at, lifted_by FROM player_bans WHERE player = $1 ORDER BY \
+             issued_at DESC, id DESC",
+            self.id
+        )
+        .fetch_all(connection)
+        .await?)
+    }
+
+    /// Permanently bans this player, without giving a reason
+    pub async fn ban(&mut self, connection: &mut PgConnection) -> Result<()> {
+        self.record_ban(None, None, connection).await.map(|_| ())
+    }
+
+    /// Bans this player for the given reason, until the given expiry date (if any)
+    ///
+    /// If the player is already banned, their current ban is replaced.
+    pub async fn issue_ban(&mut self, ban: PostBan, connection: &mut PgConnection) -> Result<Ban> {
+        ban.validate()?;
+
+        self.record_ban(Some(ban.reason), ban.expires_at, connection).await
+    }
+
+    async fn record_ban(
+        &mut self, reason: Option<String>, expires_at: Option<NaiveDateTime>, connection: &mut PgConnection,
+    ) -> Result<Ban> {
+        // Delete all submissions for this player
+        let deleted = sqlx::query!(
+            "DELETE FROM records WHERE player = $1 AND (status_ = 'SUBMITTED' OR status_ = 'UNDER_CONSIDERATION')",
+            self.id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        info!("Deleted {} submissions while banning {}", deleted.rows_affected(), self);
+
+        // We can simply reject all accepted records here! All submitted records were deleted above, and we
+        // don't have to worry about conflicts with existing rejected record when setting status to
+        // 'rejected' since rejected records are globally unique!
+
+        // Now, reject all previously accepted records, remembering which ones so that they can be
+        // restored should the ban be temporary
+        let mut rejected_records = sqlx::query!(
+            "UPDATE records SET status_ = 'REJECTED' WHERE player = $1 AND status_ = 'APPROVED' RETURNING id",
+            self.id
+        )
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|row| row.id)
+        .collect::<Vec<_>>();
+
+        info!("Rejected {} records while banning {}", rejected_records.len(), self);
+
+        // If the player is already banned, the new ban supersedes the current one. Records rejected
+        // by the current one are now owed to the new one.
+        if let Some(row) = sqlx::query!(
+            "UPDATE player_bans SET lifted_at = (NOW() AT TIME ZONE 'utc'), lifted_by = (SELECT id FROM active_user LIMIT 1) WHERE \
+             player = $1 AND lifted_at IS NULL RETURNING rejected_records",
+            self.id
+        )
+        .fetch_optional(&mut *connection)
+        .await?
+        {
+            rejected_records.extend(row.rejected_records);
+        }
+
+        let ban = sqlx::query_as!(
+            Ban,
+            "INSERT INTO player_bans (player, reason, issued_by, expires_at, rejected_records) VALUES ($1, $2, (SELECT id FROM \
+             active_user LIMIT 1), $3, $4) RETURNING id, reason, issued_by, issued_at, expires_at, lifted_at, lifted_by",
+            self.id,
+            reason,
+            expires_at,
+            &rejected_records
+        )
+        .fetch_one(&mut *connection)
+        .await?;
+
+        // Actually ban the player
+        sqlx::query!("UPDATE players SET banned = true WHERE id = $1", self.id)
+            .execute(connection)
+            .await?;
+
+        self.banned = true;
+
+        Ok(ban)
+    }
+
+    /// Lifts this player's current ban
+    pub async fn unban(&mut self, connection: &mut PgConnection) -> Result<()> {
+        self.lift_ban(false, connection).await
+    }
+
+    /// Lifts this player's current ban, either because it was `expired`, or because a moderator
+    /// decided to do so.
+    ///
+    /// If the ban was temporary, the records rejected because of it are restored. Permanent bans on
+    /// the other hand are assumed to have been deliberate about rejecting all of the player's
+    /// records, so these records stay rejected.
+    ///
+    /// Does not update the player's score.
+    pub(crate) async fn lift_ban(&mut self, expired: bool, connection: &mut PgConnection) -> Result<()> {
+        let lifted = sqlx::query!(
+            "UPDATE player_bans SET lifted_at = CASE WHEN $2 THEN expires_at ELSE (NOW() AT TIME ZONE 'utc') END, lifted_by = CASE WHEN \
+             $2 THEN NULL ELSE (SELECT id FROM active_user LIMIT 1) END WHERE player = $1 AND lifted_at IS NULL RETURNING expires_at, \
+             rejected_records",
+            self.id,
+            expired
+        )
+        .fetch_optional(&mut *connection)
+        .await?;
+
+        sqlx::query!("UPDATE players SET banned = false WHERE id = $1", self.id)
+            .execute(&mut *connection)
+            .await?;
+
+        if let Some(row) = lifted.filter(|row| row.expires_at.is_some()) {
+            let restored = sqlx::query!(
+                "UPDATE records SET status_ = 'APPROVED' WHERE player = $1 AND status_ = 'REJECTED' AND id = ANY($2::INTEGER[])",
+                self.id,
+                &row.rejected_records
+            )
+            .execute(connection)
+            .await?;
+
+            info!("Restored {} records while unbanning {}", restored.rows_affected(), self);
+        }
+
+        self.banned = false;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+use crate::error::Result;
+use futures::StreamExt;
+use pointercrate_core::audit::{AuditLogEntry, AuditLogEntryType, NamedId};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+#[derive(Serialize)]
+pub struct ClaimModificationData {
+    verified: Option<bool>,
+    lock_submissions: Option<bool>,
+
+    /// Set if the claim was moved over from a different player, which happens when players are
+    /// merged
+    player: Option<NamedId>,
+}
+
+#[derive(Serialize)]
+pub struct ClaimAuditLogEntry {
+    /// The user holding the claim this entry refers to
+    pub claimant: NamedId,
+
+    #[serde(flatten)]
+    pub entry: AuditLogEntry<ClaimModificationData>,
+}
+
+/// Gets all audit log entries for claims on the given player, in chronological order
+///
+/// The `id` of each entry is the ID of the claim it refers to. Since claims are deleted whenever
+/// they change hands, this includes claims that no longer exist.
+pub async fn audit_log_for_claims_on(player_id: i32, connection: &mut PgConnection) -> Result<Vec<ClaimAuditLogEntry>> {
+    let mut entries = Vec::new();
+
+    {
+        let mut addition_stream = sqlx::query!(
+            r#"SELECT time, audit_id, userid, users.name AS "username?", player_claim_additions.id, player_claim_additions.member_id, claimants.name AS "claimant_name?"
+                  FROM player_claim_additions
+                  LEFT OUTER JOIN members AS users ON users.member_id = userid
+                  LEFT OUTER JOIN members AS claimants ON claimants.member_id = player_claim_additions.member_id
+                  WHERE player_id = $1"#,
+            player_id
+        )
+        .fetch(&mut *connection);
+
+        while let Some(addition) = addition_stream.next().await {
+            let addition = addition?;
+
+            entries.push(ClaimAuditLogEntry {
+                claimant: NamedId {
+                    id: addition.member_id,
+                    name: addition.claimant_name,
+                },
+                entry: AuditLogEntry {
+                    time: addition.time,
+                    entry_id: addition.audit_id,
+                    id: addition.id,
+                    user: NamedId {
+                        id: addition.userid,
+                        name: addition.username,
+                    },
+                    r#type: AuditLogEntryType::Addition,
+                },
+            });
+        }
+    }
+
+    {
+        let mut modification_stream = sqlx::query!(
+            r#"SELECT time, audit_id, userid, users.name AS "username?", player_claim_modifications.id, player_claim_modifications.member_id,
+                  claimants.name AS "claimant_name?", verified, lock_submissions, player, players.name::TEXT AS player_name
+                  FROM player_claim_modifications
+                  LEFT OUTER JOIN members AS users ON users.member_id = userid
+                  LEFT OUTER JOIN members AS claimants ON claimants.member_id = player_claim_modifications.member_id
+                  LEFT OUTER JOIN players ON players.id = player
+                  WHERE player_id = $1 OR player = $1"#,
+            player_id
+        )
+        .fetch(&mut *connection);
+
+        while let Some(modification) = modification_stream.next().await {
+            let modification = modification?;
+
+            entries.push(ClaimAuditLogEntry {
+                claimant: NamedId {
+                    id: modification.member_id,
+                    name: modification.claimant_name,
+                },
+                entry: AuditLogEntry {
+                    time: modification.time,
+                    entry_id: modification.audit_id,
+                    id: modification.id,
+                    user: NamedId {
+                        id: modification.userid,
+                        name: modification.username,
+                    },
+                    r#type: AuditLogEntryType::Modification(ClaimModificationData {
+                        verified: modification.verified,
+                        lock_submissions: modification.lock_submissions,
+                        player: modification.player.map(|id| NamedId {
+                            id,
+                            name: modification.player_name,
+                        }),
+                    }),
+                },
+            });
+        }
+    }
+
+    {
+        let mut deletion_stream = sqlx::query!(
+            r#"SELECT time, audit_id, userid, users.name AS "username?", player_claim_deletions.id, player_claim_deletions.member_id, claimants.name AS "claimant_name?"
+                  FROM player_claim_deletions
+                  LEFT OUTER JOIN members AS users ON users.member_id = userid
+                  LEFT OUTER JOIN members AS claimants ON claimants.member_id = player_claim_deletions.member_id
+                  WHERE player_id = $1"#,
+            player_id
+        )
+        .fetch(&mut *connection);
+
+        while let Some(deletion) = deletion_stream.next().await {
+            let deletion = deletion?;
+
+            entries.push(ClaimAuditLogEntry {
+                claimant: NamedId {
+                    id: deletion.member_id,
+                    name: deletion.claimant_name,
+                },
+                entry: AuditLogEntry {
+                    time: deletion.time,
+                    entry_id: deletion.audit_id,
+                    id: deletion.id,
+                    user: NamedId {
+                        id: deletion.userid,
+                        name: deletion.username,
+                    },
+                    r#type: AuditLogEntryType::Deletion,
+                },
+            });
+        }
+    }
+
+    entries.sort_by_key(|entry| (entry.entry.time, entry.entry.entry_id));
+
+    Ok(entries)
+}
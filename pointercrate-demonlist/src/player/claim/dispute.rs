@@ -0,0 +1,286 @@
+//! Module for disputes of verified claims
+//!
+//! Once a claim on a player is verified, nobody else can claim that player. Users who believe a
+//! verified claim to be illegitimate can instead open a dispute, providing evidence for their case.
+//! List moderators then resolve the dispute by either transferring the claim to the disputing user,
+//! revoking it altogether, or rejecting the dispute. Resolved disputes are kept, together with
+//! the reason given for their resolution.
+
+use crate::{
+    error::{DemonlistError, Result},
+    player::{claim::PlayerClaim, DatabasePlayer},
+};
+use chrono::NaiveDateTime;
+use futures::StreamExt;
+use log::info;
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+
+#[derive(Debug, Deserialize, Serialize, Hash, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeResolution {
+    /// The disputing user is given a verified claim on the player, replacing the existing one
+    Transferred,
+
+    /// The existing claim stays in place
+    Rejected,
+
+    /// The existing claim is deleted without being replaced
+    Revoked,
+}
+
+impl DisputeResolution {
+    fn to_sql(self) -> &'static str {
+        match self {
+            DisputeResolution::Transferred => "TRANSFERRED",
+            DisputeResolution::Rejected => "REJECTED",
+            DisputeResolution::Revoked => "REVOKED",
+        }
+    }
+
+    fn from_sql(sql: &str) -> Self {
+        match sql {
+            "TRANSFERRED" => DisputeResolution::Transferred,
+            "REJECTED" => DisputeResolution::Rejected,
+            "REVOKED" => DisputeResolution::Revoked,
+            _ => panic!("invalid dispute resolution: {}", sql),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
+pub struct ClaimDispute {
+    pub id: i32,
+    pub player: DatabasePlayer,
+
+    /// The ID of the user contesting the claim
+    pub disputed_by: i32,
+
+    /// The ID of the user holding the verified claim at the time the dispute was opened
+    pub holder: Option<i32>,
+    pub evidence: String,
+    pub created_at: NaiveDateTime,
+
+    /// `None` while the dispute is still open
+    pub resolution: Option<DisputeResolution>,
+    pub resolution_reason: Option<String>,
+    pub resolved_at: Option<NaiveDateTime>,
+
+    /// The ID of the moderator who resolved the dispute
+    pub resolved_by: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PostClaimDispute {
+    pub evidence: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveClaimDispute {
+    pub resolution: DisputeResolution,
+    pub reason: String,
+}
+
+macro_rules! construct_from_row {
+    ($row:expr) => {
+        ClaimDispute {
+            id: $row.id,
+            player: DatabasePlayer {
+                id: $row.player_id,
+                name: $row.player_name,
+                banned: $row.player_banned,
+            },
+            disputed_by: $row.disputed_by,
+            holder: $row.holder,
+            evidence: $row.evidence,
+            created_at: $row.created_at,
+            resolution: $row.resolution.as_deref().map(DisputeResolution::from_sql),
+            resolution_reason: $row.resolution_reason,
+            resolved_at: $row.resolved_at,
+            resolved_by: $row.resolved_by,
+        }
+    };
+}
+
+impl ClaimDispute {
+    pub async fn by_id(id: i32, connection: &mut PgConnection) -> Result<ClaimDispute> {
+        let row = sqlx::query!(
+            r#"SELECT claim_disputes.id, player_id, players.name::text AS "player_name!", players.banned AS player_banned, disputed_by, holder,
+             evidence, created_at, resolution::text, resolution_reason, resolved_at, resolved_by FROM claim_disputes INNER JOIN players ON
+             players.id = player_id WHERE claim_disputes.id = $1"#,
+            id
+        )
+        .fetch_optional(connection)
+        .await?
+        .ok_or(DemonlistError::ClaimDisputeNotFound { id })?;
+
+        Ok(construct_from_row!(row))
+    }
+
+    /// Gets all disputes that have not been resolved yet, oldest first
+    pub async fn open(connection: &mut PgConnection) -> Result<Vec<ClaimDispute>> {
+        let mut stream = sqlx::query!(
+            r#"SELECT claim_disputes.id, player_id, players.name::text AS "player_name!", players.banned AS player_banned, disputed_by, holder,
+             evidence, created_at, resolution::text, resolution_reason, resolved_at, resolved_by FROM claim_disputes INNER JOIN players ON
+             players.id = player_id WHERE resolution IS NULL ORDER BY created_at, claim_disputes.id"#
+        )
+        .fetch(connection);
+
+        let mut disputes = Vec::new();
+
+        while let Some(row) = stream.next().await {
+            let row = row?;
+
+            disputes.push(construct_from_row!(row))
+        }
+
+        Ok(disputes)
+    }
+
+    /// Gets all disputes ever opened on claims on the given player, most recent first
+    pub async fn on_player(player_id: i32, connection: &mut PgConnection) -> Result<Vec<ClaimDispute>> {
+        let mut stream = sqlx::query!(
+            r#"SELECT claim_disputes.id, player_id, players.name::text AS "player_name!", players.banned AS player_banned, disputed_by, holder,
+             evidence, created_at, resolution::text, resolution_reason, resolved_at, resolved_by FROM claim_disputes INNER JOIN players ON
+             players.id = player_id WHERE player_id = $1 ORDER BY created_at DESC, claim_disputes.id DESC"#,
+            player_id
+        )
+        .fetch(connection);
+
+        let mut disputes = Vec::new();
+
+        while let Some(row) = stream.next().await {
+            let row = row?;
+
+            disputes.push(construct_from_row!(row))
+        }
+
+        Ok(disputes)
+    }
+
+    /// Resolves this dispute, applying the given resolution to the claims on the disputed player
+    ///
+    /// Transferring or revoking the claim fails if the verified claim on the player is no longer
+    /// held by the user that held it when the dispute was opened.
+    pub async fn resolve(&mut self, resolve: ResolveClaimDispute, connection: &mut PgConnection) -> Result<()> {
+        if self.resolution.is_some() {
+            return Err(DemonlistError::DisputeAlreadyResolved);
+        }
+
+        if resolve.reason.trim().is_empty() {
+            return Err(DemonlistError::InvalidDispute);
+        }
+
+        if resolve.resolution != DisputeResolution::Rejected {
+            let current_holder = PlayerClaim::verified_claim_on(self.player.id, &mut *connection)
+                .await?
+                .map(|claim| claim.user_id);
+
+            if current_holder != self.holder {
+                return Err(DemonlistError::DisputedClaimChanged);
+            }
+        }
+
+        info!(
+            "Resolving dispute {} on claim on player {} as {:?}",
+            self.id, self.player, resolve.resolution
+        );
+
+        match resolve.resolution {
+            DisputeResolution::Transferred => {
+                // A user can only hold a single claim, and a verified claim on a player replaces all
+                // other claims on that player
+                sqlx::query!(
+                    "DELETE FROM player_claims WHERE member_id = $1 OR player_id = $2",
+                    self.disputed_by,
+                    self.player.id
+                )
+                .execute(&mut *connection)
+                .await?;
+
+                sqlx::query!(
+                    "INSERT INTO player_claims (member_id, player_id, verified) VALUES ($1, $2, TRUE)",
+                    self.disputed_by,
+                    self.player.id
+                )
+                .execute(&mut *connection)
+                .await?;
+            },
+            DisputeResolution::Revoked => {
+                sqlx::query!("DELETE FROM player_claims WHERE player_id = $1 AND verified", self.player.id)
+                    .execute(&mut *connection)
+                    .await?;
+            },
+            DisputeResolution::Rejected => (),
+        }
+
+        let resolved = sqlx::query!(
+            "UPDATE claim_disputes SET resolution = $2::text::claim_dispute_resolution, resolution_reason = $3, resolved_at = (NOW() AT \
+             TIME ZONE 'utc'), resolved_by = (SELECT id FROM active_user LIMIT 1) WHERE id = $1 RETURNING resolved_at, resolved_by",
+            self.id,
+            resolve.resolution.to_sql(),
+            resolve.reason
+        )
+        .fetch_one(connection)
+        .await?;
+
+        self.resolution = Some(resolve.resolution);
+        self.resolution_reason = Some(resolve.reason);
+        self.resolved_at = resolved.resolved_at;
+        self.resolved_by = resolved.resolved_by;
+
+        Ok(())
+    }
+}
+
+impl DatabasePlayer {
+    /// Opens a dispute by the given user on the verified claim on this player
+    pub async fn dispute_claim(&self, disputed_by: i32, dispute: PostClaimDispute, connection: &mut PgConnection) -> Result<ClaimDispute> {
+        if dispute.evidence.trim().is_empty() {
+            return Err(DemonlistError::InvalidDispute);
+        }
+
+        let holder = match PlayerClaim::verified_claim_on(self.id, &mut *connection).await? {
+            Some(claim) if claim.user_id != disputed_by => claim.user_id,
+            _ => return Err(DemonlistError::UndisputableClaim),
+        };
+
+        let already_open = sqlx::query!(
+            r#"SELECT EXISTS (SELECT 1 FROM claim_disputes WHERE player_id = $1 AND disputed_by = $2 AND resolution IS NULL) AS "already_open!""#,
+            self.id,
+            disputed_by
+        )
+        .fetch_one(&mut *connection)
+        .await?
+        .already_open;
+
+        if already_open {
+            return Err(DemonlistError::DisputeAlreadyOpen);
+        }
+
+        info!("User {} disputes claim of user {} on player {}", disputed_by, holder, self);
+
+        let created = sqlx::query!(
+            "INSERT INTO claim_disputes (player_id, disputed_by, holder, evidence) VALUES ($1, $2, $3, $4) RETURNING id, created_at",
+            self.id,
+            disputed_by,
+            holder,
+            dispute.evidence
+        )
+        .fetch_one(connection)
+        .await?;
+
+        Ok(ClaimDispute {
+            id: created.id,
+            player: self.clone(),
+            disputed_by,
+            holder: Some(holder),
+            evidence: dispute.evidence,
+            created_at: created.created_at,
+            resolution: None,
+            resolution_reason: None,
+            resolved_at: None,
+            resolved_by: None,
+        })
+    }
+}
@@ -8,6 +8,9 @@ pub struct ClaimBy {
     pub player: DatabasePlayer,
     pub verified: bool,
     pub lock_submissions: bool,
+
+    /// The code to place on the claimed player's Geometry Dash account to verify the claim
+    pub verification_code: String,
 }
 
 impl PlayerClaim {
@@ -32,7 +35,7 @@ impl PlayerClaim {
 
     pub async fn by_user(user_id: i32, connection: &mut PgConnection) -> Result<Option<ClaimBy>> {
         match sqlx::query!(
-            r#"SELECT verified, lock_submissions, player_id, players.name::text as "name!", players.banned FROM player_claims INNER JOIN players ON player_id=players.id
+            r#"SELECT verified, lock_submissions, verification_code, player_id, players.name::text as "name!", players.banned FROM player_claims INNER JOIN players ON player_id=players.id
              WHERE member_id = $1"#,
             user_id
         )
@@ -47,13 +50,31 @@ impl PlayerClaim {
                         banned: row.banned,
                     },
                     verified: row.verified,
-                    lock_submissions: row.lock_submissions
+                    lock_submissions: row.lock_submissions,
+                    verification_code: row.verification_code,
                 })),
             Err(sqlx::Error::RowNotFound) => Ok(None),
             Err(err) => Err(err.into()),
         }
     }
 
+    /// Gets the code the claimant has to place on the claimed player's Geometry Dash account to
+    /// verify this claim
+    pub async fn verification_code(&self, connection: &mut PgConnection) -> Result<String> {
+        sqlx::query!(
+            "SELECT verification_code FROM player_claims WHERE member_id = $1 AND player_id = $2",
+            self.user_id,
+            self.player_id
+        )
+        .fetch_optional(connection)
+        .await?
+        .map(|row| row.verification_code)
+        .ok_or(DemonlistError::ClaimNotFound {
+            member_id: self.user_id,
+            player_id: self.player_id,
+        })
+    }
+
     pub async fn get(member_id: i32, player_id: i32, connection: &mut PgConnection) -> Result<PlayerClaim> {
         match PlayerClaim::by_user(member_id, connection).await? {
             Some(claim) if claim.player.id == player_id => Ok(PlayerClaim {
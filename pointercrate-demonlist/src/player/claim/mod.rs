@@ -1,14 +1,19 @@
+pub use dispute::{ClaimDispute, DisputeResolution, PostClaimDispute, ResolveClaimDispute};
 pub use paginate::{ListedClaim, PlayerClaimPagination};
 pub use patch::PatchPlayerClaim;
 use serde::{Deserialize, Serialize};
+pub use suggestion::{ClaimSuggestion, PostClaimSuggestion, ReviewClaimSuggestion, SuggestedEdit, SuggestionStatus};
 
+pub mod audit;
 mod delete;
+mod dispute;
 mod get;
 mod paginate;
 mod patch;
 mod put;
+mod suggestion;
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash)]
 pub struct PlayerClaim {
     pub user_id: i32,
     pub player_id: i32,
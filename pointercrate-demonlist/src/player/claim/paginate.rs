@@ -43,7 +43,7 @@ impl PaginationQuery for PlayerClaimPagination {
 }
 
 impl Paginatable<PlayerClaimPagination> for ListedClaim {
-    first_and_last!("player_claims");
+    first_and_last!(PlayerClaimPagination, "player_claims");
 
     async fn page(query: &PlayerClaimPagination, connection: &mut PgConnection) -> Result<(Vec<ListedClaim>, PageContext), sqlx::Error> {
         let order = query.params.order();
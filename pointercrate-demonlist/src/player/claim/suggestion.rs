@@ -0,0 +1,403 @@
+//! Module for edits verified claimants suggest for their claimed player
+//!
+//! Claimants cannot edit their player directly. Instead, they suggest edits, which end up in a
+//! queue for list moderators to review. Approved suggestions are applied via [`PatchPlayer`] and
+//! [`PatchRecord`] (or, for record removals, by deleting the record), with the resulting audit log
+//! entries attributed to the claimant who suggested the edit.
+
+use crate::{
+    error::{DemonlistError, Result},
+    player::{claim::PlayerClaim, DatabasePlayer, PatchPlayer, Player},
+    record::{approval::ApprovalPolicy, FullRecord, PatchRecord, RecordStatus},
+};
+use chrono::NaiveDateTime;
+use futures::StreamExt;
+use log::info;
+use pointercrate_core::pool::audit_connection;
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+
+/// An edit a claimant can suggest for their claimed player
+#[derive(Debug, Deserialize, Serialize, Hash, Eq, PartialEq, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SuggestedEdit {
+    /// Changes the capitalization of the player's name. Actual renames need to be requested from
+    /// the list team directly, as they might require merging players.
+    Name { name: String },
+
+    /// Sets the subdivision of the player's nationality, or removes it if `None`
+    Subdivision { subdivision: Option<String> },
+
+    /// Replaces the video of one of the player's approved records
+    RecordVideo { record_id: i32, video: String },
+
+    /// Deletes one of the player's records
+    RecordRemoval { record_id: i32 },
+}
+
+impl SuggestedEdit {
+    fn kind(&self) -> &'static str {
+        match self {
+            SuggestedEdit::Name { .. } => "NAME",
+            SuggestedEdit::Subdivision { .. } => "SUBDIVISION",
+            SuggestedEdit::RecordVideo { .. } => "RECORD_VIDEO",
+            SuggestedEdit::RecordRemoval { .. } => "RECORD_REMOVAL",
+        }
+    }
+
+    fn from_sql(kind: &str, name: Option<String>, subdivision: Option<String>, record_id: Option<i32>, video: Option<String>) -> Self {
+        match kind {
+            "NAME" => SuggestedEdit::Name {
+                name: name.unwrap_or_default(),
+            },
+            "SUBDIVISION" => SuggestedEdit::Subdivision { subdivision },
+            "RECORD_VIDEO" => SuggestedEdit::RecordVideo {
+                record_id: record_id.unwrap_or_default(),
+                video: video.unwrap_or_default(),
+            },
+            "RECORD_REMOVAL" => SuggestedEdit::RecordRemoval {
+                record_id: record_id.unwrap_or_default(),
+            },
+            _ => panic!("invalid suggested edit: {}", kind),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Hash, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl SuggestionStatus {
+    fn from_sql(sql: &str) -> Self {
+        match sql {
+            "PENDING" => SuggestionStatus::Pending,
+            "APPROVED" => SuggestionStatus::Approved,
+            "REJECTED" => SuggestionStatus::Rejected,
+            _ => panic!("invalid suggestion status: {}", sql),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
+pub struct ClaimSuggestion {
+    pub id: i32,
+    pub player: DatabasePlayer,
+
+    /// The ID of the claimant who suggested the edit
+    pub suggested_by: i32,
+    pub edit: SuggestedEdit,
+    pub comment: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub status: SuggestionStatus,
+    pub reviewed_at: Option<NaiveDateTime>,
+
+    /// The ID of the moderator who reviewed the suggestion
+    pub reviewed_by: Option<i32>,
+    pub review_note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PostClaimSuggestion {
+    #[serde(flatten)]
+    pub edit: SuggestedEdit,
+
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewClaimSuggestion {
+    pub approve: bool,
+
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+macro_rules! construct_from_row {
+    ($row:expr) => {
+        ClaimSuggestion {
+            id: $row.id,
+            player: DatabasePlayer {
+                id: $row.player_id,
+                name: $row.player_name,
+                banned: $row.player_banned,
+            },
+            suggested_by: $row.suggested_by,
+            edit: SuggestedEdit::from_sql(&$row.kind, $row.name, $row.subdivision, $row.record_id, $row.video),
+            comment: $row.comment,
+            created_at: $row.created_at,
+            status: SuggestionStatus::from_sql(&$row.status),
+            reviewed_at: $row.reviewed_at,
+            reviewed_by: $row.reviewed_by,
+            review_note: $row.review_note,
+        }
+    };
+}
+
+impl ClaimSuggestion {
+    pub async fn by_id(id: i32, connection: &mut PgConnection) -> Result<ClaimSuggestion> {
+        let row = sqlx::query!(
+            r#"SELECT claim_suggestions.id, player_id, players.name::text AS "player_name!", players.banned AS player_banned, suggested_by,
+             kind::text AS "kind!", claim_suggestions.name, claim_suggestions.subdivision::text, record_id, video, comment, created_at, status::text AS "status!",
+             reviewed_at, reviewed_by, review_note FROM claim_suggestions INNER JOIN players ON players.id = player_id WHERE
+             claim_suggestions.id = $1"#,
+            id
+        )
+        .fetch_optional(connection)
+        .await?
+        .ok_or(DemonlistError::SuggestionNotFound { id })?;
+
+        Ok(construct_from_row!(row))
+    }
+
+    /// Gets all suggestions that have not been reviewed yet, oldest first
+    pub async fn pending(connection: &mut PgConnection) -> Result<Vec<ClaimSuggestion>> {
+        let mut stream = sqlx::query!(
+            r#"SELECT claim_suggestions.id, player_id, players.name::text AS "player_name!", players.banned AS player_banned, suggested_by,
+             kind::text AS "kind!", claim_suggestions.name, claim_suggestions.subdivision::text, record_id, video, comment, created_at, status::text AS "status!",
+             reviewed_at, reviewed_by, review_note FROM claim_suggestions INNER JOIN players ON players.id = player_id WHERE status =
+             'PENDING' ORDER BY created_at, claim_suggestions.id"#
+        )
+        .fetch(connection);
+
+        let mut suggestions = Vec::new();
+
+        while let Some(row) = stream.next().await {
+            let row = row?;
+
+            suggestions.push(construct_from_row!(row))
+        }
+
+        Ok(suggestions)
+    }
+
+    /// Gets all suggestions ever made for the given player, most recent first
+    pub async fn for_player(player_id: i32, connection: &mut PgConnection) -> Result<Vec<ClaimSuggestion>> {
+        let mut stream = sqlx::query!(
+            r#"SELECT claim_suggestions.id, player_id, players.name::text AS "player_name!", players.banned AS player_banned, suggested_by,
+             kind::text AS "kind!", claim_suggestions.name, claim_suggestions.subdivision::text, record_id, video, comment, created_at, status::text AS "status!",
+             reviewed_at, reviewed_by, review_note FROM claim_suggestions INNER JOIN players ON players.id = player_id WHERE player_id = $1
+             ORDER BY created_at DESC, claim_suggestions.id DESC"#,
+            player_id
+        )
+        .fetch(connection);
+
+        let mut suggestions = Vec::new();
+
+        while let Some(row) = stream.next().await {
+            let row = row?;
+
+            suggestions.push(construct_from_row!(row))
+        }
+
+        Ok(suggestions)
+    }
+
+    /// Approves or rejects this suggestion. Approved suggestions are applied immediately.
+    ///
+    /// Suggestions can only be approved while their suggester still holds a verified claim on the
+    /// player.
+    ///
+    /// Must be called inside a transaction
+    pub async fn review(&mut self, review: ReviewClaimSuggestion, policy: &ApprovalPolicy, connection: &mut PgConnection) -> Result<()> {
+        if self.status != SuggestionStatus::Pending {
+            return Err(DemonlistError::SuggestionAlreadyReviewed);
+        }
+
+        info!(
+            "Reviewing suggestion {} for player {} (approve: {})",
+            self.id, self.player, review.approve
+        );
+
+        if review.approve {
+            match PlayerClaim::get(self.suggested_by, self.player.id, &mut *connection).await {
+                Ok(claim) if claim.verified => (),
+                Ok(_) | Err(DemonlistError::ClaimNotFound { .. }) => return Err(DemonlistError::SuggesterNotClaimant),
+                Err(err) => return Err(err),
+            }
+
+            // Attribute the changes to the claimant, instead of the reviewing moderator
+            let reviewer = sqlx::query!(r#"SELECT id AS "id!" FROM active_user LIMIT 1"#)
+                .fetch_one(&mut *connection)
+                .await?
+                .id;
+
+            audit_connection(&mut *connection, self.suggested_by).await?;
+
+            let result = self.apply(policy, &mut *connection).await;
+
+            audit_connection(&mut *connection, reviewer).await?;
+
+            result?;
+        }
+
+        let status = match review.approve {
+            true => "APPROVED",
+            false => "REJECTED",
+        };
+
+        let reviewed = sqlx::query!(
+            "UPDATE claim_suggestions SET status = $2::text::suggestion_status, reviewed_at = (NOW() AT TIME ZONE 'utc'), reviewed_by = \
+             (SELECT id FROM active_user LIMIT 1), review_note = $3 WHERE id = $1 RETURNING reviewed_at, reviewed_by",
+            self.id,
+            status,
+            review.note
+        )
+        .fetch_one(connection)
+        .await?;
+
+        self.status = SuggestionStatus::from_sql(status);
+        self.reviewed_at = reviewed.reviewed_at;
+        self.reviewed_by = reviewed.reviewed_by;
+        self.review_note = review.note;
+
+        Ok(())
+    }
+
+    async fn apply(&self, policy: &ApprovalPolicy, connection: &mut PgConnection) -> Result<()> {
+        match self.edit {
+            SuggestedEdit::Name { ref name } => {
+                let patch = PatchPlayer {
+                    name: Some(name.clone()),
+                    ..Default::default()
+                };
+
+                Player::by_id(self.player.id, &mut *connection)
+                    .await?
+                    .upgrade(&mut *connection)
+                    .await?
+                    .apply_patch(patch, connection)
+                    .await?;
+            },
+            SuggestedEdit::Subdivision { ref subdivision } => {
+                let patch = PatchPlayer {
+                    subdivision: Some(subdivision.clone()),
+                    ..Default::default()
+                };
+
+                Player::by_id(self.player.id, &mut *connection)
+                    .await?
+                    .upgrade(&mut *connection)
+                    .await?
+                    .apply_patch(patch, connection)
+                    .await?;
+            },
+            SuggestedEdit::RecordVideo { record_id, ref video } => {
+                let record = self.record(record_id, &mut *connection).await?;
+
+                record.apply_patch(PatchRecord::video(video.clone()), policy, connection).await?;
+            },
+            SuggestedEdit::RecordRemoval { record_id } => {
+                self.record(record_id, &mut *connection).await?.delete(connection).await?;
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Gets the record with the given ID, making sure it (still) belongs to the player this
+    /// suggestion was made for
+    async fn record(&self, record_id: i32, connection: &mut PgConnection) -> Result<FullRecord> {
+        let record = FullRecord::by_id(record_id, connection).await?;
+
+        if record.player.id != self.player.id {
+            return Err(DemonlistError::InvalidSuggestion);
+        }
+
+        Ok(record)
+    }
+}
+
+impl PlayerClaim {
+    /// Suggests an edit for the player claimed by this claim, which has to be verified
+    pub async fn suggest_edit(&self, suggestion: PostClaimSuggestion, connection: &mut PgConnection) -> Result<ClaimSuggestion> {
+        if !self.verified {
+            return Err(DemonlistError::ClaimUnverified);
+        }
+
+        let player = Player::by_id(self.player_id, &mut *connection).await?;
+
+        // Normalize the suggestion, and reject any that could not be applied anyway
+        let edit = match suggestion.edit {
+            SuggestedEdit::Name { name } => {
+                let name = name.trim().to_string();
+
+                if name == player.base.name || name.to_lowercase() != player.base.name.to_lowercase() {
+                    return Err(DemonlistError::InvalidSuggestion);
+                }
+
+                SuggestedEdit::Name { name }
+            },
+            SuggestedEdit::Subdivision { subdivision } => {
+                let nationality = player.nationality.as_ref().ok_or(DemonlistError::NoNationSet)?;
+
+                let subdivision = match subdivision {
+                    Some(code) => Some(nationality.subdivision_by_code(&code, &mut *connection).await?.iso_code),
+                    None => None,
+                };
+
+                SuggestedEdit::Subdivision { subdivision }
+            },
+            SuggestedEdit::RecordVideo { record_id, video } => {
+                let record = FullRecord::by_id(record_id, &mut *connection).await?;
+
+                if record.player.id != self.player_id || record.status != RecordStatus::Approved {
+                    return Err(DemonlistError::InvalidSuggestion);
+                }
+
+                SuggestedEdit::RecordVideo {
+                    record_id,
+                    video: crate::video::identify(&video)?.url(),
+                }
+            },
+            SuggestedEdit::RecordRemoval { record_id } => {
+                if FullRecord::by_id(record_id, &mut *connection).await?.player.id != self.player_id {
+                    return Err(DemonlistError::InvalidSuggestion);
+                }
+
+                SuggestedEdit::RecordRemoval { record_id }
+            },
+        };
+
+        let (name, subdivision, record_id, video) = match edit {
+            SuggestedEdit::Name { ref name } => (Some(name.clone()), None, None, None),
+            SuggestedEdit::Subdivision { ref subdivision } => (None, subdivision.clone(), None, None),
+            SuggestedEdit::RecordVideo { record_id, ref video } => (None, None, Some(record_id), Some(video.clone())),
+            SuggestedEdit::RecordRemoval { record_id } => (None, None, Some(record_id), None),
+        };
+
+        info!("User {} suggests {:?} for player {}", self.user_id, edit, player.base);
+
+        let created = sqlx::query!(
+            "INSERT INTO claim_suggestions (player_id, suggested_by, kind, name, subdivision, record_id, video, comment) VALUES ($1, $2, \
+             $3::text::suggested_edit, $4, $5, $6, $7, $8) RETURNING id, created_at",
+            self.player_id,
+            self.user_id,
+            edit.kind(),
+            name,
+            subdivision,
+            record_id,
+            video,
+            suggestion.comment
+        )
+        .fetch_one(connection)
+        .await?;
+
+        Ok(ClaimSuggestion {
+            id: created.id,
+            player: player.base,
+            suggested_by: self.user_id,
+            edit,
+            comment: suggestion.comment,
+            created_at: created.created_at,
+            status: SuggestionStatus::Pending,
+            reviewed_at: None,
+            reviewed_by: None,
+            review_note: None,
+        })
+    }
+}
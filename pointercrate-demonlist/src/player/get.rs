@@ -3,7 +3,7 @@ use crate::{
     demon::{published_by, verified_by},
     error::{DemonlistError, Result},
     nationality::{Nationality, Subdivision},
-    player::{DatabasePlayer, FullPlayer, Player},
+    player::{DatabasePlayer, FullPlayer, ListScore, Player},
     record::approved_records_by,
 };
 use sqlx::{Error, PgConnection};
@@ -14,6 +14,8 @@ impl Player {
         let published = published_by(&self.base, connection).await?;
         let verified = verified_by(&self.base, connection).await?;
         let created = created_by(self.base.id, connection).await?;
+        let scores = self.base.scores(connection).await?;
+        let aliases = self.base.aliases(false, connection).await?;
 
         Ok(FullPlayer {
             player: self,
@@ -21,6 +23,8 @@ impl Player {
             created,
             verified,
             published,
+            scores,
+            aliases,
         })
     }
 
@@ -68,7 +72,39 @@ impl Player {
 }
 
 impl DatabasePlayer {
+    /// Gets this player's non-zero scores on all lists
+    pub async fn scores(&self, connection: &mut PgConnection) -> Result<Vec<ListScore>> {
+        Ok(sqlx::query_as!(
+            ListScore,
+            "SELECT list, score FROM player_scores WHERE player = $1 AND score > 0.0 ORDER BY list",
+            self.id
+        )
+        .fetch_all(connection)
+        .await?)
+    }
+
+    /// Looks up a player by name, falling back to the players' non-hidden
+    /// [aliases](crate::player::alias) if no player currently goes by the given name
+    ///
+    /// If multiple players were previously known under the given name, the one that used it most
+    /// recently is returned.
     pub async fn by_name(name: &str, connection: &mut PgConnection) -> Result<DatabasePlayer> {
+        match Self::by_current_name(name, &mut *connection).await {
+            Err(DemonlistError::PlayerNotFoundName { player_name }) => sqlx::query_as!(
+                DatabasePlayer,
+                r#"SELECT players.id, players.name::text AS "name!", banned FROM player_names INNER JOIN players ON players.id = 
+                 player_names.player WHERE player_names.name = $1::text::citext AND NOT hidden ORDER BY used_until DESC LIMIT 1"#,
+                player_name
+            )
+            .fetch_optional(connection)
+            .await?
+            .ok_or(DemonlistError::PlayerNotFoundName { player_name }),
+            result => result,
+        }
+    }
+
+    /// Looks up a player by the name they currently go by, ignoring aliases
+    pub async fn by_current_name(name: &str, connection: &mut PgConnection) -> Result<DatabasePlayer> {
         let name = name.trim();
 
         let result = sqlx::query_as!(DatabasePlayer, "SELECT id, name, banned FROM players WHERE name = $1", name)
@@ -0,0 +1,460 @@
+//! Module for previewing and undoing player merges
+//!
+//! Merging two players cannot be undone by simply renaming them again, so list moderators get to
+//! see what a merge would do before performing it. To make sure the preview is exact, it is
+//! computed by actually performing the merge inside a savepoint that is rolled back afterwards.
+//!
+//! The preview is [`Taggable`], and merges are only performed if the moderator confirms them by
+//! providing the ETag of an up-to-date preview.
+//!
+//! Should a merge still turn out to be a mistake, it can be undone. For this, a snapshot of
+//! everything the merge is about to change is taken right before it is performed (see
+//! [`FullPlayer::undo_merge`]).
+
+use crate::{
+    creator::created_by,
+    demon::{published_by, verified_by, MinimalDemon},
+    error::{DemonlistError, Result},
+    player::{claim::PlayerClaim, DatabasePlayer, FullPlayer, Player},
+    record::approved_records_by,
+};
+use chrono::NaiveDateTime;
+use log::info;
+use pointercrate_core::etag::Taggable;
+use serde::Serialize;
+use sqlx::{Connection, PgConnection};
+use std::hash::{Hash, Hasher};
+
+/// Description of all changes merging one player into another would cause
+#[derive(Debug, Serialize)]
+pub struct MergePreview {
+    /// The player that would remain after the merge
+    pub player: DatabasePlayer,
+
+    /// The player that would be merged into [`MergePreview::player`] and deleted
+    pub merged: DatabasePlayer,
+
+    /// Whether both players have verified claims by different users, in which case the merge is
+    /// impossible. If set, no other changes are reported.
+    pub conflicting_claims: bool,
+
+    /// IDs of the records of [`MergePreview::merged`] that would be moved over
+    pub records_moved: Vec<i32>,
+
+    /// IDs of the records of either player that would be deleted as duplicates
+    pub records_deleted: Vec<i32>,
+
+    /// Demons whose verifier would change
+    pub verified: Vec<MinimalDemon>,
+
+    /// Demons whose publisher would change
+    pub published: Vec<MinimalDemon>,
+
+    /// Demons whose list of creators would change
+    pub created: Vec<MinimalDemon>,
+
+    /// Claims on either player that would be deleted
+    pub claims_deleted: Vec<PlayerClaim>,
+
+    /// The score of [`MergePreview::player`] before the merge
+    pub score_before: f64,
+
+    /// The score of [`MergePreview::merged`] before the merge
+    pub merged_score_before: f64,
+
+    /// The score of [`MergePreview::player`] after the merge
+    pub score_after: f64,
+}
+
+// `f64` does not implement hash, see the `Hash` implementation of `Player`
+impl Hash for MergePreview {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.player.hash(state);
+        self.merged.hash(state);
+        self.conflicting_claims.hash(state);
+        self.records_moved.hash(state);
+        self.records_deleted.hash(state);
+        self.verified.hash(state);
+        self.published.hash(state);
+        self.created.hash(state);
+        self.claims_deleted.hash(state);
+        ((self.score_before * 100f64) as u64).hash(state);
+        ((self.merged_score_before * 100f64) as u64).hash(state);
+        ((self.score_after * 100f64) as u64).hash(state);
+    }
+}
+
+impl Taggable for MergePreview {}
+
+/// A merge that was performed in the past
+#[derive(Debug, Serialize)]
+pub struct PlayerMerge {
+    pub id: i32,
+
+    /// The player that was merged into the other one, as it was right before the merge
+    pub merged: DatabasePlayer,
+    pub merged_at: NaiveDateTime,
+
+    /// The time at which this merge was undone, if it was undone
+    pub undone_at: Option<NaiveDateTime>,
+}
+
+impl DatabasePlayer {
+    /// Gets all merges of other players into this one, most recent first
+    pub async fn merges(&self, connection: &mut PgConnection) -> Result<Vec<PlayerMerge>> {
+        let rows = sqlx::query!(
+            r#"SELECT id, (merged->>'id')::INTEGER AS "merged_id!", merged->>'name' AS "merged_name!", (merged->>'banned')::BOOLEAN AS
+             "merged_banned!", merged_at, undone_at FROM player_merges WHERE player = $1 ORDER BY merged_at DESC, id DESC"#,
+            self.id
+        )
+        .fetch_all(connection)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| PlayerMerge {
+                id: row.id,
+                merged: DatabasePlayer {
+                    id: row.merged_id,
+                    name: row.merged_name,
+                    banned: row.merged_banned,
+                },
+                merged_at: row.merged_at,
+                undone_at: row.undone_at,
+            })
+            .collect())
+    }
+
+    /// Records everything merging the given player into this one is about to change, returning
+    /// the ID of the snapshot
+    pub(crate) async fn snapshot_merge(&self, with: &DatabasePlayer, connection: &mut PgConnection) -> Result<i32> {
+        let merge_id = sqlx::query!(
+            "INSERT INTO player_merges (player, merged, created, co_created, verified, published, claims, aliases, merges, bans, disputes) \
+             SELECT $1, to_jsonb(players.*), ARRAY(SELECT demon FROM creators AS c1 WHERE c1.creator = $2 AND NOT EXISTS (SELECT 1 FROM \
+             creators AS c2 WHERE c2.demon = c1.demon AND c2.creator = $1)), ARRAY(SELECT demon FROM creators AS c1 WHERE c1.creator = $2 \
+             AND EXISTS (SELECT 1 FROM creators AS c2 WHERE c2.demon = c1.demon AND c2.creator = $1)), ARRAY(SELECT id FROM demons WHERE \
+             verifier = $2), ARRAY(SELECT id FROM demons WHERE publisher = $2), COALESCE((SELECT jsonb_agg(to_jsonb(player_claims.*)) FROM \
+             player_claims WHERE player_id = $1 OR player_id = $2), '[]'), ARRAY(SELECT id FROM player_names WHERE player = $2), \
+             ARRAY(SELECT id FROM player_merges WHERE player = $2), COALESCE((SELECT jsonb_agg(to_jsonb(player_bans.*)) FROM player_bans \
+             WHERE player = $2), '[]'), COALESCE((SELECT jsonb_agg(to_jsonb(claim_disputes.*)) FROM claim_disputes WHERE player_id = $2), \
+             '[]') FROM players WHERE players.id = $2 RETURNING id",
+            self.id,
+            with.id
+        )
+        .fetch_one(&mut *connection)
+        .await?
+        .id;
+
+        sqlx::query!(
+            "INSERT INTO player_merge_records (merge, record, from_merged, snapshot, notes, raw_footage) SELECT $1, records.id, \
+             records.player = $3, to_jsonb(records.*), ARRAY(SELECT id FROM record_notes WHERE record_notes.record = records.id), \
+             ARRAY(SELECT url FROM record_raw_footage WHERE record_raw_footage.record = records.id ORDER BY id) FROM records WHERE \
+             records.player = $2 OR records.player = $3",
+            merge_id,
+            self.id,
+            with.id
+        )
+        .execute(connection)
+        .await?;
+
+        Ok(merge_id)
+    }
+}
+
+impl FullPlayer {
+    /// Determines what merging the given player into this one (via [`FullPlayer::merge`]) would do,
+    /// without actually changing anything
+    ///
+    /// Must be run within a transaction!
+    pub async fn preview_merge(&self, with: &DatabasePlayer, connection: &mut PgConnection) -> Result<MergePreview> {
+        let player_id = self.player.base.id;
+
+        if player_id == with.id {
+            return Err(DemonlistError::SelfMerge);
+        }
+
+        let mut preview = MergePreview {
+            player: self.player.base.clone(),
+            merged: with.clone(),
+            conflicting_claims: false,
+            records_moved: Vec::new(),
+            records_deleted: Vec::new(),
+            verified: Vec::new(),
+            published: Vec::new(),
+            created: Vec::new(),
+            claims_deleted: Vec::new(),
+            score_before: self.player.score,
+            merged_score_before: Player::by_id(with.id, &mut *connection).await?.score,
+            score_after: self.player.score,
+        };
+
+        let claim_on_self = PlayerClaim::verified_claim_on(player_id, &mut *connection).await?;
+        let claim_on_with = PlayerClaim::verified_claim_on(with.id, &mut *connection).await?;
+
+        if claim_on_self.is_some() && claim_on_with.is_some() {
+            preview.conflicting_claims = true;
+
+            return Ok(preview);
+        }
+
+        preview.verified = verified_by(with, &mut *connection).await?;
+        preview.published = published_by(with, &mut *connection).await?;
+        preview.created = created_by(with.id, &mut *connection).await?;
+
+        let records_of_self = record_ids_of(player_id, &mut *connection).await?;
+        let records_of_with = record_ids_of(with.id, &mut *connection).await?;
+        let claims_before = claims_on(&[player_id, with.id], &mut *connection).await?;
+
+        let mut savepoint = connection.begin().await?;
+
+        let mut simulated = Player::by_id(player_id, &mut savepoint).await?.upgrade(&mut savepoint).await?;
+
+        simulated.merge(with.clone(), &mut savepoint).await?;
+
+        let records_after = record_ids_of(player_id, &mut savepoint).await?;
+        let claims_after = claims_on(&[player_id], &mut savepoint).await?;
+
+        savepoint.rollback().await?;
+
+        preview.score_after = simulated.player.score;
+        preview.records_moved = records_of_with.iter().copied().filter(|id| records_after.contains(id)).collect();
+        preview.records_deleted = records_of_self
+            .into_iter()
+            .chain(records_of_with)
+            .filter(|id| !records_after.contains(id))
+            .collect();
+        preview.claims_deleted = claims_before
+            .into_iter()
+            .filter(|claim| !claims_after.iter().any(|after| after.user_id == claim.user_id))
+            .collect();
+
+        Ok(preview)
+    }
+
+    /// Undoes the merge with the given ID, splitting the player that was merged into this one
+    /// back out. Returns the restored player.
+    ///
+    /// The restored player gets back its original ID, and everything that was transferred to this
+    /// player during the merge is transferred back, unless it was changed in the meantime. Records
+    /// that were deleted as duplicates are restored as well, as are the bans and claim disputes of
+    /// the restored player.
+    ///
+    /// Must be run within a transaction!
+    pub async fn undo_merge(&mut self, merge_id: i32, connection: &mut PgConnection) -> Result<DatabasePlayer> {
+        let player_id = self.player.base.id;
+
+        let merge = sqlx::query!(
+            r#"SELECT merged->>'name' AS "merged_name!", (merged->>'id')::INTEGER AS "merged_id!" FROM player_merges WHERE id = $1 AND
+             player = $2 AND undone_at IS NULL"#,
+            merge_id,
+            player_id
+        )
+        .fetch_optional(&mut *connection)
+        .await?
+        .ok_or(DemonlistError::PlayerMergeNotFound { player_id, merge_id })?;
+
+        info!("Undoing merge {} of player {} into {}", merge_id, merge.merged_name, self);
+
+        // Someone else might have taken the name in the meantime
+        match DatabasePlayer::by_current_name(&merge.merged_name, &mut *connection).await {
+            Ok(existing) => return Err(DemonlistError::PlayerNameTaken { player_id: existing.id }),
+            Err(DemonlistError::PlayerNotFoundName { .. }) => (),
+            Err(err) => return Err(err),
+        }
+
+        sqlx::query!(
+            "INSERT INTO players SELECT (jsonb_populate_record(NULL::players, merged)).* FROM player_merges WHERE id = $1",
+            merge_id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        let restored = DatabasePlayer::by_id(merge.merged_id, &mut *connection).await?;
+
+        self.player.base.remove_alias(&restored.name, &mut *connection).await?;
+
+        sqlx::query!(
+            "UPDATE player_names SET player = $3 FROM player_merges WHERE player_merges.id = $1 AND player_names.player = $2 AND \
+             player_names.id = ANY(player_merges.aliases)",
+            merge_id,
+            player_id,
+            restored.id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        // Creator, verifier and publisher information
+        sqlx::query!(
+            "UPDATE creators SET creator = $3 FROM player_merges WHERE player_merges.id = $1 AND creators.creator = $2 AND creators.demon \
+             = ANY(player_merges.created)",
+            merge_id,
+            player_id,
+            restored.id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!(
+            "INSERT INTO creators (demon, creator) SELECT UNNEST(co_created), $2 FROM player_merges WHERE id = $1 ON CONFLICT DO NOTHING",
+            merge_id,
+            restored.id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE demons SET verifier = $3 FROM player_merges WHERE player_merges.id = $1 AND demons.verifier = $2 AND demons.id = \
+             ANY(player_merges.verified)",
+            merge_id,
+            player_id,
+            restored.id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE demons SET publisher = $3 FROM player_merges WHERE player_merges.id = $1 AND demons.publisher = $2 AND demons.id = \
+             ANY(player_merges.published)",
+            merge_id,
+            player_id,
+            restored.id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        // Claims are restored to exactly what they were before the merge
+        sqlx::query!(
+            "DELETE FROM player_claims USING player_merges WHERE player_merges.id = $1 AND player_claims.id IN (SELECT (claim->>'id')::INTEGER \
+             FROM jsonb_array_elements(player_merges.claims) AS claim)",
+            merge_id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!(
+            "INSERT INTO player_claims SELECT claim.* FROM player_merges, jsonb_populate_recordset(NULL::player_claims, player_merges.claims) \
+             AS claim WHERE player_merges.id = $1 AND EXISTS (SELECT 1 FROM members WHERE members.member_id = claim.member_id) ON \
+             CONFLICT DO NOTHING",
+            merge_id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        // Bans and claim disputes were deleted together with the merged player
+        sqlx::query!(
+            "INSERT INTO player_bans SELECT ban.* FROM player_merges, jsonb_populate_recordset(NULL::player_bans, player_merges.bans) AS \
+             ban WHERE player_merges.id = $1 ON CONFLICT DO NOTHING",
+            merge_id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!(
+            "INSERT INTO claim_disputes SELECT dispute.* FROM player_merges, jsonb_populate_recordset(NULL::claim_disputes, \
+             player_merges.disputes) AS dispute WHERE player_merges.id = $1 AND EXISTS (SELECT 1 FROM members WHERE members.member_id = \
+             dispute.disputed_by) ON CONFLICT DO NOTHING",
+            merge_id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        // Merges into the merged player that happened before it was merged into this one
+        sqlx::query!(
+            "UPDATE player_merges AS earlier SET player = $3 FROM player_merges WHERE player_merges.id = $1 AND earlier.player = $2 AND \
+             earlier.id = ANY(player_merges.merges)",
+            merge_id,
+            player_id,
+            restored.id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        // Records that still exist are moved back, records that were deleted are re-created
+        let moved = sqlx::query!(
+            "UPDATE records SET player = $3 FROM player_merge_records WHERE player_merge_records.merge = $1 AND \
+             player_merge_records.from_merged AND records.id = player_merge_records.record AND records.player = $2",
+            merge_id,
+            player_id,
+            restored.id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        let recreated = sqlx::query!(
+            "INSERT INTO records SELECT (jsonb_populate_record(NULL::records, snapshot)).* FROM player_merge_records WHERE merge = $1 AND \
+             NOT EXISTS (SELECT 1 FROM records WHERE records.id = player_merge_records.record) ON CONFLICT DO NOTHING",
+            merge_id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        info!(
+            "Moved {} records back to {} and re-created {} deleted records",
+            moved.rows_affected(),
+            restored,
+            recreated.rows_affected()
+        );
+
+        sqlx::query!(
+            "INSERT INTO record_raw_footage (record, url) SELECT record, UNNEST(raw_footage) FROM player_merge_records WHERE merge = $1 AND \
+             EXISTS (SELECT 1 FROM records WHERE records.id = player_merge_records.record) ON CONFLICT DO NOTHING",
+            merge_id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE record_notes SET record = player_merge_records.record FROM player_merge_records WHERE player_merge_records.merge = $1 \
+             AND record_notes.id = ANY(player_merge_records.notes) AND EXISTS (SELECT 1 FROM records WHERE records.id = \
+             player_merge_records.record)",
+            merge_id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE player_merges SET undone_at = (NOW() AT TIME ZONE 'utc') WHERE id = $1",
+            merge_id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        restored.update_score(&mut *connection).await?;
+
+        self.player.score = self.player.base.update_score(&mut *connection).await?;
+        self.records = approved_records_by(&self.player.base, &mut *connection).await?;
+        self.created = created_by(player_id, &mut *connection).await?;
+        self.verified = verified_by(&self.player.base, &mut *connection).await?;
+        self.published = published_by(&self.player.base, &mut *connection).await?;
+        self.aliases = self.player.base.aliases(false, connection).await?;
+
+        Ok(restored)
+    }
+}
+
+async fn record_ids_of(player_id: i32, connection: &mut PgConnection) -> Result<Vec<i32>> {
+    let rows = sqlx::query!("SELECT id FROM records WHERE player = $1 ORDER BY id", player_id)
+        .fetch_all(connection)
+        .await?;
+
+    Ok(rows.into_iter().map(|row| row.id).collect())
+}
+
+async fn claims_on(player_ids: &[i32], connection: &mut PgConnection) -> Result<Vec<PlayerClaim>> {
+    let rows = sqlx::query!(
+        "SELECT member_id, player_id, verified, lock_submissions FROM player_claims WHERE player_id = ANY($1) ORDER BY member_id",
+        player_ids
+    )
+    .fetch_all(connection)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| PlayerClaim {
+            user_id: row.member_id,
+            player_id: row.player_id,
+            verified: row.verified,
+            lock_submissions: row.lock_submissions,
+        })
+        .collect())
+}
@@ -2,7 +2,7 @@ pub use self::{
     paginate::{PlayerPagination, RankedPlayer, RankingPagination},
     patch::PatchPlayer,
 };
-use crate::{demon::MinimalDemon, nationality::Nationality, record::MinimalRecordD};
+use crate::{demon::MinimalDemon, nationality::Nationality, player::alias::PlayerAlias, record::MinimalRecordD};
 use derive_more::Display;
 use pointercrate_core::{error::CoreError, etag::Taggable};
 use serde::{Deserialize, Serialize};
@@ -12,8 +12,12 @@ use std::{
     hash::{Hash, Hasher},
 };
 
+pub mod alias;
+mod ban;
 pub mod claim;
 mod get;
+pub mod merge;
+pub mod nationality_history;
 mod paginate;
 mod patch;
 
@@ -34,6 +38,12 @@ pub struct FullPlayer {
     pub created: Vec<MinimalDemon>,
     pub verified: Vec<MinimalDemon>,
     pub published: Vec<MinimalDemon>,
+
+    /// This [`Player`]'s scores on each list they have a non-zero score on
+    pub scores: Vec<ListScore>,
+
+    /// The names this [`Player`] was previously known under, excluding hidden ones
+    pub aliases: Vec<PlayerAlias>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Display, Deserialize)]
@@ -62,6 +72,15 @@ pub struct Player {
     pub nationality: Option<Nationality>,
 }
 
+/// A [`Player`]'s score on one specific [`List`](crate::list::List)
+///
+/// Cached in the `player_scores` table and updated on the same events as [`Player::score`]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ListScore {
+    pub list: i32,
+    pub score: f64,
+}
+
 // `f64` does not implement hash. Most things in the pointercrate frontend only display score with an accuracy of two digits after the dot,
 // so hashing only this part should be fine for ETag purposes.
 impl Hash for Player {
@@ -72,6 +91,13 @@ impl Hash for Player {
     }
 }
 
+impl Hash for ListScore {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.list.hash(state);
+        ((self.score * 100f64) as u64).hash(state);
+    }
+}
+
 impl Taggable for FullPlayer {
     fn patch_part(&self) -> u64 {
         let mut hasher = DefaultHasher::new();
@@ -81,9 +107,19 @@ impl Taggable for FullPlayer {
 }
 
 impl DatabasePlayer {
-    /// Recomputes this player's score and updates it in the database.
+    /// Recomputes this player's score on every list and updates it in the database.
+    ///
+    /// Returns the player's new score on the default list.
     pub async fn update_score(&self, connection: &mut PgConnection) -> Result<f64, CoreError> {
         // No need to specially handle banned players - they have no approved records, so `score_of_player` will return 0
+        sqlx::query!(
+            "INSERT INTO player_scores (player, list, score) SELECT $1, lists.id, coalesce(score_of_player($1, lists.id), 0) FROM lists \
+             ON CONFLICT (player, list) DO UPDATE SET score = EXCLUDED.score",
+            self.id
+        )
+        .execute(&mut *connection)
+        .await?;
+
         let new_score = sqlx::query!(
             "UPDATE players SET score = coalesce(score_of_player($1), 0) WHERE id = $1 RETURNING score",
             self.id
@@ -93,6 +129,7 @@ impl DatabasePlayer {
 
         sqlx::query!("UPDATE nationalities SET score = coalesce(score_of_nation(nationalities.iso_country_code), 0) FROM players WHERE players.id = $1 AND players.nationality = nationalities.iso_country_code", self.id).execute(&mut *connection).await?;
         sqlx::query!("UPDATE subdivisions SET score = coalesce(score_of_subdivision(subdivisions.nation, subdivisions.iso_code), 0) FROM players WHERE players.id = $1 AND players.nationality = subdivisions.nation AND players.subdivision = subdivisions.iso_code", self.id).execute(&mut *connection).await?;
+        sqlx::query!("UPDATE continents SET score = coalesce(score_of_continent(continents.id), 0) FROM players INNER JOIN nationalities ON players.nationality = nationalities.iso_country_code WHERE players.id = $1 AND nationalities.continent = continents.id", self.id).execute(&mut *connection).await?;
 
         Ok(new_score.score)
     }
@@ -101,6 +138,9 @@ impl DatabasePlayer {
 pub async fn recompute_scores(connection: &mut PgConnection) -> Result<(), CoreError> {
     sqlx::query!("SELECT recompute_player_scores();").execute(&mut *connection).await?;
     sqlx::query!("SELECT recompute_nation_scores();").execute(&mut *connection).await?;
-    sqlx::query!("SELECT recompute_subdivision_scores();").execute(connection).await?;
+    sqlx::query!("SELECT recompute_subdivision_scores();")
+        .execute(&mut *connection)
+        .await?;
+    sqlx::query!("SELECT recompute_continent_scores();").execute(connection).await?;
     Ok(())
 }
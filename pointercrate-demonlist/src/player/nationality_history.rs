@@ -0,0 +1,67 @@
+//! Module containing code for dealing with the nationalities players held in the past
+//!
+//! Whenever a player's nationality or subdivision changes, a database trigger records the new
+//! nationality together with the time of the change. This allows nation rankings to optionally
+//! attribute records to the nationality a player held at the time the record was approved, instead
+//! of retroactively moving all of a player's records to their new nation.
+
+use crate::{
+    error::Result,
+    nationality::{Nationality, Subdivision},
+    player::DatabasePlayer,
+};
+use chrono::NaiveDateTime;
+use futures::StreamExt;
+use serde::Serialize;
+use sqlx::PgConnection;
+
+#[derive(Debug, Serialize, PartialEq, Eq, Hash)]
+pub struct NationalityChange {
+    /// The nationality the player held from [`NationalityChange::since`] on, or `None` if their
+    /// nationality was unset
+    pub nationality: Option<Nationality>,
+
+    /// The time at which the player started holding this nationality, or `None` if they held it
+    /// since before nationality changes were tracked
+    pub since: Option<NaiveDateTime>,
+}
+
+impl DatabasePlayer {
+    /// Gets the nationalities this player held, most recent (that is, the current one) first
+    pub async fn nationality_history(&self, connection: &mut PgConnection) -> Result<Vec<NationalityChange>> {
+        let mut stream = sqlx::query!(
+            r#"SELECT history.since, history.nationality AS iso_country_code, nationalities.nation::text AS nation, history.subdivision AS
+             subdivision_code, subdivisions.name::text AS subdivision_name FROM player_nationality_history AS history LEFT OUTER JOIN
+             nationalities ON nationalities.iso_country_code = history.nationality LEFT OUTER JOIN subdivisions ON subdivisions.nation =
+             history.nationality AND subdivisions.iso_code = history.subdivision WHERE history.player = $1 ORDER BY history.since DESC NULLS
+             LAST, history.id DESC"#,
+            self.id
+        )
+        .fetch(connection);
+
+        let mut history = Vec::new();
+
+        while let Some(row) = stream.next().await {
+            let row = row?;
+
+            let nationality = match (row.iso_country_code, row.nation) {
+                (Some(iso_country_code), Some(nation)) => Some(Nationality {
+                    iso_country_code,
+                    nation,
+                    subdivision: match (row.subdivision_code, row.subdivision_name) {
+                        (Some(iso_code), Some(name)) => Some(Subdivision { iso_code, name }),
+                        _ => None,
+                    },
+                }),
+                _ => None,
+            };
+
+            history.push(NationalityChange {
+                nationality,
+                since: row.since,
+            })
+        }
+
+        Ok(history)
+    }
+}
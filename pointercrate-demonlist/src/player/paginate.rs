@@ -1,5 +1,6 @@
 use crate::{
-    nationality::{Continent, Nationality},
+    list::DEFAULT_LIST,
+    nationality::Nationality,
     player::{DatabasePlayer, Player},
 };
 use futures::StreamExt;
@@ -43,7 +44,7 @@ impl PaginationQuery for PlayerPagination {
 }
 
 impl Paginatable<PlayerPagination> for Player {
-    first_and_last!("players");
+    first_and_last!(PlayerPagination, "players");
 
     async fn page(query: &PlayerPagination, connection: &mut PgConnection) -> Result<(Vec<Player>, PageContext), sqlx::Error> {
         let order = query.params.order();
@@ -103,14 +104,19 @@ pub struct RankingPagination {
     #[serde(default, deserialize_with = "nullable")]
     nation: Option<Option<String>>,
 
+    /// The id of the continent whose players to paginate
     #[serde(default, deserialize_with = "non_nullable")]
-    continent: Option<Continent>,
+    continent: Option<i32>,
 
     #[serde(default, deserialize_with = "non_nullable")]
     subdivision: Option<String>,
 
     #[serde(default, deserialize_with = "non_nullable")]
     name_contains: Option<String>,
+
+    /// The list whose ranking to paginate. Defaults to [`DEFAULT_LIST`]
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub list: Option<i32>,
 }
 
 impl PaginationQuery for RankingPagination {
@@ -136,8 +142,8 @@ pub struct RankedPlayer {
 }
 
 impl Paginatable<RankingPagination> for RankedPlayer {
-    async fn first_and_last(connection: &mut PgConnection) -> Result<Option<(i32, i32)>, sqlx::Error> {
-        Ok(sqlx::query!("SELECT COUNT(*) FROM players WHERE NOT banned AND score > 0.0")
+    async fn first_and_last(query: &RankingPagination, connection: &mut PgConnection) -> Result<Option<(i32, i32)>, sqlx::Error> {
+        Ok(sqlx::query!("SELECT COUNT(*) FROM list_ranked_players WHERE list = $1", query.list.unwrap_or(DEFAULT_LIST))
             .fetch_one(connection)
             .await?
             .count
@@ -155,9 +161,10 @@ impl Paginatable<RankingPagination> for RankedPlayer {
             .bind(query.name_contains.as_deref())
             .bind(&query.nation)
             .bind(query.nation == Some(None))
-            .bind(query.continent.as_ref().map(|c| c.to_sql()))
+            .bind(query.continent)
             .bind(&query.subdivision)
             .bind(query.params.limit + 1)
+            .bind(query.list.unwrap_or(DEFAULT_LIST))
             .fetch(connection);
 
         let mut players = Vec::new();
@@ -1,4 +1,6 @@
 use crate::{
+    creator::created_by,
+    demon::{published_by, verified_by},
     error::{DemonlistError, Result},
     nationality::Nationality,
     player::{claim::PlayerClaim, DatabasePlayer, FullPlayer, Player},
@@ -65,6 +67,9 @@ impl FullPlayer {
                 self.records.clear();
             } else if !banned && self.player.base.banned {
                 self.player.base.unban(connection).await?;
+
+                // Lifting a temporary ban restores records
+                self.records = approved_records_by(&self.player.base, connection).await?;
             }
         }
 
@@ -84,12 +89,14 @@ impl FullPlayer {
         if name == self.player.base.name.as_ref() {
             return Ok(());
         } else if name.to_lowercase() != self.player.base.name.to_lowercase() {
-            // If they are equal case insensitively, we're only doing a cosmetic rename, which won't
-            // even require a merge
-
-            // try to see if a player with new name already exists
-            match DatabasePlayer::by_name(name.as_ref(), &mut *connection).await {
-                Ok(existing) => self.merge(existing, &mut *connection).await?,
+            // If they are equal case insensitively, we're only doing a cosmetic rename, which cannot
+            // clash with another player
+
+            // try to see if a player with new name already exists. Merging with it needs to be done
+            // explicitly (see `FullPlayer::preview_merge`). Other players' aliases do not matter
+            // here, as those do not need to be unique
+            match DatabasePlayer::by_current_name(name.as_ref(), &mut *connection).await {
+                Ok(existing) => return Err(DemonlistError::PlayerNameTaken { player_id: existing.id }),
                 Err(DemonlistError::PlayerNotFoundName { .. }) => (),
                 Err(err) => return Err(err),
             }
@@ -100,30 +107,47 @@ impl FullPlayer {
             name.to_string(),
             self.player.base.id
         )
-        .execute(connection)
+        .execute(&mut *connection)
         .await?;
 
+        // Cosmetic renames do not change how the player can be looked up, so there's no need to
+        // keep the old name around
+        if name.to_lowercase() != self.player.base.name.to_lowercase() {
+            self.player.base.add_alias(&self.player.base.name, &mut *connection).await?;
+        }
+
+        // The player might be going back to a name they previously used
+        self.player.base.remove_alias(&name, &mut *connection).await?;
+
         self.player.base.name = name;
+        self.aliases = self.player.base.aliases(false, connection).await?;
 
         Ok(())
     }
 
     /// Merges the given player into `Self`, deleting `with`.
     ///
-    /// Note that this method **does not** rename `Self`
+    /// Note that this method **does not** rename `Self`. Use [`FullPlayer::preview_merge`] to
+    /// find out what exactly this will do beforehand, and [`FullPlayer::undo_merge`] to revert it.
     pub async fn merge(&mut self, with: DatabasePlayer, connection: &mut PgConnection) -> Result<()> {
         info!("Merging player {} with player {}", self, with);
 
         let claim_on_self = PlayerClaim::verified_claim_on(self.player.base.id, &mut *connection).await?;
         let claim_on_with = PlayerClaim::verified_claim_on(with.id, &mut *connection).await?;
 
+        if claim_on_self.is_some() && claim_on_with.is_some() {
+            return Err(DemonlistError::ConflictingClaims {
+                player1: self.player.base.name.clone(),
+                player2: with.name.clone(),
+            });
+        }
+
+        // Remember everything we are about to change, so that the merge can be undone later on
+        let merge_id = self.player.base.snapshot_merge(&with, &mut *connection).await?;
+
+        info!("Recorded snapshot {} of merge of {} into {}", merge_id, with, self);
+
         match (claim_on_self, claim_on_with) {
-            (Some(_), Some(_)) => {
-                return Err(DemonlistError::ConflictingClaims {
-                    player1: self.player.base.name.clone(),
-                    player2: with.name.clone(),
-                })
-            },
             (Some(_), None) => {
                 sqlx::query!("DELETE FROM player_claims WHERE player_id = $1", with.id)
                     .execute(&mut *connection)
@@ -141,7 +165,7 @@ impl FullPlayer {
                 .execute(&mut *connection)
                 .await?;
             },
-            (None, None) => {
+            _ => {
                 sqlx::query!(
                     "UPDATE player_claims SET player_id = $1 WHERE player_id = $2",
                     self.player.base.id,
@@ -219,11 +243,31 @@ impl FullPlayer {
 
         info!("Moved {} records from {} to {}", updated.rows_affected(), with, self);
 
+        // Keep the second player findable under its name(s)
+        self.player.base.take_aliases_of(&with, &mut *connection).await?;
+        self.player.base.add_alias(&with.name, &mut *connection).await?;
+
+        self.aliases = self.player.base.aliases(false, &mut *connection).await?;
+
+        // Keep the merges into the second player around, so that they can still be undone
+        sqlx::query!(
+            "UPDATE player_merges SET player = $1 WHERE player = $2",
+            self.player.base.id,
+            with.id
+        )
+        .execute(&mut *connection)
+        .await?;
+
         // Delete the second player
         sqlx::query!("DELETE FROM players WHERE id = $1", with.id)
-            .execute(connection)
+            .execute(&mut *connection)
             .await?;
 
+        self.player.score = self.player.base.update_score(&mut *connection).await?;
+        self.created = created_by(self.player.base.id, &mut *connection).await?;
+        self.verified = verified_by(&self.player.base, &mut *connection).await?;
+        self.published = published_by(&self.player.base, connection).await?;
+
         Ok(())
     }
 }
@@ -231,7 +275,7 @@ impl FullPlayer {
 impl Player {
     pub async fn set_nationality(&mut self, nationality: Option<Nationality>, connection: &mut PgConnection) -> Result<()> {
         let iso_country_code = nationality.as_ref().map(|n| &n.iso_country_code);
-        let subdivision_code = nationality.as_ref().map(|n| n.subdivision.as_ref().map(|s| &s.iso_code)).flatten();
+        let subdivision_code = nationality.as_ref().and_then(|n| n.subdivision.as_ref().map(|s| &s.iso_code));
 
         sqlx::query!(
             "UPDATE players SET nationality = $1, subdivision = $2 WHERE id = $3",
@@ -250,47 +294,3 @@ impl Player {
         Ok(())
     }
 }
-
-impl DatabasePlayer {
-    pub async fn unban(&mut self, connection: &mut PgConnection) -> Result<()> {
-        sqlx::query!("UPDATE players SET banned = false WHERE id=$1", self.id)
-            .execute(connection)
-            .await?;
-
-        self.banned = false;
-
-        Ok(())
-    }
-
-    pub async fn ban(&mut self, connection: &mut PgConnection) -> Result<()> {
-        // Delete all submissions for this player
-        let deleted = sqlx::query!(
-            "DELETE FROM records WHERE player = $1 AND (status_ = 'SUBMITTED' OR status_ = 'UNDER_CONSIDERATION')",
-            self.id
-        )
-        .execute(&mut *connection)
-        .await?;
-
-        info!("Deleted {} submissions while banning {}", deleted.rows_affected(), self);
-
-        // We can simply reject all accepted records here! All submitted records were deleted above, and we
-        // don't have to worry about conflicts with existing rejected record when setting status to
-        // 'rejected' since rejected records are globally unique!
-
-        // Now, reject all previously accepted records
-        let updated = sqlx::query!("UPDATE records SET status_ = 'REJECTED' WHERE player = $1", self.id)
-            .execute(&mut *connection)
-            .await?;
-
-        info!("Rejected {} records while banning {}", updated.rows_affected(), self);
-
-        // Actually ban the player
-        sqlx::query!("UPDATE players SET banned = true WHERE id = $1", self.id)
-            .execute(connection)
-            .await?;
-
-        self.banned = true;
-
-        Ok(())
-    }
-}
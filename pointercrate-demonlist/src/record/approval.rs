@@ -0,0 +1,241 @@
+//! Module containing the multi-reviewer approval workflow for records
+//!
+//! By default, a single list team member setting a record's status to 'approved' is enough to
+//! approve it. For records on the very top of a list, lists might want more oversight, and can
+//! configure an [`ApprovalPolicy`] under which such records need approvals from several distinct
+//! list team members. Until enough approvals have been gathered, attempts to approve the record
+//! merely record an approving vote, and report the record's [`PendingApproval`].
+//!
+//! Votes are stored in the audit log. Reviewers can also cast dissenting votes, which do not block
+//! approval, but are visible to everyone reviewing the record afterwards. If a reviewer changes
+//! their mind, only their most recent vote counts.
+
+use crate::{
+    error::Result,
+    list::List,
+    record::{FullRecord, RecordStatus},
+};
+use chrono::NaiveDateTime;
+use futures::StreamExt;
+use log::info;
+use pointercrate_core::audit::NamedId;
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use std::collections::HashMap;
+
+/// Policy determining how many independent approvals a record needs before it is approved,
+/// configurable per list
+///
+/// Lists without an explicitly configured policy use this one.
+#[derive(Debug, Clone)]
+pub struct ApprovalPolicy {
+    required_approvals: i64,
+    up_to_position: Option<i16>,
+    main_list_completions: bool,
+    list_policies: HashMap<String, ApprovalPolicy>,
+}
+
+impl Default for ApprovalPolicy {
+    /// The policy used on pointercrate.com: A single approval is enough for all records
+    fn default() -> Self {
+        ApprovalPolicy {
+            required_approvals: 1,
+            up_to_position: None,
+            main_list_completions: false,
+            list_policies: HashMap::new(),
+        }
+    }
+}
+
+impl ApprovalPolicy {
+    /// Constructs a policy requiring the given number of approvals from distinct list team members
+    ///
+    /// The policy does not apply to any records until restricted via
+    /// [`ApprovalPolicy::up_to_position`] or [`ApprovalPolicy::for_main_list_completions`].
+    pub fn requiring(required_approvals: i64) -> Self {
+        ApprovalPolicy {
+            required_approvals,
+            ..Default::default()
+        }
+    }
+
+    /// Makes this policy apply to records on demons at or above the given position
+    pub fn up_to_position(mut self, position: i16) -> Self {
+        self.up_to_position = Some(position);
+        self
+    }
+
+    /// Makes this policy apply to 100% records on demons on the main list
+    pub fn for_main_list_completions(mut self) -> Self {
+        self.main_list_completions = true;
+        self
+    }
+
+    /// Uses the given policy for records on the list with the given slug, instead of this one
+    ///
+    /// Any list specific policies configured on the given policy itself are ignored.
+    pub fn with_list_policy(mut self, slug: impl Into<String>, policy: ApprovalPolicy) -> Self {
+        self.list_policies.insert(slug.into(), policy);
+        self
+    }
+
+    pub fn required_approvals(&self, record: &FullRecord, list: &List) -> i64 {
+        match self.list_policies.get(&list.slug) {
+            Some(policy) => policy.required_approvals_ignoring_lists(record, list),
+            None => self.required_approvals_ignoring_lists(record, list),
+        }
+    }
+
+    fn required_approvals_ignoring_lists(&self, record: &FullRecord, list: &List) -> i64 {
+        let position = record.demon.position;
+
+        let applies = self.up_to_position.map(|up_to| position <= up_to).unwrap_or(false)
+            || (self.main_list_completions && position <= list.list_size && record.progress == 100);
+
+        if applies {
+            self.required_approvals
+        } else {
+            1
+        }
+    }
+}
+
+/// A vote some list team member cast on a record
+#[derive(Debug, Serialize)]
+pub struct ApprovalVote {
+    pub reviewer: NamedId,
+    pub approve: bool,
+    pub time: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CastVote {
+    pub approve: bool,
+}
+
+/// The state of a record that has not yet gathered the number of approvals its [`ApprovalPolicy`]
+/// requires
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct PendingApproval {
+    /// The number of distinct list team members that currently approve of the record
+    pub approvals: i64,
+
+    /// The number of approvals required for the record to be approved
+    pub required_approvals: i64,
+}
+
+/// Gets all votes cast on the given record, in chronological order
+pub async fn votes_on(record_id: i32, connection: &mut PgConnection) -> Result<Vec<ApprovalVote>> {
+    let mut stream = sqlx::query!(
+        r#"SELECT userid, members.name AS "name?", approve, time FROM record_approval_votes LEFT OUTER JOIN members ON members.member_id =
+         userid WHERE id = $1 ORDER BY time, audit_id"#,
+        record_id
+    )
+    .fetch(connection);
+
+    let mut votes = Vec::new();
+
+    while let Some(row) = stream.next().await {
+        let row = row?;
+
+        votes.push(ApprovalVote {
+            reviewer: NamedId {
+                id: row.userid,
+                name: row.name,
+            },
+            approve: row.approve,
+            time: row.time,
+        })
+    }
+
+    Ok(votes)
+}
+
+impl FullRecord {
+    /// Records a vote of the currently active user on this record
+    ///
+    /// If the active user held a review lock on this record, it is released, so that the record
+    /// can be handed to other reviewers from the review queue.
+    pub async fn cast_vote(&self, approve: bool, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!(
+            "INSERT INTO record_approval_votes (userid, id, approve) (SELECT id, $1, $2 FROM active_user LIMIT 1)",
+            self.id,
+            approve
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM review_locks WHERE record = $1 AND reviewer = (SELECT id FROM active_user LIMIT 1)",
+            self.id
+        )
+        .execute(connection)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The number of distinct list team members that currently approve of this record
+    pub async fn approvals(&self, connection: &mut PgConnection) -> Result<i64> {
+        Ok(sqlx::query!(r#"SELECT record_approvals($1) AS "approvals!""#, self.id)
+            .fetch_one(connection)
+            .await?
+            .approvals)
+    }
+
+    /// Approves this record on behalf of the currently active user, subject to the given policy
+    ///
+    /// If the policy requires more than a single approval for this record, an approving vote is
+    /// cast, and the record is only approved if this vote brings the number of approvals up to the
+    /// required amount. Returns `None` if the record was approved, and how many of the required
+    /// approvals it has gathered otherwise.
+    ///
+    /// Must be called inside a transaction
+    pub async fn approve(&mut self, policy: &ApprovalPolicy, connection: &mut PgConnection) -> Result<Option<PendingApproval>> {
+        if self.status == RecordStatus::Approved {
+            return Ok(None);
+        }
+
+        let list = self.demon.list(&mut *connection).await?;
+        let required = policy.required_approvals(self, &list);
+
+        if required > 1 {
+            self.cast_vote(true, &mut *connection).await?;
+
+            let approvals = self.approvals(&mut *connection).await?;
+
+            if approvals < required {
+                info!(
+                    "Record {} has {} out of {} required approvals, not approving yet",
+                    self, approvals, required
+                );
+
+                return Ok(Some(PendingApproval {
+                    approvals,
+                    required_approvals: required,
+                }));
+            }
+        }
+
+        self.set_status(RecordStatus::Approved, connection).await?;
+
+        Ok(None)
+    }
+
+    /// Determines whether this record is waiting for further approvals under the given policy,
+    /// i.e. whether it is not yet approved although some list team member approves of it
+    pub async fn pending_approval(&self, policy: &ApprovalPolicy, connection: &mut PgConnection) -> Result<Option<PendingApproval>> {
+        let approvals = self.approvals(&mut *connection).await?;
+
+        if self.status == RecordStatus::Approved || approvals == 0 {
+            return Ok(None);
+        }
+
+        let list = self.demon.list(&mut *connection).await?;
+
+        Ok(Some(PendingApproval {
+            approvals,
+            required_approvals: policy.required_approvals(self, &list),
+        }))
+    }
+}
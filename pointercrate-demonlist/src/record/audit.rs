@@ -1,5 +1,9 @@
-use crate::{error::Result, record::RecordStatus};
+use crate::{
+    error::Result,
+    record::{FullRecord, RecordStatus},
+};
 
+use chrono::NaiveDateTime;
 use futures::StreamExt;
 use pointercrate_core::audit::{AuditLogEntry, AuditLogEntryType, NamedId};
 use serde::Serialize;
@@ -8,10 +12,16 @@ use sqlx::PgConnection;
 #[derive(Serialize)]
 pub struct RecordModificationData {
     progress: Option<i16>,
+    completion_time: Option<i64>,
     video: Option<String>,
     status: Option<RecordStatus>,
     player: Option<NamedId>,
     demon: Option<NamedId>,
+    rejection_reason: Option<i32>,
+
+    /// Set if this entry is a vote cast on the record (see the [`approval`](crate::record::approval)
+    /// module) instead of an actual modification
+    approval_vote: Option<bool>,
 }
 
 /// Gets all audit log entries for the given record, in chronological order
@@ -49,12 +59,14 @@ pub async fn audit_log_for_record(record_id: i32, connection: &mut PgConnection)
                   members.name AS "username?",
                   userid,
                   progress,
+                  record_modifications.completion_time,
                   record_modifications.video,
                   status_::TEXT,
                   players.name::TEXT AS player_name,
                   player AS player_id,
                   demons.name::TEXT AS demon_name,
-                  demon AS demon_id
+                  demon AS demon_id,
+                  record_modifications.rejection_reason
                   FROM record_modifications 
                   LEFT OUTER JOIN members ON members.member_id = userid
                   LEFT OUTER JOIN players ON players.id = player
@@ -74,6 +86,7 @@ pub async fn audit_log_for_record(record_id: i32, connection: &mut PgConnection)
                 id: record_id,
                 r#type: AuditLogEntryType::Modification(RecordModificationData {
                     progress: modification.progress,
+                    completion_time: modification.completion_time,
                     status: modification.status_.as_deref().map(RecordStatus::from_sql),
                     player: match modification.player_id {
                         Some(id) => Some(NamedId {
@@ -90,6 +103,8 @@ pub async fn audit_log_for_record(record_id: i32, connection: &mut PgConnection)
                         _ => None,
                     },
                     video: modification.video,
+                    rejection_reason: modification.rejection_reason,
+                    approval_vote: None,
                 }),
                 user: NamedId {
                     name: modification.username,
@@ -99,6 +114,46 @@ pub async fn audit_log_for_record(record_id: i32, connection: &mut PgConnection)
         }
     }
 
+    {
+        let mut vote_stream = sqlx::query!(
+            r#"SELECT time, audit_id, userid, members.name AS "username?", approve FROM record_approval_votes
+                  LEFT OUTER JOIN members ON members.member_id = userid
+                  WHERE id = $1
+                  ORDER BY time"#,
+            record_id
+        )
+        .fetch(&mut *connection);
+
+        while let Some(vote) = vote_stream.next().await {
+            let vote = vote?;
+
+            entries.push(AuditLogEntry {
+                time: vote.time,
+                entry_id: vote.audit_id,
+                id: record_id,
+                r#type: AuditLogEntryType::Modification(RecordModificationData {
+                    progress: None,
+                    completion_time: None,
+                    video: None,
+                    status: None,
+                    player: None,
+                    demon: None,
+                    rejection_reason: None,
+                    approval_vote: Some(vote.approve),
+                }),
+                user: NamedId {
+                    name: vote.username,
+                    id: vote.userid,
+                },
+            })
+        }
+    }
+
+    // Votes and modifications are interleaved in time. A vote approving a record and the resulting
+    // status change happen in the same transaction (and thus at the same time), so break ties by
+    // audit id to keep the vote in front
+    entries.sort_by_key(|entry| (entry.time, entry.entry_id));
+
     let deletion_row = sqlx::query!(
         r#"SELECT time, audit_id, 
                   userid,
@@ -124,3 +179,53 @@ pub async fn audit_log_for_record(record_id: i32, connection: &mut PgConnection)
 
     Ok(entries)
 }
+
+/// A point in time at which a record transitioned into some status
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct StatusChange {
+    pub time: NaiveDateTime,
+    pub status: RecordStatus,
+}
+
+/// Gets the history of status changes of the given record, in chronological order
+///
+/// The first entry is the status the record was added with. Unlike the full audit log, this does
+/// not contain any information about who made the changes.
+pub async fn status_history(record: &FullRecord, connection: &mut PgConnection) -> Result<Vec<StatusChange>> {
+    let added_at = sqlx::query!("SELECT time FROM record_additions WHERE id = $1", record.id)
+        .fetch_optional(&mut *connection)
+        .await?
+        .map(|row| row.time);
+
+    let changes = sqlx::query!(
+        r#"SELECT time, status_::TEXT AS "status!" FROM record_modifications WHERE id = $1 AND status_ IS NOT NULL ORDER BY time, audit_id"#,
+        record.id
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    // Modifications store the status a record had _before_ the change. For records predating the
+    // audit log we do not know when they were added, and thus cannot report their initial status.
+    let mut history = Vec::new();
+    let mut since = added_at;
+
+    for change in changes {
+        if let Some(time) = since {
+            history.push(StatusChange {
+                time,
+                status: RecordStatus::from_sql(&change.status),
+            });
+        }
+
+        since = Some(change.time);
+    }
+
+    if let Some(time) = since {
+        history.push(StatusChange {
+            time,
+            status: record.status,
+        });
+    }
+
+    Ok(history)
+}
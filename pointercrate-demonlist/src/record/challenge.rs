@@ -0,0 +1,111 @@
+//! Module for challenges anonymous users have to solve before submitting records
+//!
+//! Record submissions by users that are not logged in are only protected by ratelimits, which are
+//! easily evaded by anyone with access to a sufficiently large number of IP addresses. Deployments
+//! can thus require anonymous submitters to solve a challenge (e.g. a captcha). How the challenge is
+//! presented to the user and how the user's response is verified is encapsulated in a
+//! [`ChallengeVerifier`]. The [`SubmissionChallenge`] configured for a deployment decides whether
+//! (and which) challenge is required.
+
+use crate::error::{DemonlistError, Result};
+use async_trait::async_trait;
+use log::info;
+use std::{net::IpAddr, sync::Arc};
+
+/// Describes how a challenge is presented on the record submission form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChallengeWidget {
+    /// A widget provided by a third party captcha service (such as hCaptcha or Cloudflare
+    /// Turnstile), which is loaded from the given script and rendered into an element with the
+    /// given class and `data-sitekey` attribute. Once solved, the widget writes its response into a
+    /// hidden input with the given name.
+    Hosted {
+        script: String,
+        class: String,
+        site_key: String,
+        response_field: String,
+    },
+
+    /// A plain text input the response has to be entered into
+    Input,
+}
+
+/// Something that can verify the responses to a specific kind of challenge
+#[async_trait]
+pub trait ChallengeVerifier: Send + Sync {
+    /// How the challenge is presented to the user
+    fn widget(&self) -> ChallengeWidget;
+
+    /// Checks whether the given response (submitted from the given IP address) solves the
+    /// challenge
+    async fn verify(&self, response: &str, ip: IpAddr) -> bool;
+}
+
+/// A challenge whose only valid response is a fixed token
+///
+/// Meant for tests and local development, where relying on a third party service is not an
+/// option.
+pub struct StaticChallenge {
+    token: String,
+}
+
+impl StaticChallenge {
+    pub fn new(token: impl Into<String>) -> Self {
+        StaticChallenge { token: token.into() }
+    }
+}
+
+#[async_trait]
+impl ChallengeVerifier for StaticChallenge {
+    fn widget(&self) -> ChallengeWidget {
+        ChallengeWidget::Input
+    }
+
+    async fn verify(&self, response: &str, _: IpAddr) -> bool {
+        response == self.token
+    }
+}
+
+/// The challenge anonymous record submissions have to pass
+///
+/// By default, no challenge is required.
+#[derive(Clone, Default)]
+pub struct SubmissionChallenge {
+    verifier: Option<Arc<dyn ChallengeVerifier>>,
+}
+
+impl SubmissionChallenge {
+    /// Requires anonymous submissions to pass the challenge of the given verifier
+    pub fn new(verifier: impl ChallengeVerifier + 'static) -> Self {
+        SubmissionChallenge {
+            verifier: Some(Arc::new(verifier)),
+        }
+    }
+
+    /// Does not require any challenge to be passed
+    pub fn disabled() -> Self {
+        SubmissionChallenge::default()
+    }
+
+    /// How the challenge is presented to the user, or `None` if no challenge is required
+    pub fn widget(&self) -> Option<ChallengeWidget> {
+        self.verifier.as_ref().map(|verifier| verifier.widget())
+    }
+
+    /// Checks that the given response solves the challenge, if one is required
+    pub async fn verify(&self, response: Option<&str>, ip: IpAddr) -> Result<()> {
+        let verifier = match self.verifier {
+            Some(ref verifier) => verifier,
+            None => return Ok(()),
+        };
+
+        match response {
+            Some(response) if verifier.verify(response, ip).await => Ok(()),
+            _ => {
+                info!("Submission from {} failed challenge", ip);
+
+                Err(DemonlistError::ChallengeFailed)
+            },
+        }
+    }
+}
@@ -14,7 +14,8 @@ impl FullRecord {
     }
 
     /// `FullRecord::delete` should be preferred. Only exists to delete invalid submissions
-    /// in the asychronous validation (which is why no score adjustment needs to take place here)
+    /// in the asychronous validation (which is why no score adjustment needs to take place here).
+    /// See also [`FullRecord::delete_invalid`].
     pub async fn delete_by_id(record_id: i32, connection: &mut PgConnection) -> Result<()> {
         // Associated notes get deleted due to the ON DELETE CASCADE on record_notes.record
 
@@ -24,4 +25,18 @@ impl FullRecord {
 
         Ok(())
     }
+
+    /// Deletes the given submission because its video turned out to be invalid, counting it
+    /// against its submitter's [statistics](crate::submitter::SubmitterStatistics)
+    pub async fn delete_invalid(record_id: i32, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!(
+            "UPDATE submitters SET deleted_invalid = deleted_invalid + 1 FROM records WHERE records.id = $1 AND submitters.submitter_id = \
+             records.submitter",
+            record_id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        FullRecord::delete_by_id(record_id, connection).await
+    }
 }
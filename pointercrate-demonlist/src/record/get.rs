@@ -3,8 +3,8 @@ use crate::{
     error::{DemonlistError, Result},
     nationality::Nationality,
     player::DatabasePlayer,
-    record::{FullRecord, MinimalRecordD, MinimalRecordP, RecordStatus},
-    submitter::Submitter,
+    record::{raw_footage::raw_footage_of, rejection::RejectionReason, FullRecord, MinimalRecordD, MinimalRecordP, RecordStatus},
+    submitter::{Submitter, SubmitterStatistics},
 };
 use futures::stream::StreamExt;
 use sqlx::{Error, PgConnection};
@@ -12,6 +12,7 @@ use sqlx::{Error, PgConnection};
 // Required until https://github.com/launchbadge/sqlx/pull/108 is merged
 struct FetchedRecord {
     progress: i16,
+    completion_time: Option<i64>,
     video: Option<String>,
     status: String,
     player_id: i32,
@@ -22,6 +23,15 @@ struct FetchedRecord {
     position: i16,
     submitter_id: i32,
     submitter_banned: bool,
+    submitter_flagged: bool,
+    submitted: i32,
+    approved: i32,
+    rejected: i32,
+    deleted_invalid: i32,
+    reputation: f64,
+    rejection_reason_id: Option<i32>,
+    rejection_reason_name: Option<String>,
+    rejection_reason_description: Option<String>,
 }
 
 impl FullRecord {
@@ -34,6 +44,7 @@ impl FullRecord {
             Ok(row) => Ok(FullRecord {
                 id,
                 progress: row.progress,
+                completion_time: row.completion_time,
                 video: row.video,
                 status: RecordStatus::from_sql(&row.status),
                 player: DatabasePlayer {
@@ -49,7 +60,24 @@ impl FullRecord {
                 submitter: Some(Submitter {
                     id: row.submitter_id,
                     banned: row.submitter_banned,
+                    flagged: row.submitter_flagged,
+                    statistics: SubmitterStatistics {
+                        submitted: row.submitted,
+                        approved: row.approved,
+                        rejected: row.rejected,
+                        deleted_invalid: row.deleted_invalid,
+                        reputation: row.reputation,
+                    },
                 }),
+                rejection_reason: match (row.rejection_reason_id, row.rejection_reason_name) {
+                    (Some(id), Some(name)) => Some(RejectionReason {
+                        id,
+                        name,
+                        description: row.rejection_reason_description,
+                    }),
+                    _ => None,
+                },
+                raw_footage: raw_footage_of(id, connection).await?,
             }),
 
             Err(Error::RowNotFound) => Err(DemonlistError::RecordNotFound { record_id: id }),
@@ -60,7 +88,7 @@ impl FullRecord {
 
 pub async fn approved_records_by(player: &DatabasePlayer, connection: &mut PgConnection) -> Result<Vec<MinimalRecordD>> {
     let mut stream = sqlx::query!(
-        r#"SELECT records.id, progress, CASE WHEN players.link_banned THEN NULL ELSE records.video::text END, demons.id AS demon_id, 
+        r#"SELECT records.id, progress, completion_time, CASE WHEN players.link_banned THEN NULL ELSE records.video::text END, demons.id AS demon_id, 
          demons.name, demons.position FROM records INNER JOIN demons ON records.demon = demons.id INNER JOIN players ON players.id 
          = $1 WHERE status_ = 'APPROVED' AND records.player = $1"#,
         player.id
@@ -75,6 +103,7 @@ pub async fn approved_records_by(player: &DatabasePlayer, connection: &mut PgCon
         records.push(MinimalRecordD {
             id: row.id,
             progress: row.progress,
+            completion_time: row.completion_time,
             video: row.video,
             status: RecordStatus::Approved,
             demon: MinimalDemon {
@@ -92,6 +121,7 @@ pub async fn approved_records_on(demon: &MinimalDemon, connection: &mut PgConnec
     struct Fetched {
         id: i32,
         progress: i16,
+        completion_time: Option<i64>,
         video: Option<String>,
         player_id: i32,
         name: String,
@@ -102,9 +132,9 @@ pub async fn approved_records_on(demon: &MinimalDemon, connection: &mut PgConnec
 
     let mut stream = sqlx::query_as!(
         Fetched,
-        r#"SELECT records.id, progress, CASE WHEN players.link_banned THEN NULL ELSE video::text END, players.id AS player_id, 
+        r#"SELECT records.id, progress, completion_time, CASE WHEN players.link_banned THEN NULL ELSE video::text END, players.id AS player_id, 
          players.name, players.banned, nation::TEXT, iso_country_code::TEXT FROM records INNER JOIN players ON records.player = players.id LEFT OUTER JOIN nationalities ON nationality = iso_country_code WHERE status_ = 'APPROVED' AND 
-         records.demon = $1 ORDER BY progress DESC, id ASC"#,
+         records.demon = $1 ORDER BY progress DESC, completion_time ASC NULLS LAST, id ASC"#,
         demon.id
     )
     .fetch(connection);
@@ -117,6 +147,7 @@ pub async fn approved_records_on(demon: &MinimalDemon, connection: &mut PgConnec
         records.push(MinimalRecordP {
             id: row.id,
             progress: row.progress,
+            completion_time: row.completion_time,
             video: row.video,
             status: RecordStatus::Approved,
             player: DatabasePlayer {
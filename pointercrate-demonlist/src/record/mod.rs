@@ -20,6 +20,10 @@
 //! * 'under consideration' means essentially the same as 'submitted', only that all further
 //!   submissions for this (demon, player) tuple are disallowed. Note that this does not mean that
 //!   the 'under consideration' status makes. A record under consideration IS NOT UNIQUE!
+//!
+//! For demons whose records are ranked by completion time (see
+//! [`RecordKind`](crate::demon::RecordKind)), all records have 100% progress, and "higher progress"
+//! above is to be read as "faster completion time".
 
 pub use self::{
     get::{approved_records_by, approved_records_on},
@@ -27,7 +31,10 @@ pub use self::{
     patch::PatchRecord,
     post::Submission,
 };
-use crate::{demon::MinimalDemon, error::Result, nationality::Nationality, player::DatabasePlayer, submitter::Submitter};
+use crate::{
+    demon::MinimalDemon, error::Result, nationality::Nationality, player::DatabasePlayer, record::rejection::RejectionReason,
+    submitter::Submitter,
+};
 use derive_more::Display;
 use pointercrate_core::etag::Taggable;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -38,16 +45,24 @@ use std::{
     hash::{Hash, Hasher},
 };
 
+pub mod approval;
 pub mod audit;
+pub mod challenge;
 mod delete;
 mod get;
 pub mod note;
 mod paginate;
 mod patch;
+pub mod policy;
 mod post;
+pub mod queue;
+pub mod raw_footage;
+pub mod rejection;
+pub mod visibility;
 
-#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy, Hash)]
 pub enum RecordStatus {
+    #[default]
     Submitted,
     Approved,
     Rejected,
@@ -76,12 +91,6 @@ impl RecordStatus {
     }
 }
 
-impl Default for RecordStatus {
-    fn default() -> Self {
-        RecordStatus::Submitted
-    }
-}
-
 impl Display for RecordStatus {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         match self {
@@ -127,11 +136,17 @@ impl<'de> Deserialize<'de> for RecordStatus {
 pub struct FullRecord {
     pub id: i32,
     pub progress: i16,
+    /// Completion time in milliseconds, only set for records on time based demons
+    pub completion_time: Option<i64>,
     pub video: Option<String>,
     pub status: RecordStatus,
     pub player: DatabasePlayer,
     pub demon: MinimalDemon,
     pub submitter: Option<Submitter>,
+    /// Only ever set on rejected records
+    pub rejection_reason: Option<RejectionReason>,
+    /// Only visible to list team members and the verified claimant of the record's holder
+    pub raw_footage: Vec<String>,
 }
 
 impl Taggable for FullRecord {
@@ -139,13 +154,15 @@ impl Taggable for FullRecord {
         let mut hasher = DefaultHasher::new();
         self.id.hash(&mut hasher);
         self.progress.hash(&mut hasher);
+        self.completion_time.hash(&mut hasher);
         self.video.hash(&mut hasher);
         self.status.hash(&mut hasher);
         self.player.id.hash(&mut hasher);
         self.demon.id.hash(&mut hasher);
+        self.rejection_reason.as_ref().map(|reason| reason.id).hash(&mut hasher);
+        self.raw_footage.hash(&mut hasher);
         // notes have sub-endpoint -> no hash
         // submitter cannot be patched -> no hash
-        // raw footage cannot be patched -> no hash
         hasher.finish()
     }
 }
@@ -155,6 +172,7 @@ impl Taggable for FullRecord {
 pub struct MinimalRecordPD {
     pub id: i32,
     pub progress: i16,
+    pub completion_time: Option<i64>,
     pub video: Option<String>,
     pub status: RecordStatus,
     pub demon: MinimalDemon,
@@ -166,6 +184,7 @@ pub struct MinimalRecordPD {
 pub struct MinimalRecordD {
     pub id: i32,
     pub progress: i16,
+    pub completion_time: Option<i64>,
     pub video: Option<String>,
     pub status: RecordStatus,
     pub demon: MinimalDemon,
@@ -176,6 +195,7 @@ pub struct MinimalRecordD {
 pub struct MinimalRecordP {
     pub id: i32,
     pub progress: i16,
+    pub completion_time: Option<i64>,
     pub video: Option<String>,
     pub status: RecordStatus,
     pub player: DatabasePlayer,
@@ -54,6 +54,9 @@ pub struct RecordPagination {
 
     #[serde(default, deserialize_with = "non_nullable")]
     pub submitter: Option<i32>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub list: Option<i32>,
 }
 
 impl PaginationQuery for RecordPagination {
@@ -70,7 +73,7 @@ impl PaginationQuery for RecordPagination {
 }
 
 impl Paginatable<RecordPagination> for MinimalRecordPD {
-    first_and_last!("records");
+    first_and_last!(RecordPagination, "records");
 
     async fn page(query: &RecordPagination, connection: &mut PgConnection) -> Result<(Vec<MinimalRecordPD>, PageContext), sqlx::Error> {
         let order = query.params.order();
@@ -94,6 +97,7 @@ impl Paginatable<RecordPagination> for MinimalRecordPD {
             .bind(query.player)
             .bind(query.submitter)
             .bind(query.params.limit + 1)
+            .bind(query.list)
             .fetch(&mut *connection);
 
         let mut records = Vec::new();
@@ -104,6 +108,7 @@ impl Paginatable<RecordPagination> for MinimalRecordPD {
             records.push(MinimalRecordPD {
                 id: row.try_get("id")?,
                 progress: row.try_get("progress")?,
+                completion_time: row.try_get("completion_time")?,
                 video: row.try_get("video")?,
                 status: RecordStatus::from_sql(&row.try_get::<String, _>("status")?),
                 player: DatabasePlayer {
@@ -1,8 +1,9 @@
 use crate::{
+    config,
     demon::MinimalDemon,
     error::{DemonlistError, Result},
     player::DatabasePlayer,
-    record::{FullRecord, RecordStatus},
+    record::{approval::ApprovalPolicy, rejection::RejectionReason, FullRecord, RecordStatus},
 };
 use log::{info, warn};
 use pointercrate_core::{
@@ -12,14 +13,21 @@ use pointercrate_core::{
 use serde::Deserialize;
 use sqlx::PgConnection;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 pub struct PatchRecord {
     #[serde(default, deserialize_with = "non_nullable")]
     progress: Option<i16>,
 
+    #[serde(default, deserialize_with = "non_nullable")]
+    completion_time: Option<i64>,
+
     #[serde(default, deserialize_with = "nullable")]
     video: Option<Option<String>>,
 
+    /// Replaces all raw footage links of the record. An empty list removes all raw footage
+    #[serde(default, deserialize_with = "non_nullable")]
+    raw_footage: Option<Vec<String>>,
+
     #[serde(default, deserialize_with = "non_nullable")]
     status: Option<RecordStatus>,
 
@@ -31,17 +39,44 @@ pub struct PatchRecord {
 
     #[serde(default, deserialize_with = "non_nullable")]
     demon_id: Option<i32>,
+
+    /// The ID of the [`RejectionReason`] for rejecting this record. Can only be set if the record
+    /// is (or, as part of this patch, becomes) rejected
+    #[serde(default, deserialize_with = "non_nullable")]
+    rejection_reason: Option<i32>,
+}
+
+impl PatchRecord {
+    /// A patch that only replaces the record's video
+    pub(crate) fn video(video: String) -> Self {
+        PatchRecord {
+            video: Some(Some(video)),
+            ..Default::default()
+        }
+    }
+
+    /// Whether this patch attempts to set the record's status to 'approved'
+    pub fn requests_approval(&self) -> bool {
+        self.status == Some(RecordStatus::Approved)
+    }
 }
 
 impl FullRecord {
     /// Must be called inside a transaction
-    pub async fn apply_patch(mut self, data: PatchRecord, connection: &mut PgConnection) -> Result<Self> {
+    ///
+    /// Setting the status to 'approved' is subject to the given [`ApprovalPolicy`], see
+    /// [`FullRecord::approve`].
+    pub async fn apply_patch(mut self, data: PatchRecord, policy: &ApprovalPolicy, connection: &mut PgConnection) -> Result<Self> {
         info!("Applying patch {:?} for record {}", data, self);
 
         if let Some(progress) = data.progress {
             self.set_progress(progress, connection).await?;
         }
 
+        if let Some(completion_time) = data.completion_time {
+            self.set_completion_time(completion_time, connection).await?;
+        }
+
         if let Some(video) = data.video {
             match video {
                 None => self.delete_video(connection).await?,
@@ -49,8 +84,32 @@ impl FullRecord {
             }
         }
 
-        if let Some(status) = data.status {
-            self.set_status(status, connection).await?
+        if let Some(raw_footage) = data.raw_footage {
+            self.set_raw_footage(raw_footage, connection).await?;
+        }
+
+        match data.status {
+            Some(RecordStatus::Approved) => {
+                self.approve(policy, connection).await?;
+            },
+            Some(status) => self.set_status(status, connection).await?,
+            None => (),
+        }
+
+        match data.rejection_reason {
+            Some(_) if self.status != RecordStatus::Rejected => return Err(DemonlistError::RejectionReasonWithoutRejection),
+            Some(reason_id) => {
+                let reason = RejectionReason::by_id(reason_id, connection).await?;
+
+                self.set_rejection_reason(Some(reason), connection).await?
+            },
+            None if data.status == Some(RecordStatus::Rejected)
+                && self.rejection_reason.is_none()
+                && config::rejection_reason_required() =>
+            {
+                return Err(DemonlistError::RejectionReasonRequired)
+            },
+            None => (),
         }
 
         if let Some(player) = data.player {
@@ -115,24 +174,30 @@ impl FullRecord {
             RecordStatus::Approved => {
                 // In this case we have to do multiple things:
                 // * delete all (player, demon)-records that are 'rejected' (at most one) TODO: maybe reconsider?
-                // * if a (player, demon)-record exists that is 'approved' and has higher progress than this one, we
-                //   override our progress and video with the values of that record
-                // * delete all (player, demon)-records that are 'submitted' with a progress (potentially as
-                //   determined above) less than or equal to that of this record
+                // * if a (player, demon)-record exists that is 'approved' and is better than this one (higher
+                //   progress or faster completion time), we override our progress, time and video with the values of
+                //   that record
+                // * delete all (player, demon)-records that are 'submitted' and are (potentially as determined above)
+                //   no better than this record
 
                 struct _Existing {
                     id: i32,
                     progress: i16,
+                    completion_time: Option<i64>,
                     video: Option<String>,
+                    video_host: Option<String>,
+                    video_id: Option<String>,
+                    video_start: Option<i32>,
                 }
 
                 let row = sqlx::query_as!(
                     _Existing,
-                    "SELECT id, progress, video::TEXT FROM records WHERE status_ = 'APPROVED' AND demon = $1 AND player = $2 AND progress \
-                     > $3",
+                    "SELECT id, progress, completion_time, video::TEXT, video_host, video_id, video_start FROM records WHERE status_ = 'APPROVED' AND demon = $1 AND player = $2 \
+                     AND NOT record_worse_or_equal(progress, completion_time, $3, $4)",
                     demon,
                     player,
-                    self.progress
+                    self.progress,
+                    self.completion_time
                 )
                 .fetch_optional(&mut *connection)
                 .await?;
@@ -141,33 +206,45 @@ impl FullRecord {
                     sqlx::query!("DELETE FROM records WHERE id = $1", row.id)
                         .execute(&mut *connection)
                         .await?;
-                    sqlx::query("UPDATE records SET video = $1::TEXT, progress = $2 WHERE id = $3")
-                        .bind(&row.video)
-                        .bind(row.progress)
-                        .bind(self.id)
-                        .execute(&mut *connection)
-                        .await?;
+                    sqlx::query(
+                        "UPDATE records SET video = $1::TEXT, progress = $2, completion_time = $3, video_host = $4, video_id = $5, \
+                         video_start = $6 WHERE id = $7",
+                    )
+                    .bind(&row.video)
+                    .bind(row.progress)
+                    .bind(row.completion_time)
+                    .bind(&row.video_host)
+                    .bind(&row.video_id)
+                    .bind(row.video_start)
+                    .bind(self.id)
+                    .execute(&mut *connection)
+                    .await?;
 
                     self.progress = row.progress;
+                    self.completion_time = row.completion_time;
                     self.video = row.video;
                 }
 
                 let notes_transferred = sqlx::query!(
                     "UPDATE record_notes SET record = $1 FROM records WHERE record_notes.record = records.id AND records.demon = $2 AND \
-                     records.player = $3 AND (records.status_ = 'REJECTED' OR records.progress <= $4)",
+                     records.player = $3 AND (records.status_ = 'REJECTED' OR record_worse_or_equal(records.progress, \
+                     records.completion_time, $4, $5))",
                     self.id,
                     demon,
                     player,
-                    self.progress
+                    self.progress,
+                    self.completion_time
                 )
                 .execute(&mut *connection)
                 .await?;
 
                 let records_deleted = sqlx::query!(
-                    "DELETE FROM records WHERE demon = $1 AND player = $2 AND (status_ = 'REJECTED' OR progress <= $3)",
+                    "DELETE FROM records WHERE demon = $1 AND player = $2 AND (status_ = 'REJECTED' OR record_worse_or_equal(progress, \
+                     completion_time, $3, $4))",
                     demon,
                     player,
-                    self.progress
+                    self.progress,
+                    self.completion_time
                 )
                 .execute(connection)
                 .await?;
@@ -189,9 +266,12 @@ impl FullRecord {
     }
 
     pub async fn delete_video(&mut self, connection: &mut PgConnection) -> Result<()> {
-        sqlx::query!("UPDATE records SET video = NULL WHERE id = $1", self.id)
-            .execute(connection)
-            .await?;
+        sqlx::query!(
+            "UPDATE records SET video = NULL, video_host = NULL, video_id = NULL, video_start = NULL WHERE id = $1",
+            self.id
+        )
+        .execute(connection)
+        .await?;
 
         self.video = None;
 
@@ -199,22 +279,35 @@ impl FullRecord {
     }
 
     pub async fn set_video(&mut self, video: String, connection: &mut PgConnection) -> Result<()> {
-        let video = crate::video::validate(&video)?;
+        let identity = crate::video::identify(&video)?;
+        let video = identity.url();
 
         if Some(&video) == self.video.as_ref() {
             return Ok(());
         }
 
-        if let Some(row) = sqlx::query!(r#"SELECT id FROM records WHERE video = $1"#, video.to_string())
-            .fetch_optional(&mut *connection)
-            .await?
+        if let Some(row) = sqlx::query!(
+            "SELECT id FROM records WHERE video_host = $1 AND video_id = $2 AND id <> $3",
+            identity.host,
+            identity.video_id,
+            self.id
+        )
+        .fetch_optional(&mut *connection)
+        .await?
         {
             return Err(DemonlistError::DuplicateVideo { id: row.id });
         }
 
-        sqlx::query!("UPDATE records SET video = $1::text WHERE id = $2", video, self.id)
-            .execute(connection)
-            .await?;
+        sqlx::query!(
+            "UPDATE records SET video = $1::text, video_host = $2, video_id = $3, video_start = $4 WHERE id = $5",
+            video,
+            identity.host,
+            identity.video_id,
+            identity.start,
+            self.id
+        )
+        .execute(connection)
+        .await?;
 
         self.video = Some(video);
 
@@ -222,11 +315,7 @@ impl FullRecord {
     }
 
     pub async fn set_demon(&mut self, demon: MinimalDemon, connection: &mut PgConnection) -> Result<()> {
-        let requirement = demon.requirement(connection).await?;
-
-        if self.progress < requirement {
-            return Err(DemonlistError::InvalidProgress { requirement });
-        }
+        demon.validate_record(self.progress, self.completion_time, connection).await?;
 
         self.ensure_invariants(self.player.id, self.demon.id, connection).await?;
 
@@ -302,26 +391,29 @@ impl FullRecord {
             (RecordStatus::Submitted, RecordStatus::Approved) | (RecordStatus::UnderConsideration, RecordStatus::Approved) => {
                 // Since a rejected record is globally unique, we know no other (player,
                 // demon)-record is 'rejected'. We also know that the submission has at least as
-                // much progress as an 'accepted' (player, demon)-record. We can therefore just
-                // delete all other records with less or equal progress to the current one
+                // much progress (or as fast a completion time) as an 'accepted' (player, demon)-record. We can
+                // therefore just delete all other records that are no better than the current one
 
                 sqlx::query!(
                     "UPDATE record_notes SET record = $1 FROM records WHERE record_notes.record = records.id AND records.player = $2 AND \
-                     records.demon = $3 AND progress <= $4",
+                     records.demon = $3 AND record_worse_or_equal(progress, completion_time, $4, $5)",
                     self.id,
                     self.player.id,
                     self.demon.id,
-                    self.progress
+                    self.progress,
+                    self.completion_time
                 )
                 .execute(&mut *connection)
                 .await?;
 
                 sqlx::query!(
-                    "DELETE FROM records WHERE id <> $1 AND records.player = $2 AND records.demon = $3 AND progress <= $4",
+                    "DELETE FROM records WHERE id <> $1 AND records.player = $2 AND records.demon = $3 AND record_worse_or_equal(progress, \
+                     completion_time, $4, $5)",
                     self.id,
                     self.player.id,
                     self.demon.id,
-                    self.progress
+                    self.progress,
+                    self.completion_time
                 )
                 .execute(&mut *connection)
                 .await?;
@@ -331,6 +423,18 @@ impl FullRecord {
             _ => (),
         }
 
+        // Rejection reasons only make sense on rejected records
+        if status != RecordStatus::Rejected && self.rejection_reason.is_some() {
+            self.set_rejection_reason(None, &mut *connection).await?;
+        }
+
+        // Once a record leaves the review queue, nobody needs to hold a review lock on it anymore
+        if status == RecordStatus::Approved || status == RecordStatus::Rejected {
+            sqlx::query!("DELETE FROM review_locks WHERE record = $1", self.id)
+                .execute(&mut *connection)
+                .await?;
+        }
+
         sqlx::query!(
             "UPDATE records SET status_ = cast($1::text as record_status) WHERE id = $2", /* FIXME(sqlx) ridiculous query
                                                                                            * format to trick sqlx into working
@@ -346,16 +450,26 @@ impl FullRecord {
         Ok(())
     }
 
+    pub async fn set_rejection_reason(&mut self, reason: Option<RejectionReason>, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!(
+            "UPDATE records SET rejection_reason = $1 WHERE id = $2",
+            reason.as_ref().map(|reason| reason.id),
+            self.id
+        )
+        .execute(connection)
+        .await?;
+
+        self.rejection_reason = reason;
+
+        Ok(())
+    }
+
     /// Updates this record's progress
     ///
     /// If this record is approved, all submissions with lower progress of the same (player,
     /// demon)-tuple are deleted and have their notes transferred to this record.
     pub async fn set_progress(&mut self, progress: i16, connection: &mut PgConnection) -> Result<()> {
-        let requirement = self.demon.requirement(&mut *connection).await?;
-
-        if progress > 100 || progress < requirement {
-            return Err(DemonlistError::InvalidProgress { requirement });
-        }
+        self.demon.validate_record(progress, self.completion_time, &mut *connection).await?;
 
         if self.status == RecordStatus::Approved {
             // Transfer over all notes from the records deleted below
@@ -395,4 +509,52 @@ impl FullRecord {
 
         Ok(())
     }
+
+    /// Updates this record's completion time
+    ///
+    /// If this record is approved, all submissions of the same (player, demon)-tuple are deleted
+    /// and the notes of those with a slower completion time are transferred to this record.
+    pub async fn set_completion_time(&mut self, completion_time: i64, connection: &mut PgConnection) -> Result<()> {
+        self.demon
+            .validate_record(self.progress, Some(completion_time), &mut *connection)
+            .await?;
+
+        if self.status == RecordStatus::Approved {
+            // Transfer over all notes from the records deleted below
+            sqlx::query!(
+                "UPDATE record_notes SET record = $1 FROM records WHERE record_notes.record = records.id AND player = $2 AND demon = $3 \
+                 AND completion_time > $4 AND status_='SUBMITTED'",
+                self.id,
+                self.player.id,
+                self.demon.id,
+                completion_time
+            )
+            .execute(&mut *connection)
+            .await?;
+
+            let deleted = sqlx::query!(
+                "DELETE FROM records WHERE player = $1 AND demon = $2 AND status_='SUBMITTED'",
+                self.player.id,
+                self.demon.id
+            )
+            .execute(&mut *connection)
+            .await?;
+
+            info!(
+                "Changing completion time of record {} from {:?} to {} caused the deletion of {} submissions",
+                self,
+                self.completion_time,
+                completion_time,
+                deleted.rows_affected()
+            );
+        }
+
+        sqlx::query!("UPDATE records SET completion_time = $1 WHERE id = $2", completion_time, self.id)
+            .execute(connection)
+            .await?;
+
+        self.completion_time = Some(completion_time);
+
+        Ok(())
+    }
 }
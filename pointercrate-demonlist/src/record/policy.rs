@@ -0,0 +1,171 @@
+//! Module containing the configurable rules record submissions have to satisfy
+//!
+//! Which submissions a list accepts is largely a matter of that list's policy. For example,
+//! pointercrate does not accept submissions for legacy demons, and only accepts 100% records for
+//! demons on the extended list, but other lists might want to accept progress records on their
+//! extended list, require raw footage for records on their top demons, or reject videos that
+//! predate a demon's placement. Such rules are configured at startup via a [`SubmissionPolicy`],
+//! which is managed by rocket.
+//!
+//! Note that some checks are not part of any policy, as they uphold the invariants outlined in the
+//! [`record`](crate::record) module documentation: Records must always meet their demon's
+//! requirement, banned players cannot have records, and duplicate submissions are rejected.
+
+use crate::{
+    error::{DemonlistError, Result},
+    list::List,
+    record::{post::NormalizedSubmission, RecordStatus},
+    video::upload_date::UploadDateResolver,
+};
+use sqlx::PgConnection;
+use std::collections::HashMap;
+
+/// A single rule that record submissions have to satisfy
+///
+/// Rules only apply to actual submissions (i.e. records with status
+/// [`RecordStatus::Submitted`]). List team members directly adding records in another status
+/// bypass them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmissionRule {
+    /// Demons past the extended list do not accept submissions
+    ///
+    /// Violations produce [`DemonlistError::SubmitLegacy`]
+    NoLegacySubmissions,
+
+    /// Demons on the extended list only accept submissions of 100% records
+    ///
+    /// Violations produce [`DemonlistError::Non100Extended`]
+    OnlyCompletionsOnExtended,
+
+    /// Submissions for demons at or above the given position need to provide raw footage
+    ///
+    /// Violations produce [`DemonlistError::RawRequired`]
+    RawFootageRequired {
+        /// The lowest position for which raw footage is required
+        up_to_position: i16,
+    },
+
+    /// Submissions whose video was uploaded before the demon was added to the list are rejected
+    ///
+    /// Upload dates are determined by the policy's [`UploadDateResolver`]. Submissions whose video's
+    /// upload date cannot be determined pass this rule, as do submissions for demons whose addition
+    /// predates the audit log.
+    ///
+    /// Violations produce [`DemonlistError::VideoPredatesPlacement`]
+    NoVideosPredatingPlacement,
+}
+
+impl SubmissionRule {
+    /// Checks the given submission against this rule
+    ///
+    /// Rules that cannot be decided based on the submission alone (that is,
+    /// [`SubmissionRule::NoVideosPredatingPlacement`]) always pass here, and are instead checked by
+    /// [`SubmissionPolicy::check`].
+    pub fn check(&self, submission: &NormalizedSubmission, list: &List) -> Result<()> {
+        let position = submission.demon.position;
+
+        match *self {
+            SubmissionRule::NoLegacySubmissions if position > list.extended_list_size => Err(DemonlistError::SubmitLegacy),
+            SubmissionRule::OnlyCompletionsOnExtended if position > list.list_size && submission.progress != 100 => {
+                Err(DemonlistError::Non100Extended)
+            },
+            SubmissionRule::RawFootageRequired { up_to_position } if position <= up_to_position && submission.raw_footage.is_empty() => {
+                Err(DemonlistError::RawRequired)
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The set of [`SubmissionRule`]s applied to record submissions, configurable per list
+///
+/// Lists without an explicitly configured set of rules use the default rules.
+#[derive(Debug, Clone)]
+pub struct SubmissionPolicy {
+    default_rules: Vec<SubmissionRule>,
+    list_rules: HashMap<String, Vec<SubmissionRule>>,
+    upload_dates: UploadDateResolver,
+}
+
+impl Default for SubmissionPolicy {
+    /// The policy used on pointercrate.com: No submissions for legacy demons, and only 100% records
+    /// on the extended list
+    fn default() -> Self {
+        SubmissionPolicy::empty()
+            .with_rule(SubmissionRule::NoLegacySubmissions)
+            .with_rule(SubmissionRule::OnlyCompletionsOnExtended)
+    }
+}
+
+impl SubmissionPolicy {
+    /// Constructs a policy that accepts all submissions (modulo the non-configurable checks
+    /// described in the module documentation)
+    pub fn empty() -> Self {
+        SubmissionPolicy {
+            default_rules: Vec::new(),
+            list_rules: HashMap::new(),
+            upload_dates: UploadDateResolver::default(),
+        }
+    }
+
+    /// Adds a rule to the default rules, which apply to all lists without explicitly configured
+    /// rules
+    pub fn with_rule(mut self, rule: SubmissionRule) -> Self {
+        self.default_rules.push(rule);
+        self
+    }
+
+    /// Sets the rules for the list with the given slug, replacing the default rules for that list
+    pub fn with_list_rules(mut self, slug: impl Into<String>, rules: Vec<SubmissionRule>) -> Self {
+        self.list_rules.insert(slug.into(), rules);
+        self
+    }
+
+    /// Sets the resolver used to determine the upload dates of videos for the purpose of
+    /// [`SubmissionRule::NoVideosPredatingPlacement`]
+    pub fn with_upload_dates(mut self, resolver: UploadDateResolver) -> Self {
+        self.upload_dates = resolver;
+        self
+    }
+
+    pub fn rules_for(&self, list: &List) -> &[SubmissionRule] {
+        self.list_rules.get(&list.slug).unwrap_or(&self.default_rules)
+    }
+
+    /// Checks the given submission against all rules configured for the given list, returning the
+    /// error of the first violated rule
+    pub async fn check(&self, submission: &NormalizedSubmission, list: &List, connection: &mut PgConnection) -> Result<()> {
+        if submission.status != RecordStatus::Submitted {
+            return Ok(());
+        }
+
+        for rule in self.rules_for(list) {
+            match rule {
+                SubmissionRule::NoVideosPredatingPlacement => self.check_video_age(submission, &mut *connection).await?,
+                _ => rule.check(submission, list)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn check_video_age(&self, submission: &NormalizedSubmission, connection: &mut PgConnection) -> Result<()> {
+        let Some(ref video) = submission.video else { return Ok(()) };
+
+        let placed_at = sqlx::query!(
+            "SELECT MIN(time) AS placed_at FROM demon_additions WHERE id = $1",
+            submission.demon.id
+        )
+        .fetch_one(connection)
+        .await?
+        .placed_at;
+
+        // Only query the video host if there is anything to compare against
+        let Some(placed_at) = placed_at else { return Ok(()) };
+
+        match self.upload_dates.resolve(video).await {
+            Some(uploaded_at) if uploaded_at < placed_at => Err(DemonlistError::VideoPredatesPlacement { uploaded_at, placed_at }),
+            _ => Ok(()),
+        }
+    }
+}
@@ -2,25 +2,31 @@ use crate::{
     demon::MinimalDemon,
     error::{DemonlistError, Result},
     player::{claim::PlayerClaim, DatabasePlayer},
-    record::{FullRecord, RecordStatus},
+    record::{approval::ApprovalPolicy, policy::SubmissionPolicy, FullRecord, RecordStatus},
     submitter::Submitter,
+    video::VideoIdentity,
 };
 use derive_more::Display;
 use log::debug;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 use sqlx::{PgConnection, Row};
-use url::Url;
 
 #[derive(Deserialize, Debug, Display)]
 #[display(fmt = "{}% on {} by {} [status: {}]", progress, demon, player, status)]
 pub struct Submission {
     progress: i16,
+    /// Completion time in milliseconds. Required for records on time based demons, and disallowed
+    /// for all others
+    #[serde(default)]
+    completion_time: Option<i64>,
     player: String,
     demon: i32,
     #[serde(default)]
     video: Option<String>,
-    #[serde(default)]
-    raw_footage: Option<String>,
+    /// Links to the raw footage of the record. For backwards compatibility, a single link is also
+    /// accepted.
+    #[serde(default, deserialize_with = "one_or_many")]
+    raw_footage: Vec<String>,
     #[serde(default)]
     status: RecordStatus,
 
@@ -31,21 +37,23 @@ pub struct Submission {
 
 #[derive(Debug)]
 pub struct NormalizedSubmission {
-    progress: i16,
-    player: DatabasePlayer,
-    demon: MinimalDemon,
-    status: RecordStatus,
-
-    video: Option<String>,
-    raw_footage: Option<String>,
-    note: Option<String>,
+    pub(super) progress: i16,
+    pub(super) completion_time: Option<i64>,
+    pub(super) player: DatabasePlayer,
+    pub(super) demon: MinimalDemon,
+    pub(super) status: RecordStatus,
+
+    pub(super) video: Option<VideoIdentity>,
+    pub(super) raw_footage: Vec<String>,
+    pub(super) note: Option<String>,
 }
 
 #[derive(Debug)]
 pub struct ValidatedSubmission {
     progress: i16,
-    video: Option<String>,
-    raw_footage: Option<String>,
+    completion_time: Option<i64>,
+    video: Option<VideoIdentity>,
+    raw_footage: Vec<String>,
     status: RecordStatus,
     player: DatabasePlayer,
     demon: MinimalDemon,
@@ -64,64 +72,76 @@ impl Submission {
     pub async fn normalize(self, connection: &mut PgConnection) -> Result<NormalizedSubmission> {
         // validate video
         let video = match self.video {
-            Some(ref video) => Some(crate::video::validate(video)?),
+            Some(ref video) => Some(crate::video::identify(video)?),
             None => None,
         };
 
+        let raw_footage = super::raw_footage::validate_all(self.raw_footage)?;
+
         // Resolve player and demon name against the database
         let player = DatabasePlayer::by_name_or_create(self.player.as_ref(), connection).await?;
         let demon = MinimalDemon::by_id(self.demon, connection).await?;
 
         Ok(NormalizedSubmission {
             progress: self.progress,
+            completion_time: self.completion_time,
             player,
             demon,
             status: self.status,
             video,
-            raw_footage: self.raw_footage,
+            raw_footage,
             note: self.note,
         })
     }
 }
 
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Option::<OneOrMany>::deserialize(deserializer)? {
+        None => Vec::new(),
+        Some(OneOrMany::One(url)) => vec![url],
+        Some(OneOrMany::Many(urls)) => urls,
+    })
+}
+
 impl NormalizedSubmission {
     pub async fn verified_player_claim(&self, connection: &mut PgConnection) -> Result<Option<PlayerClaim>> {
         PlayerClaim::verified_claim_on(self.player.id, connection).await
     }
 
-    pub async fn validate(self, connection: &mut PgConnection) -> Result<ValidatedSubmission> {
+    pub async fn validate(self, policy: &SubmissionPolicy, connection: &mut PgConnection) -> Result<ValidatedSubmission> {
         // Banned player can't have records on the list
         if self.player.banned {
             return Err(DemonlistError::PlayerBanned);
         }
 
-        // Cannot submit records for the legacy list (it is possible to directly add them for list mods)
-        if self.demon.position > crate::config::extended_list_size() && self.status == RecordStatus::Submitted {
-            return Err(DemonlistError::SubmitLegacy);
-        }
-
-        // Can only submit 100% records for the extended list (it is possible to directly add them for list
-        // mods)
-        if self.demon.position > crate::config::list_size() && self.progress != 100 && self.status == RecordStatus::Submitted {
-            return Err(DemonlistError::Non100Extended);
-        }
-
-        let requirement = self.demon.requirement(&mut *connection).await?;
-
-        // Check if the record meets the record requirement for this demon
-        if self.progress > 100 || self.progress < requirement {
-            return Err(DemonlistError::InvalidProgress { requirement });
-        }
+        // Check if the record meets the record requirement (or maximal time) for this demon
+        self.demon
+            .validate_record(self.progress, self.completion_time, &mut *connection)
+            .await?;
 
         debug!("Submission is valid, checking for duplicates!");
 
         // Search for existing records. If a video exists, we also check if a record with
-        // exactly that video exists.
+        // that video exists, regardless of the exact link used (or the timestamp it starts at).
 
         if let Some(ref video) = self.video {
-            if let Some(row) = sqlx::query!(r#"SELECT id, status_::text as "status_!: String" FROM records WHERE video = $1"#, video.to_string())
-                .fetch_optional(&mut *connection) // FIXME(sqlx)
-                .await?
+            if let Some(row) = sqlx::query!(
+                r#"SELECT id, status_::text as "status_!: String" FROM records WHERE video_host = $1 AND video_id = $2"#,
+                video.host,
+                video.video_id
+            )
+            .fetch_optional(&mut *connection)
+            .await?
             {
                 return Err(DemonlistError::SubmissionExists {
                     existing: row.id,
@@ -132,10 +152,11 @@ impl NormalizedSubmission {
 
         let existing = sqlx::query!(
             r#"SELECT id, status_::text as "status_!: String" FROM records WHERE demon = $1 AND player = $2 AND (status_ = 'REJECTED' OR status_ = 
-             'UNDER_CONSIDERATION' OR (status_ = 'APPROVED' AND progress >= $3)) LIMIT 1"#,
+             'UNDER_CONSIDERATION' OR (status_ = 'APPROVED' AND record_worse_or_equal($3, $4, progress, completion_time))) LIMIT 1"#,
             self.demon.id,
             self.player.id,
-            self.progress
+            self.progress,
+            self.completion_time
         )
             .fetch_optional(&mut *connection)
             .await?;
@@ -147,15 +168,15 @@ impl NormalizedSubmission {
             });
         }
 
-        match self.raw_footage {
-            Some(ref raw) => {
-                let _ = Url::parse(raw).map_err(|_| DemonlistError::MalformedRawUrl)?;
-            },
-            _ => (),
-        }
+        let list = self.demon.list(&mut *connection).await?;
+
+        // Check the rules configured for the demon's list (e.g. no submissions for legacy demons). This
+        // comes last, as some rules need to ask the video host about the submitted video.
+        policy.check(&self, &list, &mut *connection).await?;
 
         Ok(ValidatedSubmission {
             progress: self.progress,
+            completion_time: self.completion_time,
             video: self.video,
             raw_footage: self.raw_footage,
             status: self.status,
@@ -167,16 +188,27 @@ impl NormalizedSubmission {
 }
 
 impl ValidatedSubmission {
-    pub async fn create(self, submitter: Submitter, connection: &mut PgConnection) -> Result<FullRecord> {
+    /// Creates the record in the database
+    ///
+    /// If the record is to be added as 'approved', this is subject to the given [`ApprovalPolicy`].
+    /// If the policy requires further approvals, the record stays 'submitted' (see
+    /// [`FullRecord::approve`]).
+    pub async fn create(self, submitter: Submitter, policy: &ApprovalPolicy, connection: &mut PgConnection) -> Result<FullRecord> {
+        let video = self.video.as_ref().map(VideoIdentity::url);
+
         let id = sqlx::query(
-            "INSERT INTO records (progress, video, status_, player, submitter, demon) VALUES ($1, $2::TEXT, 'SUBMITTED', $3, $4,$5) \
-             RETURNING id",
+            "INSERT INTO records (progress, video, status_, player, submitter, demon, completion_time, video_host, video_id, video_start) \
+             VALUES ($1, $2::TEXT, 'SUBMITTED', $3, $4,$5, $6, $7, $8, $9) RETURNING id",
         )
         .bind(self.progress)
-        .bind(&self.video)
+        .bind(&video)
         .bind(self.player.id)
         .bind(submitter.id)
         .bind(self.demon.id)
+        .bind(self.completion_time)
+        .bind(self.video.as_ref().map(|video| &video.host))
+        .bind(self.video.as_ref().map(|video| &video.video_id))
+        .bind(self.video.as_ref().and_then(|video| video.start))
         .fetch_one(&mut *connection)
         .await?
         .get("id");
@@ -184,17 +216,24 @@ impl ValidatedSubmission {
         let mut record = FullRecord {
             id,
             progress: self.progress,
-            video: self.video,
+            completion_time: self.completion_time,
+            video,
             status: RecordStatus::Submitted,
             player: self.player,
             demon: self.demon,
             submitter: Some(submitter),
+            rejection_reason: None,
+            raw_footage: Vec::new(),
         };
 
         // Dealing with different status and upholding their invariant is complicated, we should not
         // duplicate that code!
-        if self.status != RecordStatus::Submitted {
-            record.set_status(self.status, &mut *connection).await?;
+        match self.status {
+            RecordStatus::Submitted => (),
+            RecordStatus::Approved => {
+                record.approve(policy, &mut *connection).await?;
+            },
+            status => record.set_status(status, &mut *connection).await?,
         }
 
         if let Some(note) = self.note {
@@ -205,17 +244,9 @@ impl ValidatedSubmission {
             }
         }
 
-        if let Some(raw_footage) = self.raw_footage {
-            sqlx::query!(
-                "INSERT INTO record_notes (record, content) VALUES ($1, $2)",
-                record.id,
-                format!("Raw footage: {}", raw_footage)
-            )
-            .execute(&mut *connection)
-            .await?;
-        }
+        record.set_raw_footage(self.raw_footage, &mut *connection).await?;
 
-        if self.status != RecordStatus::Submitted {
+        if record.status != RecordStatus::Submitted {
             record.player.update_score(connection).await?;
         }
 
@@ -229,7 +260,7 @@ mod tests {
         demon::MinimalDemon,
         error::DemonlistError,
         player::DatabasePlayer,
-        record::{post::NormalizedSubmission, RecordStatus},
+        record::{policy::SubmissionPolicy, post::NormalizedSubmission, RecordStatus},
     };
     use pointercrate_core::pool::PointercratePool;
     use sqlx::{Postgres, Transaction};
@@ -246,6 +277,7 @@ mod tests {
 
         let result = NormalizedSubmission {
             progress: 100,
+            completion_time: None,
             player: DatabasePlayer {
                 id: 1,
                 name: "stardust1971".to_string(),
@@ -258,10 +290,10 @@ mod tests {
             },
             status: RecordStatus::Submitted,
             video: None,
-            raw_footage: None,
+            raw_footage: Vec::new(),
             note: None,
         }
-        .validate(&mut conn)
+        .validate(&SubmissionPolicy::default(), &mut conn)
         .await;
 
         assert!(result.is_err());
@@ -0,0 +1,243 @@
+//! Module containing the review queue for record submissions
+//!
+//! The review queue consists of all records that are still 'submitted' or 'under consideration'.
+//! List team members can claim the next record from the queue, which locks it for a limited
+//! amount of time (see [`review_lock_duration`](crate::config::review_lock_duration)), during
+//! which it will not be handed out to anyone else. Additionally, list moderators can assign
+//! records to specific reviewers, in which case the lock does not expire.
+
+use crate::{
+    config,
+    error::{DemonlistError, Result},
+};
+use chrono::NaiveDateTime;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgConnection, Row};
+
+/// The order in which records are handed out from the review queue
+///
+/// Records explicitly assigned to a reviewer are always handed to that reviewer first.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueOrder {
+    /// Oldest submissions first
+    #[default]
+    Oldest,
+
+    /// Submissions for higher positioned demons first
+    ///
+    /// Positions are only comparable within a list, so submissions are grouped by list first
+    Position,
+
+    /// Submissions from submitters with a higher
+    /// [reputation](crate::submitter::SubmitterStatistics::reputation) first
+    Reputation,
+}
+
+impl QueueOrder {
+    fn to_sql(self) -> &'static str {
+        match self {
+            QueueOrder::Oldest => "records.id",
+            QueueOrder::Position => "demons.list, demons.position, records.id",
+            QueueOrder::Reputation => "(SELECT reputation FROM submitters WHERE submitter_id = records.submitter) DESC, records.id",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ClaimQuery {
+    #[serde(default)]
+    pub order: QueueOrder,
+}
+
+/// A lock some list team member holds on a record from the review queue
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct ReviewLock {
+    pub record: i32,
+
+    /// The member ID of the reviewer holding this lock
+    pub reviewer: i32,
+
+    /// The name of the reviewer holding this lock
+    pub reviewer_name: String,
+
+    pub locked_at: NaiveDateTime,
+
+    /// The time at which this lock expires. `None` if the record was explicitly assigned to the
+    /// reviewer, in which case the lock does not expire.
+    pub locked_until: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignReviewer {
+    pub reviewer: i32,
+}
+
+/// The number of records some reviewer currently holds locks on
+#[derive(Debug, Serialize)]
+pub struct ReviewerLoad {
+    pub reviewer: i32,
+    pub reviewer_name: String,
+    pub locked: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueStatistics {
+    pub submitted: i64,
+    pub under_consideration: i64,
+
+    /// The number of records in the queue that are currently locked by some reviewer
+    pub locked: i64,
+
+    /// The time the oldest record still in the queue was submitted at
+    pub oldest_submission: Option<NaiveDateTime>,
+
+    pub reviewers: Vec<ReviewerLoad>,
+}
+
+impl ReviewLock {
+    /// Gets the active (i.e. unexpired) lock on the given record, if any
+    pub async fn active_on(record_id: i32, connection: &mut PgConnection) -> Result<Option<ReviewLock>> {
+        Ok(sqlx::query_as!(
+            ReviewLock,
+            r#"SELECT record, reviewer, members.name AS "reviewer_name!", locked_at, locked_until FROM review_locks INNER JOIN members
+             ON members.member_id = reviewer WHERE record = $1 AND (locked_until IS NULL OR locked_until > (NOW() AT TIME ZONE 'utc'))"#,
+            record_id
+        )
+        .fetch_optional(connection)
+        .await?)
+    }
+
+    /// Errors with [`DemonlistError::RecordLocked`] if the given record is locked by someone other
+    /// than the given reviewer
+    pub async fn ensure_unlocked_for(record_id: i32, reviewer: i32, connection: &mut PgConnection) -> Result<()> {
+        match ReviewLock::active_on(record_id, connection).await? {
+            Some(lock) if lock.reviewer != reviewer => Err(DemonlistError::RecordLocked {
+                reviewer: lock.reviewer_name,
+                locked_until: lock.locked_until,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Claims the next record from the review queue for the given reviewer
+    ///
+    /// If the reviewer already holds a lock on a record in the queue, that record is returned (and
+    /// its lock renewed) instead. Records the reviewer already voted on (see the
+    /// [`approval`](crate::record::approval) module) are not handed out again. Must be run inside a
+    /// transaction!
+    pub async fn claim_next(reviewer: i32, order: QueueOrder, connection: &mut PgConnection) -> Result<ReviewLock> {
+        let query = format!(
+            "SELECT records.id FROM records INNER JOIN demons ON demons.id = records.demon LEFT OUTER JOIN review_locks ON \
+             review_locks.record = records.id WHERE records.status_ IN ('SUBMITTED', 'UNDER_CONSIDERATION') AND (review_locks.record IS \
+             NULL OR review_locks.reviewer = $1 OR review_locks.locked_until <= (NOW() AT TIME ZONE 'utc')) AND NOT EXISTS (SELECT 1 FROM \
+             record_approval_votes WHERE record_approval_votes.id = records.id AND record_approval_votes.userid = $1) ORDER BY \
+             (review_locks.reviewer IS NOT DISTINCT FROM $1) DESC, {} LIMIT 1 FOR UPDATE OF records SKIP LOCKED",
+            order.to_sql()
+        );
+
+        // SKIP LOCKED makes sure two reviewers claiming concurrently do not get handed the same record
+        let record_id: i32 = sqlx::query(&query)
+            .bind(reviewer)
+            .fetch_optional(&mut *connection)
+            .await?
+            .ok_or(DemonlistError::ReviewQueueEmpty)?
+            .get("id");
+
+        // If the record was explicitly assigned to this reviewer, keep it that way
+        sqlx::query!(
+            "INSERT INTO review_locks (record, reviewer, locked_until) VALUES ($1, $2, (NOW() AT TIME ZONE 'utc') + make_interval(mins => \
+             $3)) ON CONFLICT (record) DO UPDATE SET reviewer = EXCLUDED.reviewer, locked_at = EXCLUDED.locked_at, locked_until = CASE \
+             WHEN review_locks.reviewer = EXCLUDED.reviewer AND review_locks.locked_until IS NULL THEN NULL ELSE EXCLUDED.locked_until END",
+            record_id,
+            reviewer,
+            config::review_lock_duration()
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        ReviewLock::active_on(record_id, connection)
+            .await?
+            .ok_or(DemonlistError::ReviewLockNotFound { record_id })
+    }
+
+    /// Assigns the given record to the given reviewer, replacing any existing lock
+    ///
+    /// Assignments do not expire. Only records in the review queue can be assigned.
+    pub async fn assign(record_id: i32, reviewer: i32, connection: &mut PgConnection) -> Result<ReviewLock> {
+        let in_queue = sqlx::query!(
+            r#"SELECT status_ IN ('SUBMITTED', 'UNDER_CONSIDERATION') AS "in_queue!" FROM records WHERE id = $1"#,
+            record_id
+        )
+        .fetch_optional(&mut *connection)
+        .await?
+        .ok_or(DemonlistError::RecordNotFound { record_id })?
+        .in_queue;
+
+        if !in_queue {
+            return Err(DemonlistError::RecordNotInQueue);
+        }
+
+        sqlx::query!(
+            "INSERT INTO review_locks (record, reviewer, locked_until) VALUES ($1, $2, NULL) ON CONFLICT (record) DO UPDATE SET reviewer \
+             = EXCLUDED.reviewer, locked_at = EXCLUDED.locked_at, locked_until = NULL",
+            record_id,
+            reviewer
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        ReviewLock::active_on(record_id, connection)
+            .await?
+            .ok_or(DemonlistError::ReviewLockNotFound { record_id })
+    }
+
+    pub async fn release(self, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!("DELETE FROM review_locks WHERE record = $1", self.record)
+            .execute(connection)
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl QueueStatistics {
+    pub async fn compute(connection: &mut PgConnection) -> Result<QueueStatistics> {
+        let counts = sqlx::query!(
+            r#"SELECT COUNT(*) FILTER (WHERE status_ = 'SUBMITTED') AS "submitted!", COUNT(*) FILTER (WHERE status_ =
+             'UNDER_CONSIDERATION') AS "under_consideration!", COUNT(review_locks.record) FILTER (WHERE review_locks.locked_until IS NULL
+             OR review_locks.locked_until > (NOW() AT TIME ZONE 'utc')) AS "locked!", MIN(record_additions.time) AS oldest_submission FROM
+             records LEFT OUTER JOIN review_locks ON review_locks.record = records.id LEFT OUTER JOIN record_additions ON
+             record_additions.id = records.id WHERE status_ IN ('SUBMITTED', 'UNDER_CONSIDERATION')"#
+        )
+        .fetch_one(&mut *connection)
+        .await?;
+
+        let mut stream = sqlx::query!(
+            r#"SELECT reviewer, members.name, COUNT(*) AS "locked!" FROM review_locks INNER JOIN members ON members.member_id = reviewer
+             WHERE locked_until IS NULL OR locked_until > (NOW() AT TIME ZONE 'utc') GROUP BY reviewer, members.name ORDER BY reviewer"#
+        )
+        .fetch(connection);
+
+        let mut reviewers = Vec::new();
+
+        while let Some(row) = stream.next().await {
+            let row = row?;
+
+            reviewers.push(ReviewerLoad {
+                reviewer: row.reviewer,
+                reviewer_name: row.name,
+                locked: row.locked,
+            })
+        }
+
+        Ok(QueueStatistics {
+            submitted: counts.submitted,
+            under_consideration: counts.under_consideration,
+            locked: counts.locked,
+            oldest_submission: counts.oldest_submission,
+            reviewers,
+        })
+    }
+}
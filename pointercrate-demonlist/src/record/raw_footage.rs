@@ -0,0 +1,57 @@
+//! Module containing code for dealing with the raw footage of records
+//!
+//! Raw footage is only visible to list team members and to the verified claimant of the record's
+//! holder. It is the caller's responsibility to strip it from records shown to anyone else.
+
+use crate::{error::Result, record::FullRecord};
+use log::info;
+use sqlx::PgConnection;
+
+/// Validates (and normalizes) each of the given raw footage links, removing duplicates
+pub(crate) fn validate_all(urls: Vec<String>) -> Result<Vec<String>> {
+    let mut validated: Vec<String> = Vec::with_capacity(urls.len());
+
+    for url in urls {
+        let url = crate::video::validate_raw_footage(&url)?;
+
+        if !validated.contains(&url) {
+            validated.push(url)
+        }
+    }
+
+    Ok(validated)
+}
+
+/// Gets the links to all raw footage of the given record, in the order they were added
+pub async fn raw_footage_of(record_id: i32, connection: &mut PgConnection) -> Result<Vec<String>> {
+    let rows = sqlx::query!("SELECT url FROM record_raw_footage WHERE record = $1 ORDER BY id", record_id)
+        .fetch_all(connection)
+        .await?;
+
+    Ok(rows.into_iter().map(|row| row.url).collect())
+}
+
+impl FullRecord {
+    /// Replaces all raw footage of this record with the given links
+    ///
+    /// Must be run within a transaction!
+    pub async fn set_raw_footage(&mut self, urls: Vec<String>, connection: &mut PgConnection) -> Result<()> {
+        let urls = validate_all(urls)?;
+
+        info!("Setting raw footage of record {} to {:?}", self, urls);
+
+        sqlx::query!("DELETE FROM record_raw_footage WHERE record = $1", self.id)
+            .execute(&mut *connection)
+            .await?;
+
+        for url in &urls {
+            sqlx::query!("INSERT INTO record_raw_footage (record, url) VALUES ($1, $2)", self.id, url)
+                .execute(&mut *connection)
+                .await?;
+        }
+
+        self.raw_footage = urls;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+//! Module containing the taxonomy of reasons records can be rejected for
+//!
+//! Rejected records can optionally (or mandatorily, see
+//! [`rejection_reason_required`](crate::config::rejection_reason_required)) carry one of these
+//! reasons, which is visible to list team members and to the verified claimant of the record's
+//! holder.
+
+use crate::error::{DemonlistError, Result};
+use futures::StreamExt;
+use log::info;
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
+pub struct RejectionReason {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PostRejectionReason {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+/// The number of rejected records citing some rejection reason
+#[derive(Debug, Serialize)]
+pub struct RejectionReasonStatistics {
+    pub reason: RejectionReason,
+    pub rejected: i64,
+}
+
+impl RejectionReason {
+    pub async fn all(connection: &mut PgConnection) -> Result<Vec<RejectionReason>> {
+        Ok(sqlx::query_as!(
+            RejectionReason,
+            r#"SELECT id, name::text AS "name!", description FROM rejection_reasons ORDER BY id"#
+        )
+        .fetch_all(connection)
+        .await?)
+    }
+
+    pub async fn by_id(id: i32, connection: &mut PgConnection) -> Result<RejectionReason> {
+        sqlx::query_as!(
+            RejectionReason,
+            r#"SELECT id, name::text AS "name!", description FROM rejection_reasons WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(connection)
+        .await?
+        .ok_or(DemonlistError::RejectionReasonNotFound { reason_id: id })
+    }
+
+    /// Must be run within a transaction!
+    pub async fn create_from(data: PostRejectionReason, connection: &mut PgConnection) -> Result<RejectionReason> {
+        info!("Creating new rejection reason from {:?}", data);
+
+        let name = data.name.trim();
+
+        if name.is_empty() || name.len() > 64 {
+            return Err(DemonlistError::MalformedRejectionReason);
+        }
+
+        if sqlx::query!("SELECT id FROM rejection_reasons WHERE name = $1", name)
+            .fetch_optional(&mut *connection)
+            .await?
+            .is_some()
+        {
+            return Err(DemonlistError::RejectionReasonExists);
+        }
+
+        let id = sqlx::query!(
+            "INSERT INTO rejection_reasons (name, description) VALUES ($1::text, $2) RETURNING id",
+            name,
+            data.description
+        )
+        .fetch_one(connection)
+        .await?
+        .id;
+
+        Ok(RejectionReason {
+            id,
+            name: name.to_string(),
+            description: data.description,
+        })
+    }
+}
+
+impl RejectionReasonStatistics {
+    /// Counts the currently rejected records for each rejection reason
+    pub async fn compute(connection: &mut PgConnection) -> Result<Vec<RejectionReasonStatistics>> {
+        let mut stream = sqlx::query!(
+            r#"SELECT rejection_reasons.id, rejection_reasons.name::text AS "name!", description, COUNT(records.id) AS "rejected!" FROM
+             rejection_reasons LEFT OUTER JOIN records ON records.rejection_reason = rejection_reasons.id AND records.status_ = 'REJECTED'
+             GROUP BY rejection_reasons.id ORDER BY rejection_reasons.id"#
+        )
+        .fetch(connection);
+
+        let mut statistics = Vec::new();
+
+        while let Some(row) = stream.next().await {
+            let row = row?;
+
+            statistics.push(RejectionReasonStatistics {
+                reason: RejectionReason {
+                    id: row.id,
+                    name: row.name,
+                    description: row.description,
+                },
+                rejected: row.rejected,
+            })
+        }
+
+        Ok(statistics)
+    }
+}
@@ -0,0 +1,106 @@
+//! Module defining which records, and which parts of them, a user gets to see
+//!
+//! There are three levels of access to the records of some player:
+//! * Everyone can see approved records, without their submitter or raw footage
+//! * The verified claimant of the player can additionally see records in any status, including
+//!   their raw footage, public notes and the history of status changes. They never get to see who
+//!   submitted a record.
+//! * List helpers can see everything
+//!
+//! Record endpoints determine the [`RecordAccess`] of the requesting user and defer to it, instead
+//! of doing their own permission checks.
+
+use crate::{
+    error::{DemonlistError, Result},
+    player::claim::PlayerClaim,
+    record::{
+        audit::{status_history, StatusChange},
+        note::{notes_on, Note},
+        FullRecord, RecordPagination, RecordStatus,
+    },
+    LIST_HELPER,
+};
+use pointercrate_core::error::CoreError;
+use sqlx::PgConnection;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordAccess {
+    Public,
+    Claimant,
+    Team,
+}
+
+impl RecordAccess {
+    /// Determines the access the given user has to the records of the given player
+    ///
+    /// `player_id` may be `None` if the records in question are not restricted to a single player
+    /// (for example during pagination), in which case claims are irrelevant.
+    pub async fn of(
+        user_id: Option<i32>, is_team_member: bool, player_id: Option<i32>, connection: &mut PgConnection,
+    ) -> Result<RecordAccess> {
+        if is_team_member {
+            return Ok(RecordAccess::Team);
+        }
+
+        let (Some(user_id), Some(player_id)) = (user_id, player_id) else {
+            return Ok(RecordAccess::Public);
+        };
+
+        match PlayerClaim::by_user(user_id, connection).await? {
+            Some(claim) if claim.verified && claim.player.id == player_id => Ok(RecordAccess::Claimant),
+            _ => Ok(RecordAccess::Public),
+        }
+    }
+
+    /// Removes everything from the given record that should not be visible with this access level
+    ///
+    /// Errors with [`DemonlistError::RecordNotFound`] if the record should not be visible at all
+    pub fn redact(self, record: &mut FullRecord) -> Result<()> {
+        match self {
+            RecordAccess::Team => (),
+            RecordAccess::Claimant => record.submitter = None,
+            RecordAccess::Public if record.status != RecordStatus::Approved => {
+                return Err(DemonlistError::RecordNotFound { record_id: record.id })
+            },
+            RecordAccess::Public => {
+                record.submitter = None;
+                record.raw_footage.clear();
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Restricts the given pagination request to records visible with this access level
+    ///
+    /// Explicitly filtering for non-approved records without access to them is an error, as
+    /// opposed to silently returning only approved records.
+    pub fn restrict(self, pagination: &mut RecordPagination) -> Result<()> {
+        if self == RecordAccess::Public {
+            if pagination.status.is_some() && pagination.status != Some(RecordStatus::Approved) {
+                return Err(CoreError::MissingPermissions { required: LIST_HELPER }.into());
+            }
+
+            pagination.status = Some(RecordStatus::Approved);
+        }
+
+        Ok(())
+    }
+
+    /// Gets the notes on the given record that are visible with this access level
+    pub async fn notes_on(self, record_id: i32, connection: &mut PgConnection) -> Result<Vec<Note>> {
+        match self {
+            RecordAccess::Team => notes_on(record_id, false, connection).await,
+            RecordAccess::Claimant => notes_on(record_id, true, connection).await,
+            RecordAccess::Public => Err(DemonlistError::RecordNotFound { record_id }),
+        }
+    }
+
+    /// Gets the history of status changes of the given record, if visible with this access level
+    pub async fn status_history(self, record: &FullRecord, connection: &mut PgConnection) -> Result<Vec<StatusChange>> {
+        match self {
+            RecordAccess::Public => Err(DemonlistError::RecordNotFound { record_id: record.id }),
+            _ => status_history(record, connection).await,
+        }
+    }
+}
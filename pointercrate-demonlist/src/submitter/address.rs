@@ -0,0 +1,115 @@
+//! Module for deriving a submitter's identity from their IP address
+//!
+//! Residential IPv6 connections are usually assigned an entire /64 (or even larger) network, out
+//! of which a client can pick arbitrary addresses. To prevent people from evading bans and
+//! ratelimits by simply switching addresses, IPv6 addresses are only considered up to the prefix
+//! length configured via [`ipv6_prefix_length`](crate::config::ipv6_prefix_length). IPv4 addresses
+//! are always considered in full.
+//!
+//! Submitters are stored with the network their address was normalized to, and looked up by the
+//! network containing an address. Changing the prefix length therefore only affects submitters
+//! created afterwards, existing ones are still found under the network they were created with.
+
+use crate::{
+    config,
+    error::{DemonlistError, Result},
+};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Normalizes the given address to the part of it that identifies a submitter
+///
+/// IPv6 addresses are truncated to the configured prefix length. IPv4-mapped IPv6 addresses are
+/// converted to the IPv4 address they represent.
+pub fn normalize_ip(ip: IpAddr) -> IpAddr {
+    match unmap(ip) {
+        ip @ IpAddr::V6(_) => mask(ip, config::ipv6_prefix_length()),
+        ipv4 => ipv4,
+    }
+}
+
+/// Converts IPv4-mapped IPv6 addresses to the IPv4 address they represent, leaving all other
+/// addresses untouched
+pub(super) fn unmap(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(ipv6) => ipv6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+        ipv4 => ipv4,
+    }
+}
+
+/// Formats the normalized form of the given address as an `INET` value, e.g. `2001:db8::/64`
+pub(super) fn to_inet(ip: IpAddr) -> String {
+    match normalize_ip(ip) {
+        ip @ IpAddr::V6(_) => format!("{}/{}", ip, config::ipv6_prefix_length().min(128)),
+        ip => ip.to_string(),
+    }
+}
+
+/// Parses an address range given in CIDR notation (e.g. `192.0.2.0/24`), returning it in canonical
+/// form (that is, with all bits after the prefix cleared). A single address is treated as a range
+/// containing only that address.
+pub(super) fn parse_range(range: &str) -> Result<String> {
+    let (address, prefix_length) = match range.trim().split_once('/') {
+        Some((address, prefix_length)) => (address, Some(prefix_length)),
+        None => (range.trim(), None),
+    };
+
+    let address: IpAddr = address.parse().map_err(|_| DemonlistError::InvalidIpRange)?;
+    let max_length = match address {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    };
+    let prefix_length = match prefix_length {
+        Some(prefix_length) => prefix_length.parse().map_err(|_| DemonlistError::InvalidIpRange)?,
+        None => max_length,
+    };
+
+    if prefix_length > max_length {
+        return Err(DemonlistError::InvalidIpRange);
+    }
+
+    Ok(format!("{}/{}", mask(address, prefix_length), prefix_length))
+}
+
+/// Clears all bits of the given address after the first `prefix_length` ones
+fn mask(ip: IpAddr, prefix_length: u8) -> IpAddr {
+    match ip {
+        IpAddr::V4(ipv4) => {
+            let mask = u32::MAX.checked_shl(32 - prefix_length.min(32) as u32).unwrap_or(0);
+
+            IpAddr::V4(Ipv4Addr::from(u32::from(ipv4) & mask))
+        },
+        IpAddr::V6(ipv6) => {
+            let mask = u128::MAX.checked_shl(128 - prefix_length.min(128) as u32).unwrap_or(0);
+
+            IpAddr::V6(Ipv6Addr::from(u128::from(ipv6) & mask))
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mask, parse_range};
+    use std::{net::IpAddr, str::FromStr};
+
+    fn ip(ip: &str) -> IpAddr {
+        IpAddr::from_str(ip).unwrap()
+    }
+
+    #[test]
+    fn test_mask() {
+        assert_eq!(mask(ip("2001:db8:1:2:3:4:5:6"), 64), ip("2001:db8:1:2::"));
+        assert_eq!(mask(ip("2001:db8:1:2:3:4:5:6"), 128), ip("2001:db8:1:2:3:4:5:6"));
+        assert_eq!(mask(ip("2001:db8:1:2:3:4:5:6"), 0), ip("::"));
+        assert_eq!(mask(ip("192.0.2.55"), 24), ip("192.0.2.0"));
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("192.0.2.55/24").unwrap(), "192.0.2.0/24");
+        assert_eq!(parse_range("192.0.2.55").unwrap(), "192.0.2.55/32");
+        assert_eq!(parse_range("2001:db8::1/48").unwrap(), "2001:db8::/48");
+        assert!(parse_range("192.0.2.0/33").is_err());
+        assert!(parse_range("2001:db8::/a").is_err());
+        assert!(parse_range("pointercrate").is_err());
+    }
+}
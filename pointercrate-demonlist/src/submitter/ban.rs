@@ -0,0 +1,103 @@
+use crate::{
+    ban::{Ban, PostBan},
+    error::Result,
+    submitter::Submitter,
+};
+use chrono::NaiveDateTime;
+use log::info;
+use sqlx::PgConnection;
+
+impl Submitter {
+    /// Gets all bans ever issued against this submitter, most recent first
+    pub async fn bans(&self, connection: &mut PgConnection) -> Result<Vec<Ban>> {
+        Ok(sqlx::query_as!(
+            Ban,
+            "SELECT id, reason, issued_by, issued_at, expires_at, lifted_at, lifted_by FROM submitter_bans WHERE submitter = $1 ORDER \
+             BY issued_at DESC, id DESC",
+            self.id
+        )
+        .fetch_all(connection)
+        .await?)
+    }
+
+    /// Permanently bans this submitter, without giving a reason
+    pub async fn ban(&mut self, connection: &mut PgConnection) -> Result<()> {
+        self.record_ban(None, None, connection).await.map(|_| ())
+    }
+
+    /// Bans this submitter for the given reason, until the given expiry date (if any)
+    ///
+    /// If the submitter is already banned, their current ban is replaced.
+    pub async fn issue_ban(&mut self, ban: PostBan, connection: &mut PgConnection) -> Result<Ban> {
+        ban.validate()?;
+
+        self.record_ban(Some(ban.reason), ban.expires_at, connection).await
+    }
+
+    async fn record_ban(
+        &mut self, reason: Option<String>, expires_at: Option<NaiveDateTime>, connection: &mut PgConnection,
+    ) -> Result<Ban> {
+        let deleted = sqlx::query!("DELETE FROM records WHERE submitter = $1 AND status_ = 'SUBMITTED'", self.id)
+            .execute(&mut *connection)
+            .await?;
+
+        info!(
+            "Banning submitter {} caused deletion of {} submissions",
+            self,
+            deleted.rows_affected()
+        );
+
+        sqlx::query!(
+            "UPDATE submitter_bans SET lifted_at = (NOW() AT TIME ZONE 'utc'), lifted_by = (SELECT id FROM active_user LIMIT 1) WHERE \
+             submitter = $1 AND lifted_at IS NULL",
+            self.id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        let ban = sqlx::query_as!(
+            Ban,
+            "INSERT INTO submitter_bans (submitter, reason, issued_by, expires_at) VALUES ($1, $2, (SELECT id FROM active_user LIMIT 1), \
+             $3) RETURNING id, reason, issued_by, issued_at, expires_at, lifted_at, lifted_by",
+            self.id,
+            reason,
+            expires_at
+        )
+        .fetch_one(&mut *connection)
+        .await?;
+
+        sqlx::query!("UPDATE submitters SET banned = true WHERE submitter_id = $1", self.id)
+            .execute(connection)
+            .await?;
+
+        self.banned = true;
+
+        Ok(ban)
+    }
+
+    /// Lifts this submitter's current ban
+    pub async fn unban(&mut self, connection: &mut PgConnection) -> Result<()> {
+        self.lift_ban(false, connection).await
+    }
+
+    /// Lifts this submitter's current ban, either because it was `expired`, or because a moderator
+    /// decided to do so. Deleted submissions are not restored.
+    pub(crate) async fn lift_ban(&mut self, expired: bool, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!(
+            "UPDATE submitter_bans SET lifted_at = CASE WHEN $2 THEN expires_at ELSE (NOW() AT TIME ZONE 'utc') END, lifted_by = CASE \
+             WHEN $2 THEN NULL ELSE (SELECT id FROM active_user LIMIT 1) END WHERE submitter = $1 AND lifted_at IS NULL",
+            self.id,
+            expired
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!("UPDATE submitters SET banned = false WHERE submitter_id = $1", self.id)
+            .execute(connection)
+            .await?;
+
+        self.banned = false;
+
+        Ok(())
+    }
+}
@@ -1,33 +1,69 @@
 use crate::{
     error::{DemonlistError, Result},
-    submitter::Submitter,
+    submitter::{address::unmap, Submitter, SubmitterStatistics},
 };
 use sqlx::{Error, PgConnection};
 use std::net::IpAddr;
 
+pub(super) struct FetchedSubmitter {
+    pub submitter_id: i32,
+    pub banned: bool,
+    pub flagged: bool,
+    pub submitted: i32,
+    pub approved: i32,
+    pub rejected: i32,
+    pub deleted_invalid: i32,
+    pub reputation: f64,
+}
+
+impl From<FetchedSubmitter> for Submitter {
+    fn from(row: FetchedSubmitter) -> Self {
+        Submitter {
+            id: row.submitter_id,
+            banned: row.banned,
+            flagged: row.flagged,
+            statistics: SubmitterStatistics {
+                submitted: row.submitted,
+                approved: row.approved,
+                rejected: row.rejected,
+                deleted_invalid: row.deleted_invalid,
+                reputation: row.reputation,
+            },
+        }
+    }
+}
+
 impl Submitter {
     pub async fn by_id(id: i32, connection: &mut PgConnection) -> Result<Submitter> {
-        let result = sqlx::query!("SELECT submitter_id, banned FROM submitters WHERE submitter_id = $1", id)
-            .fetch_one(connection)
-            .await;
+        let result = sqlx::query_as!(
+            FetchedSubmitter,
+            r#"SELECT submitter_id, banned, flagged, submitted, approved, rejected, deleted_invalid, reputation AS "reputation!" FROM
+             submitters WHERE submitter_id = $1"#,
+            id
+        )
+        .fetch_one(connection)
+        .await;
 
         match result {
-            Ok(row) => Ok(Submitter { id, banned: row.banned }),
+            Ok(row) => Ok(row.into()),
             Err(Error::RowNotFound) => Err(DemonlistError::SubmitterNotFound { id }),
             Err(err) => Err(err.into()),
         }
     }
 
+    /// Gets the submitter identified by the given address, see [`normalize_ip`](super::normalize_ip)
+    ///
+    /// If the networks of multiple submitters contain the address (because the IPv6 prefix length
+    /// was changed), the one with the most specific network is returned.
     pub async fn by_ip(ip: IpAddr, connection: &mut PgConnection) -> Result<Option<Submitter>> {
-        Ok(sqlx::query!(
-            "SELECT submitter_id, banned FROM submitters WHERE ip_address = cast($1::text as inet)",
-            ip.to_string()
+        Ok(sqlx::query_as!(
+            FetchedSubmitter,
+            r#"SELECT submitter_id, banned, flagged, submitted, approved, rejected, deleted_invalid, reputation AS "reputation!" FROM
+             submitters WHERE ip_address >>= cast($1::text as inet) ORDER BY masklen(ip_address) DESC, submitter_id LIMIT 1"#,
+            unmap(ip).to_string()
         )
         .fetch_optional(&mut *connection)
         .await?
-        .map(|row| Submitter {
-            id: row.submitter_id,
-            banned: row.banned,
-        }))
+        .map(Into::into))
     }
 }
@@ -1,21 +1,81 @@
 use derive_more::Display;
 use serde::Deserialize;
 use serde::Serialize;
+use std::hash::{Hash, Hasher};
 
+pub use address::normalize_ip;
 pub use paginate::SubmitterPagination;
 pub use patch::PatchSubmitter;
 use pointercrate_core::etag::Taggable;
+pub use range_ban::{PostRangeBan, RangeBan};
+pub use reputation::ReputationPolicy;
 
+mod address;
+mod ban;
 mod get;
 mod paginate;
 mod patch;
 mod post;
+mod range_ban;
+mod reputation;
 
-#[derive(Debug, Deserialize, Serialize, Hash, Display, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, Hash, Display, Copy, Clone, PartialEq)]
 #[display(fmt = "{} (Banned: {})", id, banned)]
 pub struct Submitter {
     pub id: i32,
     pub banned: bool,
+
+    /// Whether this submitter's rejection rate crossed the threshold configured in the
+    /// [`ReputationPolicy`]. Cleared by list moderators once they looked into it.
+    pub flagged: bool,
+    pub statistics: SubmitterStatistics,
+}
+
+/// Statistics about how the submissions of a submitter fared in review
+///
+/// Only submissions that went through the review queue count as approved or rejected.
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, PartialEq)]
+pub struct SubmitterStatistics {
+    pub submitted: i32,
+    pub approved: i32,
+    pub rejected: i32,
+
+    /// Submissions that were deleted because their video turned out to be invalid
+    pub deleted_invalid: i32,
+
+    /// The share of reviewed submissions that were approved, smoothed towards 0.5 for submitters
+    /// with only few reviewed submissions
+    pub reputation: f64,
+}
+
+impl SubmitterStatistics {
+    /// The number of submissions that left the review queue in some way other than being approved
+    pub fn refused(&self) -> i32 {
+        self.rejected + self.deleted_invalid
+    }
+
+    /// The number of submissions that left the review queue
+    pub fn reviewed(&self) -> i32 {
+        self.approved + self.refused()
+    }
+
+    /// The share of reviewed submissions that were not approved
+    pub fn rejection_rate(&self) -> f64 {
+        match self.reviewed() {
+            0 => 0.0,
+            reviewed => self.refused() as f64 / reviewed as f64,
+        }
+    }
+}
+
+impl Hash for SubmitterStatistics {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // reputation is derived from the other fields
+        self.submitted.hash(state);
+        self.approved.hash(state);
+        self.rejected.hash(state);
+        self.deleted_invalid.hash(state);
+    }
 }
 
 impl Taggable for Submitter {}
@@ -1,8 +1,8 @@
-use crate::submitter::Submitter;
+use crate::submitter::{Submitter, SubmitterStatistics};
 use futures::StreamExt;
 use pointercrate_core::{
     first_and_last,
-    pagination::{PageContext, Paginatable, PaginationParameters, PaginationQuery, __pagination_compat},
+    pagination::{__pagination_compat, PageContext, Paginatable, PaginationParameters, PaginationQuery},
     util::non_nullable,
 };
 use serde::{Deserialize, Serialize};
@@ -15,6 +15,9 @@ pub struct SubmitterPagination {
 
     #[serde(default, deserialize_with = "non_nullable")]
     banned: Option<bool>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    flagged: Option<bool>,
 }
 
 impl PaginationQuery for SubmitterPagination {
@@ -31,17 +34,18 @@ impl PaginationQuery for SubmitterPagination {
 }
 
 impl Paginatable<SubmitterPagination> for Submitter {
-    first_and_last!("submitters", "submitter_id");
+    first_and_last!(SubmitterPagination, "submitters", "submitter_id");
 
     async fn page(query: &SubmitterPagination, connection: &mut PgConnection) -> Result<(Vec<Submitter>, PageContext), sqlx::Error> {
         let order = query.params.order();
 
-        let sql_query = format!("SELECT submitter_id, banned FROM submitters WHERE (submitter_id < $1 OR $1 IS NULL) AND (submitter_id > $2 OR $2 IS NULL) AND (banned = $3 OR $3 IS NULL) ORDER BY submitter_id {} LIMIT $4", order);
+        let sql_query = format!("SELECT submitter_id, banned, flagged, submitted, approved, rejected, deleted_invalid, reputation FROM submitters WHERE (submitter_id < $1 OR $1 IS NULL) AND (submitter_id > $2 OR $2 IS NULL) AND (banned = $3 OR $3 IS NULL) AND (flagged = $4 OR $4 IS NULL) ORDER BY submitter_id {} LIMIT $5", order);
 
         let mut stream = sqlx::query(&sql_query)
             .bind(query.params.before)
             .bind(query.params.after)
             .bind(query.banned)
+            .bind(query.flagged)
             .bind(query.params.limit + 1)
             .fetch(connection);
 
@@ -53,6 +57,14 @@ impl Paginatable<SubmitterPagination> for Submitter {
             submitters.push(Submitter {
                 id: row.get("submitter_id"),
                 banned: row.get("banned"),
+                flagged: row.get("flagged"),
+                statistics: SubmitterStatistics {
+                    submitted: row.get("submitted"),
+                    approved: row.get("approved"),
+                    rejected: row.get("rejected"),
+                    deleted_invalid: row.get("deleted_invalid"),
+                    reputation: row.get("reputation"),
+                },
             })
         }
 
@@ -8,48 +8,43 @@ use sqlx::PgConnection;
 pub struct PatchSubmitter {
     #[serde(default, deserialize_with = "non_nullable")]
     banned: Option<bool>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    flagged: Option<bool>,
 }
 
 impl Submitter {
-    pub async fn ban(&mut self, connection: &mut PgConnection) -> Result<()> {
-        sqlx::query!("UPDATE submitters SET banned = true WHERE submitter_id = $1", self.id)
-            .execute(&mut *connection)
-            .await?;
-
-        let deleted = sqlx::query!("DELETE FROM records WHERE submitter = $1 AND status_ = 'SUBMITTED'", self.id)
-            .execute(connection)
-            .await?;
-
-        info!(
-            "Banning submitter {} caused deletion of {} submissions",
-            self,
-            deleted.rows_affected()
-        );
-
-        self.banned = true;
-
-        Ok(())
-    }
-
-    pub async fn unban(&mut self, connection: &mut PgConnection) -> Result<()> {
-        sqlx::query!("UPDATE submitters SET banned = false WHERE submitter_id = $1", self.id)
-            .execute(connection)
-            .await?;
-
-        self.banned = false;
-
-        Ok(())
-    }
-
     pub async fn apply_patch(mut self, patch: PatchSubmitter, connection: &mut PgConnection) -> Result<Self> {
         info!("Patching submitter {} with {:?}", self, patch);
 
         match patch.banned {
-            Some(true) => self.ban(connection).await?,
-            Some(false) => self.unban(connection).await?,
+            Some(true) if !self.banned => self.ban(connection).await?,
+            Some(false) if self.banned => self.unban(connection).await?,
             _ => (),
         }
 
+        if let Some(flagged) = patch.flagged {
+            self.set_flagged(flagged, connection).await?;
+        }
+
         Ok(self)
     }
+
+    /// Flags or unflags this submitter
+    ///
+    /// Unflagging a submitter stops the [`ReputationPolicy`](crate::submitter::ReputationPolicy)
+    /// from flagging them again.
+    pub async fn set_flagged(&mut self, flagged: bool, connection: &mut PgConnection) -> Result<()> {
+        sqlx::query!(
+            "UPDATE submitters SET flagged = $1, flag_cleared = flag_cleared OR NOT $1 WHERE submitter_id = $2",
+            flagged,
+            self.id
+        )
+        .execute(connection)
+        .await?;
+
+        self.flagged = flagged;
+
+        Ok(())
+    }
 }
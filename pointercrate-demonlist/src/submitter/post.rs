@@ -1,17 +1,21 @@
-use crate::{error::Result, submitter::Submitter};
+use crate::{
+    error::Result,
+    submitter::{address::to_inet, get::FetchedSubmitter, Submitter},
+};
 use sqlx::PgConnection;
 use std::net::IpAddr;
 
 impl Submitter {
+    /// Creates a submitter identified by the given address, see [`normalize_ip`](super::normalize_ip)
     pub async fn create_submitter(ip: IpAddr, connection: &mut PgConnection) -> Result<Submitter> {
-        let id = sqlx::query!(
-            "INSERT INTO submitters (ip_address) VALUES (cast($1::text as inet)) RETURNING submitter_id",
-            ip.to_string()
+        Ok(sqlx::query_as!(
+            FetchedSubmitter,
+            r#"INSERT INTO submitters (ip_address) VALUES (cast($1::text as inet)) RETURNING submitter_id, banned, flagged, submitted,
+             approved, rejected, deleted_invalid, reputation AS "reputation!""#,
+            to_inet(ip)
         )
         .fetch_one(connection)
         .await?
-        .submitter_id;
-
-        Ok(Submitter { id, banned: false })
+        .into())
     }
 }
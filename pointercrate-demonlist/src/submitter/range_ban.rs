@@ -0,0 +1,125 @@
+//! Module for bans of entire address ranges
+//!
+//! Unlike bans of individual submitters, range bans also apply to addresses that have never
+//! submitted anything before, making them the tool of choice against people who can freely switch
+//! between addresses of a larger network.
+
+use crate::{
+    ban::PostBan,
+    error::{DemonlistError, Result},
+    submitter::address::parse_range,
+};
+use chrono::NaiveDateTime;
+use log::info;
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use std::net::IpAddr;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
+pub struct RangeBan {
+    pub id: i32,
+
+    /// The banned address range, in CIDR notation
+    pub range: String,
+    pub reason: String,
+
+    /// The ID of the member who issued this ban
+    pub issued_by: Option<i32>,
+    pub issued_at: NaiveDateTime,
+
+    /// The time at which this ban lifts automatically. `None` for permanent bans
+    pub expires_at: Option<NaiveDateTime>,
+    pub lifted_at: Option<NaiveDateTime>,
+
+    /// The ID of the member who lifted this ban. `None` if the ban expired
+    pub lifted_by: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PostRangeBan {
+    pub range: String,
+
+    #[serde(flatten)]
+    pub ban: PostBan,
+}
+
+impl RangeBan {
+    pub async fn by_id(id: i32, connection: &mut PgConnection) -> Result<RangeBan> {
+        sqlx::query_as!(
+            RangeBan,
+            r#"SELECT id, range::text AS "range!", reason, issued_by, issued_at, expires_at, lifted_at, lifted_by FROM
+             submitter_range_bans WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(connection)
+        .await?
+        .ok_or(DemonlistError::RangeBanNotFound { id })
+    }
+
+    /// Gets all range bans ever issued, most recent first
+    pub async fn all(connection: &mut PgConnection) -> Result<Vec<RangeBan>> {
+        Ok(sqlx::query_as!(
+            RangeBan,
+            r#"SELECT id, range::text AS "range!", reason, issued_by, issued_at, expires_at, lifted_at, lifted_by FROM
+             submitter_range_bans ORDER BY issued_at DESC, id DESC"#
+        )
+        .fetch_all(connection)
+        .await?)
+    }
+
+    /// Gets the active range ban covering the given address, if any
+    pub async fn covering(ip: IpAddr, connection: &mut PgConnection) -> Result<Option<RangeBan>> {
+        Ok(sqlx::query_as!(
+            RangeBan,
+            r#"SELECT id, range::text AS "range!", reason, issued_by, issued_at, expires_at, lifted_at, lifted_by FROM
+             submitter_range_bans WHERE lifted_at IS NULL AND cast($1::text as inet) <<= range ORDER BY issued_at DESC LIMIT 1"#,
+            ip.to_string()
+        )
+        .fetch_optional(connection)
+        .await?)
+    }
+
+    /// Bans the given address range. Submitters in the range are not banned individually, but
+    /// will be unable to submit records for as long as the range ban is active.
+    pub async fn create(ban: PostRangeBan, connection: &mut PgConnection) -> Result<RangeBan> {
+        ban.ban.validate()?;
+
+        let range = parse_range(&ban.range)?;
+
+        info!("Banning address range {}", range);
+
+        Ok(sqlx::query_as!(
+            RangeBan,
+            r#"INSERT INTO submitter_range_bans (range, reason, issued_by, expires_at) VALUES (cast($1::text as cidr), $2, (SELECT id
+             FROM active_user LIMIT 1), $3) RETURNING id, range::text AS "range!", reason, issued_by, issued_at, expires_at, lifted_at,
+             lifted_by"#,
+            range,
+            ban.ban.reason,
+            ban.ban.expires_at
+        )
+        .fetch_one(connection)
+        .await?)
+    }
+
+    /// Lifts this range ban, if it is still active
+    pub async fn lift(&mut self, connection: &mut PgConnection) -> Result<()> {
+        if self.lifted_at.is_some() {
+            return Ok(());
+        }
+
+        info!("Lifting ban of address range {}", self.range);
+
+        let lifted = sqlx::query!(
+            "UPDATE submitter_range_bans SET lifted_at = (NOW() AT TIME ZONE 'utc'), lifted_by = (SELECT id FROM active_user LIMIT 1) \
+             WHERE id = $1 RETURNING lifted_at, lifted_by",
+            self.id
+        )
+        .fetch_one(connection)
+        .await?;
+
+        self.lifted_at = lifted.lifted_at;
+        self.lifted_by = lifted.lifted_by;
+
+        Ok(())
+    }
+}
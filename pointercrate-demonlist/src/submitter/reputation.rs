@@ -0,0 +1,115 @@
+//! Module for automatically moderating submitters based on how their submissions fare in review
+//!
+//! Every submitter keeps track of how many of their submissions were approved, rejected, or
+//! deleted because of an invalid video (see [`SubmitterStatistics`]). Submitters who get too many
+//! of their submissions refused can automatically be flagged for a list moderator to look into, or
+//! be banned outright. The thresholds for this are configured at startup via a
+//! [`ReputationPolicy`], which is managed by rocket.
+
+use crate::{
+    ban::PostBan,
+    error::Result,
+    submitter::{Submitter, SubmitterStatistics},
+};
+use log::info;
+use sqlx::PgConnection;
+
+/// Thresholds on the rejection rate of submitters at which they are automatically flagged or
+/// banned
+#[derive(Debug, Clone)]
+pub struct ReputationPolicy {
+    min_reviewed: i32,
+    flag_at: Option<f64>,
+    ban_at: Option<f64>,
+}
+
+impl Default for ReputationPolicy {
+    /// The policy used on pointercrate.com: Once at least 10 of their submissions were reviewed,
+    /// submitters with half or more of them refused get flagged. Nobody gets banned automatically.
+    fn default() -> Self {
+        ReputationPolicy::disabled().after_reviewed(10).flag_at(0.5)
+    }
+}
+
+impl ReputationPolicy {
+    /// Constructs a policy that never flags or bans anyone
+    pub fn disabled() -> Self {
+        ReputationPolicy {
+            min_reviewed: 0,
+            flag_at: None,
+            ban_at: None,
+        }
+    }
+
+    /// Makes this policy flag submitters whose rejection rate reaches the given value
+    pub fn flag_at(mut self, rejection_rate: f64) -> Self {
+        self.flag_at = Some(rejection_rate);
+        self
+    }
+
+    /// Makes this policy permanently ban submitters whose rejection rate reaches the given value
+    pub fn ban_at(mut self, rejection_rate: f64) -> Self {
+        self.ban_at = Some(rejection_rate);
+        self
+    }
+
+    /// Makes this policy only apply to submitters with at least the given number of reviewed
+    /// submissions, so that a single rejection does not get anyone banned
+    pub fn after_reviewed(mut self, reviewed: i32) -> Self {
+        self.min_reviewed = reviewed;
+        self
+    }
+
+    fn exceeds(&self, threshold: Option<f64>, statistics: &SubmitterStatistics) -> bool {
+        match threshold {
+            Some(threshold) => statistics.reviewed() >= self.min_reviewed && statistics.rejection_rate() >= threshold,
+            None => false,
+        }
+    }
+
+    /// Flags or bans the submitter with the given ID if their rejection rate crossed the
+    /// respective threshold. Should be called whenever one of their submissions is refused.
+    ///
+    /// Submitters that already are banned are left alone. Submitters whose flag was cleared by a
+    /// moderator are not flagged again, but can still be banned.
+    pub async fn enforce(&self, submitter_id: i32, connection: &mut PgConnection) -> Result<Submitter> {
+        let mut submitter = Submitter::by_id(submitter_id, &mut *connection).await?;
+
+        if submitter.banned {
+            return Ok(submitter);
+        }
+
+        if self.exceeds(self.ban_at, &submitter.statistics) {
+            info!(
+                "Automatically banning submitter {} with statistics {:?}",
+                submitter, submitter.statistics
+            );
+
+            let reason = format!(
+                "Automatic ban: {} of {} reviewed submissions were refused",
+                submitter.statistics.refused(),
+                submitter.statistics.reviewed()
+            );
+
+            submitter.issue_ban(PostBan { reason, expires_at: None }, connection).await?;
+        } else if !submitter.flagged && self.exceeds(self.flag_at, &submitter.statistics) {
+            let flag_cleared = sqlx::query!("SELECT flag_cleared FROM submitters WHERE submitter_id = $1", submitter.id)
+                .fetch_one(&mut *connection)
+                .await?
+                .flag_cleared;
+
+            if flag_cleared {
+                return Ok(submitter);
+            }
+
+            info!(
+                "Automatically flagging submitter {} with statistics {:?}",
+                submitter, submitter.statistics
+            );
+
+            submitter.set_flagged(true, connection).await?;
+        }
+
+        Ok(submitter)
+    }
+}
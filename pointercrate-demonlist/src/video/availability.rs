@@ -0,0 +1,214 @@
+//! Module for keeping track of whether the videos we link to are still available
+//!
+//! Videos on approved records and demons get deleted or privated over time. We periodically probe
+//! them using some [`VideoProber`] and keep a history of the results, so that list moderators can
+//! look into videos that went missing. Nothing is ever deleted based on these checks.
+
+use crate::error::Result;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use futures::StreamExt;
+use log::info;
+use serde::{Serialize, Serializer};
+use sqlx::PgConnection;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum VideoState {
+    Available,
+    /// The video still exists, but cannot be watched (e.g. because it was privated)
+    Unavailable,
+    /// The video no longer exists
+    Dead,
+    /// We were unable to determine whether the video is available (e.g. because the host did not
+    /// respond)
+    Unknown,
+}
+
+impl VideoState {
+    pub fn to_sql(self) -> String {
+        match self {
+            VideoState::Available => "AVAILABLE",
+            VideoState::Unavailable => "UNAVAILABLE",
+            VideoState::Dead => "DEAD",
+            VideoState::Unknown => "UNKNOWN",
+        }
+        .to_owned()
+    }
+
+    fn from_sql(sql: &str) -> Self {
+        match sql {
+            "AVAILABLE" => VideoState::Available,
+            "UNAVAILABLE" => VideoState::Unavailable,
+            "DEAD" => VideoState::Dead,
+            "UNKNOWN" => VideoState::Unknown,
+            _ => panic!("invalid video state: {}", sql),
+        }
+    }
+}
+
+impl Display for VideoState {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            VideoState::Available => write!(f, "available"),
+            VideoState::Unavailable => write!(f, "unavailable"),
+            VideoState::Dead => write!(f, "dead"),
+            VideoState::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl Serialize for VideoState {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// The outcome of probing a single video
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Probe {
+    pub state: VideoState,
+    /// Human readable explanation of how we arrived at the state, e.g. the HTTP status code the
+    /// host responded with
+    pub detail: Option<String>,
+}
+
+/// Something that can determine whether a video is still available
+#[async_trait]
+pub trait VideoProber: Send + Sync {
+    async fn probe(&self, video: &str) -> Probe;
+}
+
+/// A single entry in a video's availability history
+#[derive(Debug, Serialize)]
+pub struct VideoCheck {
+    pub state: VideoState,
+    pub detail: Option<String>,
+    pub checked_at: NaiveDateTime,
+}
+
+/// A video whose most recent check found it to be dead or unavailable
+#[derive(Debug, Serialize)]
+pub struct UnavailableVideo {
+    pub video: String,
+    pub state: VideoState,
+    pub detail: Option<String>,
+    pub last_checked: NaiveDateTime,
+    /// The time of the first check after the video was last seen available
+    pub unavailable_since: NaiveDateTime,
+    /// IDs of the approved records using this video
+    pub records: Vec<i32>,
+    /// IDs of the demons using this video as their verification video
+    pub demons: Vec<i32>,
+}
+
+/// Gets the videos of approved records and demons that have not been checked in the given number
+/// of hours, least recently checked first
+pub async fn videos_due_for_check(recheck_after_hours: i32, limit: i64, connection: &mut PgConnection) -> Result<Vec<String>> {
+    let rows = sqlx::query!(
+        r#"SELECT videos.video AS "video!" FROM (SELECT video FROM records WHERE status_ = 'APPROVED' AND video IS NOT NULL UNION SELECT
+         video FROM demons WHERE video IS NOT NULL) videos LEFT OUTER JOIN (SELECT video, MAX(checked_at) AS last_checked FROM
+         video_checks GROUP BY video) checks ON checks.video = videos.video WHERE checks.last_checked IS NULL OR checks.last_checked <
+         (NOW() AT TIME ZONE 'utc') - make_interval(hours => $1) ORDER BY checks.last_checked NULLS FIRST LIMIT $2"#,
+        recheck_after_hours,
+        limit
+    )
+    .fetch_all(connection)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| row.video).collect())
+}
+
+/// Adds the result of probing the given video to its availability history
+pub async fn record_check(video: &str, probe: &Probe, connection: &mut PgConnection) -> Result<()> {
+    sqlx::query!(
+        "INSERT INTO video_checks (video, state, detail) VALUES ($1, $2::text::video_state, $3)",
+        video,
+        probe.state.to_sql(),
+        probe.detail
+    )
+    .execute(connection)
+    .await?;
+
+    Ok(())
+}
+
+/// Probes up to `limit` videos that are due for a check (see [`videos_due_for_check`]) and records
+/// the results. Returns the number of videos checked
+pub async fn check_due_videos(
+    prober: &dyn VideoProber, recheck_after_hours: i32, limit: i64, connection: &mut PgConnection,
+) -> Result<usize> {
+    let videos = videos_due_for_check(recheck_after_hours, limit, &mut *connection).await?;
+
+    for video in &videos {
+        let probe = prober.probe(video).await;
+
+        if probe.state != VideoState::Available {
+            info!("Video {} is {} ({:?})", video, probe.state, probe.detail);
+        }
+
+        record_check(video, &probe, &mut *connection).await?;
+    }
+
+    Ok(videos.len())
+}
+
+/// Gets the availability history of the given video, most recent check first
+pub async fn history(video: &str, connection: &mut PgConnection) -> Result<Vec<VideoCheck>> {
+    let mut stream = sqlx::query!(
+        r#"SELECT state::text AS "state!", detail, checked_at FROM video_checks WHERE video = $1 ORDER BY checked_at DESC, id DESC"#,
+        video
+    )
+    .fetch(connection);
+
+    let mut checks = Vec::new();
+
+    while let Some(row) = stream.next().await {
+        let row = row?;
+
+        checks.push(VideoCheck {
+            state: VideoState::from_sql(&row.state),
+            detail: row.detail,
+            checked_at: row.checked_at,
+        })
+    }
+
+    Ok(checks)
+}
+
+/// Gets all videos still in use whose most recent check found them to be dead or unavailable,
+/// longest unavailable first
+pub async fn unavailable_videos(connection: &mut PgConnection) -> Result<Vec<UnavailableVideo>> {
+    let mut stream = sqlx::query!(
+        r#"SELECT latest.video AS "video!", latest.state::text AS "state!", latest.detail, latest.checked_at AS "last_checked!", (SELECT
+         MIN(checked_at) FROM video_checks WHERE video_checks.video = latest.video AND checked_at > COALESCE((SELECT MAX(checked_at) FROM
+         video_checks WHERE video_checks.video = latest.video AND state = 'AVAILABLE'), '-infinity')) AS "unavailable_since!", ARRAY(SELECT
+         id FROM records WHERE records.video = latest.video AND status_ = 'APPROVED' ORDER BY id) AS "records!", ARRAY(SELECT id FROM demons
+         WHERE demons.video = latest.video ORDER BY id) AS "demons!" FROM (SELECT DISTINCT ON (video) video, state, detail, checked_at FROM
+         video_checks ORDER BY video, checked_at DESC, id DESC) latest WHERE latest.state IN ('UNAVAILABLE', 'DEAD') AND (EXISTS (SELECT 1
+         FROM records WHERE records.video = latest.video AND status_ = 'APPROVED') OR EXISTS (SELECT 1 FROM demons WHERE demons.video =
+         latest.video)) ORDER BY 5"#
+    )
+    .fetch(connection);
+
+    let mut videos = Vec::new();
+
+    while let Some(row) = stream.next().await {
+        let row = row?;
+
+        videos.push(UnavailableVideo {
+            video: row.video,
+            state: VideoState::from_sql(&row.state),
+            detail: row.detail,
+            last_checked: row.last_checked,
+            unavailable_since: row.unavailable_since,
+            records: row.records,
+            demons: row.demons,
+        })
+    }
+
+    Ok(videos)
+}
@@ -0,0 +1,430 @@
+//! The video hosts we know how to handle
+//!
+//! Each host knows how to extract a [`VideoIdentity`] from the various link formats it uses, and
+//! how to turn such an identity back into a canonical link. To support a new host, implement
+//! [`VideoHost`] for it and add it to [`HOSTS`].
+
+use crate::video::VideoIdentity;
+use url::Url;
+
+/// All video hosts we recognize, including defunct ones
+pub static HOSTS: [&(dyn VideoHost + Sync); 7] = [&YouTube, &Twitch, &Bilibili, &Vimeo, &Medal, &Streamable, &Everyplay];
+
+pub trait VideoHost {
+    /// The identifier of this host, as stored in the database (e.g. `"youtube"`)
+    fn name(&self) -> &'static str;
+
+    /// The human readable name of this host (e.g. `"YouTube"`)
+    fn display_name(&self) -> &'static str;
+
+    /// The domains links to videos on this host can have
+    fn domains(&self) -> &'static [&'static str];
+
+    /// Description of the link formats we accept for this host, used in error messages
+    fn expected_format(&self) -> &'static str;
+
+    /// Extracts the video ID and the start time (in seconds), if any, from a link to a video on
+    /// this host. Returns `None` if the link is not in any format we recognize
+    fn identify(&self, url: &Url) -> Option<(String, Option<i32>)>;
+
+    /// The canonical link for the given video
+    fn url(&self, video: &VideoIdentity) -> String;
+
+    /// A link that can be used to embed the given video on our website, if the host supports that
+    fn embed(&self, _video: &VideoIdentity) -> Option<String> {
+        None
+    }
+
+    /// The [oEmbed](https://oembed.com/) endpoint of this host, if it has one. Used to check
+    /// whether videos are still available
+    fn oembed_endpoint(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Whether this host has shut down, meaning we no longer accept new videos from it. Existing
+    /// links are still recognized.
+    fn defunct(&self) -> bool {
+        false
+    }
+}
+
+/// Looks up a video host by its [name](VideoHost::name)
+pub fn by_name(name: &str) -> Option<&'static (dyn VideoHost + Sync)> {
+    HOSTS.iter().copied().find(|host| host.name() == name)
+}
+
+/// Looks up the video host responsible for the given domain
+pub fn by_domain(domain: &str) -> Option<&'static (dyn VideoHost + Sync)> {
+    HOSTS.iter().copied().find(|host| host.domains().contains(&domain))
+}
+
+fn query_parameter(url: &Url, parameter: &str) -> Option<String> {
+    url.query_pairs()
+        .find_map(|(key, value)| if key == parameter { Some(value.into_owned()) } else { None })
+}
+
+fn timestamp_parameter(url: &Url, parameter: &str) -> Option<i32> {
+    query_parameter(url, parameter).and_then(|timestamp| parse_timestamp(&timestamp))
+}
+
+/// Parses timestamps such as `90`, `90s` or `1h2m3s` into seconds. A timestamp of zero is the same
+/// as no timestamp at all
+fn parse_timestamp(timestamp: &str) -> Option<i32> {
+    let mut total = 0i32;
+    let mut current: Option<i32> = None;
+
+    for c in timestamp.chars() {
+        let multiplier = match c {
+            '0'..='9' => {
+                let digit = c.to_digit(10)? as i32;
+
+                current = Some(current.unwrap_or(0).checked_mul(10)?.checked_add(digit)?);
+                continue;
+            },
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+
+        total = total.checked_add(current.take()?.checked_mul(multiplier)?)?;
+    }
+
+    // trailing digits without unit are seconds
+    total = total.checked_add(current.unwrap_or(0))?;
+
+    Some(total).filter(|&total| total > 0)
+}
+
+/// Formats a timestamp in seconds as `1h2m3s`
+fn format_timestamp(seconds: i32) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    match (hours, minutes) {
+        (0, 0) => format!("{}s", seconds),
+        (0, _) => format!("{}m{}s", minutes, seconds),
+        _ => format!("{}h{}m{}s", hours, minutes, seconds),
+    }
+}
+
+pub struct YouTube;
+
+impl VideoHost for YouTube {
+    fn name(&self) -> &'static str {
+        "youtube"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "YouTube"
+    }
+
+    fn domains(&self) -> &'static [&'static str] {
+        &["www.youtube.com", "m.youtube.com", "youtube.com", "youtu.be"]
+    }
+
+    fn expected_format(&self) -> &'static str {
+        "https://www.youtube.com/watch?v={video_id}' or \
+         'https://youtu.be/{video_id}' or \
+         'https://www.youtube.com/shorts/{video_id}' or \
+         'https://www.youtube.com/live/{video_id}' or \
+         'https://www.youtube.com/embed/{video_id}"
+    }
+
+    fn identify(&self, url: &Url) -> Option<(String, Option<i32>)> {
+        let segments = url.path_segments()?.collect::<Vec<_>>();
+
+        let video_id = match (url.domain()?, &segments[..]) {
+            ("youtu.be", [video_id]) => video_id.to_string(),
+            (_, ["watch"]) => query_parameter(url, "v")?,
+            (_, ["shorts", video_id]) | (_, ["live", video_id]) | (_, ["embed", video_id]) => video_id.to_string(),
+            _ => return None,
+        };
+
+        // youtube video IDs are always 11 characters long, but people like to append garbage
+        let video_id = video_id.chars().take(11).collect::<String>();
+
+        if video_id.is_empty() {
+            return None;
+        }
+
+        let start = timestamp_parameter(url, "t").or_else(|| timestamp_parameter(url, "start"));
+
+        Some((video_id, start))
+    }
+
+    fn url(&self, video: &VideoIdentity) -> String {
+        match video.start {
+            Some(start) => format!("https://www.youtube.com/watch?v={}&t={}s", video.video_id, start),
+            None => format!("https://www.youtube.com/watch?v={}", video.video_id),
+        }
+    }
+
+    fn embed(&self, video: &VideoIdentity) -> Option<String> {
+        Some(match video.start {
+            Some(start) => format!("https://www.youtube.com/embed/{}?start={}", video.video_id, start),
+            None => format!("https://www.youtube.com/embed/{}", video.video_id),
+        })
+    }
+
+    fn oembed_endpoint(&self) -> Option<&'static str> {
+        Some("https://www.youtube.com/oembed")
+    }
+}
+
+pub struct Twitch;
+
+impl VideoHost for Twitch {
+    fn name(&self) -> &'static str {
+        "twitch"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Twitch"
+    }
+
+    fn domains(&self) -> &'static [&'static str] {
+        &["www.twitch.tv", "m.twitch.tv", "twitch.tv"]
+    }
+
+    fn expected_format(&self) -> &'static str {
+        "https://www.twitch.tv/videos/{video_id}' or \
+         'https://www.twitch.tv/{channel_name}/v/{video_id}"
+    }
+
+    fn identify(&self, url: &Url) -> Option<(String, Option<i32>)> {
+        let video_id = match &url.path_segments()?.collect::<Vec<_>>()[..] {
+            ["videos", video_id] | [_, "v", video_id] => video_id.to_string(),
+            _ => return None,
+        };
+
+        Some((video_id, timestamp_parameter(url, "t")))
+    }
+
+    fn url(&self, video: &VideoIdentity) -> String {
+        match video.start {
+            Some(start) => format!("https://www.twitch.tv/videos/{}?t={}", video.video_id, format_timestamp(start)),
+            None => format!("https://www.twitch.tv/videos/{}", video.video_id),
+        }
+    }
+
+    fn embed(&self, video: &VideoIdentity) -> Option<String> {
+        Some(match video.start {
+            Some(start) => format!(
+                "https://player.twitch.tv/?video={}&autoplay=false&time={}",
+                video.video_id,
+                format_timestamp(start)
+            ),
+            None => format!("https://player.twitch.tv/?video={}&autoplay=false", video.video_id),
+        })
+    }
+}
+
+pub struct Bilibili;
+
+impl VideoHost for Bilibili {
+    fn name(&self) -> &'static str {
+        "bilibili"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Bilibili"
+    }
+
+    fn domains(&self) -> &'static [&'static str] {
+        &["www.bilibili.com", "m.bilibili.com", "bilibili.com"]
+    }
+
+    fn expected_format(&self) -> &'static str {
+        "https://www.bilibili.com/video/{video_id}"
+    }
+
+    fn identify(&self, url: &Url) -> Option<(String, Option<i32>)> {
+        match &url.path_segments()?.filter(|segment| !segment.is_empty()).collect::<Vec<_>>()[..] {
+            ["video", video_id] => Some((video_id.to_string(), timestamp_parameter(url, "t"))),
+            _ => None,
+        }
+    }
+
+    fn url(&self, video: &VideoIdentity) -> String {
+        match video.start {
+            Some(start) => format!("https://www.bilibili.com/video/{}?t={}", video.video_id, start),
+            None => format!("https://www.bilibili.com/video/{}", video.video_id),
+        }
+    }
+}
+
+pub struct Vimeo;
+
+impl VideoHost for Vimeo {
+    fn name(&self) -> &'static str {
+        "vimeo"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Vimeo"
+    }
+
+    fn domains(&self) -> &'static [&'static str] {
+        &["vimeo.com", "www.vimeo.com"]
+    }
+
+    fn expected_format(&self) -> &'static str {
+        "https://vimeo.com/{video_id}"
+    }
+
+    fn identify(&self, url: &Url) -> Option<(String, Option<i32>)> {
+        match &url.path_segments()?.collect::<Vec<_>>()[..] {
+            [video_id] if !video_id.is_empty() => {
+                // vimeo puts timestamps into the fragment, e.g. '#t=90s'
+                let start = url
+                    .fragment()
+                    .and_then(|fragment| fragment.strip_prefix("t="))
+                    .and_then(parse_timestamp);
+
+                Some((video_id.to_string(), start))
+            },
+            _ => None,
+        }
+    }
+
+    fn url(&self, video: &VideoIdentity) -> String {
+        match video.start {
+            Some(start) => format!("https://vimeo.com/{}#t={}s", video.video_id, start),
+            None => format!("https://vimeo.com/{}", video.video_id),
+        }
+    }
+
+    fn oembed_endpoint(&self) -> Option<&'static str> {
+        Some("https://vimeo.com/api/oembed.json")
+    }
+}
+
+pub struct Medal;
+
+impl VideoHost for Medal {
+    fn name(&self) -> &'static str {
+        "medal"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Medal"
+    }
+
+    fn domains(&self) -> &'static [&'static str] {
+        &["medal.tv", "www.medal.tv"]
+    }
+
+    fn expected_format(&self) -> &'static str {
+        "https://medal.tv/clips/{clip_id}' or \
+         'https://medal.tv/games/{game}/clips/{clip_id}"
+    }
+
+    fn identify(&self, url: &Url) -> Option<(String, Option<i32>)> {
+        match &url.path_segments()?.collect::<Vec<_>>()[..] {
+            ["clips", clip_id, ..] | ["games", _, "clips", clip_id, ..] if !clip_id.is_empty() => Some((clip_id.to_string(), None)),
+            _ => None,
+        }
+    }
+
+    fn url(&self, video: &VideoIdentity) -> String {
+        format!("https://medal.tv/clips/{}", video.video_id)
+    }
+}
+
+pub struct Streamable;
+
+impl VideoHost for Streamable {
+    fn name(&self) -> &'static str {
+        "streamable"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Streamable"
+    }
+
+    fn domains(&self) -> &'static [&'static str] {
+        &["streamable.com", "www.streamable.com"]
+    }
+
+    fn expected_format(&self) -> &'static str {
+        "https://streamable.com/{video_id}"
+    }
+
+    fn identify(&self, url: &Url) -> Option<(String, Option<i32>)> {
+        match &url.path_segments()?.collect::<Vec<_>>()[..] {
+            [video_id] | ["e", video_id] if !video_id.is_empty() => Some((video_id.to_string(), None)),
+            _ => None,
+        }
+    }
+
+    fn url(&self, video: &VideoIdentity) -> String {
+        format!("https://streamable.com/{}", video.video_id)
+    }
+
+    fn embed(&self, video: &VideoIdentity) -> Option<String> {
+        Some(format!("https://streamable.com/e/{}", video.video_id))
+    }
+
+    fn oembed_endpoint(&self) -> Option<&'static str> {
+        Some("https://api.streamable.com/oembed.json")
+    }
+}
+
+/// Everyplay shut down in 2018, but some old records still link to it
+pub struct Everyplay;
+
+impl VideoHost for Everyplay {
+    fn name(&self) -> &'static str {
+        "everyplay"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Everyplay"
+    }
+
+    fn domains(&self) -> &'static [&'static str] {
+        &["everyplay.com", "www.everyplay.com"]
+    }
+
+    fn expected_format(&self) -> &'static str {
+        "https://everyplay.com/videos/{video_id}"
+    }
+
+    fn identify(&self, url: &Url) -> Option<(String, Option<i32>)> {
+        match &url.path_segments()?.collect::<Vec<_>>()[..] {
+            ["videos", video_id] => Some((video_id.to_string(), None)),
+            _ => None,
+        }
+    }
+
+    fn url(&self, video: &VideoIdentity) -> String {
+        format!("https://everyplay.com/videos/{}", video.video_id)
+    }
+
+    fn defunct(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_timestamp, parse_timestamp};
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("90"), Some(90));
+        assert_eq!(parse_timestamp("90s"), Some(90));
+        assert_eq!(parse_timestamp("1m30s"), Some(90));
+        assert_eq!(parse_timestamp("1h2m3s"), Some(3723));
+        assert_eq!(parse_timestamp("0"), None);
+        assert_eq!(parse_timestamp("1x"), None);
+        assert_eq!(parse_timestamp("m"), None);
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(42), "42s");
+        assert_eq!(format_timestamp(90), "1m30s");
+        assert_eq!(format_timestamp(3723), "1h2m3s");
+    }
+}
@@ -0,0 +1,64 @@
+//! Module for deriving a demon's thumbnail from its verification video
+//!
+//! How (and whether) a thumbnail can be derived depends on the host the video is uploaded to. For
+//! some hosts it can be computed from the video ID alone, while others require querying the host's
+//! API. Each host's approach is encapsulated in a [`ThumbnailStrategy`], and a [`ThumbnailResolver`]
+//! picks the right strategy for a given video.
+
+use crate::video::VideoIdentity;
+use async_trait::async_trait;
+use std::{collections::HashMap, sync::Arc};
+
+/// The thumbnail used for demons for which no thumbnail could be derived
+pub const DEFAULT_THUMBNAIL: &str = "https://i.ytimg.com/vi/zebrafishes/mqdefault.jpg";
+
+/// Something that can determine the thumbnail of videos uploaded to a specific host
+#[async_trait]
+pub trait ThumbnailStrategy: Send + Sync {
+    /// Determines the thumbnail of the given video, returning `None` if it cannot be determined
+    async fn thumbnail(&self, video: &VideoIdentity) -> Option<String>;
+}
+
+/// Derives thumbnails of YouTube videos from their video ID
+pub struct YouTubeThumbnails;
+
+#[async_trait]
+impl ThumbnailStrategy for YouTubeThumbnails {
+    async fn thumbnail(&self, video: &VideoIdentity) -> Option<String> {
+        Some(format!("https://i.ytimg.com/vi/{}/mqdefault.jpg", video.video_id))
+    }
+}
+
+/// Maps video hosts to the [`ThumbnailStrategy`] to use for videos uploaded to them
+///
+/// By default, only YouTube thumbnails are derived.
+#[derive(Clone)]
+pub struct ThumbnailResolver {
+    strategies: HashMap<&'static str, Arc<dyn ThumbnailStrategy>>,
+}
+
+impl Default for ThumbnailResolver {
+    fn default() -> Self {
+        ThumbnailResolver {
+            strategies: HashMap::new(),
+        }
+        .with("youtube", YouTubeThumbnails)
+    }
+}
+
+impl ThumbnailResolver {
+    /// Uses the given strategy for videos uploaded to the host with the given
+    /// [name](crate::video::VideoHost::name), replacing any previously registered strategy
+    pub fn with(mut self, host: &'static str, strategy: impl ThumbnailStrategy + 'static) -> Self {
+        self.strategies.insert(host, Arc::new(strategy));
+        self
+    }
+
+    /// Determines the thumbnail of the given video, returning `None` if the video's host is not
+    /// supported or its strategy failed
+    pub async fn resolve(&self, video: &str) -> Option<String> {
+        let identity = VideoIdentity::of(video).ok()?;
+
+        self.strategies.get(identity.host.as_str())?.thumbnail(&identity).await
+    }
+}
@@ -0,0 +1,54 @@
+//! Module for determining when videos were uploaded
+//!
+//! Unlike a video's [identity](crate::video::VideoIdentity), its upload date cannot be derived from
+//! its link, and generally requires querying the API of the host the video is uploaded to. Each
+//! host's approach is encapsulated in an [`UploadDateStrategy`], and an [`UploadDateResolver`] picks
+//! the right strategy for a given video.
+
+use crate::video::VideoIdentity;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+/// Something that can determine when videos uploaded to a specific host were uploaded
+#[async_trait]
+pub trait UploadDateStrategy: Send + Sync {
+    /// Determines the (UTC) upload date of the given video, returning `None` if it cannot be
+    /// determined
+    async fn upload_date(&self, video: &VideoIdentity) -> Option<NaiveDateTime>;
+}
+
+/// Maps video hosts to the [`UploadDateStrategy`] to use for videos uploaded to them
+///
+/// By default, no upload dates are determined.
+#[derive(Clone, Default)]
+pub struct UploadDateResolver {
+    strategies: HashMap<&'static str, Arc<dyn UploadDateStrategy>>,
+}
+
+impl Debug for UploadDateResolver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UploadDateResolver")
+            .field("hosts", &self.strategies.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl UploadDateResolver {
+    /// Uses the given strategy for videos uploaded to the host with the given
+    /// [name](crate::video::VideoHost::name), replacing any previously registered strategy
+    pub fn with(mut self, host: &'static str, strategy: impl UploadDateStrategy + 'static) -> Self {
+        self.strategies.insert(host, Arc::new(strategy));
+        self
+    }
+
+    /// Determines the upload date of the given video, returning `None` if the video's host is not
+    /// supported or its strategy failed
+    pub async fn resolve(&self, video: &VideoIdentity) -> Option<NaiveDateTime> {
+        self.strategies.get(video.host.as_str())?.upload_date(video).await
+    }
+}
@@ -0,0 +1,50 @@
+//! Synchronizes the nations and subdivisions known to pointercrate with an ISO 3166 dataset
+//!
+//! Usage: `cargo run --bin sync_nationalities -- [--dry-run] [path/to/dataset.json]`
+//!
+//! Without a path, the dataset bundled with `pointercrate-demonlist` is used. With `--dry-run`, the
+//! changes that would be made are only reported, not applied.
+
+use pointercrate_core::pool::PointercratePool;
+use pointercrate_demonlist::nationality::sync::{synchronize, Iso3166Dataset};
+
+#[rocket::main]
+async fn main() {
+    dotenv::dotenv().unwrap();
+
+    let mut dry_run = false;
+    let mut path = None;
+
+    for argument in std::env::args().skip(1) {
+        match argument.as_str() {
+            "--dry-run" => dry_run = true,
+            _ => path = Some(argument),
+        }
+    }
+
+    let dataset = match path {
+        Some(path) => {
+            let json = std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("Failed to read dataset at '{}': {}", path, err));
+
+            Iso3166Dataset::from_json(&json).unwrap_or_else(|err| panic!("Malformed dataset at '{}': {}", path, err))
+        },
+        None => Iso3166Dataset::bundled(),
+    };
+
+    let pool = PointercratePool::init().await;
+    let mut transaction = pool.transaction().await.expect("Failed to start transaction");
+
+    let report = synchronize(&dataset, &mut *transaction)
+        .await
+        .expect("Failed to synchronize nations and subdivisions");
+
+    println!("{}", report);
+
+    if dry_run {
+        transaction.rollback().await.expect("Failed to roll back changes");
+
+        println!("Dry run, no changes were applied");
+    } else {
+        transaction.commit().await.expect("Failed to commit changes");
+    }
+}
@@ -7,7 +7,19 @@ use pointercrate_core_pages::{
     navigation::{NavigationBar, TopLevelNavigationBarItem},
     PageConfiguration,
 };
-use pointercrate_demonlist::LIST_ADMINISTRATOR;
+use pointercrate_demonlist::{
+    list::{List, DEFAULT_LIST},
+    record::{
+        approval::ApprovalPolicy,
+        policy::{SubmissionPolicy, SubmissionRule},
+    },
+    LIST_ADMINISTRATOR,
+};
+use pointercrate_demonlist_api::{
+    dead_links::{DeadLinkChecker, HttpVideoProber},
+    thumbnails::http_thumbnail_resolver,
+    upload_dates::http_upload_date_resolver,
+};
 use pointercrate_demonlist_pages::account::{
     demons::DemonsTab, list_integration::ListIntegrationTab, players::PlayersPage, records::RecordsPage,
 };
@@ -43,12 +55,15 @@ async fn rocket() -> _ {
     // DATABASE_URL environment variable
     let pool = PointercratePool::init().await;
 
+    // The list whose sections are linked in the footer
+    let list = List::by_id(DEFAULT_LIST, &mut *pool.connection().await.unwrap()).await.unwrap();
+
     // Set up the HTTP server
     let rocket = rocket::build()
         // Tell it about the connection pool to use (individual handlers can get hold of this pool by declaring an argument of type `&State<PointercratePool>`)
         .manage(pool)
         // Tell pointercrate's core components about navigation bar and footers, so that it knows how to render the website
-        .manage(page_configuration())
+        .manage(page_configuration(&list))
         // Register our 404 catcher
         .register("/", rocket::catchers![catch_404])
         // Register our home page
@@ -87,6 +102,43 @@ async fn rocket() -> _ {
 
     let rocket = rocket.manage(account_page_config);
 
+    // Define which record submissions your lists accept. The default policy is the one used on
+    // pointercrate.com (no submissions for legacy demons, and only 100% records on the extended list),
+    // but you can for example require raw footage for records on your top 25 demons, or have a
+    // different set of rules for each of your lists. Rejecting videos uploaded before a demon was
+    // added to the list requires determining upload dates, which for YouTube videos only works if
+    // the `YOUTUBE_API_KEY` environment variable is set. If you do not manage a policy yourself,
+    // the default one is used.
+    let submission_policy = SubmissionPolicy::default()
+        .with_rule(SubmissionRule::RawFootageRequired { up_to_position: 25 })
+        .with_rule(SubmissionRule::NoVideosPredatingPlacement)
+        .with_upload_dates(http_upload_date_resolver());
+
+    let rocket = rocket.manage(submission_policy);
+
+    // Define how many list team members need to approve a record before it actually gets approved.
+    // By default, a single approval is enough for every record, but you can for example require two
+    // independent approvals for all records on your top 10 demons. Lists can be given their own
+    // policy via `ApprovalPolicy::with_list_policy`. If you do not manage a policy yourself, the
+    // default one is used.
+    let approval_policy = ApprovalPolicy::requiring(2).up_to_position(10);
+
+    let rocket = rocket.manage(approval_policy);
+
+    // Periodically check whether the videos of approved records and demons are still available, so
+    // that list moderators can look into the ones that were deleted or privated (see the
+    // `/api/v1/videos/unavailable` endpoint). Nothing gets deleted automatically. If you do not
+    // register a checker, no videos are checked.
+    let rocket = rocket.manage(DeadLinkChecker::new(HttpVideoProber::default()));
+
+    // Define how demon thumbnails are derived from verification videos. By default, thumbnails of
+    // YouTube, Vimeo and Bilibili videos are determined automatically, and thumbnails of Twitch
+    // videos are as well if the `TWITCH_CLIENT_ID` and `TWITCH_ACCESS_TOKEN` environment variables
+    // are set. If you do not want pointercrate to query any third party APIs, register
+    // `ThumbnailResolver::default()`, which only supports YouTube. List moderators can always
+    // override thumbnails manually.
+    let rocket = rocket.manage(http_thumbnail_resolver());
+
     // Changing `false` to `true` here will put your website into "maintenance mode", which will disable all mutating request handlers and always return 503 SERVICE UNAVAILABLE responses for non-GET requests.
     let rocket = rocket.attach(MaintenanceFairing::new(false));
 
@@ -116,7 +168,7 @@ async fn rocket() -> _ {
 /// look-and-feel. It defines the navigation bar and footer layouts (e.g. what
 /// links to include) and various metadata without you needing to worry (much)
 /// about styling and layout.
-fn page_configuration() -> PageConfiguration {
+fn page_configuration(list: &List) -> PageConfiguration {
     // Define a navigation bar with only two items, a link to the user account page,
     // and a link to your demonlist.
     let nav_bar = NavigationBar::new("/static/images/path/to/your/logo.png")
@@ -163,11 +215,11 @@ fn page_configuration() -> PageConfiguration {
         links: vec![
             Link::new("/demonlist/1/", "Current Top Demon"),
             Link::new(
-                format!("/demonlist/{}/", pointercrate_demonlist::config::list_size() + 1),
+                format!("/demonlist/{}/", list.list_size + 1),
                 "Extended List",
             ),
             Link::new(
-                format!("/demonlist/{}/", pointercrate_demonlist::config::extended_list_size() + 1),
+                format!("/demonlist/{}/", list.extended_list_size + 1),
                 "Legacy List",
             ),
         ],
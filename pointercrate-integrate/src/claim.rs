@@ -0,0 +1,220 @@
+//! Self-service verification of player claims via Geometry Dash
+//!
+//! When initiating a claim, the claimant is issued a verification code. To prove that they own the
+//! Geometry Dash account of the claimed player, they then place this code either in a comment on
+//! their profile, or in the description of one of their levels. The [`ClaimVerifier`] checks
+//! whether the code can be found there.
+//!
+//! Accessing the Geometry Dash servers is abstracted away behind a [`GeometryDashBackend`], so that
+//! tests can use a [`MockGeometryDash`] instead. In production, the [`GeometryDashConnector`] is
+//! used, which is subject to the [`IntegrationRatelimits`](crate::gd::IntegrationRatelimits).
+
+use crate::gd::GeometryDashConnector;
+use async_trait::async_trait;
+use dash_rs::{
+    request::{comment::ProfileCommentsRequest, level::LevelsRequest, user::UserSearchRequest},
+    response::{parse_get_gj_acccount_comment_response, parse_get_gj_levels_response, parse_get_gj_users_response},
+};
+use log::info;
+use serde::Deserialize;
+use std::{collections::HashMap, sync::Arc};
+
+/// A Geometry Dash account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GdAccount {
+    pub name: String,
+
+    /// The ID identifying the user in level listings
+    pub user_id: u64,
+
+    /// The ID identifying the user's account, used to retrieve their profile
+    pub account_id: u64,
+}
+
+/// The parts of a Geometry Dash level relevant to claim verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GdLevel {
+    /// The user ID of the level's creator
+    pub creator: u64,
+    pub description: String,
+}
+
+/// Where the claimant placed their verification code
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "location", rename_all = "snake_case")]
+pub enum CodeLocation {
+    /// In one of the recent comments on the claimed player's Geometry Dash profile
+    ProfileComment,
+
+    /// In the description of the given level, which has to be uploaded by the claimed player
+    LevelDescription { level_id: u64 },
+}
+
+/// Something that can retrieve accounts, profile comments and levels from Geometry Dash
+#[async_trait]
+pub trait GeometryDashBackend: Send + Sync {
+    /// Gets the account with the given name, if it exists
+    async fn account_by_name(&self, name: &str) -> Option<GdAccount>;
+
+    /// Gets the contents of the most recent comments on the given account's profile
+    async fn profile_comments(&self, account_id: u64) -> Vec<String>;
+
+    /// Gets the level with the given ID, if it exists
+    async fn level(&self, level_id: u64) -> Option<GdLevel>;
+}
+
+/// Checks verification codes of player claims via a [`GeometryDashBackend`]
+///
+/// To verify claims against the official Geometry Dash servers, construct it from a
+/// [`GeometryDashConnector`].
+#[derive(Clone)]
+pub struct ClaimVerifier {
+    backend: Arc<dyn GeometryDashBackend>,
+}
+
+impl ClaimVerifier {
+    pub fn new(backend: impl GeometryDashBackend + 'static) -> Self {
+        ClaimVerifier {
+            backend: Arc::new(backend),
+        }
+    }
+
+    /// Checks whether the given code was placed at the given location by the Geometry Dash
+    /// account with the given name
+    pub async fn check(&self, player_name: &str, code: &str, location: &CodeLocation) -> bool {
+        let Some(account) = self.backend.account_by_name(player_name).await else {
+            info!("No Geometry Dash account named {} found", player_name);
+
+            return false;
+        };
+
+        // Geometry Dash only does a case-insensitive match when searching for users
+        if !account.name.eq_ignore_ascii_case(player_name) {
+            return false;
+        }
+
+        match location {
+            CodeLocation::ProfileComment => self
+                .backend
+                .profile_comments(account.account_id)
+                .await
+                .iter()
+                .any(|comment| comment.contains(code)),
+            CodeLocation::LevelDescription { level_id } => match self.backend.level(*level_id).await {
+                Some(level) => level.creator == account.user_id && level.description.contains(code),
+                None => false,
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl GeometryDashBackend for GeometryDashConnector {
+    async fn account_by_name(&self, name: &str) -> Option<GdAccount> {
+        self.claim_request_permitted()?;
+
+        let request = UserSearchRequest::new(name);
+        let response = self.make_request(request.to_url(), request.to_string()).await.ok()?;
+        let user = parse_get_gj_users_response(&response).ok()?;
+
+        Some(GdAccount {
+            name: user.name.to_string(),
+            user_id: user.user_id,
+            account_id: user.account_id,
+        })
+    }
+
+    async fn profile_comments(&self, account_id: u64) -> Vec<String> {
+        if self.claim_request_permitted().is_none() {
+            return Vec::new();
+        }
+
+        let request = ProfileCommentsRequest::new(account_id);
+        let Ok(response) = self.make_request(request.to_url(), request.to_string()).await else {
+            return Vec::new();
+        };
+
+        parse_get_gj_acccount_comment_response(&response)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|comment| Some(comment.content.as_ref()?.as_processed().ok()?.to_string()))
+            .collect()
+    }
+
+    async fn level(&self, level_id: u64) -> Option<GdLevel> {
+        self.claim_request_permitted()?;
+
+        // Geometry Dash treats numeric search strings as level IDs
+        let level_id_string = level_id.to_string();
+        let request = LevelsRequest::default().search(&level_id_string);
+        let response = self.make_request(request.to_url(), request.to_string()).await.ok()?;
+        let level = parse_get_gj_levels_response(&response)
+            .ok()?
+            .into_iter()
+            .find(|level| level.level_id == level_id)?;
+
+        Some(GdLevel {
+            creator: level.creator.as_ref()?.user_id,
+            description: level
+                .description
+                .as_ref()
+                .and_then(|description| description.as_processed().ok().map(|description| description.to_string()))
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// A [`GeometryDashBackend`] serving a fixed set of accounts, profile comments and levels
+///
+/// Meant for tests.
+#[derive(Default)]
+pub struct MockGeometryDash {
+    accounts: Vec<GdAccount>,
+    profile_comments: HashMap<u64, Vec<String>>,
+    levels: HashMap<u64, GdLevel>,
+}
+
+impl MockGeometryDash {
+    pub fn with_account(mut self, name: impl Into<String>, user_id: u64, account_id: u64) -> Self {
+        self.accounts.push(GdAccount {
+            name: name.into(),
+            user_id,
+            account_id,
+        });
+        self
+    }
+
+    pub fn with_profile_comment(mut self, account_id: u64, comment: impl Into<String>) -> Self {
+        self.profile_comments.entry(account_id).or_default().push(comment.into());
+        self
+    }
+
+    pub fn with_level(mut self, level_id: u64, creator: u64, description: impl Into<String>) -> Self {
+        self.levels.insert(
+            level_id,
+            GdLevel {
+                creator,
+                description: description.into(),
+            },
+        );
+        self
+    }
+}
+
+#[async_trait]
+impl GeometryDashBackend for MockGeometryDash {
+    async fn account_by_name(&self, name: &str) -> Option<GdAccount> {
+        self.accounts
+            .iter()
+            .find(|account| account.name.eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    async fn profile_comments(&self, account_id: u64) -> Vec<String> {
+        self.profile_comments.get(&account_id).cloned().unwrap_or_default()
+    }
+
+    async fn level(&self, level_id: u64) -> Option<GdLevel> {
+        self.levels.get(&level_id).cloned()
+    }
+}
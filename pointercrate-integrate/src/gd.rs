@@ -8,7 +8,7 @@ use dash_rs::{
     request::level::{LevelRequest, LevelRequestType, LevelsRequest, SearchFilters},
     response::{parse_download_gj_level_response, parse_get_gj_levels_response},
 };
-use log::{error, trace};
+use log::{error, trace, warn};
 use pointercrate_core::ratelimits;
 use pointercrate_demonlist::demon::Demon;
 use reqwest::{header::CONTENT_TYPE, Client};
@@ -26,6 +26,7 @@ ratelimits! {
         demon_refresh[1u32 per 86400 per i32] => "Only one refresh per day per demon",
         throttle[1u32 per 60] => "Wait at least 1 minute between level requests",
         throttle_throttle[1u32 per 600 per i32] => "Only hit the global throttle rate limit once per 10 minutes per demon",
+        claim_requests[30u32 per 60] => "Too many requests to the Geometry Dash servers for claim verification",
     }
 }
 
@@ -117,7 +118,20 @@ impl GeometryDashConnector {
             .await;
     }
 
-    async fn make_request(&self, url: String, body: String) -> Result<String, reqwest::Error> {
+    /// Checks whether another request for claim verification may be sent to the Geometry Dash
+    /// servers, returning `None` (after logging) if not
+    pub(crate) fn claim_request_permitted(&self) -> Option<()> {
+        match self.ratelimits.claim_requests() {
+            Ok(()) => Some(()),
+            Err(err) => {
+                warn!("Not querying Geometry Dash servers for claim verification: {}", err);
+
+                None
+            },
+        }
+    }
+
+    pub(crate) async fn make_request(&self, url: String, body: String) -> Result<String, reqwest::Error> {
         let response = self.http_client
             .post(url)
               // boomlings.com rejects any request with a User-Agent header set, so make sure reqwest doesn't "helpfully" add one
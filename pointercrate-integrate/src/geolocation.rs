@@ -0,0 +1,134 @@
+//! [`GeolocationProvider`]s backed by a local MaxMind database and by abstract's IP geolocation API
+
+use async_trait::async_trait;
+use log::info;
+use maxminddb::{geoip2, MaxMindDBError, Reader};
+use pointercrate_core::error::CoreError;
+use pointercrate_demonlist::{
+    error::Result,
+    nationality::geolocation::{GeolocationProvider, Location},
+};
+use reqwest::Client;
+use serde::Deserialize;
+use std::{net::IpAddr, path::Path, time::Duration};
+
+/// Looks up IP addresses in a local database in MaxMind's format (such as GeoLite2-City)
+///
+/// Works fully offline. Since location databases do not contain information about whether an
+/// address belongs to a VPN, an additional anonymous IP database (such as GeoIP2-Anonymous-IP) can
+/// be provided for VPN detection. Without it, no addresses are considered VPNs.
+pub struct MaxMindDatabase {
+    locations: Reader<Vec<u8>>,
+    anonymous_ips: Option<Reader<Vec<u8>>>,
+}
+
+impl MaxMindDatabase {
+    pub fn open(locations: impl AsRef<Path>, anonymous_ips: Option<impl AsRef<Path>>) -> std::result::Result<Self, MaxMindDBError> {
+        Ok(MaxMindDatabase {
+            locations: Reader::open_readfile(locations)?,
+            anonymous_ips: anonymous_ips.map(Reader::open_readfile).transpose()?,
+        })
+    }
+
+    fn is_vpn(&self, ip: IpAddr) -> Result<bool> {
+        let Some(ref anonymous_ips) = self.anonymous_ips else {
+            return Ok(false);
+        };
+
+        match anonymous_ips.lookup::<geoip2::AnonymousIp>(ip) {
+            Ok(anonymous) => Ok(anonymous.is_anonymous_vpn.unwrap_or(false)
+                || anonymous.is_public_proxy.unwrap_or(false)
+                || anonymous.is_hosting_provider.unwrap_or(false)
+                || anonymous.is_tor_exit_node.unwrap_or(false)),
+            // Only anonymous addresses are listed in this database
+            Err(MaxMindDBError::AddressNotFoundError(_)) => Ok(false),
+            Err(err) => Err(CoreError::internal_server_error(format!("Anonymous IP database lookup failed: {}", err)).into()),
+        }
+    }
+}
+
+#[async_trait]
+impl GeolocationProvider for MaxMindDatabase {
+    async fn locate(&self, ip: IpAddr) -> Result<Option<Location>> {
+        let city = match self.locations.lookup::<geoip2::City>(ip) {
+            Ok(city) => city,
+            Err(MaxMindDBError::AddressNotFoundError(_)) => return Ok(None),
+            Err(err) => return Err(CoreError::internal_server_error(format!("Geolocation database lookup failed: {}", err)).into()),
+        };
+
+        let Some(country_code) = city.country.and_then(|country| country.iso_code) else {
+            info!("Geolocation database knows {}, but not which country it is located in", ip);
+
+            return Ok(None);
+        };
+
+        Ok(Some(Location {
+            country_code: country_code.to_string(),
+            // Subdivisions are ordered from largest to smallest, and only the largest ones are
+            // ISO 3166-2 subdivisions of the country
+            subdivision_code: city
+                .subdivisions
+                .and_then(|subdivisions| subdivisions.into_iter().next())
+                .and_then(|subdivision| subdivision.iso_code)
+                .map(ToString::to_string),
+            is_vpn: self.is_vpn(ip)?,
+        }))
+    }
+}
+
+/// Queries abstract's IP geolocation API (<https://www.abstractapi.com/ip-geolocation-api>)
+pub struct AbstractApi {
+    client: Client,
+    api_key: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct Security {
+    is_vpn: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct GeolocationResponse {
+    security: Security,
+    country_code: Option<String>,
+    region_iso_code: Option<String>,
+}
+
+impl AbstractApi {
+    pub fn new(api_key: String) -> Self {
+        AbstractApi {
+            client: Client::builder().timeout(Duration::from_secs(10)).build().unwrap(),
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl GeolocationProvider for AbstractApi {
+    async fn locate(&self, ip: IpAddr) -> Result<Option<Location>> {
+        let response = self
+            .client
+            .get("https://ipgeolocation.abstractapi.com/v1/")
+            .query(&[
+                ("api_key", self.api_key.as_str()),
+                ("ip_address", ip.to_string().as_str()),
+                ("fields", "security,country_code,region_iso_code"),
+            ])
+            .send()
+            .await
+            .map_err(|err| CoreError::internal_server_error(format!("Ip Geolocation failed: {}", err)))?;
+
+        let data = response.json::<GeolocationResponse>().await.map_err(|err| {
+            CoreError::internal_server_error(format!(
+                "Ip Geolocation succeeded, but we could not deserialize the response: {}",
+                err
+            ))
+        })?;
+
+        Ok(data.country_code.map(|country_code| Location {
+            country_code,
+            subdivision_code: data.region_iso_code,
+            is_vpn: data.security.is_vpn,
+        }))
+    }
+}
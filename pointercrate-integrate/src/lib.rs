@@ -1,3 +1,5 @@
 //! this crate is a burning pile of trash
 
+pub mod claim;
 pub mod gd;
+pub mod geolocation;
@@ -3,11 +3,13 @@ use pointercrate_core::etag::Taggable;
 use pointercrate_core::{permission::PermissionsManager, pool::PointercratePool};
 use pointercrate_demonlist::demon::FullDemon;
 use pointercrate_demonlist::{
+    nationality::geolocation::{Geolocator, StaticGeolocation},
     player::{claim::PlayerClaim, FullPlayer},
-    record::RecordStatus,
+    record::{approval::ApprovalPolicy, challenge::SubmissionChallenge, policy::SubmissionPolicy, RecordStatus},
     submitter::Submitter,
     LIST_ADMINISTRATOR, LIST_HELPER, LIST_MODERATOR,
 };
+use pointercrate_integrate::claim::{ClaimVerifier, MockGeometryDash};
 use pointercrate_user::AuthenticatedUser;
 use pointercrate_user_pages::account::AccountPageConfig;
 use rocket::{http::Status, local::asynchronous::Client};
@@ -15,6 +17,69 @@ use sqlx::{pool::PoolConnection, PgConnection, Pool, Postgres};
 use std::{net::IpAddr, str::FromStr};
 
 pub async fn setup_rocket(pool: Pool<Postgres>) -> (TestClient, PoolConnection<Postgres>) {
+    setup_rocket_with_policy(pool, SubmissionPolicy::default()).await
+}
+
+pub async fn setup_rocket_with_policy(pool: Pool<Postgres>, policy: SubmissionPolicy) -> (TestClient, PoolConnection<Postgres>) {
+    setup_rocket_with_policies(pool, policy, ApprovalPolicy::default()).await
+}
+
+pub async fn setup_rocket_with_policies(
+    pool: Pool<Postgres>, policy: SubmissionPolicy, approval_policy: ApprovalPolicy,
+) -> (TestClient, PoolConnection<Postgres>) {
+    configure_rocket(
+        pool,
+        policy,
+        approval_policy,
+        SubmissionChallenge::disabled(),
+        ClaimVerifier::new(MockGeometryDash::default()),
+        Geolocator::disabled(),
+    )
+    .await
+}
+
+pub async fn setup_rocket_with_challenge(pool: Pool<Postgres>, challenge: SubmissionChallenge) -> (TestClient, PoolConnection<Postgres>) {
+    configure_rocket(
+        pool,
+        SubmissionPolicy::default(),
+        ApprovalPolicy::default(),
+        challenge,
+        ClaimVerifier::new(MockGeometryDash::default()),
+        Geolocator::disabled(),
+    )
+    .await
+}
+
+pub async fn setup_rocket_with_geometry_dash(
+    pool: Pool<Postgres>, geometry_dash: MockGeometryDash,
+) -> (TestClient, PoolConnection<Postgres>) {
+    configure_rocket(
+        pool,
+        SubmissionPolicy::default(),
+        ApprovalPolicy::default(),
+        SubmissionChallenge::disabled(),
+        ClaimVerifier::new(geometry_dash),
+        Geolocator::disabled(),
+    )
+    .await
+}
+
+pub async fn setup_rocket_with_geolocation(pool: Pool<Postgres>, geolocation: StaticGeolocation) -> (TestClient, PoolConnection<Postgres>) {
+    configure_rocket(
+        pool,
+        SubmissionPolicy::default(),
+        ApprovalPolicy::default(),
+        SubmissionChallenge::disabled(),
+        ClaimVerifier::new(MockGeometryDash::default()),
+        Geolocator::new(geolocation),
+    )
+    .await
+}
+
+async fn configure_rocket(
+    pool: Pool<Postgres>, policy: SubmissionPolicy, approval_policy: ApprovalPolicy, challenge: SubmissionChallenge,
+    claim_verifier: ClaimVerifier, geolocator: Geolocator,
+) -> (TestClient, PoolConnection<Postgres>) {
     let _ = dotenv::dotenv();
 
     let mut connection = pool.acquire().await.unwrap();
@@ -24,7 +89,15 @@ pub async fn setup_rocket(pool: Pool<Postgres>) -> (TestClient, PoolConnection<P
         .implies(LIST_ADMINISTRATOR, LIST_MODERATOR)
         .implies(LIST_MODERATOR, LIST_HELPER);
 
-    let rocket = pointercrate_demonlist_api::setup(rocket::build().manage(PointercratePool::from(pool)))
+    let rocket = rocket::build()
+        .manage(PointercratePool::from(pool))
+        .manage(policy)
+        .manage(approval_policy)
+        .manage(challenge)
+        .manage(claim_verifier)
+        .manage(geolocator);
+
+    let rocket = pointercrate_demonlist_api::setup(rocket)
         .manage(permissions)
         .manage(AccountPageConfig::default());
 
@@ -53,6 +126,57 @@ pub async fn add_demon(
     .id
 }
 
+pub async fn add_list(slug: impl Into<String>, list_size: i16, extended_list_size: i16, connection: &mut PgConnection) -> i32 {
+    let slug = slug.into();
+
+    sqlx::query!(
+        "INSERT INTO lists (slug, name, list_size, extended_list_size) VALUES ($1, $1, $2, $3) RETURNING id",
+        slug,
+        list_size,
+        extended_list_size
+    )
+    .fetch_one(&mut *connection)
+    .await
+    .unwrap()
+    .id
+}
+
+pub async fn add_demon_to_list(
+    list: i32, name: impl Into<String>, position: i16, requirement: i16, verifier_id: i32, publisher_id: i32, connection: &mut PgConnection,
+) -> i32 {
+    sqlx::query!(
+        "INSERT INTO demons (name, position, requirement, verifier, publisher, list) VALUES ($1::TEXT::CITEXT, $2, $3, $4, $5, $6) RETURNING id",
+        name.into(),
+        position,
+        requirement,
+        verifier_id,
+        publisher_id,
+        list
+    )
+    .fetch_one(&mut *connection)
+    .await
+    .unwrap()
+    .id
+}
+
+pub async fn add_timed_demon(
+    name: impl Into<String>, position: i16, max_time: Option<i64>, verifier_id: i32, publisher_id: i32, connection: &mut PgConnection,
+) -> i32 {
+    sqlx::query!(
+        "INSERT INTO demons (name, position, requirement, verifier, publisher, record_kind, max_time) VALUES ($1::TEXT::CITEXT, $2, 100, \
+         $3, $4, 'TIME', $5) RETURNING id",
+        name.into(),
+        position,
+        verifier_id,
+        publisher_id,
+        max_time
+    )
+    .fetch_one(&mut *connection)
+    .await
+    .unwrap()
+    .id
+}
+
 pub async fn put_claim(user_id: i32, player_id: i32, verified: bool, lock_submissions: bool, connection: &mut PgConnection) -> PlayerClaim {
     sqlx::query!(
         "INSERT INTO player_claims (member_id, player_id, verified, lock_submissions) VALUES ($1, $2, $3, $4)",
@@ -94,6 +218,17 @@ pub async fn add_simple_record(progress: i16, player: i32, demon: i32, status: R
     .id
 }
 
+pub async fn add_timed_record(completion_time: i64, player: i32, demon: i32, status: RecordStatus, connection: &mut PgConnection) -> i32 {
+    let record = add_simple_record(100, player, demon, status, &mut *connection).await;
+
+    sqlx::query!("UPDATE records SET completion_time = $1 WHERE id = $2", completion_time, record)
+        .execute(connection)
+        .await
+        .unwrap();
+
+    record
+}
+
 impl TestClient {
     pub async fn patch_player(&self, player_id: i32, auth_context: &AuthenticatedUser, patch: serde_json::Value) -> TestRequest {
         let player: FullPlayer = self
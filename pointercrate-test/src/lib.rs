@@ -63,6 +63,11 @@ impl<'c> TestRequest<'c> {
         self
     }
 
+    pub fn body(mut self, body: &impl Serialize) -> Self {
+        self.request = self.request.json(body);
+        self
+    }
+
     pub fn authorize_as(self, user: &AuthenticatedUser) -> Self {
         self.header("Authorization", format!("Bearer {}", user.generate_access_token()))
     }
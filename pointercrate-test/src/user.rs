@@ -26,9 +26,14 @@ pub async fn setup_rocket(pool: Pool<Postgres>) -> (TestClient, PoolConnection<P
 }
 
 pub async fn system_user_with_perms(perm: Permission, connection: &mut PgConnection) -> AuthenticatedUser {
+    named_system_user_with_perms("Patrick", perm, connection).await
+}
+
+/// Like [`system_user_with_perms`], for tests that need more than one user (usernames are unique)
+pub async fn named_system_user_with_perms(name: &str, perm: Permission, connection: &mut PgConnection) -> AuthenticatedUser {
     let user = AuthenticatedUser::register(
         Registration {
-            name: "Patrick".to_string(),
+            name: name.to_string(),
             password: "bad password".to_string(),
         },
         &mut *connection,
@@ -1,4 +1,17 @@
-use pointercrate_demonlist::player::{claim::PlayerClaim, DatabasePlayer};
+use pointercrate_core::error::PointercrateError;
+use pointercrate_demonlist::{
+    error::DemonlistError,
+    nationality::{
+        geolocation::{Location, StaticGeolocation},
+        Nationality,
+    },
+    player::{
+        claim::{ClaimDispute, ClaimSuggestion, DisputeResolution, PlayerClaim, SuggestionStatus},
+        DatabasePlayer,
+    },
+    LIST_MODERATOR,
+};
+use pointercrate_integrate::claim::MockGeometryDash;
 use rocket::http::Status;
 use sqlx::{Pool, Postgres};
 
@@ -33,3 +46,395 @@ async fn test_put_claim(pool: Pool<Postgres>) {
         }
     );
 }
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_verify_claim(pool: Pool<Postgres>) {
+    let geometry_dash = MockGeometryDash::default()
+        .with_account("stardust1971", 1, 2)
+        .with_account("Aquatias", 3, 4)
+        .with_profile_comment(2, "my code is pointercrate-abc123")
+        .with_level(5, 3, "pointercrate-abc123");
+
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket_with_geometry_dash(pool, geometry_dash).await;
+    let user = pointercrate_test::user::add_normal_user(&mut *connection).await;
+    let other_user = pointercrate_test::user::named_system_user_with_perms("Aquatias", 0, &mut *connection).await;
+
+    let player_id = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection)
+        .await
+        .unwrap()
+        .id;
+
+    pointercrate_test::demonlist::put_claim(user.inner().id, player_id, false, false, &mut *connection).await;
+
+    sqlx::query!("UPDATE player_claims SET verification_code = 'pointercrate-abc123'")
+        .execute(&mut *connection)
+        .await
+        .unwrap();
+
+    // Only the claimant can verify their claim
+    client
+        .post(
+            format!("/api/v1/players/{}/claims/{}/verify", player_id, user.inner().id),
+            &serde_json::json!({"location": "profile_comment"}),
+        )
+        .authorize_as(&other_user)
+        .expect_status(Status::NotFound)
+        .execute()
+        .await;
+
+    // The level containing the code was not uploaded by the claimed player
+    let json: serde_json::Value = client
+        .post(
+            format!("/api/v1/players/{}/claims/{}/verify", player_id, user.inner().id),
+            &serde_json::json!({"location": "level_description", "level_id": 5}),
+        )
+        .authorize_as(&user)
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(
+        json["code"].as_i64(),
+        Some(DemonlistError::ClaimVerificationFailed.error_code() as i64)
+    );
+
+    let claim: PlayerClaim = client
+        .post(
+            format!("/api/v1/players/{}/claims/{}/verify", player_id, user.inner().id),
+            &serde_json::json!({"location": "profile_comment"}),
+        )
+        .authorize_as(&user)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert!(claim.verified);
+    assert!(PlayerClaim::verified_claim_on(player_id, &mut *connection).await.unwrap().is_some());
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_dispute_claim(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+    let holder = pointercrate_test::user::add_normal_user(&mut *connection).await;
+    let disputer = pointercrate_test::user::named_system_user_with_perms("Aquatias", 0, &mut *connection).await;
+    let moderator = pointercrate_test::user::named_system_user_with_perms("Moderator", LIST_MODERATOR, &mut *connection).await;
+
+    let player_id = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection)
+        .await
+        .unwrap()
+        .id;
+
+    pointercrate_test::demonlist::put_claim(holder.inner().id, player_id, true, false, &mut *connection).await;
+
+    // Users cannot dispute their own claims
+    client
+        .post(
+            format!("/api/v1/players/{}/claims/disputes", player_id),
+            &serde_json::json!({"evidence": "It's me"}),
+        )
+        .authorize_as(&holder)
+        .expect_status(Status::UnprocessableEntity)
+        .execute()
+        .await;
+
+    let dispute: ClaimDispute = client
+        .post(
+            format!("/api/v1/players/{}/claims/disputes", player_id),
+            &serde_json::json!({"evidence": "I am stardust1971"}),
+        )
+        .authorize_as(&disputer)
+        .expect_status(Status::Created)
+        .get_result()
+        .await;
+
+    assert_eq!(dispute.disputed_by, disputer.inner().id);
+    assert_eq!(dispute.holder, Some(holder.inner().id));
+    assert_eq!(dispute.resolution, None);
+
+    client
+        .post(
+            format!("/api/v1/players/{}/claims/disputes", player_id),
+            &serde_json::json!({"evidence": "I really am stardust1971"}),
+        )
+        .authorize_as(&disputer)
+        .expect_status(Status::Conflict)
+        .execute()
+        .await;
+
+    let open: Vec<ClaimDispute> = client
+        .get("/api/v1/players/claims/disputes")
+        .authorize_as(&moderator)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(open, vec![dispute.clone()]);
+
+    // Only moderators can resolve disputes
+    client
+        .post(
+            format!("/api/v1/players/claims/disputes/{}/resolution", dispute.id),
+            &serde_json::json!({"resolution": "transferred", "reason": "Please"}),
+        )
+        .authorize_as(&disputer)
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+
+    let resolved: ClaimDispute = client
+        .post(
+            format!("/api/v1/players/claims/disputes/{}/resolution", dispute.id),
+            &serde_json::json!({"resolution": "transferred", "reason": "Verified via discord"}),
+        )
+        .authorize_as(&moderator)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(resolved.resolution, Some(DisputeResolution::Transferred));
+    assert_eq!(resolved.resolution_reason.as_deref(), Some("Verified via discord"));
+    assert_eq!(resolved.resolved_by, Some(moderator.inner().id));
+    assert!(resolved.resolved_at.is_some());
+
+    let claim = PlayerClaim::verified_claim_on(player_id, &mut *connection).await.unwrap().unwrap();
+
+    assert_eq!(claim.user_id, disputer.inner().id);
+    assert!(PlayerClaim::by_user(holder.inner().id, &mut *connection).await.unwrap().is_none());
+
+    client
+        .post(
+            format!("/api/v1/players/claims/disputes/{}/resolution", dispute.id),
+            &serde_json::json!({"resolution": "revoked", "reason": "Changed my mind"}),
+        )
+        .authorize_as(&moderator)
+        .expect_status(Status::Conflict)
+        .execute()
+        .await;
+
+    // The transfer shows up in the claim history of the player
+    let audit: Vec<serde_json::Value> = client
+        .get(format!("/api/v1/players/{}/claims/audit", player_id))
+        .authorize_as(&moderator)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    let history: Vec<_> = audit
+        .iter()
+        .map(|entry| (entry["claimant"]["id"].as_i64().unwrap() as i32, entry["type"].as_str()))
+        .collect();
+
+    assert_eq!(
+        history,
+        vec![
+            (holder.inner().id, Some("Addition")),
+            (holder.inner().id, Some("Deletion")),
+            (disputer.inner().id, Some("Addition"))
+        ]
+    );
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_dispute_on_changed_claim(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+    let holder = pointercrate_test::user::add_normal_user(&mut *connection).await;
+    let disputer = pointercrate_test::user::named_system_user_with_perms("Aquatias", 0, &mut *connection).await;
+    let new_holder = pointercrate_test::user::named_system_user_with_perms("Pennutoh", 0, &mut *connection).await;
+    let moderator = pointercrate_test::user::named_system_user_with_perms("Moderator", LIST_MODERATOR, &mut *connection).await;
+
+    let player_id = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection)
+        .await
+        .unwrap()
+        .id;
+
+    pointercrate_test::demonlist::put_claim(holder.inner().id, player_id, true, false, &mut *connection).await;
+
+    let dispute: ClaimDispute = client
+        .post(
+            format!("/api/v1/players/{}/claims/disputes", player_id),
+            &serde_json::json!({"evidence": "I am stardust1971"}),
+        )
+        .authorize_as(&disputer)
+        .expect_status(Status::Created)
+        .get_result()
+        .await;
+
+    // The claim changes hands while the dispute is open
+    sqlx::query!("DELETE FROM player_claims WHERE player_id = $1", player_id)
+        .execute(&mut *connection)
+        .await
+        .unwrap();
+    pointercrate_test::demonlist::put_claim(new_holder.inner().id, player_id, true, false, &mut *connection).await;
+
+    let json: serde_json::Value = client
+        .post(
+            format!("/api/v1/players/claims/disputes/{}/resolution", dispute.id),
+            &serde_json::json!({"resolution": "transferred", "reason": "Verified via discord"}),
+        )
+        .authorize_as(&moderator)
+        .expect_status(Status::Conflict)
+        .get_result()
+        .await;
+
+    assert_eq!(
+        json["code"].as_i64(),
+        Some(DemonlistError::DisputedClaimChanged.error_code() as i64)
+    );
+
+    let claim = PlayerClaim::verified_claim_on(player_id, &mut *connection).await.unwrap().unwrap();
+
+    assert_eq!(claim.user_id, new_holder.inner().id);
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_suggest_edit(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+    let claimant = pointercrate_test::user::add_normal_user(&mut *connection).await;
+    let moderator = pointercrate_test::user::named_system_user_with_perms("Moderator", LIST_MODERATOR, &mut *connection).await;
+
+    let player_id = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection)
+        .await
+        .unwrap()
+        .id;
+
+    pointercrate_test::demonlist::put_claim(claimant.inner().id, player_id, true, false, &mut *connection).await;
+
+    // Actual renames are not possible via suggestions
+    let json: serde_json::Value = client
+        .post(
+            format!("/api/v1/players/{}/suggestions", player_id),
+            &serde_json::json!({"kind": "name", "name": "stardust1972"}),
+        )
+        .authorize_as(&claimant)
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(json["code"].as_i64(), Some(DemonlistError::InvalidSuggestion.error_code() as i64));
+
+    let suggestion: ClaimSuggestion = client
+        .post(
+            format!("/api/v1/players/{}/suggestions", player_id),
+            &serde_json::json!({"kind": "name", "name": "Stardust1971", "comment": "Proper capitalization"}),
+        )
+        .authorize_as(&claimant)
+        .expect_status(Status::Created)
+        .get_result()
+        .await;
+
+    assert_eq!(suggestion.suggested_by, claimant.inner().id);
+    assert_eq!(suggestion.status, SuggestionStatus::Pending);
+
+    // Only moderators can review suggestions
+    client
+        .post(
+            format!("/api/v1/players/claims/suggestions/{}/review", suggestion.id),
+            &serde_json::json!({"approve": true}),
+        )
+        .authorize_as(&claimant)
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+
+    let reviewed: ClaimSuggestion = client
+        .post(
+            format!("/api/v1/players/claims/suggestions/{}/review", suggestion.id),
+            &serde_json::json!({"approve": true, "note": "Looks good"}),
+        )
+        .authorize_as(&moderator)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(reviewed.status, SuggestionStatus::Approved);
+    assert_eq!(reviewed.reviewed_by, Some(moderator.inner().id));
+    assert_eq!(reviewed.review_note.as_deref(), Some("Looks good"));
+
+    let player = DatabasePlayer::by_id(player_id, &mut *connection).await.unwrap();
+
+    assert_eq!(player.name, "Stardust1971");
+
+    // The rename is attributed to the claimant, not the reviewing moderator
+    let modified_by = sqlx::query!("SELECT userid FROM player_modifications WHERE id = $1", player_id)
+        .fetch_one(&mut *connection)
+        .await
+        .unwrap()
+        .userid;
+
+    assert_eq!(modified_by, claimant.inner().id);
+
+    client
+        .post(
+            format!("/api/v1/players/claims/suggestions/{}/review", suggestion.id),
+            &serde_json::json!({"approve": false}),
+        )
+        .authorize_as(&moderator)
+        .expect_status(Status::Conflict)
+        .execute()
+        .await;
+
+    let suggestion: ClaimSuggestion = client
+        .post(
+            format!("/api/v1/players/{}/suggestions", player_id),
+            &serde_json::json!({"kind": "name", "name": "STARDUST1971"}),
+        )
+        .authorize_as(&claimant)
+        .expect_status(Status::Created)
+        .get_result()
+        .await;
+
+    // Suggestions by users who lost their claim in the meantime cannot be approved anymore
+    sqlx::query!("DELETE FROM player_claims WHERE player_id = $1", player_id)
+        .execute(&mut *connection)
+        .await
+        .unwrap();
+
+    let json: serde_json::Value = client
+        .post(
+            format!("/api/v1/players/claims/suggestions/{}/review", suggestion.id),
+            &serde_json::json!({"approve": true}),
+        )
+        .authorize_as(&moderator)
+        .expect_status(Status::Conflict)
+        .get_result()
+        .await;
+
+    assert_eq!(
+        json["code"].as_i64(),
+        Some(DemonlistError::SuggesterNotClaimant.error_code() as i64)
+    );
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_geolocate_nationality(pool: Pool<Postgres>) {
+    let geolocation = StaticGeolocation::default().with_location(
+        "127.0.0.1".parse().unwrap(),
+        Location {
+            country_code: "AR".to_string(),
+            subdivision_code: Some("B".to_string()),
+            is_vpn: false,
+        },
+    );
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket_with_geolocation(pool, geolocation).await;
+    let user = pointercrate_test::user::add_normal_user(&mut *connection).await;
+
+    let player_id = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection)
+        .await
+        .unwrap()
+        .id;
+
+    pointercrate_test::demonlist::put_claim(user.inner().id, player_id, true, false, &mut *connection).await;
+
+    let nationality: Nationality = client
+        .post(format!("/api/v1/players/{}/geolocate", player_id), &())
+        .authorize_as(&user)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(nationality.iso_country_code, "AR");
+    assert_eq!(
+        nationality.subdivision.map(|subdivision| subdivision.iso_code).as_deref(),
+        Some("B")
+    );
+}
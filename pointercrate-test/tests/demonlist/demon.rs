@@ -1,8 +1,10 @@
+use pointercrate_core::etag::Taggable;
 use pointercrate_core::pagination::PaginationParameters;
 use pointercrate_core_api::pagination::LinksBuilder;
 use pointercrate_demonlist::{
-    demon::{Demon, DemonPositionPagination},
-    player::DatabasePlayer,
+    demon::{Demon, DemonPositionPagination, FullDemon, RecordKind},
+    player::{recompute_scores, DatabasePlayer, Player},
+    record::RecordStatus,
     LIST_MODERATOR,
 };
 use rocket::http::Status;
@@ -107,6 +109,89 @@ async fn test_default_thumbnail_with_video(pool: Pool<Postgres>) {
     )
 }
 
+#[sqlx::test(migrations = "../migrations")]
+async fn test_thumbnail_follows_video_unless_overridden(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let user = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+
+    let demon = serde_json::json! {{"name": "Bloodbath", "requirement": 90, "position": 1, "verifier": "Riot", "publisher": "Riot", "creators": [], "video": "https://www.youtube.com/watch?v=dQw4w9WgXcQ"}};
+
+    let demon: FullDemon = clnt
+        .post("/api/v2/demons/", &demon)
+        .authorize_as(&user)
+        .expect_status(Status::Created)
+        .get_success_result()
+        .await;
+
+    // Changing the verification video updates the thumbnail
+    let demon: FullDemon = clnt
+        .patch(
+            format!("/api/v2/demons/{}/", demon.demon.base.id),
+            &serde_json::json! {{"video": "https://youtu.be/oHg5SJYRHA0"}},
+        )
+        .authorize_as(&user)
+        .header("If-Match", demon.etag_string())
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert_eq!(demon.demon.thumbnail, "https://i.ytimg.com/vi/oHg5SJYRHA0/mqdefault.jpg");
+
+    // ... as does removing it
+    let demon: FullDemon = clnt
+        .patch(
+            format!("/api/v2/demons/{}/", demon.demon.base.id),
+            &serde_json::json! {{"video": null}},
+        )
+        .authorize_as(&user)
+        .header("If-Match", demon.etag_string())
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert_eq!(demon.demon.thumbnail, DEFAULT_THUMBNAIL);
+
+    // A manually set thumbnail sticks around when the video changes
+    let demon: FullDemon = clnt
+        .patch(
+            format!("/api/v2/demons/{}/", demon.demon.base.id),
+            &serde_json::json! {{"thumbnail": "https://example.com/thumbnail.png"}},
+        )
+        .authorize_as(&user)
+        .header("If-Match", demon.etag_string())
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    let demon: FullDemon = clnt
+        .patch(
+            format!("/api/v2/demons/{}/", demon.demon.base.id),
+            &serde_json::json! {{"video": "https://www.youtube.com/watch?v=dQw4w9WgXcQ"}},
+        )
+        .authorize_as(&user)
+        .header("If-Match", demon.etag_string())
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert_eq!(demon.demon.thumbnail, "https://example.com/thumbnail.png");
+
+    // Resetting the thumbnail derives it from the video again
+    let demon: FullDemon = clnt
+        .patch(
+            format!("/api/v2/demons/{}/", demon.demon.base.id),
+            &serde_json::json! {{"thumbnail": null}},
+        )
+        .authorize_as(&user)
+        .header("If-Match", demon.etag_string())
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert_eq!(demon.demon.thumbnail, "https://i.ytimg.com/vi/dQw4w9WgXcQ/mqdefault.jpg");
+}
+
 #[sqlx::test(migrations = "../migrations")]
 async fn test_demon_pagination(pool: Pool<Postgres>) {
     /// The URL of the endpoint we are testing
@@ -255,3 +340,39 @@ async fn test_demon_pagination(pool: Pool<Postgres>) {
 
     assert_eq!(links, expected.generate(&base).unwrap());
 }
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_timed_demon_records_ordered_by_time(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let verifier = DatabasePlayer::by_name_or_create("Riot", &mut *connection).await.unwrap();
+    let slow = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let fast = DatabasePlayer::by_name_or_create("stardust1972", &mut *connection).await.unwrap();
+
+    let demon = pointercrate_test::demonlist::add_timed_demon("Press Start", 1, None, verifier.id, verifier.id, &mut *connection).await;
+
+    let slow_record =
+        pointercrate_test::demonlist::add_timed_record(120_000, slow.id, demon, RecordStatus::Approved, &mut *connection).await;
+    let fast_record =
+        pointercrate_test::demonlist::add_timed_record(60_000, fast.id, demon, RecordStatus::Approved, &mut *connection).await;
+
+    recompute_scores(&mut *connection).await.unwrap();
+
+    let full_demon: FullDemon = clnt
+        .get(format!("/api/v2/demons/{}/", demon))
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert_eq!(full_demon.demon.record_kind, RecordKind::Time);
+    assert_eq!(full_demon.records.len(), 2);
+    assert_eq!(full_demon.records[0].id, fast_record);
+    assert_eq!(full_demon.records[0].completion_time, Some(60_000));
+    assert_eq!(full_demon.records[1].id, slow_record);
+
+    // The fastest completion gets the full score, slower ones are scaled down
+    let slow_score = Player::by_id(slow.id, &mut *connection).await.unwrap().score;
+    let fast_score = Player::by_id(fast.id, &mut *connection).await.unwrap().score;
+
+    assert!(fast_score > slow_score, "{} <= {}", fast_score, slow_score);
+}
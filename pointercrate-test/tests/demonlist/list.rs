@@ -0,0 +1,159 @@
+use pointercrate_demonlist::{
+    demon::{Demon, FullDemon},
+    list::List,
+    player::{recompute_scores, DatabasePlayer, FullPlayer},
+    record::RecordStatus,
+    LIST_ADMINISTRATOR, LIST_MODERATOR,
+};
+use rocket::http::Status;
+use sqlx::{Pool, Postgres};
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_create_list(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(LIST_ADMINISTRATOR, &mut *connection).await;
+    let moderator = pointercrate_test::user::named_system_user_with_perms("Stardust", LIST_MODERATOR, &mut *connection).await;
+
+    let list = serde_json::json! {{"slug": "platformer", "name": "Platformer List", "list_size": 25, "extended_list_size": 50}};
+
+    // Only list administrators can create lists
+    clnt.post("/api/v2/lists/", &list)
+        .authorize_as(&moderator)
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+
+    let created: List = clnt
+        .post("/api/v2/lists/", &list)
+        .authorize_as(&admin)
+        .expect_status(Status::Created)
+        .get_success_result()
+        .await;
+
+    assert_eq!(created.slug, "platformer");
+    assert_eq!(created.list_size, 25);
+    assert_eq!(created.extended_list_size, 50);
+
+    let result: serde_json::Value = clnt
+        .post("/api/v2/lists/", &list)
+        .authorize_as(&admin)
+        .expect_status(Status::Conflict)
+        .get_result()
+        .await;
+
+    assert_eq!(result["code"].as_i64(), Some(40909));
+
+    let result: serde_json::Value = clnt
+        .post(
+            "/api/v2/lists/",
+            &serde_json::json! {{"slug": "challenge", "name": "Challenge List", "list_size": 50, "extended_list_size": 25}},
+        )
+        .authorize_as(&admin)
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(result["code"].as_i64(), Some(42234));
+
+    let lists: Vec<List> = clnt.get("/api/v2/lists/").expect_status(Status::Ok).get_result().await;
+
+    assert_eq!(lists.len(), 2);
+    assert_eq!(lists[0].slug, "main");
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_positions_are_per_list(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let moderator = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+
+    let platformer = pointercrate_test::demonlist::add_list("platformer", 25, 50, &mut *connection).await;
+    let main_demon = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 100, player.id, player.id, &mut *connection).await;
+
+    let demon: FullDemon = clnt
+        .post(
+            "/api/v2/demons/",
+            &serde_json::json! {{"name": "Press Start", "requirement": 100, "position": 1, "verifier": "Riot", "publisher": "Riot", "creators": [], "list": platformer}},
+        )
+        .authorize_as(&moderator)
+        .expect_status(Status::Created)
+        .get_success_result()
+        .await;
+
+    assert_eq!(demon.demon.base.position, 1);
+    assert_eq!(demon.demon.list, platformer);
+
+    // Adding a demon to the platformer list must not have shifted the main list
+    let bloodbath: FullDemon = clnt
+        .get(format!("/api/v2/demons/{}/", main_demon))
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert_eq!(bloodbath.demon.base.position, 1);
+
+    let (demons, _) = clnt
+        .get("/api/v2/lists/platformer/demons/listed/")
+        .expect_status(Status::Ok)
+        .get_pagination_result::<Demon>()
+        .await;
+
+    assert_eq!(demons.len(), 1);
+    assert_eq!(demons[0].base.id, demon.demon.base.id);
+
+    let (demons, _) = clnt
+        .get("/api/v2/demons/listed/")
+        .expect_status(Status::Ok)
+        .get_pagination_result::<Demon>()
+        .await;
+
+    assert_eq!(demons.len(), 1);
+    assert_eq!(demons[0].base.id, main_demon);
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_scores_are_per_list(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let verifier = DatabasePlayer::by_name_or_create("stardust1972", &mut *connection).await.unwrap();
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+
+    let platformer = pointercrate_test::demonlist::add_list("platformer", 25, 50, &mut *connection).await;
+    let demon =
+        pointercrate_test::demonlist::add_demon_to_list(platformer, "Press Start", 1, 100, verifier.id, verifier.id, &mut *connection)
+            .await;
+
+    pointercrate_test::demonlist::add_simple_record(100, player.id, demon, RecordStatus::Approved, &mut *connection).await;
+
+    recompute_scores(&mut *connection).await.unwrap();
+
+    let full_player: FullPlayer = clnt
+        .get(format!("/api/v1/players/{}/", player.id))
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    // The score on the default list is unaffected by records on other lists
+    assert_eq!(full_player.player.score, 0.0f64);
+    assert_eq!(full_player.scores.len(), 1);
+    assert_eq!(full_player.scores[0].list, platformer);
+    assert_ne!(full_player.scores[0].score, 0.0f64);
+
+    let (ranking, _) = clnt
+        .get("/api/v2/lists/platformer/players/ranking/")
+        .expect_status(Status::Ok)
+        .get_pagination_result::<serde_json::Value>()
+        .await;
+
+    assert_eq!(ranking.len(), 2);
+
+    let (ranking, _) = clnt
+        .get("/api/v1/players/ranking/")
+        .expect_status(Status::Ok)
+        .get_pagination_result::<serde_json::Value>()
+        .await;
+
+    assert!(ranking.is_empty(), "{:?}", ranking);
+}
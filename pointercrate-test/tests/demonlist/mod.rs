@@ -1,4 +1,7 @@
 mod claim;
 mod demon;
+mod list;
 mod player;
 mod record;
+mod submitter;
+mod video;
@@ -1,7 +1,13 @@
 use pointercrate_demonlist::{
+    ban::{lift_expired_bans, Ban},
     nationality::{Nationality, Subdivision},
-    player::{DatabasePlayer, FullPlayer, Player},
-    LIST_HELPER,
+    player::{
+        alias::PlayerAlias,
+        claim::{ClaimDispute, PostClaimDispute},
+        DatabasePlayer, FullPlayer, Player,
+    },
+    record::{approved_records_by, RecordStatus},
+    LIST_HELPER, LIST_MODERATOR,
 };
 use rocket::http::Status;
 use sqlx::{PgConnection, Pool, Postgres};
@@ -168,3 +174,377 @@ async fn test_patch_player_nationality(pool: Pool<Postgres>) {
     assert_eq!(result["data"]["nation_code"], "BE");
     assert_eq!(result["data"]["subdivision_code"], "ENG");
 }
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_renamed_player_findable_by_old_name(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let user = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+
+    let patched_player: FullPlayer = client
+        .patch_player(player.id, &user, serde_json::json!({"name": "stardust1972"}))
+        .await
+        .get_success_result()
+        .await;
+
+    assert_eq!(patched_player.player.base.name, "stardust1972");
+    assert_eq!(patched_player.aliases.len(), 1);
+    assert_eq!(patched_player.aliases[0].name, "stardust1971");
+
+    let json: Vec<Player> = client
+        .get("/api/v1/players?name=stardust1971")
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(json.len(), 1, "Pagination did not resolve alias");
+    assert_eq!(json[0].base.id, player.id);
+    assert_eq!(
+        DatabasePlayer::by_name("stardust1971", &mut *connection).await.unwrap().id,
+        player.id
+    );
+
+    // Hidden aliases are no longer associated with the player
+    client
+        .patch(
+            format!("/api/v1/players/{}/aliases/{}", player.id, patched_player.aliases[0].id),
+            &serde_json::json!({"hidden": true}),
+        )
+        .authorize_as(&user)
+        .expect_status(Status::Ok)
+        .execute()
+        .await;
+
+    let fetched_player: FullPlayer = client
+        .get(format!("/api/v1/players/{}", player.id))
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert!(fetched_player.aliases.is_empty());
+
+    let json: Vec<Player> = client
+        .get("/api/v1/players?name=stardust1971")
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert!(json.is_empty(), "Pagination resolved hidden alias");
+    assert!(DatabasePlayer::by_name("stardust1971", &mut *connection).await.is_err());
+
+    // ... but are still visible to the list team
+    let aliases: Vec<PlayerAlias> = client
+        .get(format!("/api/v1/players/{}/aliases", player.id))
+        .authorize_as(&user)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(aliases.len(), 1);
+    assert!(aliases[0].hidden);
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_merge_requires_confirmed_preview(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+    let user = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let duplicate = DatabasePlayer::by_name_or_create("stardust 1971", &mut *connection).await.unwrap();
+
+    let demon1 = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 50, duplicate.id, duplicate.id, &mut *connection).await;
+    let demon2 = pointercrate_test::demonlist::add_demon("Cadrega City", 2, 50, duplicate.id, duplicate.id, &mut *connection).await;
+
+    let kept = pointercrate_test::demonlist::add_simple_record(100, player.id, demon1, RecordStatus::Approved, &mut *connection).await;
+    let duplicate_record =
+        pointercrate_test::demonlist::add_simple_record(60, duplicate.id, demon1, RecordStatus::Approved, &mut *connection).await;
+    let moved = pointercrate_test::demonlist::add_simple_record(70, duplicate.id, demon2, RecordStatus::Approved, &mut *connection).await;
+
+    // Renaming no longer implicitly merges
+    let result: serde_json::Value = client
+        .patch_player(duplicate.id, &user, serde_json::json!({"name": "stardust1971"}))
+        .await
+        .expect_status(Status::Conflict)
+        .get_result()
+        .await;
+
+    assert_eq!(result["code"], 40912);
+
+    let response = client
+        .get(format!("/api/v1/players/{}/merge/{}", player.id, duplicate.id))
+        .authorize_as(&user)
+        .expect_status(Status::Ok)
+        .execute()
+        .await;
+
+    let etag = response.headers().get_one("ETag").unwrap().to_string();
+    let preview: serde_json::Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+    assert_eq!(preview["data"]["records_moved"], serde_json::json!([moved]));
+    assert_eq!(preview["data"]["records_deleted"], serde_json::json!([duplicate_record]));
+    assert_eq!(preview["data"]["verified"].as_array().unwrap().len(), 2);
+    assert_eq!(preview["data"]["conflicting_claims"], false);
+
+    // The preview did not actually change anything
+    assert!(DatabasePlayer::by_id(duplicate.id, &mut *connection).await.is_ok());
+
+    // The world changed since the preview was generated
+    pointercrate_test::demonlist::add_simple_record(80, duplicate.id, demon2, RecordStatus::Submitted, &mut *connection).await;
+
+    client
+        .post(format!("/api/v1/players/{}/merge/{}", player.id, duplicate.id), &())
+        .authorize_as(&user)
+        .header("If-Match", etag)
+        .expect_status(Status::PreconditionFailed)
+        .execute()
+        .await;
+
+    let response = client
+        .get(format!("/api/v1/players/{}/merge/{}", player.id, duplicate.id))
+        .authorize_as(&user)
+        .expect_status(Status::Ok)
+        .execute()
+        .await;
+
+    let etag = response.headers().get_one("ETag").unwrap().to_string();
+
+    let merged: FullPlayer = client
+        .post(format!("/api/v1/players/{}/merge/{}", player.id, duplicate.id), &())
+        .authorize_as(&user)
+        .header("If-Match", etag)
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert_eq!(merged.player.base.id, player.id);
+    assert_eq!(merged.verified.len(), 2);
+    assert!(merged.records.iter().any(|record| record.id == kept));
+    assert!(merged.records.iter().any(|record| record.id == moved));
+    assert!(DatabasePlayer::by_id(duplicate.id, &mut *connection).await.is_err());
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_undo_merge(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+    let user = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let other = DatabasePlayer::by_name_or_create("Zoink", &mut *connection).await.unwrap();
+
+    let demon1 = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 50, other.id, other.id, &mut *connection).await;
+    let demon2 = pointercrate_test::demonlist::add_demon("Cadrega City", 2, 50, player.id, player.id, &mut *connection).await;
+
+    let kept = pointercrate_test::demonlist::add_simple_record(100, player.id, demon1, RecordStatus::Approved, &mut *connection).await;
+    let deleted = pointercrate_test::demonlist::add_simple_record(60, other.id, demon1, RecordStatus::Approved, &mut *connection).await;
+    let moved = pointercrate_test::demonlist::add_simple_record(70, other.id, demon2, RecordStatus::Approved, &mut *connection).await;
+
+    let score_before = player.update_score(&mut *connection).await.unwrap();
+    let other_score_before = other.update_score(&mut *connection).await.unwrap();
+
+    let mut merged = Player::by_id(player.id, &mut *connection)
+        .await
+        .unwrap()
+        .upgrade(&mut *connection)
+        .await
+        .unwrap();
+    merged.merge(other.clone(), &mut *connection).await.unwrap();
+
+    assert!(DatabasePlayer::by_id(other.id, &mut *connection).await.is_err());
+
+    let merges: serde_json::Value = client
+        .get(format!("/api/v1/players/{}/merges", player.id))
+        .authorize_as(&user)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(merges.as_array().unwrap().len(), 1);
+    assert_eq!(merges[0]["merged"]["id"], other.id);
+
+    let merge_id = merges[0]["id"].as_i64().unwrap();
+
+    let restored: FullPlayer = client
+        .post(format!("/api/v1/players/{}/merges/{}/undo", player.id, merge_id), &())
+        .authorize_as(&user)
+        .expect_status(Status::Created)
+        .get_success_result()
+        .await;
+
+    assert_eq!(restored.player.base, other);
+    assert_eq!(restored.verified.len(), 1);
+    assert_eq!(restored.verified[0].id, demon1);
+    assert!(restored.records.iter().any(|record| record.id == deleted));
+    assert!(restored.records.iter().any(|record| record.id == moved));
+    assert_eq!(restored.player.score, other_score_before);
+    assert!(restored.aliases.is_empty());
+
+    let player = Player::by_id(player.id, &mut *connection)
+        .await
+        .unwrap()
+        .upgrade(&mut *connection)
+        .await
+        .unwrap();
+
+    assert_eq!(player.records.len(), 1);
+    assert_eq!(player.records[0].id, kept);
+    assert_eq!(player.verified.len(), 1);
+    assert_eq!(player.verified[0].id, demon2);
+    assert_eq!(player.player.score, score_before);
+    assert!(player.aliases.is_empty());
+
+    // A merge can only be undone once
+    client
+        .post(format!("/api/v1/players/{}/merges/{}/undo", player.player.base.id, merge_id), &())
+        .authorize_as(&user)
+        .expect_status(Status::NotFound)
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_undo_chained_merges(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+    let user = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+    let holder = pointercrate_test::user::add_normal_user(&mut *connection).await;
+    let disputer = pointercrate_test::user::named_system_user_with_perms("Aquatias", 0, &mut *connection).await;
+
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let middle = DatabasePlayer::by_name_or_create("stardust1972", &mut *connection).await.unwrap();
+    let mut other = DatabasePlayer::by_name_or_create("Zoink", &mut *connection).await.unwrap();
+
+    let demon = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 50, other.id, other.id, &mut *connection).await;
+
+    pointercrate_test::demonlist::put_claim(holder.inner().id, other.id, true, false, &mut *connection).await;
+    other
+        .dispute_claim(
+            disputer.inner().id,
+            PostClaimDispute {
+                evidence: "I am Zoink".to_string(),
+            },
+            &mut *connection,
+        )
+        .await
+        .unwrap();
+    other.ban(&mut *connection).await.unwrap();
+
+    // First "Zoink" is merged into "stardust1972", which is then merged into "stardust1971"
+    let mut merged = Player::by_id(middle.id, &mut *connection)
+        .await
+        .unwrap()
+        .upgrade(&mut *connection)
+        .await
+        .unwrap();
+    merged.merge(other.clone(), &mut *connection).await.unwrap();
+
+    let mut merged = Player::by_id(player.id, &mut *connection)
+        .await
+        .unwrap()
+        .upgrade(&mut *connection)
+        .await
+        .unwrap();
+    merged.merge(middle.clone(), &mut *connection).await.unwrap();
+
+    // Both merges can be undone from the remaining player
+    let merges = player.merges(&mut *connection).await.unwrap();
+
+    assert_eq!(merges.len(), 2);
+
+    let first_merge = merges.iter().find(|merge| merge.merged.id == other.id).unwrap().id;
+    let second_merge = merges.iter().find(|merge| merge.merged.id == middle.id).unwrap().id;
+
+    let restored: FullPlayer = client
+        .post(format!("/api/v1/players/{}/merges/{}/undo", player.id, second_merge), &())
+        .authorize_as(&user)
+        .expect_status(Status::Created)
+        .get_success_result()
+        .await;
+
+    assert_eq!(restored.player.base, middle);
+    assert_eq!(restored.verified.len(), 1);
+
+    // The merge of "Zoink" went back to "stardust1972"
+    assert_eq!(player.merges(&mut *connection).await.unwrap().len(), 1);
+    assert_eq!(middle.merges(&mut *connection).await.unwrap()[0].id, first_merge);
+
+    let restored: FullPlayer = client
+        .post(format!("/api/v1/players/{}/merges/{}/undo", middle.id, first_merge), &())
+        .authorize_as(&user)
+        .expect_status(Status::Created)
+        .get_success_result()
+        .await;
+
+    assert_eq!(restored.player.base, other);
+    assert_eq!(restored.verified.len(), 1);
+    assert_eq!(restored.verified[0].id, demon);
+
+    // Its bans and claim disputes are back as well
+    assert_eq!(other.bans(&mut *connection).await.unwrap().len(), 1);
+    assert_eq!(ClaimDispute::on_player(other.id, &mut *connection).await.unwrap().len(), 1);
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_temporary_ban_restores_records(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+    let user = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 50, player.id, player.id, &mut *connection).await;
+    let record = pointercrate_test::demonlist::add_simple_record(100, player.id, demon, RecordStatus::Approved, &mut *connection).await;
+
+    // Bans need a reason and cannot expire in the past
+    let result: serde_json::Value = client
+        .post(
+            format!("/api/v1/players/{}/bans", player.id),
+            &serde_json::json!({"reason": "Hacking", "expires_at": "2000-01-01T00:00:00"}),
+        )
+        .authorize_as(&user)
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(result["code"], 42248);
+
+    let ban: Ban = client
+        .post(
+            format!("/api/v1/players/{}/bans", player.id),
+            &serde_json::json!({"reason": "Hacking", "expires_at": "2999-01-01T00:00:00"}),
+        )
+        .authorize_as(&user)
+        .expect_status(Status::Created)
+        .get_result()
+        .await;
+
+    assert_eq!(ban.reason.as_deref(), Some("Hacking"));
+    assert_eq!(ban.issued_by, Some(user.inner().id));
+
+    let banned = DatabasePlayer::by_id(player.id, &mut *connection).await.unwrap();
+
+    assert!(banned.banned);
+    assert!(approved_records_by(&banned, &mut *connection).await.unwrap().is_empty());
+
+    // Nothing has expired yet
+    assert_eq!(lift_expired_bans(&mut *connection).await.unwrap(), 0);
+
+    sqlx::query!("UPDATE player_bans SET expires_at = '2000-01-01 00:00:00' WHERE id = $1", ban.id)
+        .execute(&mut *connection)
+        .await
+        .unwrap();
+
+    assert_eq!(lift_expired_bans(&mut *connection).await.unwrap(), 1);
+
+    let unbanned = DatabasePlayer::by_id(player.id, &mut *connection).await.unwrap();
+
+    assert!(!unbanned.banned);
+    assert_eq!(approved_records_by(&unbanned, &mut *connection).await.unwrap()[0].id, record);
+
+    let history: Vec<Ban> = client
+        .get(format!("/api/v1/players/{}/bans", player.id))
+        .authorize_as(&user)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(history.len(), 1);
+    assert!(history[0].lifted_at.is_some());
+    assert_eq!(history[0].lifted_by, None);
+}
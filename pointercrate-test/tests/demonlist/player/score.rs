@@ -2,7 +2,10 @@
 
 use pointercrate_core::etag::Taggable;
 use pointercrate_demonlist::{
-    player::{DatabasePlayer, FullPlayer},
+    error::DemonlistError,
+    list::{List, DEFAULT_LIST},
+    nationality::sync::{synchronize, DatasetSubdivision, Iso3166Dataset},
+    player::{DatabasePlayer, FullPlayer, Player},
     record::FullRecord,
     LIST_MODERATOR,
 };
@@ -127,6 +130,194 @@ pub async fn test_player_score_reflects_to_nationality(pool: Pool<Postgres>) {
     assert_ne!(nationality_score("DE", &mut connection).await, 0f64);
 }
 
+async fn continent_id(name: &str, connection: &mut PgConnection) -> i32 {
+    sqlx::query!("SELECT id FROM continents WHERE name = $1::text", name)
+        .fetch_one(&mut *connection)
+        .await
+        .unwrap()
+        .id
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_player_score_reflects_to_continent(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let helper = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+    let demon = clnt.add_demon(&helper, "Bloodbath", 1, 100, "stardust1971", "stardust1971").await;
+
+    let north_america = continent_id("North America", &mut connection).await;
+    let europe = continent_id("Europe", &mut connection).await;
+
+    clnt.patch_player(demon.demon.verifier.id, &helper, serde_json::json!({"nationality": "US"}))
+        .await
+        .execute()
+        .await;
+
+    let ranking: serde_json::Value = clnt.get("/api/v1/continents/").expect_status(Status::Ok).get_result().await;
+
+    assert_eq!(ranking[0]["id"], north_america);
+    assert_eq!(ranking[0]["rank"], 1);
+    assert_ne!(ranking[0]["score"], 0.0);
+
+    let continent: serde_json::Value = clnt
+        .get(format!("/api/v1/continents/{}", north_america))
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert_eq!(continent["continent"]["name"], "North America");
+    assert_eq!(continent["verified"][0]["id"], demon.demon.base.id);
+
+    let (players, _) = clnt
+        .get(format!("/api/v1/players/ranking?continent={}", europe))
+        .expect_status(Status::Ok)
+        .get_pagination_result::<serde_json::Value>()
+        .await;
+
+    assert!(players.is_empty());
+
+    clnt.patch_player(demon.demon.verifier.id, &helper, serde_json::json!({"nationality": "DE"}))
+        .await
+        .execute()
+        .await;
+
+    let ranking: serde_json::Value = clnt.get("/api/v1/continents/").expect_status(Status::Ok).get_result().await;
+
+    assert_eq!(ranking[0]["id"], europe);
+    assert_ne!(ranking[0]["score"], 0.0);
+
+    for continent in ranking.as_array().unwrap().iter().skip(1) {
+        assert_eq!(continent["score"], 0.0, "{:?} still has score", continent["name"]);
+    }
+
+    clnt.get("/api/v1/continents/0").expect_status(Status::NotFound).execute().await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_nation_ranking_at_approval(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let helper = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+    let demon = clnt.add_demon(&helper, "Bloodbath", 1, 100, "stardust1972", "stardust1972").await;
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+
+    clnt.patch_player(player.id, &helper, serde_json::json!({"nationality": "US"}))
+        .await
+        .execute()
+        .await;
+
+    let submission = serde_json::json! {{"progress": 100, "demon": demon.demon.base.id, "player": "stardust1971", "video": "https://youtube.com/watch?v=1234567890", "status": "Approved"}};
+
+    clnt.post("/api/v1/records", &submission)
+        .authorize_as(&helper)
+        .expect_status(Status::Ok)
+        .execute()
+        .await;
+
+    clnt.patch_player(player.id, &helper, serde_json::json!({"nationality": "DE"}))
+        .await
+        .execute()
+        .await;
+
+    // The verifier has no nationality, so only the record counts towards any nation
+    let ranking: serde_json::Value = clnt
+        .get("/api/v1/nationalities/ranking")
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(ranking.as_array().unwrap().len(), 1);
+    assert_eq!(ranking[0]["country_code"], "DE");
+
+    let ranking: serde_json::Value = clnt
+        .get("/api/v1/nationalities/ranking?at_approval=true")
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(ranking.as_array().unwrap().len(), 1);
+    assert_eq!(ranking[0]["country_code"], "US");
+
+    let nation: serde_json::Value = clnt
+        .get("/api/v1/nationalities/US?at_approval=true")
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert_eq!(nation["records"][0]["id"], demon.demon.base.id);
+    assert_eq!(nation["unbeaten"].as_array().unwrap().len(), 0);
+
+    let nation: serde_json::Value = clnt
+        .get("/api/v1/nationalities/US")
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert!(nation["records"].as_array().unwrap().is_empty());
+
+    let history: serde_json::Value = clnt
+        .get(format!("/api/v1/players/{}/nationalities", player.id))
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(history.as_array().unwrap().len(), 2);
+    assert_eq!(history[0]["nationality"]["country_code"], "DE");
+    assert_eq!(history[1]["nationality"]["country_code"], "US");
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_nation_ranking_at_approval_predating_history(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let helper = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+    let demon = clnt.add_demon(&helper, "Bloodbath", 1, 100, "stardust1972", "stardust1972").await;
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+
+    let submission = serde_json::json! {{"progress": 100, "demon": demon.demon.base.id, "player": "stardust1971", "video": "https://youtube.com/watch?v=1234567890", "status": "Approved"}};
+
+    clnt.post("/api/v1/records", &submission)
+        .authorize_as(&helper)
+        .expect_status(Status::Ok)
+        .execute()
+        .await;
+
+    // Pretend the record predates the audit log, and the player was American when history tracking started
+    sqlx::query!("UPDATE records SET status_since = NULL WHERE player = $1", player.id)
+        .execute(&mut *connection)
+        .await
+        .unwrap();
+    sqlx::query!(
+        "INSERT INTO player_nationality_history (player, nationality, since) VALUES ($1, 'US', NULL)",
+        player.id
+    )
+    .execute(&mut *connection)
+    .await
+    .unwrap();
+
+    clnt.patch_player(player.id, &helper, serde_json::json!({"nationality": "DE"}))
+        .await
+        .execute()
+        .await;
+
+    let ranking: serde_json::Value = clnt
+        .get("/api/v1/nationalities/ranking?at_approval=true")
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(ranking.as_array().unwrap().len(), 1);
+    assert_eq!(ranking[0]["country_code"], "US");
+
+    let nation: serde_json::Value = clnt
+        .get("/api/v1/nationalities/DE?at_approval=true")
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert!(nation["records"].as_array().unwrap().is_empty());
+}
+
 #[sqlx::test(migrations = "../migrations")]
 pub async fn test_extended_progress_records_give_no_score(pool: Pool<Postgres>) {
     let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
@@ -134,7 +325,7 @@ pub async fn test_extended_progress_records_give_no_score(pool: Pool<Postgres>)
     let helper = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
     let player = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
 
-    let list_size = std::env::var("LIST_SIZE").unwrap().parse::<i16>().unwrap();
+    let list_size = List::by_id(DEFAULT_LIST, &mut *connection).await.unwrap().list_size;
 
     let mut last_demon_id = 0;
 
@@ -174,7 +365,7 @@ pub async fn test_score_resets_if_last_record_removed(pool: Pool<Postgres>) {
     let helper = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
     let player = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
 
-    let list_size = std::env::var("LIST_SIZE").unwrap().parse::<i16>().unwrap();
+    let list_size = List::by_id(DEFAULT_LIST, &mut *connection).await.unwrap().list_size;
 
     let mut last_demon_id = 0;
 
@@ -233,3 +424,80 @@ pub async fn test_score_resets_if_last_record_removed(pool: Pool<Postgres>) {
         "Removal of player's last record did not reset their score to 0"
     );
 }
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_nationality_sync_remaps_players(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    // The bundled dataset adds to what the migrations set up, and applying it again changes nothing
+    let report = synchronize(&Iso3166Dataset::bundled(), &mut connection).await.unwrap();
+
+    assert!(report.removed_nations.is_empty());
+    assert!(report.added_subdivisions.contains(&"MX-TLA".to_string()));
+    assert!(synchronize(&Iso3166Dataset::bundled(), &mut connection).await.unwrap().is_empty());
+
+    let helper = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+    let demon = clnt.add_demon(&helper, "Bloodbath", 1, 100, "stardust1971", "stardust1971").await;
+
+    clnt.patch_player(
+        demon.demon.verifier.id,
+        &helper,
+        serde_json::json!({"nationality": "GB", "subdivision": "ENG"}),
+    )
+    .await
+    .execute()
+    .await;
+
+    let mut dataset = Iso3166Dataset::bundled();
+    let gb = dataset.nations.iter_mut().find(|nation| nation.code == "GB").unwrap();
+
+    gb.name = "United Kingdom of Great Britain and Northern Ireland".to_string();
+    gb.subdivisions
+        .retain(|subdivision| subdivision.code != "ENG" && subdivision.code != "WLS");
+    gb.subdivisions.push(DatasetSubdivision {
+        code: "EAW".to_string(),
+        name: "England and Wales".to_string(),
+        formerly: vec!["ENG".to_string(), "WLS".to_string()],
+    });
+
+    let mut misplaced = dataset.clone();
+    misplaced.nations[0].continent = "Atlantis".to_string();
+
+    assert!(matches!(
+        synchronize(&misplaced, &mut connection).await,
+        Err(DemonlistError::UnknownContinent { .. })
+    ));
+
+    let report = synchronize(&dataset, &mut connection).await.unwrap();
+
+    assert_eq!(report.added_subdivisions, vec!["GB-EAW".to_string()]);
+    assert_eq!(report.renamed_nations.len(), 1);
+    assert_eq!(
+        report
+            .removed_subdivisions
+            .iter()
+            .map(|removal| (removal.code.as_str(), removal.replaced_by.as_deref(), removal.players_affected))
+            .collect::<Vec<_>>(),
+        vec![("GB-ENG", Some("GB-EAW"), 1), ("GB-WLS", Some("GB-EAW"), 0)]
+    );
+
+    let player = Player::by_id(demon.demon.verifier.id, &mut *connection).await.unwrap();
+
+    assert_eq!(
+        player
+            .nationality
+            .and_then(|nationality| nationality.subdivision)
+            .map(|subdivision| subdivision.iso_code),
+        Some("EAW".to_string())
+    );
+    assert_ne!(subdivision_score("GB", "EAW", &mut connection).await, 0f64);
+
+    // Being moved is not a change of nationality, the history is rewritten instead
+    let history: Vec<Option<String>> = sqlx::query_scalar("SELECT subdivision FROM player_nationality_history WHERE player = $1")
+        .bind(demon.demon.verifier.id)
+        .fetch_all(&mut *connection)
+        .await
+        .unwrap();
+
+    assert_eq!(history, vec![Some("EAW".to_string())]);
+}
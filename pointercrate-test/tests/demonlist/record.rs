@@ -3,12 +3,28 @@ use pointercrate_core::etag::Taggable;
 use pointercrate_demonlist::{
     error::DemonlistError,
     player::{DatabasePlayer, FullPlayer},
-    record::{note::Note, FullRecord, RecordStatus},
-    LIST_HELPER, LIST_MODERATOR,
+    record::{
+        approval::ApprovalPolicy,
+        challenge::{StaticChallenge, SubmissionChallenge},
+        note::Note,
+        policy::{SubmissionPolicy, SubmissionRule},
+        queue::ReviewLock,
+        rejection::RejectionReason,
+        FullRecord, RecordStatus,
+    },
+    video::{
+        upload_date::{UploadDateResolver, UploadDateStrategy},
+        VideoIdentity,
+    },
+    LIST_ADMINISTRATOR, LIST_HELPER, LIST_MODERATOR,
 };
-use pointercrate_test::{demonlist::add_simple_record, user::system_user_with_perms};
+use pointercrate_test::{demonlist::add_simple_record, user::system_user_with_perms, TestClient};
+use pointercrate_user::AuthenticatedUser;
 use rocket::http::Status;
-use sqlx::{PgConnection, Pool, Postgres};
+use sqlx::{
+    types::chrono::{NaiveDate, NaiveDateTime},
+    PgConnection, Pool, Postgres,
+};
 
 #[sqlx::test(migrations = "../migrations")]
 async fn paginate_records_unauthorized(pool: Pool<Postgres>) {
@@ -224,3 +240,736 @@ async fn test_record_deletion_updates_player_score(pool: Pool<Postgres>) {
 
     assert_eq!(player.player.score, 0.0f64, "Deleting approved record failed to lower player score");
 }
+
+#[sqlx::test(migrations = "../migrations")]
+async fn submit_timed_record_without_time(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 = pointercrate_test::demonlist::add_timed_demon("Press Start", 1, None, player1.id, player1.id, &mut *connection).await;
+
+    let submission =
+        serde_json::json! {{"progress": 100, "demon": demon1, "player": "stardust1971", "video": "https://youtube.com/watch?v=1234567890"}};
+
+    let json: serde_json::Value = clnt
+        .post("/api/v1/records/", &submission)
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(json["code"].as_i64(), Some(42236i64));
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn submit_timed_record_slower_than_max_time(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 =
+        pointercrate_test::demonlist::add_timed_demon("Press Start", 1, Some(60_000), player1.id, player1.id, &mut *connection).await;
+
+    let submission = serde_json::json! {{"progress": 100, "completion_time": 60_001, "demon": demon1, "player": "stardust1971", "video": "https://youtube.com/watch?v=1234567890"}};
+
+    let json: serde_json::Value = clnt
+        .post("/api/v1/records/", &submission)
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(json["code"].as_i64(), Some(42239i64));
+    assert_eq!(json["data"]["max_time"].as_i64(), Some(60_000));
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn submit_timed_record_slower_than_existing(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 = pointercrate_test::demonlist::add_timed_demon("Press Start", 1, None, player1.id, player1.id, &mut *connection).await;
+    let existing =
+        pointercrate_test::demonlist::add_timed_record(50_000, player1.id, demon1, RecordStatus::Approved, &mut *connection).await;
+
+    let submission = serde_json::json! {{"progress": 100, "completion_time": 55_000, "demon": demon1, "player": "stardust1971", "video": "https://youtube.com/watch?v=1234567890"}};
+
+    let json: serde_json::Value = clnt
+        .post("/api/v1/records/", &submission)
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(json["code"].as_i64(), Some(42217i64));
+    assert_eq!(json["data"]["existing"].as_i64(), Some(existing as i64));
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn submit_legacy_record(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 = pointercrate_test::demonlist::add_demon("Bloodbath", 151, 50, player1.id, player1.id, &mut *connection).await;
+
+    let submission =
+        serde_json::json! {{"progress": 100, "demon": demon1, "player": "stardust1971", "video": "https://youtube.com/watch?v=1234567890"}};
+
+    let json: serde_json::Value = clnt
+        .post("/api/v1/records/", &submission)
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(json["code"].as_i64(), Some(DemonlistError::SubmitLegacy.error_code() as i64));
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn submit_without_raw_footage_when_required(pool: Pool<Postgres>) {
+    // Accept progress on the extended list, but require raw footage for the top 25
+    let policy = SubmissionPolicy::empty().with_rule(SubmissionRule::RawFootageRequired { up_to_position: 25 });
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket_with_policy(pool, policy).await;
+
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 50, player1.id, player1.id, &mut *connection).await;
+    let demon2 = pointercrate_test::demonlist::add_demon("Cataclysm", 100, 50, player1.id, player1.id, &mut *connection).await;
+
+    let submission =
+        serde_json::json! {{"progress": 60, "demon": demon1, "player": "stardust1971", "video": "https://youtube.com/watch?v=1234567890"}};
+
+    let json: serde_json::Value = clnt
+        .post("/api/v1/records/", &submission)
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(json["code"].as_i64(), Some(DemonlistError::RawRequired.error_code() as i64));
+
+    let submission =
+        serde_json::json! {{"progress": 60, "demon": demon2, "player": "stardust1971", "video": "https://youtube.com/watch?v=1234567890"}};
+
+    clnt.post("/api/v1/records/", &submission).expect_status(Status::Ok).execute().await;
+}
+
+/// Stand-in for querying actual video hosts: Every video was uploaded at the same time
+struct FixedUploadDate(NaiveDateTime);
+
+#[rocket::async_trait]
+impl UploadDateStrategy for FixedUploadDate {
+    async fn upload_date(&self, _: &VideoIdentity) -> Option<NaiveDateTime> {
+        Some(self.0)
+    }
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn submit_video_predating_placement(pool: Pool<Postgres>) {
+    let long_ago = NaiveDate::from_ymd_opt(2013, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    let far_future = NaiveDate::from_ymd_opt(2100, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+
+    let policy = SubmissionPolicy::empty()
+        .with_rule(SubmissionRule::NoVideosPredatingPlacement)
+        .with_upload_dates(
+            UploadDateResolver::default()
+                .with("youtube", FixedUploadDate(long_ago))
+                .with("vimeo", FixedUploadDate(far_future)),
+        );
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket_with_policy(pool, policy).await;
+
+    let helper = system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+    let demon = clnt.add_demon(&helper, "Bloodbath", 1, 50, "stardust1971", "stardust1971").await;
+
+    let submission = serde_json::json! {{"progress": 60, "demon": demon.demon.base.id, "player": "stardust1971", "video": "https://youtube.com/watch?v=1234567890"}};
+
+    let json: serde_json::Value = clnt
+        .post("/api/v1/records/", &submission)
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(json["code"].as_i64(), Some(42255));
+
+    let submission = serde_json::json! {{"progress": 60, "demon": demon.demon.base.id, "player": "stardust1971", "video": "https://vimeo.com/123456789"}};
+
+    clnt.post("/api/v1/records/", &submission).expect_status(Status::Ok).execute().await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn claim_from_review_queue(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let helper1 = pointercrate_test::user::named_system_user_with_perms("Helper1", LIST_HELPER, &mut *connection).await;
+    let helper2 = pointercrate_test::user::named_system_user_with_perms("Helper2", LIST_HELPER, &mut *connection).await;
+
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 50, player1.id, player1.id, &mut *connection).await;
+    let r1 = add_simple_record(70, player1.id, demon1, RecordStatus::Submitted, &mut *connection).await;
+    let r2 = add_simple_record(80, player1.id, demon1, RecordStatus::Submitted, &mut *connection).await;
+
+    let lock1: ReviewLock = clnt
+        .post("/api/v1/records/queue/claim", &())
+        .authorize_as(&helper1)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(lock1.record, r1);
+    assert!(lock1.locked_until.is_some());
+
+    // Claiming again hands out the same record instead of locking a second one
+    let lock: ReviewLock = clnt
+        .post("/api/v1/records/queue/claim", &())
+        .authorize_as(&helper1)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(lock.record, r1);
+
+    let lock2: ReviewLock = clnt
+        .post("/api/v1/records/queue/claim", &())
+        .authorize_as(&helper2)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(lock2.record, r2);
+
+    // helper2 cannot modify the record helper1 is reviewing
+    let record: FullRecord = clnt
+        .get(format!("/api/v1/records/{}", r1))
+        .authorize_as(&helper2)
+        .get_success_result()
+        .await;
+
+    let json: serde_json::Value = clnt
+        .patch(format!("/api/v1/records/{}", r1), &serde_json::json!({"status": "approved"}))
+        .authorize_as(&helper2)
+        .header("If-Match", record.etag_string())
+        .expect_status(Status::Conflict)
+        .get_result()
+        .await;
+
+    assert_eq!(json["code"].as_i64(), Some(40910));
+
+    // Once helper1 releases the record, helper2 can work on it
+    clnt.delete(format!("/api/v1/records/{}/lock", r1))
+        .authorize_as(&helper1)
+        .expect_status(Status::NoContent)
+        .execute()
+        .await;
+
+    let approved: FullRecord = clnt
+        .patch(format!("/api/v1/records/{}", r1), &serde_json::json!({"status": "approved"}))
+        .authorize_as(&helper2)
+        .header("If-Match", record.etag_string())
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert_eq!(approved.status, RecordStatus::Approved);
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn assign_record_for_review(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let moderator = pointercrate_test::user::named_system_user_with_perms("Moderator", LIST_MODERATOR, &mut *connection).await;
+    let helper = pointercrate_test::user::named_system_user_with_perms("Helper", LIST_HELPER, &mut *connection).await;
+    let user = pointercrate_test::user::add_normal_user(&mut *connection).await;
+
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 50, player1.id, player1.id, &mut *connection).await;
+    add_simple_record(70, player1.id, demon1, RecordStatus::Submitted, &mut *connection).await;
+    let r2 = add_simple_record(80, player1.id, demon1, RecordStatus::Submitted, &mut *connection).await;
+
+    let json: serde_json::Value = clnt
+        .put(format!("/api/v1/records/{}/lock", r2))
+        .body(&serde_json::json!({"reviewer": user.inner().id}))
+        .authorize_as(&moderator)
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(json["code"].as_i64(), Some(DemonlistError::NotAReviewer.error_code() as i64));
+
+    let lock: ReviewLock = clnt
+        .put(format!("/api/v1/records/{}/lock", r2))
+        .body(&serde_json::json!({"reviewer": helper.inner().id}))
+        .authorize_as(&moderator)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(lock.locked_until, None);
+
+    // Assigned records are handed out before older ones
+    let lock: ReviewLock = clnt
+        .post("/api/v1/records/queue/claim", &())
+        .authorize_as(&helper)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(lock.record, r2);
+    assert_eq!(lock.locked_until, None);
+
+    let stats: serde_json::Value = clnt
+        .get("/api/v1/records/queue/statistics")
+        .authorize_as(&moderator)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(stats["submitted"].as_i64(), Some(2));
+    assert_eq!(stats["locked"].as_i64(), Some(1));
+    assert_eq!(stats["reviewers"][0]["reviewer"].as_i64(), Some(helper.inner().id as i64));
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn top_demon_record_requires_multiple_approvals(pool: Pool<Postgres>) {
+    let approval_policy = ApprovalPolicy::requiring(2).up_to_position(10);
+    let (clnt, mut connection) =
+        pointercrate_test::demonlist::setup_rocket_with_policies(pool, SubmissionPolicy::default(), approval_policy).await;
+
+    let helper1 = pointercrate_test::user::named_system_user_with_perms("Helper1", LIST_HELPER, &mut *connection).await;
+    let helper2 = pointercrate_test::user::named_system_user_with_perms("Helper2", LIST_HELPER, &mut *connection).await;
+    let admin = pointercrate_test::user::named_system_user_with_perms("Admin", LIST_ADMINISTRATOR, &mut *connection).await;
+
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 50, player1.id, player1.id, &mut *connection).await;
+    let record = add_simple_record(100, player1.id, demon1, RecordStatus::Submitted, &mut *connection).await;
+
+    assert_eq!(
+        approve_as(&clnt, record, &helper1, Status::Accepted).await.status,
+        RecordStatus::Submitted
+    );
+
+    // Approving twice does not count as two approvals
+    assert_eq!(
+        approve_as(&clnt, record, &helper1, Status::Accepted).await.status,
+        RecordStatus::Submitted
+    );
+
+    // A dissenting vote gets recorded, but does not block approval
+    clnt.post(format!("/api/v1/records/{}/votes", record), &serde_json::json!({"approve": false}))
+        .authorize_as(&admin)
+        .expect_status(Status::Ok)
+        .execute()
+        .await;
+
+    assert_eq!(approve_as(&clnt, record, &helper2, Status::Ok).await.status, RecordStatus::Approved);
+
+    let votes: Vec<serde_json::Value> = clnt
+        .get(format!("/api/v1/records/{}/votes", record))
+        .authorize_as(&helper1)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(votes.len(), 4);
+    assert_eq!(votes[2]["approve"].as_bool(), Some(false));
+
+    let audit_log: Vec<serde_json::Value> = clnt
+        .get(format!("/api/v1/records/{}/audit", record))
+        .authorize_as(&admin)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    let vote_entries = audit_log
+        .iter()
+        .filter(|entry| !entry["type"]["Modification"]["approval_vote"].is_null())
+        .count();
+
+    assert_eq!(vote_entries, 4);
+
+    // The final modification is the status change caused by the second approval
+    assert_eq!(
+        audit_log.last().unwrap()["type"]["Modification"]["status"].as_str(),
+        Some("submitted")
+    );
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn approval_policy_per_list(pool: Pool<Postgres>) {
+    let approval_policy = ApprovalPolicy::default().with_list_policy("legacy", ApprovalPolicy::requiring(2));
+    let (clnt, mut connection) =
+        pointercrate_test::demonlist::setup_rocket_with_policies(pool, SubmissionPolicy::default(), approval_policy).await;
+
+    let helper = pointercrate_test::user::named_system_user_with_perms("Helper", LIST_HELPER, &mut *connection).await;
+
+    let legacy = pointercrate_test::demonlist::add_list("legacy", 75, 150, &mut *connection).await;
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 50, player1.id, player1.id, &mut *connection).await;
+    let demon2 =
+        pointercrate_test::demonlist::add_demon_to_list(legacy, "Cataclysm", 1, 50, player1.id, player1.id, &mut *connection).await;
+
+    let main_record = add_simple_record(100, player1.id, demon1, RecordStatus::Submitted, &mut *connection).await;
+    let legacy_record = add_simple_record(100, player1.id, demon2, RecordStatus::Submitted, &mut *connection).await;
+
+    assert_eq!(
+        approve_as(&clnt, main_record, &helper, Status::Ok).await.status,
+        RecordStatus::Approved
+    );
+
+    let response: serde_json::Value = clnt
+        .post(
+            format!("/api/v1/records/{}/votes", legacy_record),
+            &serde_json::json!({"approve": true}),
+        )
+        .authorize_as(&helper)
+        .expect_status(Status::Accepted)
+        .get_result()
+        .await;
+
+    assert_eq!(response["data"]["status"].as_str(), Some("submitted"));
+    assert_eq!(response["pending_approval"]["approvals"].as_i64(), Some(1));
+    assert_eq!(response["pending_approval"]["required_approvals"].as_i64(), Some(2));
+}
+
+async fn approve_as(clnt: &TestClient, record_id: i32, user: &AuthenticatedUser, expected_status: Status) -> FullRecord {
+    let record: FullRecord = clnt
+        .get(format!("/api/v1/records/{}", record_id))
+        .authorize_as(user)
+        .get_success_result()
+        .await;
+
+    clnt.patch(format!("/api/v1/records/{}", record_id), &serde_json::json!({"status": "approved"}))
+        .authorize_as(user)
+        .header("If-Match", record.etag_string())
+        .expect_status(expected_status)
+        .get_success_result()
+        .await
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn reject_record_with_reason(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let helper = pointercrate_test::user::named_system_user_with_perms("Helper", LIST_HELPER, &mut *connection).await;
+    let claimant = pointercrate_test::user::add_normal_user(&mut *connection).await;
+
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 50, player1.id, player1.id, &mut *connection).await;
+    let record = add_simple_record(70, player1.id, demon1, RecordStatus::Submitted, &mut *connection).await;
+
+    let reasons: Vec<RejectionReason> = clnt.get("/api/v1/rejection_reasons/").expect_status(Status::Ok).get_result().await;
+    let spliced = reasons.into_iter().find(|reason| reason.name == "Spliced").unwrap();
+
+    let current: FullRecord = clnt
+        .get(format!("/api/v1/records/{}", record))
+        .authorize_as(&helper)
+        .get_success_result()
+        .await;
+
+    // Rejection reasons can only be set on rejected records
+    let json: serde_json::Value = clnt
+        .patch(
+            format!("/api/v1/records/{}", record),
+            &serde_json::json!({"rejection_reason": spliced.id}),
+        )
+        .authorize_as(&helper)
+        .header("If-Match", current.etag_string())
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(
+        json["code"].as_i64(),
+        Some(DemonlistError::RejectionReasonWithoutRejection.error_code() as i64)
+    );
+
+    let rejected: FullRecord = clnt
+        .patch(
+            format!("/api/v1/records/{}", record),
+            &serde_json::json!({"status": "rejected", "rejection_reason": spliced.id}),
+        )
+        .authorize_as(&helper)
+        .header("If-Match", current.etag_string())
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert_eq!(rejected.rejection_reason.as_ref(), Some(&spliced));
+
+    // Only the verified claimant of the player gets to see the rejected record
+    clnt.get(format!("/api/v1/records/{}", record))
+        .authorize_as(&claimant)
+        .expect_status(Status::NotFound)
+        .execute()
+        .await;
+
+    pointercrate_test::demonlist::put_claim(claimant.inner().id, player1.id, true, false, &mut *connection).await;
+
+    let seen: FullRecord = clnt
+        .get(format!("/api/v1/records/{}", record))
+        .authorize_as(&claimant)
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert_eq!(seen.rejection_reason, Some(spliced.clone()));
+    assert_eq!(seen.submitter, None);
+
+    let statistics: Vec<serde_json::Value> = clnt
+        .get("/api/v1/rejection_reasons/statistics")
+        .authorize_as(&helper)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    for entry in statistics {
+        let expected = if entry["reason"]["id"].as_i64() == Some(spliced.id as i64) {
+            1
+        } else {
+            0
+        };
+
+        assert_eq!(entry["rejected"].as_i64(), Some(expected));
+    }
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn raw_footage_only_visible_to_helpers_and_claimant(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let helper = pointercrate_test::user::named_system_user_with_perms("Helper", LIST_HELPER, &mut *connection).await;
+    let claimant = pointercrate_test::user::add_normal_user(&mut *connection).await;
+
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 50, player1.id, player1.id, &mut *connection).await;
+
+    let submission = serde_json::json! {{"progress": 60, "demon": demon1, "player": "stardust1971", "raw_footage": ["https://drive.google.com/file/d/1234567890/view", "https://youtu.be/1234567890a"]}};
+
+    let submitted: FullRecord = clnt
+        .post("/api/v1/records/", &submission)
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert!(submitted.raw_footage.is_empty());
+
+    let record: FullRecord = clnt
+        .get(format!("/api/v1/records/{}", submitted.id))
+        .authorize_as(&helper)
+        .get_success_result()
+        .await;
+
+    assert_eq!(
+        record.raw_footage,
+        vec![
+            "https://drive.google.com/file/d/1234567890/view".to_string(),
+            "https://www.youtube.com/watch?v=1234567890a".to_string()
+        ]
+    );
+
+    let json: serde_json::Value = clnt
+        .patch(
+            format!("/api/v1/records/{}", record.id),
+            &serde_json::json!({"raw_footage": ["not a url"]}),
+        )
+        .authorize_as(&helper)
+        .header("If-Match", record.etag_string())
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(json["code"].as_i64(), Some(DemonlistError::MalformedRawUrl.error_code() as i64));
+
+    let record: FullRecord = clnt
+        .patch(
+            format!("/api/v1/records/{}", record.id),
+            &serde_json::json!({"status": "approved", "raw_footage": ["https://pointercrate.com/raw.mp4"]}),
+        )
+        .authorize_as(&helper)
+        .header("If-Match", record.etag_string())
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert_eq!(record.raw_footage, vec!["https://pointercrate.com/raw.mp4".to_string()]);
+
+    let public: FullRecord = clnt.get(format!("/api/v1/records/{}", record.id)).get_success_result().await;
+
+    assert!(public.raw_footage.is_empty());
+
+    pointercrate_test::demonlist::put_claim(claimant.inner().id, player1.id, true, false, &mut *connection).await;
+
+    let seen: FullRecord = clnt
+        .get(format!("/api/v1/records/{}", record.id))
+        .authorize_as(&claimant)
+        .get_success_result()
+        .await;
+
+    assert_eq!(seen.raw_footage, record.raw_footage);
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn duplicate_video_detected_across_link_formats(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let helper = system_user_with_perms(LIST_HELPER, &mut *connection).await;
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 50, player1.id, player1.id, &mut *connection).await;
+
+    let submission = serde_json::json! {{"progress": 100, "demon": demon1, "player": "stardust1971", "video": "https://www.youtube.com/watch?v=dQw4w9WgXcQ", "status": "approved"}};
+
+    let existing: FullRecord = clnt
+        .post("/api/v1/records/", &submission)
+        .authorize_as(&helper)
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    // Same video, different link format
+    let submission = serde_json::json! {{"progress": 100, "demon": demon1, "player": "stardust1972", "video": "https://youtube.com/shorts/dQw4w9WgXcQ", "status": "approved"}};
+
+    let json: serde_json::Value = clnt
+        .post("/api/v1/records/", &submission)
+        .authorize_as(&helper)
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(json["code"].as_i64(), Some(42217i64));
+    assert_eq!(json["data"]["existing"].as_i64(), Some(existing.id as i64));
+
+    // Same video, but starting at a timestamp
+    let submission = serde_json::json! {{"progress": 100, "demon": demon1, "player": "stardust1972", "video": "https://youtu.be/dQw4w9WgXcQ?t=42", "status": "approved"}};
+
+    let json: serde_json::Value = clnt
+        .post("/api/v1/records/", &submission)
+        .authorize_as(&helper)
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(json["code"].as_i64(), Some(42217i64));
+    assert_eq!(json["data"]["existing"].as_i64(), Some(existing.id as i64));
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn anonymous_submission_requires_challenge(pool: Pool<Postgres>) {
+    let (clnt, mut connection) =
+        pointercrate_test::demonlist::setup_rocket_with_challenge(pool, SubmissionChallenge::new(StaticChallenge::new("not-a-bot"))).await;
+
+    let user = pointercrate_test::user::add_normal_user(&mut *connection).await;
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 87, player.id, player.id, &mut *connection).await;
+
+    let submission =
+        serde_json::json! {{"progress": 100, "demon": demon, "player": "stardust1971", "video": "https://youtube.com/watch?v=1234567890"}};
+
+    let json: serde_json::Value = clnt
+        .post("/api/v1/records/", &submission)
+        .expect_status(Status::Forbidden)
+        .get_result()
+        .await;
+
+    assert_eq!(json["code"].as_i64(), Some(DemonlistError::ChallengeFailed.error_code() as i64));
+
+    clnt.post("/api/v1/records/", &submission)
+        .header("X-Challenge-Response", "definitely-a-bot")
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+
+    clnt.post("/api/v1/records/", &submission)
+        .header("X-Challenge-Response", "not-a-bot")
+        .expect_status(Status::Ok)
+        .execute()
+        .await;
+
+    // Logged in users do not need to solve the challenge
+    let submission =
+        serde_json::json! {{"progress": 100, "demon": demon, "player": "Aquatias", "video": "https://youtube.com/watch?v=0987654321"}};
+
+    clnt.post("/api/v1/records/", &submission)
+        .authorize_as(&user)
+        .expect_status(Status::Ok)
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn claimant_record_visibility(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let helper = pointercrate_test::user::named_system_user_with_perms("Helper", LIST_HELPER, &mut *connection).await;
+    let stranger = pointercrate_test::user::named_system_user_with_perms("Aquatias", 0, &mut *connection).await;
+    let claimant = pointercrate_test::user::add_normal_user(&mut *connection).await;
+
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 50, player1.id, player1.id, &mut *connection).await;
+    let record = add_simple_record(70, player1.id, demon1, RecordStatus::Submitted, &mut *connection).await;
+
+    let submitted: FullRecord = clnt
+        .get(format!("/api/v1/records/{}", record))
+        .authorize_as(&helper)
+        .get_success_result()
+        .await;
+
+    clnt.patch(format!("/api/v1/records/{}", record), &serde_json::json!({"status": "rejected"}))
+        .authorize_as(&helper)
+        .header("If-Match", submitted.etag_string())
+        .expect_status(Status::Ok)
+        .execute()
+        .await;
+
+    for is_public in [true, false] {
+        clnt.post(
+            format!("/api/v1/records/{}/notes", record),
+            &serde_json::json!({"content": "My Note", "is_public": is_public}),
+        )
+        .authorize_as(&helper)
+        .expect_status(Status::Created)
+        .execute()
+        .await;
+    }
+
+    pointercrate_test::demonlist::put_claim(claimant.inner().id, player1.id, true, false, &mut *connection).await;
+
+    // Non-approved records, their notes and their history do not exist for anyone but the claimant
+    for endpoint in ["", "/notes", "/audit/status"] {
+        clnt.get(format!("/api/v1/records/{}{}", record, endpoint))
+            .authorize_as(&stranger)
+            .expect_status(Status::NotFound)
+            .execute()
+            .await;
+    }
+
+    let seen: FullRecord = clnt
+        .get(format!("/api/v1/records/{}", record))
+        .authorize_as(&claimant)
+        .get_success_result()
+        .await;
+
+    assert_eq!(seen.status, RecordStatus::Rejected);
+    assert_eq!(seen.submitter, None);
+
+    let notes: Vec<Note> = clnt
+        .get(format!("/api/v1/records/{}/notes", record))
+        .authorize_as(&claimant)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(notes.len(), 1);
+    assert!(notes[0].is_public);
+
+    let history: Vec<serde_json::Value> = clnt
+        .get(format!("/api/v1/records/{}/audit/status", record))
+        .authorize_as(&claimant)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(
+        history.iter().map(|change| change["status"].as_str()).collect::<Vec<_>>(),
+        vec![Some("submitted"), Some("rejected")]
+    );
+    assert!(history.iter().all(|change| change.get("user").is_none()));
+
+    // The full audit log, which includes who made which change, stays restricted
+    clnt.get(format!("/api/v1/records/{}/audit", record))
+        .authorize_as(&claimant)
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+}
@@ -0,0 +1,235 @@
+use pointercrate_core::{error::PointercrateError, etag::Taggable};
+use pointercrate_demonlist::{
+    error::DemonlistError,
+    player::DatabasePlayer,
+    record::{FullRecord, RecordStatus},
+    submitter::{RangeBan, ReputationPolicy, Submitter},
+    LIST_MODERATOR,
+};
+use pointercrate_test::demonlist::{add_demon, add_simple_record};
+use rocket::http::Status;
+use sqlx::{Pool, Postgres};
+use std::{net::IpAddr, str::FromStr};
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_submitter_reputation(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+    let user = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+
+    let submitter = Submitter::by_ip(IpAddr::from_str("127.0.0.1").unwrap(), &mut *connection)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(submitter.statistics.reputation, 0.5);
+
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 = add_demon("Bloodbath", 1, 50, player.id, player.id, &mut *connection).await;
+    let demon2 = add_demon("Cadrega City", 2, 50, player.id, player.id, &mut *connection).await;
+    let demon3 = add_demon("Sonic Wave", 3, 50, player.id, player.id, &mut *connection).await;
+
+    let approved = add_simple_record(100, player.id, demon1, RecordStatus::Submitted, &mut *connection).await;
+    let rejected = add_simple_record(100, player.id, demon2, RecordStatus::Submitted, &mut *connection).await;
+    let invalid = add_simple_record(100, player.id, demon3, RecordStatus::Submitted, &mut *connection).await;
+
+    for (record_id, status) in [(approved, "approved"), (rejected, "rejected")] {
+        let record = FullRecord::by_id(record_id, &mut *connection).await.unwrap();
+
+        client
+            .patch(format!("/api/v1/records/{}", record_id), &serde_json::json!({ "status": status }))
+            .authorize_as(&user)
+            .header("If-Match", record.etag_string())
+            .expect_status(Status::Ok)
+            .execute()
+            .await;
+    }
+
+    FullRecord::delete_invalid(invalid, &mut *connection).await.unwrap();
+
+    let submitter: Submitter = client
+        .get(format!("/api/v1/submitters/{}", submitter.id))
+        .authorize_as(&user)
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert_eq!(submitter.statistics.submitted, 3);
+    assert_eq!(submitter.statistics.approved, 1);
+    assert_eq!(submitter.statistics.rejected, 1);
+    assert_eq!(submitter.statistics.deleted_invalid, 1);
+    assert_eq!(submitter.statistics.reputation, 0.4);
+
+    // The default policy needs more reviewed submissions before it applies
+    assert!(!submitter.flagged);
+
+    let mut submitter = ReputationPolicy::disabled()
+        .after_reviewed(3)
+        .flag_at(0.5)
+        .enforce(submitter.id, &mut *connection)
+        .await
+        .unwrap();
+
+    assert!(submitter.flagged);
+    assert!(!submitter.banned);
+
+    let flagged: Vec<Submitter> = client
+        .get("/api/v1/submitters/?flagged=true")
+        .authorize_as(&user)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(flagged.len(), 1);
+    assert_eq!(flagged[0].id, submitter.id);
+
+    // Once a moderator cleared the flag, it is not reapplied
+    submitter.set_flagged(false, &mut *connection).await.unwrap();
+
+    let submitter = ReputationPolicy::disabled()
+        .after_reviewed(3)
+        .flag_at(0.5)
+        .enforce(submitter.id, &mut *connection)
+        .await
+        .unwrap();
+
+    assert!(!submitter.flagged);
+
+    let submitter = ReputationPolicy::disabled()
+        .after_reviewed(3)
+        .ban_at(0.6)
+        .enforce(submitter.id, &mut *connection)
+        .await
+        .unwrap();
+
+    assert!(submitter.banned);
+    assert!(submitter.bans(&mut *connection).await.unwrap()[0].reason.is_some());
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_moderator_added_records_not_counted(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+    let user = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 = add_demon("Bloodbath", 1, 50, player.id, player.id, &mut *connection).await;
+    let demon2 = add_demon("Cadrega City", 2, 50, player.id, player.id, &mut *connection).await;
+
+    for (demon, status, video) in [
+        (demon1, "Rejected", "https://youtube.com/watch?v=1234567890"),
+        (demon2, "Approved", "https://youtube.com/watch?v=0987654321"),
+    ] {
+        client
+            .post(
+                "/api/v1/records/",
+                &serde_json::json!({"progress": 100, "demon": demon, "player": "stardust1971", "video": video, "status": status}),
+            )
+            .authorize_as(&user)
+            .expect_status(Status::Ok)
+            .execute()
+            .await;
+    }
+
+    let submitter = Submitter::by_ip(IpAddr::from_str("127.0.0.1").unwrap(), &mut *connection)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Records added directly by a moderator never went through the review queue
+    assert_eq!(submitter.statistics.submitted, 0);
+    assert_eq!(submitter.statistics.approved, 0);
+    assert_eq!(submitter.statistics.rejected, 0);
+    assert_eq!(submitter.statistics.reputation, 0.5);
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_ipv6_submitters_identified_by_prefix(pool: Pool<Postgres>) {
+    let (_, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let submitter = Submitter::create_submitter(IpAddr::from_str("2001:db8::1").unwrap(), &mut *connection)
+        .await
+        .unwrap();
+
+    let same_prefix = Submitter::by_ip(IpAddr::from_str("2001:db8::ffff:1234").unwrap(), &mut *connection)
+        .await
+        .unwrap();
+
+    assert_eq!(same_prefix.map(|submitter| submitter.id), Some(submitter.id));
+
+    let other_prefix = Submitter::by_ip(IpAddr::from_str("2001:db8:0:1::1").unwrap(), &mut *connection)
+        .await
+        .unwrap();
+
+    assert!(other_prefix.is_none());
+
+    // Submitters created under a different prefix length are still found by the network they were created with
+    sqlx::query!("INSERT INTO submitters (ip_address) VALUES ('2001:db8:1::/48')")
+        .execute(&mut *connection)
+        .await
+        .unwrap();
+
+    let shorter_prefix = Submitter::by_ip(IpAddr::from_str("2001:db8:1:2::1").unwrap(), &mut *connection)
+        .await
+        .unwrap();
+
+    assert!(shorter_prefix.is_some());
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_range_ban(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+    let user = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon = add_demon("Bloodbath", 1, 50, player.id, player.id, &mut *connection).await;
+
+    let json: serde_json::Value = client
+        .post(
+            "/api/v1/range_bans/",
+            &serde_json::json!({"range": "127.0.0.1/33", "reason": "Spam"}),
+        )
+        .authorize_as(&user)
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(json["code"].as_i64(), Some(DemonlistError::InvalidIpRange.error_code() as i64));
+
+    let ban: RangeBan = client
+        .post(
+            "/api/v1/range_bans/",
+            &serde_json::json!({"range": "127.1.2.3/8", "reason": "Spam"}),
+        )
+        .authorize_as(&user)
+        .expect_status(Status::Created)
+        .get_result()
+        .await;
+
+    assert_eq!(ban.range, "127.0.0.0/8");
+
+    let submission =
+        serde_json::json! {{"progress": 100, "demon": demon, "player": "stardust1971", "video": "https://youtube.com/watch?v=1234567890"}};
+
+    let json: serde_json::Value = client
+        .post("/api/v1/records/", &submission)
+        .expect_status(Status::Forbidden)
+        .get_result()
+        .await;
+
+    assert_eq!(
+        json["code"].as_i64(),
+        Some(DemonlistError::BannedFromSubmissions.error_code() as i64)
+    );
+
+    client
+        .delete(format!("/api/v1/range_bans/{}/", ban.id))
+        .authorize_as(&user)
+        .expect_status(Status::NoContent)
+        .execute()
+        .await;
+
+    client
+        .post("/api/v1/records/", &submission)
+        .expect_status(Status::Ok)
+        .execute()
+        .await;
+}
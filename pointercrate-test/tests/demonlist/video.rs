@@ -0,0 +1,92 @@
+use pointercrate_demonlist::{
+    player::DatabasePlayer,
+    record::RecordStatus,
+    video::availability::{check_due_videos, Probe, VideoProber, VideoState},
+    LIST_MODERATOR,
+};
+use pointercrate_test::{demonlist::add_simple_record, user::system_user_with_perms};
+use rocket::http::Status;
+use sqlx::{Pool, Postgres};
+
+/// Stand-in for probing actual video hosts: Every video whose link contains "gone" is dead
+struct LocalProber;
+
+#[rocket::async_trait]
+impl VideoProber for LocalProber {
+    async fn probe(&self, video: &str) -> Probe {
+        if video.contains("gone") {
+            Probe {
+                state: VideoState::Dead,
+                detail: Some("HTTP 404 Not Found".to_string()),
+            }
+        } else {
+            Probe {
+                state: VideoState::Available,
+                detail: None,
+            }
+        }
+    }
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn dead_videos_are_reported(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let moderator = system_user_with_perms(LIST_MODERATOR, &mut *connection).await;
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut *connection).await.unwrap();
+    let demon1 = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 50, player1.id, player1.id, &mut *connection).await;
+    let dead = add_simple_record(100, player1.id, demon1, RecordStatus::Approved, &mut *connection).await;
+    let alive = add_simple_record(100, player1.id, demon1, RecordStatus::Approved, &mut *connection).await;
+
+    sqlx::query!(
+        "UPDATE records SET video = 'https://www.youtube.com/watch?v=gone0000000' WHERE id = $1",
+        dead
+    )
+    .execute(&mut *connection)
+    .await
+    .unwrap();
+    sqlx::query!(
+        "UPDATE records SET video = 'https://www.youtube.com/watch?v=here0000000' WHERE id = $1",
+        alive
+    )
+    .execute(&mut *connection)
+    .await
+    .unwrap();
+    sqlx::query!(
+        "UPDATE demons SET video = 'https://www.youtube.com/watch?v=verif000000' WHERE id = $1",
+        demon1
+    )
+    .execute(&mut *connection)
+    .await
+    .unwrap();
+
+    assert_eq!(check_due_videos(&LocalProber, 24, 10, &mut *connection).await.unwrap(), 3);
+    // Nothing is due for another check yet
+    assert_eq!(check_due_videos(&LocalProber, 24, 10, &mut *connection).await.unwrap(), 0);
+
+    clnt.get("/api/v1/videos/unavailable")
+        .expect_status(Status::Unauthorized)
+        .execute()
+        .await;
+
+    let unavailable: Vec<serde_json::Value> = clnt
+        .get("/api/v1/videos/unavailable")
+        .authorize_as(&moderator)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(unavailable.len(), 1);
+    assert_eq!(unavailable[0]["video"], "https://www.youtube.com/watch?v=gone0000000");
+    assert_eq!(unavailable[0]["state"], "dead");
+    assert_eq!(unavailable[0]["records"], serde_json::json!([dead]));
+
+    // Dead videos are only reported, never removed
+    let video: Option<String> = sqlx::query_scalar("SELECT video FROM records WHERE id = $1")
+        .bind(dead)
+        .fetch_one(&mut *connection)
+        .await
+        .unwrap();
+
+    assert_eq!(video.as_deref(), Some("https://www.youtube.com/watch?v=gone0000000"));
+}
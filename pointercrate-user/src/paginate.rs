@@ -44,7 +44,7 @@ impl PaginationQuery for UserPagination {
 }
 
 impl Paginatable<UserPagination> for User {
-    first_and_last!("members", "member_id");
+    first_and_last!(UserPagination, "members", "member_id");
 
     async fn page(query: &UserPagination, connection: &mut PgConnection) -> std::result::Result<(Vec<User>, PageContext), sqlx::Error> {
         let order = query.params.order();